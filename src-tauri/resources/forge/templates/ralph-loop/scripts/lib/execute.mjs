@@ -52,11 +52,18 @@ function isTaskCompleted(stateTask) {
   return phases.length > 0 && phases.every((phase) => isCompletedStatus(phase.status));
 }
 
+// A phase marked failed has used up its retry budget, so the task is not picked again.
+function isTaskExhausted(stateTask) {
+  const phases = Array.isArray(stateTask?.phases) ? stateTask.phases : [];
+  return phases.some((phase) => typeof phase.status === "string" && phase.status.trim() === "failed");
+}
+
 function isDepsSatisfied(task, stateById) {
   const deps = Array.isArray(task?.depends_on) ? task.depends_on : [];
   for (const dep of deps) {
     const st = stateById.get(dep);
-    if (!st || !isTaskCompleted(st)) {
+    // Tasks finished in a worktree only unblock dependents once merged (worktree cleared).
+    if (!st || !isTaskCompleted(st) || st.worktree) {
       return false;
     }
   }
@@ -72,7 +79,7 @@ export function findNextRunnableTask(plan, state) {
     if (!st) {
       continue;
     }
-    if (isTaskCompleted(st)) {
+    if (isTaskCompleted(st) || isTaskExhausted(st)) {
       continue;
     }
     if (isDepsSatisfied(task, stateById)) {
@@ -111,6 +118,18 @@ function dependencyNotesForTask(task, stateById) {
   return lines.join("\n");
 }
 
+function findFocusedTask(plan, state, focusTaskId) {
+  if (typeof focusTaskId !== "string" || !focusTaskId.trim()) {
+    return null;
+  }
+  const planTasks = Array.isArray(plan?.tasks) ? plan.tasks : [];
+  const task = planTasks.find((entry) => entry.id === focusTaskId.trim());
+  if (!task || !mapStateTasks(state).has(task.id)) {
+    return null;
+  }
+  return task;
+}
+
 export function renderExecutePrompt({
   templateText,
   plan,
@@ -118,6 +137,7 @@ export function renderExecutePrompt({
   templatePhases,
   progressNotes,
   todayIso,
+  focusTaskId = null,
 }) {
   const techStack = Array.isArray(plan?.context?.tech_stack)
     ? plan.context.tech_stack.join(", ")
@@ -127,7 +147,8 @@ export function renderExecutePrompt({
     : "";
 
   const stateById = mapStateTasks(state);
-  const current = findNextRunnableTask(plan, state);
+  // Parallel runs ask for a specific task; serial runs take the first runnable one.
+  const current = findFocusedTask(plan, state, focusTaskId) ?? findNextRunnableTask(plan, state);
   const templatePhaseById = mapTemplatePhases(templatePhases);
 
   if (!current) {
@@ -62,6 +62,7 @@ const NOTES_TRUNCATED_SUFFIX = " ... [truncated]";
 export const STATE_SUMMARY_MAX_LENGTH = 300;
 export const TASK_NOTES_MAX_LENGTH = 2000;
 export const PHASE_NOTES_MAX_LENGTH = 800;
+const PLAN_BUDGET_KEYS = ["max_task_tokens", "max_total_tokens", "max_task_attempts", "max_minutes"];
 
 function truncateNotes(value, maxLength) {
   if (typeof value !== "string" || value.length <= maxLength) {
@@ -125,7 +126,7 @@ export function validatePlan(plan) {
   expectNoExtraKeys(
     errors,
     plan,
-    ["$schema", "id", "title", "goal", "context", "tasks"],
+    ["$schema", "id", "title", "goal", "context", "tasks", "budget"],
     "plan",
   );
   if (plan.$schema !== "plan-v1") {
@@ -164,6 +165,20 @@ export function validatePlan(plan) {
     }
   }
 
+  if (plan.budget !== undefined) {
+    if (!isPlainObject(plan.budget)) {
+      pushError(errors, "plan.budget", "Expected object");
+    } else {
+      expectNoExtraKeys(errors, plan.budget, PLAN_BUDGET_KEYS, "plan.budget");
+      for (const key of PLAN_BUDGET_KEYS) {
+        const value = plan.budget[key];
+        if (value !== undefined && (!Number.isInteger(value) || value < 1)) {
+          pushError(errors, `plan.budget.${key}`, "Expected integer >= 1");
+        }
+      }
+    }
+  }
+
   expectArray(errors, plan.tasks, "plan.tasks", { minItems: 1 });
   const tasksById = new Map();
   let hasEntryPoint = false;
@@ -249,11 +264,35 @@ function validatePhaseList(errors, phases, path) {
     expectNoExtraKeys(
       errors,
       phase,
-      ["id", "title", "order", "iconId", "goal", "description", "checks"],
+      [
+        "id",
+        "title",
+        "order",
+        "iconId",
+        "goal",
+        "description",
+        "checks",
+        "artifacts",
+        "maxAttempts",
+        "backoffSeconds",
+        "kind",
+      ],
       phasePath,
     );
     expectString(errors, phase.id, `${phasePath}.id`, { minLength: 1, maxLength: 64 });
     expectString(errors, phase.title, `${phasePath}.title`, { minLength: 1, maxLength: 80 });
+    if (phase.kind !== undefined && phase.kind !== "agent" && phase.kind !== "manual-approval") {
+      pushError(errors, `${phasePath}.kind`, "Invalid kind");
+    }
+    if (phase.maxAttempts !== undefined && (!Number.isInteger(phase.maxAttempts) || phase.maxAttempts < 1)) {
+      pushError(errors, `${phasePath}.maxAttempts`, "Expected integer >= 1");
+    }
+    if (
+      phase.backoffSeconds !== undefined &&
+      (!Number.isInteger(phase.backoffSeconds) || phase.backoffSeconds < 0)
+    ) {
+      pushError(errors, `${phasePath}.backoffSeconds`, "Expected integer >= 0");
+    }
   }
 }
 
@@ -324,7 +363,12 @@ export function validateStateAgainstPlan(state, plan, templatePhases) {
     return errors;
   }
 
-  expectNoExtraKeys(errors, state, ["$schema", "plan_id", "iteration", "summary", "tasks"], "state");
+  expectNoExtraKeys(
+    errors,
+    state,
+    ["$schema", "plan_id", "iteration", "summary", "tasks", "run_started_at"],
+    "state",
+  );
   if (state.$schema !== "state-v2") {
     pushError(errors, "state.$schema", 'Expected "state-v2"');
   }
@@ -335,6 +379,9 @@ export function validateStateAgainstPlan(state, plan, templatePhases) {
     pushError(errors, "state.iteration", "Expected integer >= 0");
   }
   expectString(errors, state.summary, "state.summary", { maxLength: STATE_SUMMARY_MAX_LENGTH });
+  if (state.run_started_at !== undefined) {
+    expectString(errors, state.run_started_at, "state.run_started_at", { minLength: 1 });
+  }
 
   expectArray(errors, state.tasks, "state.tasks", { minItems: 1 });
 
@@ -357,7 +404,12 @@ export function validateStateAgainstPlan(state, plan, templatePhases) {
         pushError(errors, entryPath, "Expected object");
         continue;
       }
-      expectNoExtraKeys(errors, entry, ["id", "status", "attempts", "notes", "commit_sha", "phases"], entryPath);
+      expectNoExtraKeys(
+        errors,
+        entry,
+        ["id", "status", "attempts", "notes", "commit_sha", "phases", "worktree"],
+        entryPath,
+      );
       expectString(errors, entry.id, `${entryPath}.id`, { pattern: TASK_ID_RE });
       if (typeof entry.status !== "string" || !allowedStatus.has(entry.status)) {
         pushError(errors, `${entryPath}.status`, "Invalid status");
@@ -371,6 +423,21 @@ export function validateStateAgainstPlan(state, plan, templatePhases) {
         pushError(errors, `${entryPath}.commit_sha`, "Expected string or null");
       }
 
+      if (entry.worktree != null) {
+        const worktreePath = `${entryPath}.worktree`;
+        if (!isPlainObject(entry.worktree)) {
+          pushError(errors, worktreePath, "Expected object");
+        } else {
+          expectNoExtraKeys(errors, entry.worktree, ["workspace_id", "path", "branch", "base_sha"], worktreePath);
+          expectString(errors, entry.worktree.workspace_id, `${worktreePath}.workspace_id`, { minLength: 1 });
+          expectString(errors, entry.worktree.path, `${worktreePath}.path`, { minLength: 1 });
+          expectString(errors, entry.worktree.branch, `${worktreePath}.branch`, { minLength: 1 });
+          if (entry.worktree.base_sha != null && typeof entry.worktree.base_sha !== "string") {
+            pushError(errors, `${worktreePath}.base_sha`, "Expected string or null");
+          }
+        }
+      }
+
       if (planTasks[i]?.id && entry.id !== planTasks[i].id) {
         pushError(errors, entryPath, `Task id mismatch at index ${i} (expected ${planTasks[i].id})`);
       }
@@ -387,7 +454,7 @@ export function validateStateAgainstPlan(state, plan, templatePhases) {
             pushError(errors, phasePath, "Expected object");
             continue;
           }
-          expectNoExtraKeys(errors, phase, ["id", "status", "attempts", "notes"], phasePath);
+          expectNoExtraKeys(errors, phase, ["id", "status", "attempts", "notes", "commit_sha", "approval", "threads"], phasePath);
           expectString(errors, phase.id, `${phasePath}.id`, { minLength: 1, maxLength: 64 });
           if (expectedPhaseIds[j] && phase.id !== expectedPhaseIds[j]) {
             pushError(errors, `${phasePath}.id`, `Phase id mismatch at index ${j} (expected ${expectedPhaseIds[j]})`);
@@ -399,6 +466,41 @@ export function validateStateAgainstPlan(state, plan, templatePhases) {
             pushError(errors, `${phasePath}.attempts`, "Expected integer >= 0");
           }
           expectString(errors, phase.notes, `${phasePath}.notes`, { maxLength: PHASE_NOTES_MAX_LENGTH });
+          if (phase.commit_sha != null && typeof phase.commit_sha !== "string") {
+            pushError(errors, `${phasePath}.commit_sha`, "Expected string or null");
+          }
+          if (phase.approval !== undefined) {
+            const approvalPath = `${phasePath}.approval`;
+            if (!isPlainObject(phase.approval)) {
+              pushError(errors, approvalPath, "Expected object");
+            } else {
+              expectNoExtraKeys(errors, phase.approval, ["decision", "reviewer", "notes", "decided_at"], approvalPath);
+              if (phase.approval.decision !== "approved" && phase.approval.decision !== "rejected") {
+                pushError(errors, `${approvalPath}.decision`, "Invalid decision");
+              }
+              expectString(errors, phase.approval.reviewer, `${approvalPath}.reviewer`, { minLength: 1 });
+              expectString(errors, phase.approval.notes, `${approvalPath}.notes`);
+              expectString(errors, phase.approval.decided_at, `${approvalPath}.decided_at`, { minLength: 1 });
+            }
+          }
+          if (phase.threads !== undefined) {
+            const threadsPath = `${phasePath}.threads`;
+            if (!Array.isArray(phase.threads)) {
+              pushError(errors, threadsPath, "Expected array");
+            } else {
+              for (let k = 0; k < phase.threads.length; k++) {
+                const thread = phase.threads[k];
+                const threadPath = `${threadsPath}[${k}]`;
+                if (!isPlainObject(thread)) {
+                  pushError(errors, threadPath, "Expected object");
+                  continue;
+                }
+                expectNoExtraKeys(errors, thread, ["thread_id", "checked_at"], threadPath);
+                expectString(errors, thread.thread_id, `${threadPath}.thread_id`, { minLength: 1 });
+                expectString(errors, thread.checked_at, `${threadPath}.checked_at`, { minLength: 1 });
+              }
+            }
+          }
         }
       }
     }
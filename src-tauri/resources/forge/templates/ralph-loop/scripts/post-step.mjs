@@ -48,6 +48,7 @@ async function main() {
     templatePhases,
     progressNotes,
     todayIso: ctx.todayIso,
+    focusTaskId: ctx.taskId ?? null,
   });
 
   await fs.mkdir(path.dirname(ctx.generatedExecutePromptPath), { recursive: true });
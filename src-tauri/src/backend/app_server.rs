@@ -62,6 +62,8 @@ pub(crate) struct WorkspaceSession {
     pub(crate) next_id: AtomicU64,
     /// Callbacks for background threads - events for these threadIds are sent through the channel
     pub(crate) background_thread_callbacks: Mutex<HashMap<String, mpsc::UnboundedSender<Value>>>,
+    /// Observers for foreground threads - they get a copy of each event, which is still emitted
+    pub(crate) thread_observers: Mutex<HashMap<String, mpsc::UnboundedSender<Value>>>,
 }
 
 impl WorkspaceSession {
@@ -118,6 +120,13 @@ impl WorkspaceSession {
     }
 }
 
+async fn notify_thread_observer(session: &WorkspaceSession, thread_id: &str, value: &Value) {
+    let observers = session.thread_observers.lock().await;
+    if let Some(tx) = observers.get(thread_id) {
+        let _ = tx.send(value.clone());
+    }
+}
+
 pub(crate) fn build_codex_path_env(codex_bin: Option<&str>) -> Option<String> {
     let mut paths: Vec<PathBuf> = env::var_os("PATH")
         .map(|value| env::split_paths(&value).collect())
@@ -344,6 +353,7 @@ pub(crate) async fn spawn_workspace_session<E: EventSink>(
         pending: Mutex::new(HashMap::new()),
         next_id: AtomicU64::new(1),
         background_thread_callbacks: Mutex::new(HashMap::new()),
+        thread_observers: Mutex::new(HashMap::new()),
     });
 
     let session_clone = Arc::clone(&session);
@@ -386,6 +396,7 @@ pub(crate) async fn spawn_workspace_session<E: EventSink>(
                     // Check for background thread callback
                     let mut sent_to_background = false;
                     if let Some(ref tid) = thread_id {
+                        notify_thread_observer(&session_clone, tid, &value).await;
                         let callbacks = session_clone.background_thread_callbacks.lock().await;
                         if let Some(tx) = callbacks.get(tid) {
                             let _ = tx.send(value.clone());
@@ -407,6 +418,7 @@ pub(crate) async fn spawn_workspace_session<E: EventSink>(
                 // Check for background thread callback
                 let mut sent_to_background = false;
                 if let Some(ref tid) = thread_id {
+                    notify_thread_observer(&session_clone, tid, &value).await;
                     let callbacks = session_clone.background_thread_callbacks.lock().await;
                     if let Some(tx) = callbacks.get(tid) {
                         let _ = tx.send(value.clone());
@@ -24,8 +24,64 @@ pub(crate) struct TerminalExit {
     pub(crate) terminal_id: String,
 }
 
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeExecutionEvent {
+    pub(crate) workspace_id: String,
+    pub(crate) plan_id: String,
+    pub(crate) task_id: String,
+    pub(crate) phase_id: String,
+    #[serde(flatten)]
+    pub(crate) kind: ForgeExecutionEventKind,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum ForgeCheckOutputStream {
+    Stdout,
+    Stderr,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub(crate) enum ForgeExecutionEventKind {
+    PhaseStarted {
+        is_last_phase: bool,
+    },
+    CheckStarted {
+        check_id: String,
+        title: String,
+    },
+    CheckOutput {
+        check_id: String,
+        stream: ForgeCheckOutputStream,
+        data: String,
+    },
+    CheckFinished {
+        check_id: String,
+        title: String,
+        exit_code: i32,
+        duration_ms: i64,
+        timed_out: bool,
+    },
+    CommitCreated {
+        commit_sha: String,
+    },
+    /// A check run failed; the phase stays open for another attempt.
+    ChecksFailed {
+        reason: String,
+    },
+    TaskBlocked {
+        reason: String,
+    },
+    /// A `manual-approval` phase is waiting for a reviewer.
+    ApprovalRequested,
+    Cancelled,
+}
+
 pub(crate) trait EventSink: Clone + Send + Sync + 'static {
     fn emit_app_server_event(&self, event: AppServerEvent);
     fn emit_terminal_output(&self, event: TerminalOutput);
     fn emit_terminal_exit(&self, event: TerminalExit);
+    fn emit_forge_execution_event(&self, event: ForgeExecutionEvent);
 }
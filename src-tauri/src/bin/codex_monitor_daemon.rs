@@ -1,3 +1,5 @@
+#[path = "codex_monitor_daemon/access.rs"]
+mod access;
 #[allow(dead_code)]
 #[path = "../backend/mod.rs"]
 mod backend;
@@ -7,6 +9,8 @@ mod codex_args;
 mod codex_config;
 #[path = "../codex/home.rs"]
 mod codex_home;
+#[path = "codex_monitor_daemon/event_log.rs"]
+mod event_log;
 #[path = "../files/io.rs"]
 mod file_io;
 #[path = "../files/ops.rs"]
@@ -23,6 +27,10 @@ mod rules;
 mod shared;
 #[path = "../storage.rs"]
 mod storage;
+#[path = "codex_monitor_daemon/subscriptions.rs"]
+mod subscriptions;
+#[path = "codex_monitor_daemon/tls.rs"]
+mod tls;
 #[path = "codex_monitor_daemon/transport.rs"]
 mod transport;
 #[allow(dead_code)]
@@ -74,20 +82,20 @@ use std::time::Duration;
 use futures_util::{SinkExt, StreamExt};
 use ignore::WalkBuilder;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::net::{TcpListener, TcpStream};
+use tokio::net::TcpListener;
 use tokio::sync::{broadcast, mpsc, Mutex, Semaphore};
 use tokio::time::sleep;
 use tokio_tungstenite::connect_async;
 use tokio_tungstenite::tungstenite::Message;
 
 use backend::app_server::{spawn_workspace_session, WorkspaceSession};
-use backend::events::{AppServerEvent, EventSink, TerminalExit, TerminalOutput};
+use backend::events::{AppServerEvent, EventSink, ForgeExecutionEvent, TerminalExit, TerminalOutput};
 use shared::codex_core::CodexLoginCancelState;
 use shared::prompts_core::{self, CustomPromptEntry};
 use shared::{
     codex_aux_core, codex_core, files_core, forge_execute_core, forge_plans_core,
-    forge_templates_core, git_core, git_ui_core, local_usage_core, settings_core, workspaces_core,
-    worktree_core,
+    forge_template_sources_core, forge_templates_core, git_core, git_ui_core, local_usage_core, settings_core, terminal_core,
+    workspaces_core, worktree_core,
 };
 use storage::{read_settings, read_workspaces};
 use types::{
@@ -100,6 +108,7 @@ use workspace_settings::apply_workspace_settings_update;
 
 const DEFAULT_LISTEN_ADDR: &str = "127.0.0.1:4732";
 const MAX_IN_FLIGHT_RPC_PER_CONNECTION: usize = 32;
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
 const DAEMON_NAME: &str = "codex-monitor-daemon";
 
 fn spawn_with_client(
@@ -122,29 +131,53 @@ fn spawn_with_client(
 
 #[derive(Clone)]
 struct DaemonEventSink {
-    tx: broadcast::Sender<DaemonEvent>,
+    events: Arc<event_log::EventLog>,
 }
 
 #[derive(Clone)]
 enum DaemonEvent {
     AppServer(AppServerEvent),
-    #[allow(dead_code)]
     TerminalOutput(TerminalOutput),
-    #[allow(dead_code)]
     TerminalExit(TerminalExit),
+    ForgeExecution(ForgeExecutionEvent),
+}
+
+impl DaemonEvent {
+    /// The notification method the event is sent as, which is also its subscribable kind.
+    fn method(&self) -> &'static str {
+        match self {
+            Self::AppServer(_) => "app-server-event",
+            Self::TerminalOutput(_) => "terminal-output",
+            Self::TerminalExit(_) => "terminal-exit",
+            Self::ForgeExecution(_) => "forge-execution-event",
+        }
+    }
+
+    fn workspace_id(&self) -> &str {
+        match self {
+            Self::AppServer(event) => &event.workspace_id,
+            Self::TerminalOutput(event) => &event.workspace_id,
+            Self::TerminalExit(event) => &event.workspace_id,
+            Self::ForgeExecution(event) => &event.workspace_id,
+        }
+    }
 }
 
 impl EventSink for DaemonEventSink {
     fn emit_app_server_event(&self, event: AppServerEvent) {
-        let _ = self.tx.send(DaemonEvent::AppServer(event));
+        self.events.publish(DaemonEvent::AppServer(event));
     }
 
     fn emit_terminal_output(&self, event: TerminalOutput) {
-        let _ = self.tx.send(DaemonEvent::TerminalOutput(event));
+        self.events.publish(DaemonEvent::TerminalOutput(event));
     }
 
     fn emit_terminal_exit(&self, event: TerminalExit) {
-        let _ = self.tx.send(DaemonEvent::TerminalExit(event));
+        self.events.publish(DaemonEvent::TerminalExit(event));
+    }
+
+    fn emit_forge_execution_event(&self, event: ForgeExecutionEvent) {
+        self.events.publish(DaemonEvent::ForgeExecution(event));
     }
 }
 
@@ -167,6 +200,11 @@ struct DaemonState {
     app_settings: Mutex<AppSettings>,
     event_sink: DaemonEventSink,
     codex_login_cancels: Mutex<HashMap<String, CodexLoginCancelState>>,
+    forge_execution_cancels: forge_execute_core::ForgeExecutionCancels,
+    forge_runs: forge_execute_core::ForgeRuns,
+    terminal_sessions: terminal_core::TerminalSessions,
+    terminal_owners: rpc::TerminalOwners,
+    tokens: Mutex<access::TokenRegistry>,
     daemon_mode: String,
     daemon_binary_path: Option<String>,
 }
@@ -177,8 +215,36 @@ struct WorkspaceFileResponse {
     truncated: bool,
 }
 
+impl forge_execute_core::ForgeRunHost for Arc<DaemonState> {
+    type Sink = DaemonEventSink;
+
+    fn sessions(&self) -> &Mutex<HashMap<String, Arc<WorkspaceSession>>> {
+        &self.sessions
+    }
+
+    fn workspaces(&self) -> &Mutex<HashMap<String, WorkspaceEntry>> {
+        &self.workspaces
+    }
+
+    fn forge_execution_cancels(&self) -> &forge_execute_core::ForgeExecutionCancels {
+        &self.forge_execution_cancels
+    }
+
+    fn forge_runs(&self) -> &forge_execute_core::ForgeRuns {
+        &self.forge_runs
+    }
+
+    fn event_sink(&self) -> DaemonEventSink {
+        self.event_sink.clone()
+    }
+}
+
 impl DaemonState {
-    fn load(config: &DaemonConfig, event_sink: DaemonEventSink) -> Self {
+    fn load(
+        config: &DaemonConfig,
+        event_sink: DaemonEventSink,
+        tokens: access::TokenRegistry,
+    ) -> Self {
         let storage_path = config.data_dir.join("workspaces.json");
         let settings_path = config.data_dir.join("settings.json");
         let workspaces = read_workspaces(&storage_path).unwrap_or_default();
@@ -200,6 +266,11 @@ impl DaemonState {
             app_settings: Mutex::new(app_settings),
             event_sink,
             codex_login_cancels: Mutex::new(HashMap::new()),
+            forge_execution_cancels: forge_execute_core::ForgeExecutionCancels::default(),
+            forge_runs: forge_execute_core::ForgeRuns::default(),
+            terminal_sessions: terminal_core::TerminalSessions::default(),
+            terminal_owners: rpc::TerminalOwners::default(),
+            tokens: Mutex::new(tokens),
             daemon_mode,
             daemon_binary_path,
         }
@@ -659,7 +730,41 @@ impl DaemonState {
         &self,
     ) -> Result<Vec<forge_templates_core::ForgeBundledTemplateInfo>, String> {
         let root = self.bundled_templates_root_for_daemon()?;
-        forge_templates_core::list_bundled_templates_core(&root)
+        let data_dir = self.data_dir.clone();
+        tokio::task::spawn_blocking(move || {
+            forge_template_sources_core::list_all_templates_core(&root, &data_dir)
+        })
+        .await
+        .map_err(|err| err.to_string())?
+    }
+
+    async fn forge_list_template_sources(
+        &self,
+    ) -> Result<Vec<forge_template_sources_core::ForgeTemplateSourceV1>, String> {
+        forge_template_sources_core::list_template_sources_core(&self.data_dir)
+    }
+
+    async fn forge_add_template_source(
+        &self,
+        kind: forge_templates_core::ForgeTemplateSourceKind,
+        location: String,
+        git_ref: Option<String>,
+    ) -> Result<forge_template_sources_core::ForgeTemplateSourceV1, String> {
+        let data_dir = self.data_dir.clone();
+        tokio::task::spawn_blocking(move || {
+            forge_template_sources_core::add_template_source_core(
+                &data_dir,
+                kind,
+                &location,
+                git_ref.as_deref(),
+            )
+        })
+        .await
+        .map_err(|err| err.to_string())?
+    }
+
+    async fn forge_remove_template_source(&self, source_id: String) -> Result<(), String> {
+        forge_template_sources_core::remove_template_source_core(&self.data_dir, &source_id)
     }
 
     async fn forge_get_installed_template(
@@ -679,14 +784,23 @@ impl DaemonState {
         &self,
         workspace_id: String,
         template_id: String,
+        source_id: Option<String>,
     ) -> Result<forge_templates_core::ForgeTemplateLockV1, String> {
         let templates_root = self.bundled_templates_root_for_daemon()?;
+        let data_dir = self.data_dir.clone();
         let workspace_root = self.workspace_root_for_id(&workspace_id).await?;
-        let lock = forge_templates_core::install_bundled_template_core(
-            &templates_root,
-            &workspace_root,
-            template_id.trim(),
-        )?;
+        let install_root = workspace_root.clone();
+        let lock = tokio::task::spawn_blocking(move || {
+            forge_template_sources_core::install_template_from_source_core(
+                &templates_root,
+                &data_dir,
+                &install_root,
+                source_id.as_deref(),
+                template_id.trim(),
+            )
+        })
+        .await
+        .map_err(|err| err.to_string())??;
         if let Err(err) =
             forge_templates_core::sync_agent_skills_into_repo_agents_dir_core(&workspace_root)
         {
@@ -695,6 +809,51 @@ impl DaemonState {
         Ok(lock)
     }
 
+    async fn forge_template_upgrade_preview(
+        &self,
+        workspace_id: String,
+    ) -> Result<forge_templates_core::ForgeTemplateUpgradePreviewV1, String> {
+        let templates_root = self.bundled_templates_root_for_daemon()?;
+        let data_dir = self.data_dir.clone();
+        let workspace_root = self.workspace_root_for_id(&workspace_id).await?;
+        tokio::task::spawn_blocking(move || {
+            let upstream_dir = forge_template_sources_core::installed_template_upstream_dir_core(
+                &templates_root,
+                &data_dir,
+                &workspace_root,
+            )?;
+            forge_templates_core::forge_template_upgrade_preview_core(&workspace_root, &upstream_dir)
+        })
+        .await
+        .map_err(|err| err.to_string())?
+    }
+
+    async fn forge_upgrade_template(
+        &self,
+        workspace_id: String,
+    ) -> Result<forge_templates_core::ForgeTemplateUpgradeResultV1, String> {
+        let templates_root = self.bundled_templates_root_for_daemon()?;
+        let data_dir = self.data_dir.clone();
+        let workspace_root = self.workspace_root_for_id(&workspace_id).await?;
+        let upgrade_root = workspace_root.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let upstream_dir = forge_template_sources_core::installed_template_upstream_dir_core(
+                &templates_root,
+                &data_dir,
+                &upgrade_root,
+            )?;
+            forge_templates_core::forge_upgrade_template_core(&upgrade_root, &upstream_dir)
+        })
+        .await
+        .map_err(|err| err.to_string())??;
+        if let Err(err) =
+            forge_templates_core::sync_agent_skills_into_repo_agents_dir_core(&workspace_root)
+        {
+            eprintln!("forge_upgrade_template: failed to sync skills into .agents: {err}");
+        }
+        Ok(result)
+    }
+
     async fn forge_uninstall_template(&self, workspace_id: String) -> Result<(), String> {
         let workspace_root = self.workspace_root_for_id(&workspace_id).await?;
         forge_templates_core::uninstall_template_core(&workspace_root)
@@ -708,6 +867,15 @@ impl DaemonState {
         forge_plans_core::list_plans_core(&workspace_root)
     }
 
+    async fn forge_validate_plan(
+        &self,
+        workspace_id: String,
+        plan_id: String,
+    ) -> Result<forge_plans_core::ForgePlanValidationV1, String> {
+        let workspace_root = self.workspace_root_for_id(&workspace_id).await?;
+        forge_plans_core::forge_validate_plan_core(&workspace_root, &plan_id)
+    }
+
     async fn forge_get_plan_prompt(&self, workspace_id: String) -> Result<String, String> {
         let workspace_root = self.workspace_root_for_id(&workspace_id).await?;
         if let Err(err) =
@@ -724,7 +892,13 @@ impl DaemonState {
         plan_id: String,
     ) -> Result<(), String> {
         let workspace_root = self.workspace_root_for_id(&workspace_id).await?;
-        forge_execute_core::forge_prepare_execution_core(&workspace_root, &plan_id).await
+        forge_execute_core::forge_prepare_execution_core(
+            &workspace_root,
+            &plan_id,
+            &workspace_id,
+            &self.forge_execution_cancels,
+        )
+        .await
     }
 
     async fn forge_reset_execution_progress(
@@ -742,7 +916,15 @@ impl DaemonState {
         plan_id: String,
     ) -> Result<Option<forge_execute_core::ForgeNextPhasePromptV1>, String> {
         let workspace_root = self.workspace_root_for_id(&workspace_id).await?;
-        forge_execute_core::forge_get_next_phase_prompt_core(&workspace_root, &plan_id).await
+        forge_execute_core::forge_get_next_phase_prompt_core(
+            &self.workspaces,
+            &workspace_root,
+            &plan_id,
+            &workspace_id,
+            &self.event_sink,
+            &self.forge_execution_cancels,
+        )
+        .await
     }
 
     async fn forge_get_phase_status(
@@ -762,9 +944,260 @@ impl DaemonState {
         plan_id: String,
         task_id: String,
         phase_id: String,
+        thread_id: Option<String>,
     ) -> Result<forge_execute_core::ForgeRunPhaseChecksResponseV1, String> {
         let workspace_root = self.workspace_root_for_id(&workspace_id).await?;
-        forge_execute_core::forge_run_phase_checks_core(&workspace_root, &plan_id, &task_id, &phase_id).await
+        forge_execute_core::forge_run_phase_checks_core(
+            &workspace_root,
+            forge_execute_core::ForgeRunPhaseChecksRequest {
+                plan_id: &plan_id,
+                task_id: &task_id,
+                phase_id: &phase_id,
+                thread_id: thread_id.as_deref(),
+            },
+            &workspace_id,
+            &self.event_sink,
+            &self.forge_execution_cancels,
+        )
+        .await
+    }
+
+    async fn forge_get_run_history(
+        &self,
+        workspace_id: String,
+        plan_id: String,
+        task_id: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<forge_execute_core::ForgeRunHistoryV1, String> {
+        let workspace_root = self.workspace_root_for_id(&workspace_id).await?;
+        forge_execute_core::forge_get_run_history_core(
+            &workspace_root,
+            &plan_id,
+            task_id.as_deref(),
+            limit.map(|value| value as usize),
+        )
+    }
+
+    async fn forge_rollback_task(
+        &self,
+        workspace_id: String,
+        plan_id: String,
+        task_id: String,
+        cascade: bool,
+    ) -> Result<forge_execute_core::ForgeTaskRollbackResultV1, String> {
+        let workspace_root = self.workspace_root_for_id(&workspace_id).await?;
+        forge_execute_core::forge_rollback_task_core(&workspace_root, &plan_id, &task_id, cascade)
+            .await
+    }
+
+    async fn forge_analyze_plan(
+        &self,
+        workspace_id: String,
+        plan_id: String,
+    ) -> Result<forge_execute_core::ForgePlanAnalysisV1, String> {
+        let workspace_root = self.workspace_root_for_id(&workspace_id).await?;
+        forge_execute_core::forge_analyze_plan_core(&workspace_root, &plan_id)
+    }
+
+    async fn forge_plan_cost(
+        &self,
+        workspace_id: String,
+        plan_id: String,
+    ) -> Result<forge_execute_core::ForgePlanCostV1, String> {
+        let workspace_root = self.workspace_root_for_id(&workspace_id).await?;
+        forge_execute_core::forge_plan_cost_core(&self.workspaces, &workspace_root, &plan_id).await
+    }
+
+    async fn forge_update_plan(
+        &self,
+        workspace_id: String,
+        plan_id: String,
+        operations: Vec<forge_execute_core::ForgePlanOperationV1>,
+    ) -> Result<forge_execute_core::ForgePlanUpdateResultV1, String> {
+        let workspace_root = self.workspace_root_for_id(&workspace_id).await?;
+        forge_execute_core::forge_update_plan_core(&workspace_root, &plan_id, operations).await
+    }
+
+    async fn forge_approve_phase(
+        &self,
+        workspace_id: String,
+        plan_id: String,
+        task_id: String,
+        phase_id: String,
+        reviewer: String,
+        notes: Option<String>,
+    ) -> Result<forge_execute_core::ForgePhaseReviewResultV1, String> {
+        let workspace_root = self.workspace_root_for_id(&workspace_id).await?;
+        forge_execute_core::forge_approve_phase_core(
+            &workspace_root,
+            forge_execute_core::ForgePhaseReviewRequest {
+                plan_id: &plan_id,
+                task_id: &task_id,
+                phase_id: &phase_id,
+                reviewer: &reviewer,
+                notes: notes.as_deref(),
+            },
+            &workspace_id,
+            &self.event_sink,
+            &self.forge_execution_cancels,
+        )
+        .await
+    }
+
+    async fn forge_reject_phase(
+        &self,
+        workspace_id: String,
+        plan_id: String,
+        task_id: String,
+        phase_id: String,
+        reviewer: String,
+        notes: String,
+    ) -> Result<forge_execute_core::ForgePhaseReviewResultV1, String> {
+        let workspace_root = self.workspace_root_for_id(&workspace_id).await?;
+        forge_execute_core::forge_reject_phase_core(
+            &workspace_root,
+            forge_execute_core::ForgePhaseReviewRequest {
+                plan_id: &plan_id,
+                task_id: &task_id,
+                phase_id: &phase_id,
+                reviewer: &reviewer,
+                notes: Some(&notes),
+            },
+        )
+        .await
+    }
+
+    async fn forge_start_run(
+        self: Arc<Self>,
+        workspace_id: String,
+        plan_id: String,
+        options: forge_execute_core::ForgeStartRunOptionsV1,
+    ) -> Result<forge_execute_core::ForgeRunStatusV1, String> {
+        let workspace_root = self.workspace_root_for_id(&workspace_id).await?;
+        forge_execute_core::forge_start_run_core(
+            self,
+            &workspace_id,
+            workspace_root,
+            &plan_id,
+            options,
+        )
+        .await
+    }
+
+    fn forge_stop_run(
+        &self,
+        workspace_id: String,
+        plan_id: String,
+    ) -> Result<forge_execute_core::ForgeRunStatusV1, String> {
+        forge_execute_core::forge_stop_run_core(
+            &self.forge_runs,
+            &self.forge_execution_cancels,
+            &workspace_id,
+            &plan_id,
+        )
+    }
+
+    fn forge_run_status(
+        &self,
+        workspace_id: String,
+        plan_id: String,
+    ) -> Result<Option<forge_execute_core::ForgeRunStatusV1>, String> {
+        forge_execute_core::forge_run_status_core(&self.forge_runs, &workspace_id, &plan_id)
+    }
+
+    fn forge_cancel_execution(
+        &self,
+        workspace_id: String,
+        plan_id: String,
+    ) -> Result<forge_execute_core::ForgeCancelExecutionResultV1, String> {
+        forge_execute_core::forge_cancel_execution_core(
+            &self.forge_execution_cancels,
+            &workspace_id,
+            &plan_id,
+        )
+    }
+
+    async fn forge_get_runnable_tasks(
+        &self,
+        workspace_id: String,
+        plan_id: String,
+    ) -> Result<Vec<forge_execute_core::ForgeRunnableTaskV1>, String> {
+        let workspace_root = self.workspace_root_for_id(&workspace_id).await?;
+        forge_execute_core::forge_get_runnable_tasks_core(&workspace_root, &plan_id)
+    }
+
+    async fn forge_get_task_phase_prompt(
+        &self,
+        workspace_id: String,
+        plan_id: String,
+        task_id: String,
+    ) -> Result<Option<forge_execute_core::ForgeNextPhasePromptV1>, String> {
+        let workspace_root = self.workspace_root_for_id(&workspace_id).await?;
+        forge_execute_core::forge_get_task_phase_prompt_core(
+            &self.workspaces,
+            &workspace_root,
+            &plan_id,
+            &task_id,
+            &workspace_id,
+            &self.event_sink,
+        )
+        .await
+    }
+
+    async fn forge_start_task_worktree(
+        &self,
+        workspace_id: String,
+        plan_id: String,
+        task_id: String,
+        client_version: String,
+    ) -> Result<forge_execute_core::ForgeTaskWorktreeV1, String> {
+        let workspace_root = self.workspace_root_for_id(&workspace_id).await?;
+        let branch = forge_execute_core::forge_task_worktree_branch(&plan_id, &task_id)?;
+        forge_execute_core::forge_check_task_worktree_core(&workspace_root, &plan_id, &task_id)?;
+        let worktree = self
+            .add_worktree(
+                workspace_id,
+                branch.clone(),
+                Some(format!("{} {}", plan_id.trim(), task_id.trim())),
+                true,
+                client_version,
+            )
+            .await?;
+        let attached = forge_execute_core::forge_attach_task_worktree_core(
+            &workspace_root,
+            &plan_id,
+            &task_id,
+            &worktree.id,
+            &worktree.path,
+            &branch,
+        )
+        .await;
+        if attached.is_err() {
+            if let Err(err) = self.remove_worktree(worktree.id).await {
+                eprintln!("forge_start_task_worktree: failed to remove task worktree: {err}");
+            }
+        }
+        attached
+    }
+
+    async fn forge_merge_task_worktree(
+        &self,
+        workspace_id: String,
+        plan_id: String,
+        task_id: String,
+    ) -> Result<forge_execute_core::ForgeTaskMergeResultV1, String> {
+        let workspace_root = self.workspace_root_for_id(&workspace_id).await?;
+        let result =
+            forge_execute_core::forge_merge_task_worktree_core(&workspace_root, &plan_id, &task_id)
+                .await?;
+        if result.ok {
+            if let Some(worktree_id) = result.worktree_workspace_id.clone() {
+                if let Err(err) = self.remove_worktree(worktree_id).await {
+                    eprintln!("forge_merge_task_worktree: failed to remove task worktree: {err}");
+                }
+            }
+        }
+        Ok(result)
     }
 
     async fn start_thread(&self, workspace_id: String) -> Result<Value, String> {
@@ -1241,6 +1674,60 @@ impl DaemonState {
         .await
     }
 
+    async fn terminal_open(
+        &self,
+        workspace_id: String,
+        terminal_id: String,
+        cols: u16,
+        rows: u16,
+    ) -> Result<terminal_core::TerminalSessionInfo, String> {
+        terminal_core::terminal_open_core(
+            &self.terminal_sessions,
+            &self.workspaces,
+            workspace_id,
+            terminal_id,
+            cols,
+            rows,
+            self.event_sink.clone(),
+        )
+        .await
+    }
+
+    async fn terminal_write(
+        &self,
+        workspace_id: String,
+        terminal_id: String,
+        data: String,
+    ) -> Result<(), String> {
+        terminal_core::terminal_write_core(&self.terminal_sessions, workspace_id, terminal_id, data)
+            .await
+    }
+
+    async fn terminal_resize(
+        &self,
+        workspace_id: String,
+        terminal_id: String,
+        cols: u16,
+        rows: u16,
+    ) -> Result<(), String> {
+        terminal_core::terminal_resize_core(
+            &self.terminal_sessions,
+            workspace_id,
+            terminal_id,
+            cols,
+            rows,
+        )
+        .await
+    }
+
+    async fn terminal_close(
+        &self,
+        workspace_id: String,
+        terminal_id: String,
+    ) -> Result<(), String> {
+        terminal_core::terminal_close_core(&self.terminal_sessions, workspace_id, terminal_id).await
+    }
+
     async fn codex_doctor(
         &self,
         codex_bin: Option<String>,
@@ -1466,7 +1953,7 @@ fn usage() -> String {
     format!(
         "\
 USAGE:\n  codex-monitor-daemon [--listen <addr>] [--data-dir <path>] [--token <token> | --insecure-no-auth]\n  codex-monitor-daemon --orbit-url <ws-url> [--orbit-token <token>] [--orbit-auth-url <url>] [--orbit-runner-name <name>] [--data-dir <path>]\n\n\
-OPTIONS:\n  --listen <addr>          Bind address (default: {DEFAULT_LISTEN_ADDR})\n  --data-dir <path>        Data dir holding workspaces.json/settings.json\n  --token <token>          Shared token required by TCP clients\n  --insecure-no-auth       Disable TCP auth (dev only)\n  --orbit-url <ws-url>     Run in Orbit runner mode and connect outbound to this WS URL\n  --orbit-token <token>    Orbit auth token (optional if URL already includes token)\n  --orbit-auth-url <url>   Orbit auth base URL (metadata only, optional)\n  --orbit-runner-name <n>  Runner display name (metadata only, optional)\n  -h, --help               Show this help\n"
+OPTIONS:\n  --listen <addr>          TLS bind address (default: {DEFAULT_LISTEN_ADDR})\n  --data-dir <path>        Data dir holding workspaces.json/settings.json, tokens.json and tls/\n  --token <token>          Shared admin token required by TCP clients\n  --insecure-no-auth       Disable TCP auth (dev only)\n  --orbit-url <ws-url>     Run in Orbit runner mode and connect outbound to this WS URL\n  --orbit-token <token>    Orbit auth token (optional if URL already includes token)\n  --orbit-auth-url <url>   Orbit auth base URL (metadata only, optional)\n  --orbit-runner-name <n>  Runner display name (metadata only, optional)\n  -h, --help               Show this help\n"
     )
 }
 
@@ -1613,19 +2100,37 @@ mod tests {
         dir
     }
 
-    fn test_state(data_dir: &std::path::Path) -> DaemonState {
-        let (tx, _rx) = broadcast::channel::<DaemonEvent>(32);
-        DaemonState {
+    fn test_state(data_dir: &std::path::Path) -> Arc<DaemonState> {
+        Arc::new(DaemonState {
             data_dir: data_dir.to_path_buf(),
             workspaces: Mutex::new(HashMap::new()),
             sessions: Mutex::new(HashMap::new()),
             storage_path: data_dir.join("workspaces.json"),
             settings_path: data_dir.join("settings.json"),
             app_settings: Mutex::new(AppSettings::default()),
-            event_sink: DaemonEventSink { tx },
+            event_sink: DaemonEventSink {
+                events: Arc::new(event_log::EventLog::new()),
+            },
             codex_login_cancels: Mutex::new(HashMap::new()),
+            forge_execution_cancels: forge_execute_core::ForgeExecutionCancels::default(),
+            forge_runs: forge_execute_core::ForgeRuns::default(),
+            terminal_sessions: terminal_core::TerminalSessions::default(),
+            terminal_owners: rpc::TerminalOwners::default(),
+            tokens: Mutex::new(access::TokenRegistry::load(data_dir).expect("token registry")),
             daemon_mode: "tcp".to_string(),
             daemon_binary_path: Some("/tmp/codex-monitor-daemon".to_string()),
+        })
+    }
+
+    fn test_config(data_dir: &std::path::Path, token: Option<&str>) -> DaemonConfig {
+        DaemonConfig {
+            listen: DEFAULT_LISTEN_ADDR.parse().expect("listen addr"),
+            token: token.map(str::to_string),
+            data_dir: data_dir.to_path_buf(),
+            orbit_url: None,
+            orbit_token: None,
+            orbit_auth_url: None,
+            orbit_runner_name: None,
         }
     }
 
@@ -1815,6 +2320,456 @@ mod tests {
             let _ = std::fs::remove_dir_all(&tmp);
         });
     }
+    #[test]
+    fn tls_certificate_is_created_once_and_kept_across_restarts() {
+        let tmp = make_temp_dir("tls-identity");
+
+        let first = tls::load_or_create_daemon_tls(&tmp).expect("create tls identity");
+        let second = tls::load_or_create_daemon_tls(&tmp).expect("reload tls identity");
+        assert_eq!(first.fingerprint, second.fingerprint);
+        assert!(tmp.join("tls").join("daemon-cert.pem").is_file());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let key_mode = std::fs::metadata(tmp.join("tls").join("daemon-key.pem"))
+                .expect("key metadata")
+                .permissions()
+                .mode();
+            assert_eq!(key_mode & 0o777, 0o600);
+        }
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn event_log_replays_missed_events_and_reports_gaps() {
+        let log = event_log::EventLog::with_capacity(3);
+        let publish = |count: usize| {
+            for _ in 0..count {
+                log.publish(DaemonEvent::AppServer(AppServerEvent {
+                    workspace_id: "ws-1".to_string(),
+                    message: json!({}),
+                }));
+            }
+        };
+        let resume = |stream_id: &str, since_seq: u64| {
+            event_log::parse_event_resume(&json!({ "streamId": stream_id, "sinceSeq": since_seq }))
+        };
+        let seqs = |replay: &event_log::Replay| {
+            replay
+                .events
+                .iter()
+                .map(|event| event.seq)
+                .collect::<Vec<_>>()
+        };
+
+        publish(2);
+        let (live, _rx) = log.subscribe(&event_log::EventResume::default());
+        assert!(live.events.is_empty() && live.gap.is_none());
+        assert_eq!(live.last_seq, 2);
+
+        publish(3);
+        let stream_id = log.stream_id().to_string();
+        let (caught_up, _rx) = log.subscribe(&resume(&stream_id, 3));
+        assert_eq!(seqs(&caught_up), vec![4, 5]);
+        assert!(caught_up.gap.is_none());
+
+        let (overflowed, _rx) = log.subscribe(&resume(&stream_id, 1));
+        assert_eq!(seqs(&overflowed), vec![3, 4, 5]);
+        assert_eq!(
+            overflowed.gap.map(|gap| (gap.reason, gap.lost)),
+            Some(("overflow", Some((2, 2))))
+        );
+
+        let (restarted, _rx) = log.subscribe(&resume("previous-daemon", 4));
+        assert_eq!(seqs(&restarted), vec![3, 4, 5]);
+        assert_eq!(
+            restarted.gap.map(|gap| (gap.reason, gap.lost)),
+            Some(("restarted", Some((1, 2))))
+        );
+    }
+
+    #[test]
+    fn scoped_tokens_are_limited_by_role_and_revocable() {
+        run_async_test(async {
+            let tmp = make_temp_dir("scoped-token-roles");
+            let state = test_state(&tmp);
+            let config = test_config(&tmp, Some("shared-secret"));
+
+            let minted = rpc::handle_rpc_request(
+                &state,
+                "daemon_token_mint",
+                json!({ "role": "read-only", "label": "observer" }),
+                "daemon-test".to_string(),
+            )
+            .await
+            .expect("daemon_token_mint should succeed");
+            let secret = minted["token"].as_str().expect("token").to_string();
+            let token_id = minted["id"].as_str().expect("id").to_string();
+
+            let reloaded = access::TokenRegistry::load(&tmp).expect("reload registry");
+            assert!(reloaded.find_by_secret(&secret).is_some());
+            let stored = std::fs::read_to_string(tmp.join("tokens.json")).expect("tokens.json");
+            assert!(
+                !stored.contains(&secret),
+                "plaintext token must not be stored"
+            );
+
+            assert!(access::authenticate(&config, &state, "wrong")
+                .await
+                .is_none());
+            let observer = access::authenticate(&config, &state, &secret)
+                .await
+                .expect("minted token should authenticate");
+            let params = json!({ "workspaceId": "ws-1" });
+            access::authorize_request(&state, &observer, "get_git_status", &params)
+                .await
+                .expect("read-only token can read git status");
+            for method in [
+                "push_git",
+                "connect_workspace",
+                "forge_get_plan_prompt",
+                "terminal_open",
+                "daemon_shutdown",
+                "daemon_token_mint",
+            ] {
+                assert!(
+                    access::authorize_request(&state, &observer, method, &params)
+                        .await
+                        .is_err(),
+                    "read-only token must not call {method}"
+                );
+            }
+
+            rpc::handle_rpc_request(
+                &state,
+                "daemon_token_revoke",
+                json!({ "id": token_id }),
+                "daemon-test".to_string(),
+            )
+            .await
+            .expect("daemon_token_revoke should succeed");
+            let err = access::authorize_request(&state, &observer, "get_git_status", &params)
+                .await
+                .expect_err("revoked token is rejected");
+            assert_eq!(err, "token revoked");
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    #[test]
+    fn workspace_limited_tokens_only_reach_their_workspaces() {
+        run_async_test(async {
+            let tmp = make_temp_dir("scoped-token-workspaces");
+            let state = test_state(&tmp);
+            insert_workspace(&state, "ws-allowed", "/tmp/ws-allowed").await;
+            insert_workspace(&state, "ws-other", "/tmp/ws-other").await;
+            {
+                let mut workspaces = state.workspaces.lock().await;
+                let mut worktree = workspaces["ws-allowed"].clone();
+                worktree.id = "wt-allowed".to_string();
+                worktree.parent_id = Some("ws-allowed".to_string());
+                workspaces.insert(worktree.id.clone(), worktree);
+            }
+
+            let minted = state
+                .tokens
+                .lock()
+                .await
+                .mint(
+                    access::TokenRole::Operator,
+                    Some(vec!["ws-allowed".to_string()]),
+                    None,
+                )
+                .expect("mint scoped token");
+            let secret = minted["token"].as_str().expect("token");
+            let config = test_config(&tmp, None);
+            let scoped = access::authenticate(&config, &state, secret)
+                .await
+                .expect("scoped token should authenticate");
+
+            for workspace_id in ["ws-allowed", "wt-allowed"] {
+                access::authorize_request(
+                    &state,
+                    &scoped,
+                    "push_git",
+                    &json!({ "workspaceId": workspace_id }),
+                )
+                .await
+                .expect("allowed workspace and its worktrees are reachable");
+            }
+            assert!(access::authorize_request(
+                &state,
+                &scoped,
+                "push_git",
+                &json!({ "workspaceId": "ws-other" }),
+            )
+            .await
+            .is_err());
+            assert!(access::authorize_request(
+                &state,
+                &scoped,
+                "file_read",
+                &json!({ "scope": "global", "kind": "agents" }),
+            )
+            .await
+            .is_err());
+
+            let listed = json!([
+                { "id": "ws-allowed" },
+                { "id": "wt-allowed", "parentId": "ws-allowed" },
+                { "id": "ws-other" },
+            ]);
+            let visible = access::filter_response(&state, &scoped, "list_workspaces", listed).await;
+            let ids: Vec<&str> = visible
+                .as_array()
+                .expect("array result")
+                .iter()
+                .filter_map(|entry| entry["id"].as_str())
+                .collect();
+            assert_eq!(ids, vec!["ws-allowed", "wt-allowed"]);
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    #[test]
+    fn rpc_terminal_methods_validate_params_and_sessions() {
+        run_async_test(async {
+            let tmp = make_temp_dir("rpc-terminal");
+            let state = test_state(&tmp);
+            insert_workspace(&state, "ws-1", &tmp.to_string_lossy()).await;
+
+            let err = rpc::handle_rpc_request(
+                &state,
+                "terminal_open",
+                json!({ "workspaceId": "ws-1", "terminalId": "term-1", "cols": 80 }),
+                "daemon-test".to_string(),
+            )
+            .await
+            .expect_err("rows are required");
+            assert_eq!(err, "missing or invalid `rows`");
+
+            let err = rpc::handle_rpc_request(
+                &state,
+                "terminal_open",
+                json!({ "workspaceId": "missing", "terminalId": "term-1", "cols": 80, "rows": 24 }),
+                "daemon-test".to_string(),
+            )
+            .await
+            .expect_err("unknown workspace should fail");
+            assert_eq!(err, "Unknown workspace");
+
+            for (method, params) in [
+                (
+                    "terminal_write",
+                    json!({ "workspaceId": "ws-1", "terminalId": "term-1", "data": "ls\n" }),
+                ),
+                (
+                    "terminal_resize",
+                    json!({ "workspaceId": "ws-1", "terminalId": "term-1", "cols": 80, "rows": 24 }),
+                ),
+                (
+                    "terminal_close",
+                    json!({ "workspaceId": "ws-1", "terminalId": "term-1" }),
+                ),
+            ] {
+                let err =
+                    rpc::handle_rpc_request(&state, method, params, "daemon-test".to_string())
+                        .await
+                        .expect_err("no terminal is open");
+                assert_eq!(err, "Terminal session not found", "{method}");
+            }
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    /// Connects a client without a token and opens `term-1` of `ws-1` over it.
+    async fn open_terminal_on_new_connection(
+        state: &Arc<DaemonState>,
+        config: &Arc<DaemonConfig>,
+    ) -> (tokio::task::JoinHandle<()>, tokio::io::DuplexStream) {
+        let (mut client, server) = tokio::io::duplex(64 * 1024);
+        let connection = tokio::spawn(transport::handle_client(
+            server,
+            Arc::clone(config),
+            Arc::clone(state),
+            Arc::new(event_log::EventLog::new()),
+        ));
+        let open = json!({
+            "id": 1,
+            "method": "terminal_open",
+            "params": { "workspaceId": "ws-1", "terminalId": "term-1", "cols": 80, "rows": 24 },
+        });
+        client
+            .write_all(format!("{open}\n").as_bytes())
+            .await
+            .expect("send terminal_open");
+        let mut lines = BufReader::new(&mut client).lines();
+        loop {
+            let line = tokio::time::timeout(Duration::from_secs(5), lines.next_line())
+                .await
+                .expect("response in time")
+                .expect("read line")
+                .expect("connection open");
+            let message: Value = serde_json::from_str(&line).expect("json line");
+            if message.get("id") == Some(&json!(1)) {
+                assert_eq!(message["result"]["id"], json!("term-1"));
+                break;
+            }
+        }
+        (connection, client)
+    }
+
+    async fn disconnect(connection: tokio::task::JoinHandle<()>, client: tokio::io::DuplexStream) {
+        drop(client);
+        tokio::time::timeout(Duration::from_secs(5), connection)
+            .await
+            .expect("connection closes in time")
+            .expect("connection task");
+    }
+
+    #[test]
+    fn shared_terminal_closes_with_its_last_connection() {
+        run_async_test(async {
+            let tmp = make_temp_dir("connection-terminals");
+            let state = test_state(&tmp);
+            let workspace_path = tmp.to_string_lossy().to_string();
+            insert_workspace(&state, "ws-1", &workspace_path).await;
+            let config = Arc::new(test_config(&tmp, None));
+            let resize = || {
+                rpc::handle_rpc_request(
+                    &state,
+                    "terminal_resize",
+                    json!({ "workspaceId": "ws-1", "terminalId": "term-1", "cols": 100, "rows": 30 }),
+                    "daemon-test".to_string(),
+                )
+            };
+
+            // The second open is e.g. the same app after reconnecting, and gets the same shell.
+            let (first, first_client) = open_terminal_on_new_connection(&state, &config).await;
+            let (second, second_client) = open_terminal_on_new_connection(&state, &config).await;
+
+            disconnect(first, first_client).await;
+            resize()
+                .await
+                .expect("the terminal stays open for the other connection");
+
+            disconnect(second, second_client).await;
+            let err = resize()
+                .await
+                .expect_err("the last connection closed the terminal");
+            assert_eq!(err, "Terminal session not found");
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    #[test]
+    fn event_subscriptions_narrow_events_by_workspace_and_kind() {
+        let app_server = |workspace_id: &str| {
+            DaemonEvent::AppServer(AppServerEvent {
+                workspace_id: workspace_id.to_string(),
+                message: json!({ "method": "turn/started" }),
+            })
+        };
+        let terminal = DaemonEvent::TerminalOutput(TerminalOutput {
+            workspace_id: "ws-1".to_string(),
+            terminal_id: "term-1".to_string(),
+            data: "ls\n".to_string(),
+        });
+        let subscriptions = subscriptions::EventSubscriptions::default();
+        assert!(subscriptions.matches(&app_server("ws-2")));
+
+        let subscribed = subscriptions
+            .try_handle("subscribe", &json!({ "workspaceIds": ["ws-1"] }))
+            .expect("subscribe is handled")
+            .expect("subscribe succeeds");
+        assert_eq!(
+            subscribed,
+            json!({ "workspaceIds": ["ws-1"], "kinds": null })
+        );
+        assert!(subscriptions.matches(&app_server("ws-1")));
+        assert!(!subscriptions.matches(&app_server("ws-2")));
+        assert!(subscriptions.matches(&terminal));
+
+        subscriptions
+            .try_handle("subscribe", &json!({ "kinds": ["app-server-event"] }))
+            .expect("subscribe is handled")
+            .expect("subscribe succeeds");
+        assert!(subscriptions.matches(&app_server("ws-1")));
+        assert!(!subscriptions.matches(&terminal));
+
+        subscriptions
+            .try_handle("unsubscribe", &json!({ "workspaceIds": ["ws-1"] }))
+            .expect("unsubscribe is handled")
+            .expect("unsubscribe succeeds");
+        assert!(!subscriptions.matches(&app_server("ws-1")));
+
+        let unknown_kind = subscriptions
+            .try_handle("subscribe", &json!({ "kinds": ["thread-started"] }))
+            .expect("subscribe is handled");
+        assert_eq!(
+            unknown_kind,
+            Err("unknown event kind: thread-started".to_string())
+        );
+        assert!(subscriptions.try_handle("ping", &json!({})).is_none());
+    }
+
+    #[test]
+    fn auth_subscription_filters_the_replayed_events() {
+        run_async_test(async {
+            let tmp = make_temp_dir("auth-subscription-replay");
+            let state = test_state(&tmp);
+            let config = Arc::new(test_config(&tmp, Some("shared-secret")));
+            let events = Arc::new(event_log::EventLog::new());
+            for workspace_id in ["ws-1", "ws-2", "ws-1"] {
+                events.publish(DaemonEvent::AppServer(AppServerEvent {
+                    workspace_id: workspace_id.to_string(),
+                    message: json!({}),
+                }));
+            }
+
+            let (client, server) = tokio::io::duplex(64 * 1024);
+            tokio::spawn(transport::handle_client(
+                server,
+                config,
+                state,
+                Arc::clone(&events),
+            ));
+            let (reader, mut writer) = tokio::io::split(client);
+            let auth = json!({
+                "id": 1,
+                "method": "auth",
+                "params": {
+                    "token": "shared-secret",
+                    "streamId": events.stream_id(),
+                    "sinceSeq": 0,
+                    "subscribe": { "workspaceIds": ["ws-1"] },
+                },
+            });
+            writer
+                .write_all(format!("{auth}\n").as_bytes())
+                .await
+                .expect("send auth");
+
+            let mut lines = BufReader::new(reader).lines();
+            let mut replayed = Vec::new();
+            while replayed.last() != Some(&3) {
+                let line = tokio::time::timeout(Duration::from_secs(5), lines.next_line())
+                    .await
+                    .expect("replay in time")
+                    .expect("read line")
+                    .expect("connection open");
+                let message: Value = serde_json::from_str(&line).expect("json line");
+                if let Some(seq) = message.get("seq").and_then(Value::as_u64) {
+                    replayed.push(seq);
+                } else {
+                    assert_eq!(message["result"]["ok"], json!(true));
+                }
+            }
+            assert_eq!(replayed, vec![1, 3]);
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
 }
 
 fn main() {
@@ -1832,11 +2787,18 @@ fn main() {
         .expect("failed to build tokio runtime");
 
     runtime.block_on(async move {
-        let (events_tx, _events_rx) = broadcast::channel::<DaemonEvent>(2048);
+        let events = Arc::new(event_log::EventLog::new());
         let event_sink = DaemonEventSink {
-            tx: events_tx.clone(),
+            events: Arc::clone(&events),
         };
-        let state = Arc::new(DaemonState::load(&config, event_sink));
+        let tokens = match access::TokenRegistry::load(&config.data_dir) {
+            Ok(tokens) => tokens,
+            Err(err) => {
+                eprintln!("failed to load scoped tokens: {err}");
+                std::process::exit(2);
+            }
+        };
+        let state = Arc::new(DaemonState::load(&config, event_sink, tokens));
         let config = Arc::new(config);
 
         if config.orbit_url.is_some() {
@@ -1848,10 +2810,17 @@ fn main() {
                     .unwrap_or(&state.storage_path)
                     .display()
             );
-            transport::run_orbit_mode(config, state, events_tx).await;
+            transport::run_orbit_mode(config, state, events).await;
             return;
         }
 
+        let daemon_tls = match tls::load_or_create_daemon_tls(&config.data_dir) {
+            Ok(daemon_tls) => daemon_tls,
+            Err(err) => {
+                eprintln!("failed to set up TLS: {err}");
+                std::process::exit(2);
+            }
+        };
         let listener = match TcpListener::bind(config.listen).await {
             Ok(listener) => listener,
             Err(err) => {
@@ -1860,7 +2829,7 @@ fn main() {
             }
         };
         eprintln!(
-            "codex-monitor-daemon listening on {} (data dir: {})",
+            "codex-monitor-daemon listening on {} over TLS (data dir: {})",
             config.listen,
             state
                 .storage_path
@@ -1868,15 +2837,29 @@ fn main() {
                 .unwrap_or(&state.storage_path)
                 .display()
         );
+        eprintln!(
+            "TLS certificate SHA-256 fingerprint: {}",
+            daemon_tls.fingerprint
+        );
 
         loop {
             match listener.accept().await {
                 Ok((socket, _addr)) => {
+                    let acceptor = daemon_tls.acceptor.clone();
                     let config = Arc::clone(&config);
                     let state = Arc::clone(&state);
-                    let events = events_tx.clone();
+                    let events = Arc::clone(&events);
                     tokio::spawn(async move {
-                        transport::handle_client(socket, config, state, events).await;
+                        let stream = match tokio::time::timeout(
+                            TLS_HANDSHAKE_TIMEOUT,
+                            acceptor.accept(socket),
+                        )
+                        .await
+                        {
+                            Ok(Ok(stream)) => stream,
+                            Ok(Err(_)) | Err(_) => return,
+                        };
+                        transport::handle_client(stream, config, state, events).await;
                     });
                 }
                 Err(_) => continue,
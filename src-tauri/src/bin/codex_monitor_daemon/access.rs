@@ -0,0 +1,506 @@
+use super::rpc::{parse_optional_string, parse_string};
+use super::*;
+
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+const TOKENS_FILE: &str = "tokens.json";
+const SCOPED_TOKEN_PREFIX: &str = "cmt_";
+
+/// Methods a workspace-limited token may call without naming a workspace.
+/// Subscriptions only narrow the events a connection gets; the token still filters them.
+const WORKSPACE_FREE_METHODS: &[&str] = &[
+    "ping",
+    "daemon_info",
+    "list_workspaces",
+    "subscribe",
+    "unsubscribe",
+];
+
+/// Roles are ordered so that a higher role can call everything a lower one can.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(super) enum TokenRole {
+    ReadOnly,
+    Operator,
+    Admin,
+}
+
+impl TokenRole {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::ReadOnly => "read-only",
+            Self::Operator => "operator",
+            Self::Admin => "admin",
+        }
+    }
+
+    fn parse(value: &str) -> Result<Self, String> {
+        match value.trim() {
+            "read-only" => Ok(Self::ReadOnly),
+            "operator" => Ok(Self::Operator),
+            "admin" => Ok(Self::Admin),
+            other => Err(format!(
+                "unknown token role `{other}` (expected admin, operator or read-only)"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ScopedToken {
+    id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+    role: TokenRole,
+    /// Workspace ids the token is limited to. Worktrees of these workspaces are included.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    workspaces: Option<Vec<String>>,
+    token_sha256: String,
+    created_at: i64,
+}
+
+impl ScopedToken {
+    fn summary(&self) -> Value {
+        json!({
+            "id": self.id,
+            "label": self.label,
+            "role": self.role,
+            "workspaces": self.workspaces,
+            "createdAt": self.created_at,
+        })
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct TokenRegistryFile {
+    #[serde(default)]
+    tokens: Vec<ScopedToken>,
+}
+
+/// Scoped tokens minted by admins, persisted in `<data-dir>/tokens.json`.
+/// Only a SHA-256 digest of each token is stored; the token itself is returned once when minted.
+pub(super) struct TokenRegistry {
+    path: PathBuf,
+    tokens: Vec<ScopedToken>,
+}
+
+impl TokenRegistry {
+    pub(super) fn load(data_dir: &Path) -> Result<Self, String> {
+        let path = data_dir.join(TOKENS_FILE);
+        let tokens = if path.is_file() {
+            let data = std::fs::read_to_string(&path)
+                .map_err(|err| format!("Failed to read {}: {err}", path.display()))?;
+            serde_json::from_str::<TokenRegistryFile>(&data)
+                .map_err(|err| format!("Invalid {}: {err}", path.display()))?
+                .tokens
+        } else {
+            Vec::new()
+        };
+        Ok(Self { path, tokens })
+    }
+
+    fn save(&self) -> Result<(), String> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+        }
+        let file = TokenRegistryFile {
+            tokens: self.tokens.clone(),
+        };
+        let data = serde_json::to_string_pretty(&file).map_err(|err| err.to_string())?;
+        std::fs::write(&self.path, data)
+            .map_err(|err| format!("Failed to write {}: {err}", self.path.display()))
+    }
+
+    pub(super) fn find_by_secret(&self, secret: &str) -> Option<&ScopedToken> {
+        let digest = token_digest(secret);
+        self.tokens
+            .iter()
+            .find(|token| token.token_sha256 == digest)
+    }
+
+    fn find(&self, id: &str) -> Option<&ScopedToken> {
+        self.tokens.iter().find(|token| token.id == id)
+    }
+
+    pub(super) fn list(&self) -> Value {
+        Value::Array(self.tokens.iter().map(ScopedToken::summary).collect())
+    }
+
+    /// Mints a token and returns its summary together with the plaintext token.
+    pub(super) fn mint(
+        &mut self,
+        role: TokenRole,
+        workspaces: Option<Vec<String>>,
+        label: Option<String>,
+    ) -> Result<Value, String> {
+        let workspaces = workspaces.map(|ids| {
+            let mut ids = ids
+                .into_iter()
+                .map(|id| id.trim().to_string())
+                .filter(|id| !id.is_empty())
+                .collect::<Vec<_>>();
+            ids.sort();
+            ids.dedup();
+            ids
+        });
+        if let Some(ids) = &workspaces {
+            if role == TokenRole::Admin {
+                return Err("admin tokens cannot be limited to workspaces".to_string());
+            }
+            if ids.is_empty() {
+                return Err("`workspaces` must list at least one workspace id".to_string());
+            }
+        }
+
+        let secret = format!(
+            "{SCOPED_TOKEN_PREFIX}{}{}",
+            Uuid::new_v4().simple(),
+            Uuid::new_v4().simple()
+        );
+        let token = ScopedToken {
+            id: Uuid::new_v4().to_string(),
+            label: label
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty()),
+            role,
+            workspaces,
+            token_sha256: token_digest(&secret),
+            created_at: now_millis(),
+        };
+        let mut response = token.summary();
+        response["token"] = Value::String(secret);
+
+        self.tokens.push(token);
+        if let Err(err) = self.save() {
+            self.tokens.pop();
+            return Err(err);
+        }
+        Ok(response)
+    }
+
+    pub(super) fn revoke(&mut self, id: &str) -> Result<(), String> {
+        let index = self
+            .tokens
+            .iter()
+            .position(|token| token.id == id)
+            .ok_or_else(|| format!("unknown token: {id}"))?;
+        let removed = self.tokens.remove(index);
+        if let Err(err) = self.save() {
+            self.tokens.insert(index, removed);
+            return Err(err);
+        }
+        Ok(())
+    }
+}
+
+fn token_digest(secret: &str) -> String {
+    Sha256::digest(secret.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// What an authenticated connection is allowed to do.
+#[derive(Debug, Clone)]
+pub(super) enum ClientAccess {
+    /// The `--token` shared secret, `--insecure-no-auth`, or an Orbit runner connection.
+    Unrestricted,
+    /// A registry token, looked up on every request so revocation applies immediately.
+    Scoped { token_id: String },
+}
+
+impl ClientAccess {
+    async fn current_token(&self, state: &DaemonState) -> Result<Option<ScopedToken>, String> {
+        match self {
+            Self::Unrestricted => Ok(None),
+            Self::Scoped { token_id } => state
+                .tokens
+                .lock()
+                .await
+                .find(token_id)
+                .cloned()
+                .map(Some)
+                .ok_or_else(|| "token revoked".to_string()),
+        }
+    }
+}
+
+/// Resolves the token sent with `auth` to the access it grants, if it is valid.
+pub(super) async fn authenticate(
+    config: &DaemonConfig,
+    state: &DaemonState,
+    provided: &str,
+) -> Option<ClientAccess> {
+    if provided.is_empty() {
+        return None;
+    }
+    if config.token.as_deref() == Some(provided) {
+        return Some(ClientAccess::Unrestricted);
+    }
+    state
+        .tokens
+        .lock()
+        .await
+        .find_by_secret(provided)
+        .map(|token| ClientAccess::Scoped {
+            token_id: token.id.clone(),
+        })
+}
+
+fn required_role(method: &str, params: &Value) -> TokenRole {
+    match method {
+        "ping"
+        | "daemon_info"
+        | "subscribe"
+        | "unsubscribe"
+        | "is_macos_debug_build"
+        | "get_open_app_icon"
+        | "local_usage_snapshot"
+        | "list_workspaces"
+        | "worktree_setup_status"
+        | "list_workspace_files"
+        | "read_workspace_file"
+        | "file_read"
+        | "get_codex_config_path"
+        | "get_config_model"
+        | "list_threads"
+        | "resume_thread"
+        | "list_mcp_server_status"
+        | "model_list"
+        | "collaboration_mode_list"
+        | "account_rate_limits"
+        | "account_read"
+        | "skills_list"
+        | "apps_list"
+        | "get_git_status"
+        | "list_git_roots"
+        | "get_git_diffs"
+        | "get_git_log"
+        | "get_git_commit_diff"
+        | "get_git_remote"
+        | "list_git_branches"
+        | "get_github_issues"
+        | "get_github_pull_requests"
+        | "get_github_pull_request_diff"
+        | "get_github_pull_request_comments"
+        | "prompts_list"
+        | "prompts_workspace_dir"
+        | "prompts_global_dir"
+        | "forge_list_template_sources"
+        | "forge_get_installed_template"
+        | "forge_list_plans"
+        | "forge_validate_plan"
+        | "forge_analyze_plan"
+        | "forge_plan_cost"
+        | "forge_get_phase_status"
+        | "forge_get_run_history"
+        | "forge_run_status"
+        | "forge_get_runnable_tasks" => TokenRole::ReadOnly,
+        "file_write" => {
+            if parse_optional_string(params, "scope").as_deref() == Some("global") {
+                TokenRole::Admin
+            } else {
+                TokenRole::Operator
+            }
+        }
+        // Connecting spawns the workspace's codex process, listing and previewing templates
+        // clone or fetch template sources, and the plan prompt syncs skills into the repo.
+        "connect_workspace"
+        | "forge_list_bundled_templates"
+        | "forge_template_upgrade_preview"
+        | "forge_get_plan_prompt"
+        | "send_notification_fallback"
+        | "add_worktree"
+        | "add_clone"
+        | "worktree_setup_mark_ran"
+        | "remove_worktree"
+        | "rename_worktree"
+        | "rename_worktree_upstream"
+        | "update_workspace_settings"
+        | "apply_worktree_changes"
+        | "start_thread"
+        | "fork_thread"
+        | "archive_thread"
+        | "compact_thread"
+        | "set_thread_name"
+        | "send_user_message"
+        | "turn_interrupt"
+        | "turn_steer"
+        | "start_review"
+        | "codex_login"
+        | "codex_login_cancel"
+        | "respond_to_server_request"
+        | "remember_approval_rule"
+        | "generate_run_metadata"
+        | "stage_git_file"
+        | "stage_git_all"
+        | "unstage_git_file"
+        | "revert_git_file"
+        | "revert_git_all"
+        | "commit_git"
+        | "push_git"
+        | "pull_git"
+        | "fetch_git"
+        | "sync_git"
+        | "checkout_github_pull_request"
+        | "checkout_git_branch"
+        | "create_git_branch"
+        | "generate_commit_message"
+        | "prompts_create"
+        | "prompts_update"
+        | "prompts_delete"
+        | "prompts_move"
+        | "forge_install_template"
+        | "forge_upgrade_template"
+        | "forge_uninstall_template"
+        | "forge_prepare_execution"
+        | "forge_reset_execution_progress"
+        | "forge_get_next_phase_prompt"
+        | "forge_get_task_phase_prompt"
+        | "forge_run_phase_checks"
+        | "forge_rollback_task"
+        | "forge_approve_phase"
+        | "forge_reject_phase"
+        | "forge_update_plan"
+        | "forge_start_run"
+        | "forge_stop_run"
+        | "forge_cancel_execution"
+        | "forge_start_task_worktree"
+        | "forge_merge_task_worktree" => TokenRole::Operator,
+        // A terminal is an interactive shell on the daemon host.
+        "terminal_open" | "terminal_write" | "terminal_resize" | "terminal_close" => {
+            TokenRole::Admin
+        }
+        // Daemon lifecycle, token management, settings, adding or removing workspaces and
+        // anything that runs a caller-supplied binary. Unlisted methods also land here.
+        _ => TokenRole::Admin,
+    }
+}
+
+fn request_workspace_id(method: &str, params: &Value) -> Option<String> {
+    let key = match method {
+        "connect_workspace"
+        | "remove_workspace"
+        | "remove_worktree"
+        | "rename_worktree"
+        | "rename_worktree_upstream"
+        | "update_workspace_settings"
+        | "update_workspace_codex_bin" => "id",
+        "add_worktree" => "parentId",
+        "add_clone" => "sourceWorkspaceId",
+        _ => "workspaceId",
+    };
+    parse_optional_string(params, key)
+}
+
+async fn workspace_allowed(state: &DaemonState, allowed: &[String], workspace_id: &str) -> bool {
+    if allowed.iter().any(|id| id == workspace_id) {
+        return true;
+    }
+    let workspaces = state.workspaces.lock().await;
+    workspaces
+        .get(workspace_id)
+        .and_then(|entry| entry.parent_id.as_deref())
+        .is_some_and(|parent_id| allowed.iter().any(|id| id == parent_id))
+}
+
+/// Checks the connection's token against the method's role and workspace before dispatch.
+pub(super) async fn authorize_request(
+    state: &DaemonState,
+    access: &ClientAccess,
+    method: &str,
+    params: &Value,
+) -> Result<(), String> {
+    let Some(token) = access.current_token(state).await? else {
+        return Ok(());
+    };
+
+    let required = required_role(method, params);
+    if token.role < required {
+        return Err(format!(
+            "forbidden: `{method}` requires the {} role, token has {}",
+            required.as_str(),
+            token.role.as_str()
+        ));
+    }
+
+    let Some(allowed) = token.workspaces.as_deref() else {
+        return Ok(());
+    };
+    if WORKSPACE_FREE_METHODS.contains(&method) {
+        return Ok(());
+    }
+    let Some(workspace_id) = request_workspace_id(method, params) else {
+        return Err(format!(
+            "forbidden: `{method}` is not available to workspace-limited tokens"
+        ));
+    };
+    if !workspace_allowed(state, allowed, &workspace_id).await {
+        return Err(format!(
+            "forbidden: token is not allowed to access workspace `{workspace_id}`"
+        ));
+    }
+    Ok(())
+}
+
+/// Drops workspaces outside a workspace-limited token from `list_workspaces` results.
+pub(super) async fn filter_response(
+    state: &DaemonState,
+    access: &ClientAccess,
+    method: &str,
+    result: Value,
+) -> Value {
+    if method != "list_workspaces" {
+        return result;
+    }
+    let Ok(Some(ScopedToken {
+        workspaces: Some(allowed),
+        ..
+    })) = access.current_token(state).await
+    else {
+        return result;
+    };
+    let Value::Array(items) = result else {
+        return result;
+    };
+    let visible = items
+        .into_iter()
+        .filter(|item| {
+            ["id", "parentId"].iter().any(|key| {
+                item.get(key)
+                    .and_then(Value::as_str)
+                    .is_some_and(|id| allowed.iter().any(|allowed_id| allowed_id == id))
+            })
+        })
+        .collect();
+    Value::Array(visible)
+}
+
+/// Whether an event may be forwarded to the connection. `None` means the token was revoked.
+pub(super) async fn event_visible(
+    state: &DaemonState,
+    access: &ClientAccess,
+    event: &DaemonEvent,
+) -> Option<bool> {
+    let token = access.current_token(state).await.ok()?;
+    let Some(allowed) = token.and_then(|token| token.workspaces) else {
+        return Some(true);
+    };
+    Some(workspace_allowed(state, &allowed, event.workspace_id()).await)
+}
+
+pub(super) fn parse_token_role(params: &Value) -> Result<TokenRole, String> {
+    TokenRole::parse(&parse_string(params, "role")?)
+}
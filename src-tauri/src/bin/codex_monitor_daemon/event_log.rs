@@ -0,0 +1,168 @@
+use super::*;
+
+use std::collections::VecDeque;
+
+/// Events kept for clients that reconnect with `sinceSeq`. Streaming turns emit many small
+/// deltas, so this covers a short disconnect rather than a long one.
+const EVENT_REPLAY_CAPACITY: usize = 4096;
+const EVENT_BROADCAST_CAPACITY: usize = 2048;
+
+#[derive(Clone)]
+pub(super) struct SequencedEvent {
+    pub(super) seq: u64,
+    pub(super) event: DaemonEvent,
+}
+
+/// Where a client wants its event stream to pick up, sent as `streamId`/`sinceSeq` in `auth`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct EventResume {
+    stream_id: Option<String>,
+    since_seq: Option<u64>,
+}
+
+pub(super) fn parse_event_resume(params: &Value) -> EventResume {
+    let Value::Object(map) = params else {
+        return EventResume::default();
+    };
+    EventResume {
+        stream_id: map
+            .get("streamId")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        since_seq: map.get("sinceSeq").and_then(Value::as_u64),
+    }
+}
+
+/// Events a client will never receive. `restarted` means the client's cursor belongs to an
+/// earlier daemon process, so anything it missed before this stream began is unknown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct EventGap {
+    pub(super) reason: &'static str,
+    pub(super) lost: Option<(u64, u64)>,
+}
+
+pub(super) struct Replay {
+    pub(super) gap: Option<EventGap>,
+    pub(super) events: Vec<SequencedEvent>,
+    /// Highest sequence number covered by this replay; live events continue after it.
+    pub(super) last_seq: u64,
+}
+
+struct EventLogState {
+    next_seq: u64,
+    buffer: VecDeque<SequencedEvent>,
+}
+
+/// Stamps every daemon event with a sequence number, keeps the most recent ones for replay
+/// and broadcasts them to connected clients.
+pub(super) struct EventLog {
+    stream_id: String,
+    capacity: usize,
+    state: std::sync::Mutex<EventLogState>,
+    tx: broadcast::Sender<SequencedEvent>,
+}
+
+impl EventLog {
+    pub(super) fn new() -> Self {
+        Self::with_capacity(EVENT_REPLAY_CAPACITY)
+    }
+
+    pub(super) fn with_capacity(capacity: usize) -> Self {
+        let (tx, _rx) = broadcast::channel(EVENT_BROADCAST_CAPACITY);
+        Self {
+            stream_id: uuid::Uuid::new_v4().to_string(),
+            capacity,
+            state: std::sync::Mutex::new(EventLogState {
+                next_seq: 1,
+                buffer: VecDeque::with_capacity(capacity),
+            }),
+            tx,
+        }
+    }
+
+    /// Identifies this daemon process; sequence numbers restart with every stream.
+    pub(super) fn stream_id(&self) -> &str {
+        &self.stream_id
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, EventLogState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub(super) fn publish(&self, event: DaemonEvent) {
+        let mut state = self.lock_state();
+        let event = SequencedEvent {
+            seq: state.next_seq,
+            event,
+        };
+        state.next_seq += 1;
+        if state.buffer.len() == self.capacity {
+            state.buffer.pop_front();
+        }
+        state.buffer.push_back(event.clone());
+        // Sent while holding the lock so subscribers never see events out of log order.
+        let _ = self.tx.send(event);
+    }
+
+    /// Subscribes to live events and collects what the client missed according to `resume`.
+    pub(super) fn subscribe(
+        &self,
+        resume: &EventResume,
+    ) -> (Replay, broadcast::Receiver<SequencedEvent>) {
+        let state = self.lock_state();
+        let rx = self.tx.subscribe();
+        let replay = match resume.since_seq {
+            None => Replay {
+                gap: None,
+                events: Vec::new(),
+                last_seq: state.next_seq - 1,
+            },
+            Some(since_seq) => {
+                let same_stream = resume.stream_id.as_deref() == Some(self.stream_id.as_str());
+                if same_stream {
+                    replay_after(&state, since_seq, "overflow")
+                } else {
+                    let mut replay = replay_after(&state, 0, "restarted");
+                    replay.gap.get_or_insert(EventGap {
+                        reason: "restarted",
+                        lost: None,
+                    });
+                    replay
+                }
+            }
+        };
+        (replay, rx)
+    }
+
+    /// Events after `seq` that are still buffered, for a subscriber that fell behind.
+    pub(super) fn replay_after(&self, seq: u64) -> Replay {
+        replay_after(&self.lock_state(), seq, "overflow")
+    }
+}
+
+fn replay_after(state: &EventLogState, after_seq: u64, reason: &'static str) -> Replay {
+    let last_seq = state.next_seq - 1;
+    let after_seq = after_seq.min(last_seq);
+    let first_buffered = state
+        .buffer
+        .front()
+        .map(|event| event.seq)
+        .unwrap_or(last_seq + 1);
+    let gap = (after_seq + 1 < first_buffered).then(|| EventGap {
+        reason,
+        lost: Some((after_seq + 1, first_buffered - 1)),
+    });
+    let events = state
+        .buffer
+        .iter()
+        .filter(|event| event.seq > after_seq)
+        .cloned()
+        .collect();
+    Replay {
+        gap,
+        events,
+        last_seq,
+    }
+}
@@ -1,5 +1,11 @@
+use super::access::{self, ClientAccess};
+use super::event_log::{EventGap, EventLog, EventResume, Replay, SequencedEvent};
+use super::subscriptions::EventSubscriptions;
 use super::*;
 
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+
 #[path = "rpc/codex.rs"]
 mod codex;
 #[path = "rpc/daemon.rs"]
@@ -10,6 +16,8 @@ mod dispatcher;
 mod git;
 #[path = "rpc/prompts.rs"]
 mod prompts;
+#[path = "rpc/terminal.rs"]
+mod terminal;
 #[path = "rpc/workspace.rs"]
 mod workspace;
 
@@ -35,21 +43,32 @@ pub(super) fn build_result_response(id: Option<u64>, result: Value) -> Option<St
     )
 }
 
-fn build_event_notification(event: DaemonEvent) -> Option<String> {
-    let payload = match event {
-        DaemonEvent::AppServer(payload) => json!({
-            "method": "app-server-event",
-            "params": payload,
-        }),
-        DaemonEvent::TerminalOutput(payload) => json!({
-            "method": "terminal-output",
-            "params": payload,
-        }),
-        DaemonEvent::TerminalExit(payload) => json!({
-            "method": "terminal-exit",
-            "params": payload,
-        }),
+fn build_event_notification(event: &SequencedEvent) -> Option<String> {
+    let params = match &event.event {
+        DaemonEvent::AppServer(payload) => serde_json::to_value(payload),
+        DaemonEvent::TerminalOutput(payload) => serde_json::to_value(payload),
+        DaemonEvent::TerminalExit(payload) => serde_json::to_value(payload),
+        DaemonEvent::ForgeExecution(payload) => serde_json::to_value(payload),
     };
+    let payload = json!({
+        "method": event.event.method(),
+        "params": params.ok()?,
+        "seq": event.seq,
+    });
+    serde_json::to_string(&payload).ok()
+}
+
+fn build_gap_notification(stream_id: &str, gap: &EventGap) -> Option<String> {
+    let (from_seq, to_seq) = gap.lost.unzip();
+    let payload = json!({
+        "method": "event-gap",
+        "params": {
+            "streamId": stream_id,
+            "reason": gap.reason,
+            "fromSeq": from_seq,
+            "toSeq": to_seq,
+        },
+    });
     serde_json::to_string(&payload).ok()
 }
 
@@ -132,7 +151,7 @@ pub(super) fn parse_optional_value(value: &Value, key: &str) -> Option<Value> {
 }
 
 pub(super) async fn handle_rpc_request(
-    state: &DaemonState,
+    state: &Arc<DaemonState>,
     method: &str,
     params: Value,
     client_version: String,
@@ -140,25 +159,169 @@ pub(super) async fn handle_rpc_request(
     dispatcher::dispatch_rpc_request(state, method, &params, &client_version).await
 }
 
+/// Forwards daemon events to one connection, starting with whatever `resume` asks to replay.
+/// A connection that falls behind the broadcast catches up from the event log. The replay goes
+/// through the connection's subscriptions as they are when this starts.
 pub(super) async fn forward_events(
-    mut rx: broadcast::Receiver<DaemonEvent>,
+    events: Arc<EventLog>,
+    resume: EventResume,
     out_tx_events: mpsc::UnboundedSender<String>,
+    state: Arc<DaemonState>,
+    connection: RpcConnection,
 ) {
+    let (replay, mut rx) = events.subscribe(&resume);
+    let mut last_seq = replay.last_seq;
+    if !forward_replay(&events, replay, &out_tx_events, &state, &connection).await {
+        return;
+    }
+
     loop {
         let event = match rx.recv().await {
             Ok(event) => event,
-            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Lagged(_)) => {
+                let replay = events.replay_after(last_seq);
+                last_seq = replay.last_seq;
+                if !forward_replay(&events, replay, &out_tx_events, &state, &connection).await {
+                    break;
+                }
+                continue;
+            }
             Err(broadcast::error::RecvError::Closed) => break,
         };
-
-        let Some(payload) = build_event_notification(event) else {
+        if event.seq <= last_seq {
             continue;
-        };
+        }
+        last_seq = event.seq;
+        if !forward_event(&event, &out_tx_events, &state, &connection).await {
+            break;
+        }
+    }
+}
 
+async fn forward_replay(
+    events: &EventLog,
+    replay: Replay,
+    out_tx_events: &mpsc::UnboundedSender<String>,
+    state: &DaemonState,
+    connection: &RpcConnection,
+) -> bool {
+    if let Some(payload) = replay
+        .gap
+        .as_ref()
+        .and_then(|gap| build_gap_notification(events.stream_id(), gap))
+    {
         if out_tx_events.send(payload).is_err() {
-            break;
+            return false;
+        }
+    }
+    for event in &replay.events {
+        if !forward_event(event, out_tx_events, state, connection).await {
+            return false;
         }
     }
+    true
+}
+
+/// Returns `false` once the connection is gone or its token was revoked.
+async fn forward_event(
+    event: &SequencedEvent,
+    out_tx_events: &mpsc::UnboundedSender<String>,
+    state: &DaemonState,
+    connection: &RpcConnection,
+) -> bool {
+    if !connection.subscriptions.matches(&event.event) {
+        return true;
+    }
+    match access::event_visible(state, &connection.access, &event.event).await {
+        Some(true) => {}
+        Some(false) => return true,
+        None => return false,
+    }
+    let Some(payload) = build_event_notification(event) else {
+        return true;
+    };
+    out_tx_events.send(payload).is_ok()
+}
+
+static NEXT_CONNECTION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Connections using each open terminal. A shell stays open while any of them is connected, so
+/// a client that reconnects and reopens its terminal keeps it; it ends once the last one goes
+/// away or any of them sends `terminal_close`.
+#[derive(Default)]
+pub(super) struct TerminalOwners {
+    owners: std::sync::Mutex<HashMap<(String, String), HashSet<u64>>>,
+}
+
+impl TerminalOwners {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<(String, String), HashSet<u64>>> {
+        self.owners
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Records the outcome of a `terminal_open` or `terminal_close` sent on a connection.
+    fn track(&self, connection_id: u64, method: &str, params: &Value, succeeded: bool) {
+        let (Some(workspace_id), Some(terminal_id)) = (
+            parse_optional_string(params, "workspaceId"),
+            parse_optional_string(params, "terminalId"),
+        ) else {
+            return;
+        };
+        let key = (workspace_id, terminal_id);
+        match method {
+            "terminal_open" if succeeded => {
+                self.lock().entry(key).or_default().insert(connection_id);
+            }
+            "terminal_close" => {
+                self.lock().remove(&key);
+            }
+            _ => {}
+        }
+    }
+
+    /// Drops the connection's claims and closes the terminals no other connection uses.
+    async fn release(&self, state: &DaemonState, connection_id: u64) {
+        let mut orphaned = Vec::new();
+        self.lock().retain(|key, connections| {
+            let last_owner = connections.remove(&connection_id) && connections.is_empty();
+            if last_owner {
+                orphaned.push(key.clone());
+            }
+            !last_owner
+        });
+        for (workspace_id, terminal_id) in orphaned {
+            // Shells that already exited are gone from the session map.
+            let _ = state.terminal_close(workspace_id, terminal_id).await;
+        }
+    }
+}
+
+/// Per-connection context shared by the requests spawned for that connection.
+#[derive(Clone)]
+pub(super) struct RpcConnection {
+    id: u64,
+    client_version: String,
+    request_limiter: Arc<Semaphore>,
+    access: Arc<ClientAccess>,
+    pub(super) subscriptions: Arc<EventSubscriptions>,
+}
+
+impl RpcConnection {
+    pub(super) fn new(access: ClientAccess) -> Self {
+        Self {
+            id: NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed),
+            client_version: format!("daemon-{}", env!("CARGO_PKG_VERSION")),
+            request_limiter: Arc::new(Semaphore::new(MAX_IN_FLIGHT_RPC_PER_CONNECTION)),
+            access: Arc::new(access),
+            subscriptions: Arc::new(EventSubscriptions::default()),
+        }
+    }
+
+    /// Releases what the connection held on the daemon once the client is gone.
+    pub(super) async fn close(&self, state: &DaemonState) {
+        state.terminal_owners.release(state, self.id).await;
+    }
 }
 
 pub(super) fn spawn_rpc_response_task(
@@ -167,14 +330,35 @@ pub(super) fn spawn_rpc_response_task(
     id: Option<u64>,
     method: String,
     params: Value,
-    client_version: String,
-    request_limiter: Arc<Semaphore>,
+    connection: RpcConnection,
 ) {
     tokio::spawn(async move {
+        let RpcConnection {
+            id: connection_id,
+            client_version,
+            request_limiter,
+            access,
+            subscriptions,
+        } = connection;
         let Ok(_permit) = request_limiter.acquire_owned().await else {
             return;
         };
-        let result = handle_rpc_request(&state, &method, params, client_version).await;
+        let terminal_params =
+            matches!(method.as_str(), "terminal_open" | "terminal_close").then(|| params.clone());
+        let result: Result<Value, String> = async {
+            access::authorize_request(&state, &access, &method, &params).await?;
+            if let Some(result) = subscriptions.try_handle(&method, &params) {
+                return result;
+            }
+            let result = handle_rpc_request(&state, &method, params, client_version).await?;
+            Ok(access::filter_response(&state, &access, &method, result).await)
+        }
+        .await;
+        if let Some(params) = terminal_params {
+            state
+                .terminal_owners
+                .track(connection_id, &method, &params, result.is_ok());
+        }
         let response = match result {
             Ok(result) => build_result_response(id, result),
             Err(message) => build_error_response(id, &message),
@@ -15,6 +15,30 @@ pub(super) async fn try_handle(
             });
             Some(Ok(json!({ "ok": true })))
         }
+        "daemon_token_list" => Some(Ok(state.tokens.lock().await.list())),
+        "daemon_token_mint" => {
+            let role = match access::parse_token_role(params) {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let workspaces = parse_optional_string_array(params, "workspaces");
+            let label = parse_optional_string(params, "label");
+            Some(state.tokens.lock().await.mint(role, workspaces, label))
+        }
+        "daemon_token_revoke" => {
+            let id = match parse_string(params, "id") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            Some(
+                state
+                    .tokens
+                    .lock()
+                    .await
+                    .revoke(&id)
+                    .map(|_| json!({ "ok": true })),
+            )
+        }
         "menu_set_accelerators" => {
             let updates: Vec<Value> = match params {
                 Value::Object(map) => match map
@@ -1,7 +1,7 @@
 use super::*;
 
 pub(super) async fn dispatch_rpc_request(
-    state: &DaemonState,
+    state: &Arc<DaemonState>,
     method: &str,
     params: &Value,
     client_version: &str,
@@ -26,5 +26,9 @@ pub(super) async fn dispatch_rpc_request(
         return result;
     }
 
+    if let Some(result) = terminal::try_handle(state, method, params).await {
+        return result;
+    }
+
     Err(format!("unknown method: {method}"))
 }
@@ -0,0 +1,103 @@
+use super::*;
+
+fn parse_terminal_size(params: &Value, key: &str) -> Result<u16, String> {
+    parse_optional_u32(params, key)
+        .and_then(|value| u16::try_from(value).ok())
+        .ok_or_else(|| format!("missing or invalid `{key}`"))
+}
+
+pub(super) async fn try_handle(
+    state: &DaemonState,
+    method: &str,
+    params: &Value,
+) -> Option<Result<Value, String>> {
+    match method {
+        "terminal_open" => {
+            let workspace_id = match parse_string(params, "workspaceId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let terminal_id = match parse_string(params, "terminalId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let cols = match parse_terminal_size(params, "cols") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let rows = match parse_terminal_size(params, "rows") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let session = match state
+                .terminal_open(workspace_id, terminal_id, cols, rows)
+                .await
+            {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            Some(serde_json::to_value(session).map_err(|err| err.to_string()))
+        }
+        "terminal_write" => {
+            let workspace_id = match parse_string(params, "workspaceId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let terminal_id = match parse_string(params, "terminalId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let data = match parse_string(params, "data") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            Some(
+                state
+                    .terminal_write(workspace_id, terminal_id, data)
+                    .await
+                    .map(|_| json!({ "ok": true })),
+            )
+        }
+        "terminal_resize" => {
+            let workspace_id = match parse_string(params, "workspaceId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let terminal_id = match parse_string(params, "terminalId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let cols = match parse_terminal_size(params, "cols") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let rows = match parse_terminal_size(params, "rows") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            Some(
+                state
+                    .terminal_resize(workspace_id, terminal_id, cols, rows)
+                    .await
+                    .map(|_| json!({ "ok": true })),
+            )
+        }
+        "terminal_close" => {
+            let workspace_id = match parse_string(params, "workspaceId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let terminal_id = match parse_string(params, "terminalId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            Some(
+                state
+                    .terminal_close(workspace_id, terminal_id)
+                    .await
+                    .map(|_| json!({ "ok": true })),
+            )
+        }
+        _ => None,
+    }
+}
@@ -17,6 +17,15 @@ struct FileWriteRequest {
     content: String,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ForgeAddTemplateSourceRequest {
+    kind: forge_templates_core::ForgeTemplateSourceKind,
+    location: String,
+    #[serde(default)]
+    git_ref: Option<String>,
+}
+
 fn parse_file_read_request(params: &Value) -> Result<FileReadRequest, String> {
     serde_json::from_value(params.clone()).map_err(|err| err.to_string())
 }
@@ -26,7 +35,7 @@ fn parse_file_write_request(params: &Value) -> Result<FileWriteRequest, String>
 }
 
 pub(super) async fn try_handle(
-    state: &DaemonState,
+    state: &Arc<DaemonState>,
     method: &str,
     params: &Value,
     client_version: &str,
@@ -249,6 +258,40 @@ pub(super) async fn try_handle(
             };
             Some(serde_json::to_value(templates).map_err(|err| err.to_string()))
         }
+        "forge_list_template_sources" => {
+            let sources = match state.forge_list_template_sources().await {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            Some(serde_json::to_value(sources).map_err(|err| err.to_string()))
+        }
+        "forge_add_template_source" => {
+            let request: ForgeAddTemplateSourceRequest =
+                match serde_json::from_value(params.clone()) {
+                    Ok(value) => value,
+                    Err(err) => return Some(Err(err.to_string())),
+                };
+            let source = match state
+                .forge_add_template_source(request.kind, request.location, request.git_ref)
+                .await
+            {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            Some(serde_json::to_value(source).map_err(|err| err.to_string()))
+        }
+        "forge_remove_template_source" => {
+            let source_id = match parse_string(params, "sourceId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            Some(
+                state
+                    .forge_remove_template_source(source_id)
+                    .await
+                    .map(|_| json!({ "ok": true })),
+            )
+        }
         "forge_get_installed_template" => {
             let workspace_id = match parse_string(params, "workspaceId") {
                 Ok(value) => value,
@@ -269,12 +312,38 @@ pub(super) async fn try_handle(
                 Ok(value) => value,
                 Err(err) => return Some(Err(err)),
             };
-            let lock = match state.forge_install_template(workspace_id, template_id).await {
+            let source_id = parse_optional_string(params, "sourceId");
+            let lock = match state
+                .forge_install_template(workspace_id, template_id, source_id)
+                .await
+            {
                 Ok(value) => value,
                 Err(err) => return Some(Err(err)),
             };
             Some(serde_json::to_value(lock).map_err(|err| err.to_string()))
         }
+        "forge_template_upgrade_preview" => {
+            let workspace_id = match parse_string(params, "workspaceId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let preview = match state.forge_template_upgrade_preview(workspace_id).await {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            Some(serde_json::to_value(preview).map_err(|err| err.to_string()))
+        }
+        "forge_upgrade_template" => {
+            let workspace_id = match parse_string(params, "workspaceId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let result = match state.forge_upgrade_template(workspace_id).await {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            Some(serde_json::to_value(result).map_err(|err| err.to_string()))
+        }
         "forge_uninstall_template" => {
             let workspace_id = match parse_string(params, "workspaceId") {
                 Ok(value) => value,
@@ -298,6 +367,21 @@ pub(super) async fn try_handle(
             };
             Some(serde_json::to_value(plans).map_err(|err| err.to_string()))
         }
+        "forge_validate_plan" => {
+            let workspace_id = match parse_string(params, "workspaceId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let plan_id = match parse_string(params, "planId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let validation = match state.forge_validate_plan(workspace_id, plan_id).await {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            Some(serde_json::to_value(validation).map_err(|err| err.to_string()))
+        }
         "forge_get_plan_prompt" => {
             let workspace_id = match parse_string(params, "workspaceId") {
                 Ok(value) => value,
@@ -400,8 +484,331 @@ pub(super) async fn try_handle(
                 Ok(value) => value,
                 Err(err) => return Some(Err(err)),
             };
+            let thread_id = parse_optional_string(params, "threadId");
+            let result = match state
+                .forge_run_phase_checks(workspace_id, plan_id, task_id, phase_id, thread_id)
+                .await
+            {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            Some(serde_json::to_value(result).map_err(|err| err.to_string()))
+        }
+        "forge_get_run_history" => {
+            let workspace_id = match parse_string(params, "workspaceId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let plan_id = match parse_string(params, "planId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let task_id = parse_optional_string(params, "taskId");
+            let limit = parse_optional_u32(params, "limit");
+            let history = match state
+                .forge_get_run_history(workspace_id, plan_id, task_id, limit)
+                .await
+            {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            Some(serde_json::to_value(history).map_err(|err| err.to_string()))
+        }
+        "forge_rollback_task" => {
+            let workspace_id = match parse_string(params, "workspaceId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let plan_id = match parse_string(params, "planId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let task_id = match parse_string(params, "taskId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let cascade = parse_optional_bool(params, "cascade").unwrap_or(false);
+            let result = match state
+                .forge_rollback_task(workspace_id, plan_id, task_id, cascade)
+                .await
+            {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            Some(serde_json::to_value(result).map_err(|err| err.to_string()))
+        }
+        "forge_analyze_plan" => {
+            let workspace_id = match parse_string(params, "workspaceId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let plan_id = match parse_string(params, "planId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let analysis = match state.forge_analyze_plan(workspace_id, plan_id).await {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            Some(serde_json::to_value(analysis).map_err(|err| err.to_string()))
+        }
+        "forge_plan_cost" => {
+            let workspace_id = match parse_string(params, "workspaceId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let plan_id = match parse_string(params, "planId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let cost = match state.forge_plan_cost(workspace_id, plan_id).await {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            Some(serde_json::to_value(cost).map_err(|err| err.to_string()))
+        }
+        "forge_approve_phase" => {
+            let workspace_id = match parse_string(params, "workspaceId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let plan_id = match parse_string(params, "planId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let task_id = match parse_string(params, "taskId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let phase_id = match parse_string(params, "phaseId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let reviewer = match parse_string(params, "reviewer") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let notes = parse_optional_string(params, "notes");
+            let result = match state
+                .forge_approve_phase(workspace_id, plan_id, task_id, phase_id, reviewer, notes)
+                .await
+            {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            Some(serde_json::to_value(result).map_err(|err| err.to_string()))
+        }
+        "forge_reject_phase" => {
+            let workspace_id = match parse_string(params, "workspaceId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let plan_id = match parse_string(params, "planId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let task_id = match parse_string(params, "taskId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let phase_id = match parse_string(params, "phaseId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let reviewer = match parse_string(params, "reviewer") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let notes = match parse_string(params, "notes") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let result = match state
+                .forge_reject_phase(workspace_id, plan_id, task_id, phase_id, reviewer, notes)
+                .await
+            {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            Some(serde_json::to_value(result).map_err(|err| err.to_string()))
+        }
+        "forge_update_plan" => {
+            let workspace_id = match parse_string(params, "workspaceId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let plan_id = match parse_string(params, "planId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let operations = match parse_optional_value(params, "operations")
+                .ok_or_else(|| "missing `operations`".to_string())
+                .and_then(|value| {
+                    serde_json::from_value::<Vec<forge_execute_core::ForgePlanOperationV1>>(value)
+                        .map_err(|err| err.to_string())
+                }) {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let result = match state
+                .forge_update_plan(workspace_id, plan_id, operations)
+                .await
+            {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            Some(serde_json::to_value(result).map_err(|err| err.to_string()))
+        }
+        "forge_start_run" => {
+            let workspace_id = match parse_string(params, "workspaceId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let plan_id = match parse_string(params, "planId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let options = match parse_optional_value(params, "options")
+                .filter(|value| !value.is_null())
+                .map(serde_json::from_value::<forge_execute_core::ForgeStartRunOptionsV1>)
+                .transpose()
+            {
+                Ok(value) => value.unwrap_or_default(),
+                Err(err) => return Some(Err(err.to_string())),
+            };
+            let status = match Arc::clone(state)
+                .forge_start_run(workspace_id, plan_id, options)
+                .await
+            {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            Some(serde_json::to_value(status).map_err(|err| err.to_string()))
+        }
+        "forge_stop_run" => {
+            let workspace_id = match parse_string(params, "workspaceId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let plan_id = match parse_string(params, "planId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let status = match state.forge_stop_run(workspace_id, plan_id) {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            Some(serde_json::to_value(status).map_err(|err| err.to_string()))
+        }
+        "forge_run_status" => {
+            let workspace_id = match parse_string(params, "workspaceId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let plan_id = match parse_string(params, "planId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let status = match state.forge_run_status(workspace_id, plan_id) {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            Some(serde_json::to_value(status).map_err(|err| err.to_string()))
+        }
+        "forge_cancel_execution" => {
+            let workspace_id = match parse_string(params, "workspaceId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let plan_id = match parse_string(params, "planId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let result = match state.forge_cancel_execution(workspace_id, plan_id) {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            Some(serde_json::to_value(result).map_err(|err| err.to_string()))
+        }
+        "forge_get_runnable_tasks" => {
+            let workspace_id = match parse_string(params, "workspaceId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let plan_id = match parse_string(params, "planId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let tasks = match state.forge_get_runnable_tasks(workspace_id, plan_id).await {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            Some(serde_json::to_value(tasks).map_err(|err| err.to_string()))
+        }
+        "forge_get_task_phase_prompt" => {
+            let workspace_id = match parse_string(params, "workspaceId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let plan_id = match parse_string(params, "planId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let task_id = match parse_string(params, "taskId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let next = match state
+                .forge_get_task_phase_prompt(workspace_id, plan_id, task_id)
+                .await
+            {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            Some(serde_json::to_value(next).map_err(|err| err.to_string()))
+        }
+        "forge_start_task_worktree" => {
+            let workspace_id = match parse_string(params, "workspaceId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let plan_id = match parse_string(params, "planId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let task_id = match parse_string(params, "taskId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let worktree = match state
+                .forge_start_task_worktree(
+                    workspace_id,
+                    plan_id,
+                    task_id,
+                    client_version.to_string(),
+                )
+                .await
+            {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            Some(serde_json::to_value(worktree).map_err(|err| err.to_string()))
+        }
+        "forge_merge_task_worktree" => {
+            let workspace_id = match parse_string(params, "workspaceId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let plan_id = match parse_string(params, "planId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            let task_id = match parse_string(params, "taskId") {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
             let result = match state
-                .forge_run_phase_checks(workspace_id, plan_id, task_id, phase_id)
+                .forge_merge_task_worktree(workspace_id, plan_id, task_id)
                 .await
             {
                 Ok(value) => value,
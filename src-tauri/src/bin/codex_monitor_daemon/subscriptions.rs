@@ -0,0 +1,151 @@
+use super::rpc::parse_optional_string_array;
+use super::*;
+
+use std::collections::HashSet;
+
+/// Notification methods a connection can subscribe to by name.
+const EVENT_KINDS: &[&str] = &[
+    "app-server-event",
+    "terminal-output",
+    "terminal-exit",
+    "forge-execution-event",
+];
+
+#[derive(Default)]
+struct EventFilter {
+    /// `None` forwards events of every workspace.
+    workspaces: Option<HashSet<String>>,
+    /// `None` forwards every kind of event.
+    kinds: Option<HashSet<String>>,
+}
+
+/// What one connection asked to receive. A connection gets every event until its first
+/// `subscribe`, so older clients keep working unchanged.
+#[derive(Default)]
+pub(super) struct EventSubscriptions {
+    filter: std::sync::Mutex<EventFilter>,
+}
+
+impl EventSubscriptions {
+    fn lock(&self) -> std::sync::MutexGuard<'_, EventFilter> {
+        self.filter
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub(super) fn matches(&self, event: &DaemonEvent) -> bool {
+        let filter = self.lock();
+        filter
+            .workspaces
+            .as_ref()
+            .is_none_or(|workspaces| workspaces.contains(event.workspace_id()))
+            && filter
+                .kinds
+                .as_ref()
+                .is_none_or(|kinds| kinds.contains(event.method()))
+    }
+
+    /// Handles the connection-level `subscribe`/`unsubscribe` methods, which change what
+    /// this connection receives rather than daemon state.
+    pub(super) fn try_handle(&self, method: &str, params: &Value) -> Option<Result<Value, String>> {
+        let result = match method {
+            "subscribe" => self.subscribe(params),
+            "unsubscribe" => self.unsubscribe(params),
+            _ => return None,
+        };
+        Some(result)
+    }
+
+    /// Applies the `subscribe` object a client may send with `auth`, replacing what the
+    /// connection subscribed to before. It takes effect before the event stream (re)starts,
+    /// so replayed events are filtered like live ones. An empty object forwards everything.
+    pub(super) fn restore_from_auth(&self, auth_params: &Value) -> Result<(), String> {
+        let Some(params) = auth_params.get("subscribe") else {
+            return Ok(());
+        };
+        let (workspace_ids, kinds) = parse_lists(params)?;
+        *self.lock() = EventFilter {
+            workspaces: workspace_ids.map(HashSet::from_iter),
+            kinds: kinds.map(HashSet::from_iter),
+        };
+        Ok(())
+    }
+
+    fn subscribe(&self, params: &Value) -> Result<Value, String> {
+        let (workspace_ids, kinds) = parse_subscription(params)?;
+        let mut filter = self.lock();
+        if let Some(workspace_ids) = workspace_ids {
+            filter
+                .workspaces
+                .get_or_insert_with(HashSet::new)
+                .extend(workspace_ids);
+        }
+        if let Some(kinds) = kinds {
+            filter.kinds.get_or_insert_with(HashSet::new).extend(kinds);
+        }
+        Ok(filter.summary())
+    }
+
+    fn unsubscribe(&self, params: &Value) -> Result<Value, String> {
+        let (workspace_ids, kinds) = parse_subscription(params)?;
+        let mut filter = self.lock();
+        if let Some(workspace_ids) = workspace_ids {
+            let workspaces = filter
+                .workspaces
+                .as_mut()
+                .ok_or("not subscribed to individual workspaces")?;
+            for workspace_id in &workspace_ids {
+                workspaces.remove(workspace_id);
+            }
+        }
+        if let Some(kinds) = kinds {
+            let subscribed = filter
+                .kinds
+                .as_mut()
+                .ok_or("not subscribed to individual event kinds")?;
+            for kind in &kinds {
+                subscribed.remove(kind);
+            }
+        }
+        Ok(filter.summary())
+    }
+}
+
+impl EventFilter {
+    fn summary(&self) -> Value {
+        let sorted = |items: &Option<HashSet<String>>| {
+            items.as_ref().map(|items| {
+                let mut items = items.iter().cloned().collect::<Vec<_>>();
+                items.sort();
+                items
+            })
+        };
+        json!({
+            "workspaceIds": sorted(&self.workspaces),
+            "kinds": sorted(&self.kinds),
+        })
+    }
+}
+
+type SubscriptionParams = (Option<Vec<String>>, Option<Vec<String>>);
+
+fn parse_subscription(params: &Value) -> Result<SubscriptionParams, String> {
+    let (workspace_ids, kinds) = parse_lists(params)?;
+    if workspace_ids.is_none() && kinds.is_none() {
+        return Err("expected `workspaceIds` or `kinds`".to_string());
+    }
+    Ok((workspace_ids, kinds))
+}
+
+fn parse_lists(params: &Value) -> Result<SubscriptionParams, String> {
+    let workspace_ids = parse_optional_string_array(params, "workspaceIds");
+    let kinds = parse_optional_string_array(params, "kinds");
+    if let Some(kind) = kinds
+        .iter()
+        .flatten()
+        .find(|kind| !EVENT_KINDS.contains(&kind.as_str()))
+    {
+        return Err(format!("unknown event kind: {kind}"));
+    }
+    Ok((workspace_ids, kinds))
+}
@@ -0,0 +1,77 @@
+use super::*;
+
+use std::path::Path;
+
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use shared::daemon_tls_core::{certificate_fingerprint, daemon_tls_provider};
+use tokio_rustls::TlsAcceptor;
+
+const TLS_DIR: &str = "tls";
+const CERT_FILE: &str = "daemon-cert.pem";
+const KEY_FILE: &str = "daemon-key.pem";
+
+pub(super) struct DaemonTls {
+    pub(super) acceptor: TlsAcceptor,
+    pub(super) fingerprint: String,
+}
+
+/// Loads the daemon certificate from `<data-dir>/tls`, creating a self-signed one on first start.
+/// Clients pin its fingerprint, so it is kept across restarts.
+pub(super) fn load_or_create_daemon_tls(data_dir: &Path) -> Result<DaemonTls, String> {
+    let tls_dir = data_dir.join(TLS_DIR);
+    let cert_path = tls_dir.join(CERT_FILE);
+    let key_path = tls_dir.join(KEY_FILE);
+    if !cert_path.is_file() || !key_path.is_file() {
+        create_self_signed_identity(&tls_dir, &cert_path, &key_path)?;
+    }
+
+    let cert = CertificateDer::from_pem_file(&cert_path)
+        .map_err(|err| format!("Failed to read {}: {err}", cert_path.display()))?;
+    let key = PrivateKeyDer::from_pem_file(&key_path)
+        .map_err(|err| format!("Failed to read {}: {err}", key_path.display()))?;
+    let fingerprint = certificate_fingerprint(cert.as_ref());
+
+    let config = rustls::ServerConfig::builder_with_provider(daemon_tls_provider())
+        .with_safe_default_protocol_versions()
+        .map_err(|err| err.to_string())?
+        .with_no_client_auth()
+        .with_single_cert(vec![cert], key)
+        .map_err(|err| format!("Invalid daemon TLS certificate: {err}"))?;
+
+    Ok(DaemonTls {
+        acceptor: TlsAcceptor::from(Arc::new(config)),
+        fingerprint,
+    })
+}
+
+fn create_self_signed_identity(
+    tls_dir: &Path,
+    cert_path: &Path,
+    key_path: &Path,
+) -> Result<(), String> {
+    let subject_alt_names = vec!["codex-monitor-daemon".to_string(), "localhost".to_string()];
+    let certified = rcgen::generate_simple_self_signed(subject_alt_names)
+        .map_err(|err| format!("Failed to generate daemon TLS certificate: {err}"))?;
+
+    std::fs::create_dir_all(tls_dir)
+        .map_err(|err| format!("Failed to create {}: {err}", tls_dir.display()))?;
+    write_private_key(key_path, &certified.key_pair.serialize_pem())?;
+    std::fs::write(cert_path, certified.cert.pem())
+        .map_err(|err| format!("Failed to write {}: {err}", cert_path.display()))
+}
+
+fn write_private_key(path: &Path, pem: &str) -> Result<(), String> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+    std::io::Write::write_all(&mut file, pem.as_bytes())
+        .map_err(|err| format!("Failed to write {}: {err}", path.display()))
+}
@@ -1,16 +1,22 @@
+use super::access::{self, ClientAccess};
+use super::event_log::{parse_event_resume, EventLog, EventResume};
 use super::rpc::{
     build_error_response, build_result_response, forward_events, parse_auth_token,
-    spawn_rpc_response_task,
+    spawn_rpc_response_task, RpcConnection,
 };
 use super::*;
 
-pub(super) async fn handle_client(
-    socket: TcpStream,
+use tokio::io::{AsyncRead, AsyncWrite};
+
+pub(super) async fn handle_client<S>(
+    socket: S,
     config: Arc<DaemonConfig>,
     state: Arc<DaemonState>,
-    events: broadcast::Sender<DaemonEvent>,
-) {
-    let (reader, mut writer) = socket.into_split();
+    events: Arc<EventLog>,
+) where
+    S: AsyncRead + AsyncWrite + Send + 'static,
+{
+    let (reader, mut writer) = tokio::io::split(socket);
     let mut lines = BufReader::new(reader).lines();
 
     let (out_tx, mut out_rx) = mpsc::unbounded_channel::<String>();
@@ -25,15 +31,19 @@ pub(super) async fn handle_client(
         }
     });
 
-    let mut authenticated = config.token.is_none();
+    let mut connection: Option<RpcConnection> = None;
     let mut events_task: Option<tokio::task::JoinHandle<()>> = None;
-    let request_limiter = Arc::new(Semaphore::new(MAX_IN_FLIGHT_RPC_PER_CONNECTION));
-    let client_version = format!("daemon-{}", env!("CARGO_PKG_VERSION"));
 
-    if authenticated {
-        let rx = events.subscribe();
-        let out_tx_events = out_tx.clone();
-        events_task = Some(tokio::spawn(forward_events(rx, out_tx_events)));
+    if config.token.is_none() {
+        let unrestricted = RpcConnection::new(ClientAccess::Unrestricted);
+        events_task = Some(tokio::spawn(forward_events(
+            Arc::clone(&events),
+            EventResume::default(),
+            out_tx.clone(),
+            Arc::clone(&state),
+            unrestricted.clone(),
+        )));
+        connection = Some(unrestricted);
     }
 
     while let Ok(Some(line)) = lines.next_line().await {
@@ -55,7 +65,7 @@ pub(super) async fn handle_client(
             .to_string();
         let params = message.get("params").cloned().unwrap_or(Value::Null);
 
-        if !authenticated {
+        let Some(connection) = &connection else {
             if method != "auth" {
                 if let Some(response) = build_error_response(id, "unauthorized") {
                     let _ = out_tx.send(response);
@@ -63,26 +73,38 @@ pub(super) async fn handle_client(
                 continue;
             }
 
-            let expected = config.token.clone().unwrap_or_default();
             let provided = parse_auth_token(&params).unwrap_or_default();
-            if expected != provided {
+            let Some(access) = access::authenticate(&config, &state, &provided).await else {
                 if let Some(response) = build_error_response(id, "invalid token") {
                     let _ = out_tx.send(response);
                 }
                 continue;
-            }
+            };
 
-            authenticated = true;
-            if let Some(response) = build_result_response(id, json!({ "ok": true })) {
+            // The subscription is in place before the stream starts, so the replay is
+            // already filtered.
+            let authenticated = RpcConnection::new(access);
+            if let Err(err) = authenticated.subscriptions.restore_from_auth(&params) {
+                if let Some(response) = build_error_response(id, &err) {
+                    let _ = out_tx.send(response);
+                }
+                continue;
+            }
+            if let Some(response) = build_result_response(id, auth_result(&events)) {
                 let _ = out_tx.send(response);
             }
 
-            let rx = events.subscribe();
-            let out_tx_events = out_tx.clone();
-            events_task = Some(tokio::spawn(forward_events(rx, out_tx_events)));
+            events_task = Some(tokio::spawn(forward_events(
+                Arc::clone(&events),
+                parse_event_resume(&params),
+                out_tx.clone(),
+                Arc::clone(&state),
+                authenticated.clone(),
+            )));
+            connection = Some(authenticated);
 
             continue;
-        }
+        };
 
         spawn_rpc_response_task(
             Arc::clone(&state),
@@ -90,8 +112,7 @@ pub(super) async fn handle_client(
             id,
             method,
             params,
-            client_version.clone(),
-            Arc::clone(&request_limiter),
+            connection.clone(),
         );
     }
 
@@ -100,25 +121,34 @@ pub(super) async fn handle_client(
         task.abort();
     }
     write_task.abort();
+    if let Some(connection) = connection {
+        connection.close(&state).await;
+    }
+}
+
+fn auth_result(events: &EventLog) -> Value {
+    json!({ "ok": true, "streamId": events.stream_id() })
 }
 
+/// Handles one line from the Orbit relay. Returns where to restart the event stream when a
+/// client reconnected through the relay and asked for a replay.
 fn handle_orbit_line(
     line: &str,
     state: Arc<DaemonState>,
+    events: &EventLog,
     out_tx: mpsc::UnboundedSender<String>,
-    client_version: String,
-    request_limiter: Arc<Semaphore>,
-) {
+    connection: RpcConnection,
+) -> Option<EventResume> {
     let message: Value = match serde_json::from_str(line) {
         Ok(value) => value,
-        Err(_) => return,
+        Err(_) => return None,
     };
 
     if let Some(message_type) = message.get("type").and_then(Value::as_str) {
         if message_type.eq_ignore_ascii_case("ping") {
             let _ = out_tx.send(json!({ "type": "pong" }).to_string());
         }
-        return;
+        return None;
     }
 
     let id = message.get("id").and_then(|value| value.as_u64());
@@ -129,31 +159,31 @@ fn handle_orbit_line(
         .to_string();
     let params = message.get("params").cloned().unwrap_or(Value::Null);
     if method.is_empty() {
-        return;
+        return None;
     }
 
     if method == "auth" {
-        if let Some(response) = build_result_response(id, json!({ "ok": true })) {
+        if let Err(err) = connection.subscriptions.restore_from_auth(&params) {
+            if let Some(response) = build_error_response(id, &err) {
+                let _ = out_tx.send(response);
+            }
+            return None;
+        }
+        if let Some(response) = build_result_response(id, auth_result(events)) {
             let _ = out_tx.send(response);
         }
-        return;
+        let resume = parse_event_resume(&params);
+        return (resume != EventResume::default()).then_some(resume);
     }
 
-    spawn_rpc_response_task(
-        state,
-        out_tx,
-        id,
-        method,
-        params,
-        client_version,
-        request_limiter,
-    );
+    spawn_rpc_response_task(state, out_tx, id, method, params, connection);
+    None
 }
 
 pub(super) async fn run_orbit_mode(
     config: Arc<DaemonConfig>,
     state: Arc<DaemonState>,
-    events_tx: broadcast::Sender<DaemonEvent>,
+    events: Arc<EventLog>,
 ) {
     let orbit_url = config.orbit_url.clone().unwrap_or_default();
     let runner_name = config
@@ -204,11 +234,18 @@ pub(super) async fn run_orbit_mode(
             }
         });
 
-        let events_task = {
-            let rx = events_tx.subscribe();
-            let out_tx_events = out_tx.clone();
-            tokio::spawn(forward_events(rx, out_tx_events))
+        // Orbit authenticates the runner connection itself, so it is not token-scoped.
+        let connection = RpcConnection::new(ClientAccess::Unrestricted);
+        let spawn_events_task = |resume: EventResume| {
+            tokio::spawn(forward_events(
+                Arc::clone(&events),
+                resume,
+                out_tx.clone(),
+                Arc::clone(&state),
+                connection.clone(),
+            ))
         };
+        let mut events_task = spawn_events_task(EventResume::default());
 
         let _ = out_tx.send(
             json!({
@@ -220,31 +257,35 @@ pub(super) async fn run_orbit_mode(
             .to_string(),
         );
 
-        let client_version = format!("daemon-{}", env!("CARGO_PKG_VERSION"));
-        let request_limiter = Arc::new(Semaphore::new(MAX_IN_FLIGHT_RPC_PER_CONNECTION));
         while let Some(frame) = reader.next().await {
             match frame {
                 Ok(Message::Text(text)) => {
                     for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
-                        handle_orbit_line(
+                        if let Some(resume) = handle_orbit_line(
                             line,
                             Arc::clone(&state),
+                            &events,
                             out_tx.clone(),
-                            client_version.clone(),
-                            Arc::clone(&request_limiter),
-                        );
+                            connection.clone(),
+                        ) {
+                            events_task.abort();
+                            events_task = spawn_events_task(resume);
+                        }
                     }
                 }
                 Ok(Message::Binary(bytes)) => {
                     if let Ok(text) = String::from_utf8(bytes.to_vec()) {
                         for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
-                            handle_orbit_line(
+                            if let Some(resume) = handle_orbit_line(
                                 line,
                                 Arc::clone(&state),
+                                &events,
                                 out_tx.clone(),
-                                client_version.clone(),
-                                Arc::clone(&request_limiter),
-                            );
+                                connection.clone(),
+                            ) {
+                                events_task.abort();
+                                events_task = spawn_events_task(resume);
+                            }
                         }
                     }
                 }
@@ -261,6 +302,7 @@ pub(super) async fn run_orbit_mode(
         drop(out_tx);
         events_task.abort();
         write_task.abort();
+        connection.close(&state).await;
 
         eprintln!(
             "orbit runner disconnected. reconnecting in {}s",
@@ -1,6 +1,8 @@
 use tauri::{AppHandle, Emitter};
 
-use crate::backend::events::{AppServerEvent, EventSink, TerminalExit, TerminalOutput};
+use crate::backend::events::{
+    AppServerEvent, EventSink, ForgeExecutionEvent, TerminalExit, TerminalOutput,
+};
 
 #[derive(Clone)]
 pub(crate) struct TauriEventSink {
@@ -25,4 +27,8 @@ impl EventSink for TauriEventSink {
     fn emit_terminal_exit(&self, event: TerminalExit) {
         let _ = self.app.emit("terminal-exit", event);
     }
+
+    fn emit_forge_execution_event(&self, event: ForgeExecutionEvent) {
+        let _ = self.app.emit("forge-execution-event", event);
+    }
 }
@@ -1,17 +1,30 @@
 use serde_json::json;
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
 use tauri::{AppHandle, Manager, State};
+use tokio::sync::Mutex;
 
+use crate::codex::WorkspaceSession;
+use crate::event_sink::TauriEventSink;
 use crate::remote_backend;
 use crate::shared::forge_execute_core;
 use crate::shared::forge_plans_core;
+use crate::shared::forge_template_sources_core::{
+    add_template_source_core, install_template_from_source_core,
+    installed_template_upstream_dir_core, list_all_templates_core, list_template_sources_core,
+    remove_template_source_core, ForgeTemplateSourceV1,
+};
 use crate::shared::forge_templates_core::{
-    find_bundled_templates_root_near_exe, install_bundled_template_core,
-    list_bundled_templates_core, read_installed_template_lock_core, uninstall_template_core,
+    find_bundled_templates_root_near_exe, forge_template_upgrade_preview_core,
+    forge_upgrade_template_core, read_installed_template_lock_core, uninstall_template_core,
     read_installed_template_plan_prompt_core, sync_agent_skills_into_repo_agents_dir_core,
-    ForgeBundledTemplateInfo, ForgeTemplateLockV1,
+    ForgeBundledTemplateInfo, ForgeTemplateLockV1, ForgeTemplateSourceKind,
+    ForgeTemplateUpgradePreviewV1, ForgeTemplateUpgradeResultV1,
 };
 use crate::state::AppState;
+use crate::types::WorkspaceEntry;
+use crate::workspaces;
 
 fn bundled_templates_root_for_app(app: &AppHandle) -> Result<PathBuf, String> {
     if let Ok(resource_dir) = app.path().resource_dir() {
@@ -42,6 +55,14 @@ fn bundled_templates_root_for_app(app: &AppHandle) -> Result<PathBuf, String> {
         .ok_or_else(|| "Unable to locate bundled forge templates".to_string())
 }
 
+fn app_data_dir(state: &AppState) -> Result<PathBuf, String> {
+    state
+        .settings_path
+        .parent()
+        .map(|path| path.to_path_buf())
+        .ok_or_else(|| "Unable to resolve app data dir.".to_string())
+}
+
 async fn workspace_root_for_id(state: &AppState, workspace_id: &str) -> Result<PathBuf, String> {
     let workspaces = state.workspaces.lock().await;
     let entry = workspaces
@@ -63,7 +84,73 @@ pub(crate) async fn forge_list_bundled_templates(
     }
 
     let root = bundled_templates_root_for_app(&app)?;
-    list_bundled_templates_core(&root)
+    let data_dir = app_data_dir(&state)?;
+    // Sources may need a first clone or extraction, so keep them off the async runtime.
+    tokio::task::spawn_blocking(move || list_all_templates_core(&root, &data_dir))
+        .await
+        .map_err(|err| err.to_string())?
+}
+
+#[tauri::command]
+pub(crate) async fn forge_list_template_sources(
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Vec<ForgeTemplateSourceV1>, String> {
+    if remote_backend::is_remote_mode(&state).await {
+        let response =
+            remote_backend::call_remote(&state, app, "forge_list_template_sources", json!({}))
+                .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    list_template_sources_core(&app_data_dir(&state)?)
+}
+
+#[tauri::command]
+pub(crate) async fn forge_add_template_source(
+    kind: ForgeTemplateSourceKind,
+    location: String,
+    git_ref: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<ForgeTemplateSourceV1, String> {
+    if remote_backend::is_remote_mode(&state).await {
+        let response = remote_backend::call_remote(
+            &state,
+            app,
+            "forge_add_template_source",
+            json!({ "kind": kind, "location": location, "gitRef": git_ref }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    let data_dir = app_data_dir(&state)?;
+    tokio::task::spawn_blocking(move || {
+        add_template_source_core(&data_dir, kind, &location, git_ref.as_deref())
+    })
+    .await
+    .map_err(|err| err.to_string())?
+}
+
+#[tauri::command]
+pub(crate) async fn forge_remove_template_source(
+    source_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    if remote_backend::is_remote_mode(&state).await {
+        remote_backend::call_remote(
+            &state,
+            app,
+            "forge_remove_template_source",
+            json!({ "sourceId": source_id }),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    remove_template_source_core(&app_data_dir(&state)?, &source_id)
 }
 
 #[tauri::command]
@@ -94,6 +181,7 @@ pub(crate) async fn forge_get_installed_template(
 pub(crate) async fn forge_install_template(
     workspace_id: String,
     template_id: String,
+    source_id: Option<String>,
     state: State<'_, AppState>,
     app: AppHandle,
 ) -> Result<ForgeTemplateLockV1, String> {
@@ -102,21 +190,100 @@ pub(crate) async fn forge_install_template(
             &state,
             app,
             "forge_install_template",
-            json!({ "workspaceId": workspace_id, "templateId": template_id }),
+            json!({
+                "workspaceId": workspace_id,
+                "templateId": template_id,
+                "sourceId": source_id,
+            }),
         )
         .await?;
         return serde_json::from_value(response).map_err(|err| err.to_string());
     }
 
     let templates_root = bundled_templates_root_for_app(&app)?;
+    let data_dir = app_data_dir(&state)?;
     let workspace_root = workspace_root_for_id(&state, &workspace_id).await?;
-    let lock = install_bundled_template_core(&templates_root, &workspace_root, template_id.trim())?;
+    let install_root = workspace_root.clone();
+    let lock = tokio::task::spawn_blocking(move || {
+        install_template_from_source_core(
+            &templates_root,
+            &data_dir,
+            &install_root,
+            source_id.as_deref(),
+            template_id.trim(),
+        )
+    })
+    .await
+    .map_err(|err| err.to_string())??;
     if let Err(err) = sync_agent_skills_into_repo_agents_dir_core(&workspace_root) {
         eprintln!("forge_install_template: failed to sync skills into .agents: {err}");
     }
     Ok(lock)
 }
 
+#[tauri::command]
+pub(crate) async fn forge_template_upgrade_preview(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<ForgeTemplateUpgradePreviewV1, String> {
+    if remote_backend::is_remote_mode(&state).await {
+        let response = remote_backend::call_remote(
+            &state,
+            app,
+            "forge_template_upgrade_preview",
+            json!({ "workspaceId": workspace_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    let templates_root = bundled_templates_root_for_app(&app)?;
+    let data_dir = app_data_dir(&state)?;
+    let workspace_root = workspace_root_for_id(&state, &workspace_id).await?;
+    tokio::task::spawn_blocking(move || {
+        let upstream_dir =
+            installed_template_upstream_dir_core(&templates_root, &data_dir, &workspace_root)?;
+        forge_template_upgrade_preview_core(&workspace_root, &upstream_dir)
+    })
+    .await
+    .map_err(|err| err.to_string())?
+}
+
+#[tauri::command]
+pub(crate) async fn forge_upgrade_template(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<ForgeTemplateUpgradeResultV1, String> {
+    if remote_backend::is_remote_mode(&state).await {
+        let response = remote_backend::call_remote(
+            &state,
+            app,
+            "forge_upgrade_template",
+            json!({ "workspaceId": workspace_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    let templates_root = bundled_templates_root_for_app(&app)?;
+    let data_dir = app_data_dir(&state)?;
+    let workspace_root = workspace_root_for_id(&state, &workspace_id).await?;
+    let upgrade_root = workspace_root.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let upstream_dir =
+            installed_template_upstream_dir_core(&templates_root, &data_dir, &upgrade_root)?;
+        forge_upgrade_template_core(&upgrade_root, &upstream_dir)
+    })
+    .await
+    .map_err(|err| err.to_string())??;
+    if let Err(err) = sync_agent_skills_into_repo_agents_dir_core(&workspace_root) {
+        eprintln!("forge_upgrade_template: failed to sync skills into .agents: {err}");
+    }
+    Ok(result)
+}
+
 #[tauri::command]
 pub(crate) async fn forge_uninstall_template(
     workspace_id: String,
@@ -159,6 +326,28 @@ pub(crate) async fn forge_list_plans(
     forge_plans_core::list_plans_core(&workspace_root)
 }
 
+#[tauri::command]
+pub(crate) async fn forge_validate_plan(
+    workspace_id: String,
+    plan_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<forge_plans_core::ForgePlanValidationV1, String> {
+    if remote_backend::is_remote_mode(&state).await {
+        let response = remote_backend::call_remote(
+            &state,
+            app,
+            "forge_validate_plan",
+            json!({ "workspaceId": workspace_id, "planId": plan_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    let workspace_root = workspace_root_for_id(&state, &workspace_id).await?;
+    forge_plans_core::forge_validate_plan_core(&workspace_root, &plan_id)
+}
+
 #[tauri::command]
 pub(crate) async fn forge_get_plan_prompt(
     workspace_id: String,
@@ -202,7 +391,13 @@ pub(crate) async fn forge_prepare_execution(
     }
 
     let workspace_root = workspace_root_for_id(&state, &workspace_id).await?;
-    forge_execute_core::forge_prepare_execution_core(&workspace_root, &plan_id).await
+    forge_execute_core::forge_prepare_execution_core(
+        &workspace_root,
+        &plan_id,
+        &workspace_id,
+        &state.forge_execution_cancels,
+    )
+    .await
 }
 
 #[tauri::command]
@@ -246,7 +441,16 @@ pub(crate) async fn forge_get_next_phase_prompt(
     }
 
     let workspace_root = workspace_root_for_id(&state, &workspace_id).await?;
-    forge_execute_core::forge_get_next_phase_prompt_core(&workspace_root, &plan_id).await
+    let event_sink = TauriEventSink::new(app);
+    forge_execute_core::forge_get_next_phase_prompt_core(
+        &state.workspaces,
+        &workspace_root,
+        &plan_id,
+        &workspace_id,
+        &event_sink,
+        &state.forge_execution_cancels,
+    )
+    .await
 }
 
 #[tauri::command]
@@ -279,6 +483,7 @@ pub(crate) async fn forge_run_phase_checks(
     plan_id: String,
     task_id: String,
     phase_id: String,
+    thread_id: Option<String>,
     state: State<'_, AppState>,
     app: AppHandle,
 ) -> Result<forge_execute_core::ForgeRunPhaseChecksResponseV1, String> {
@@ -287,13 +492,527 @@ pub(crate) async fn forge_run_phase_checks(
             &state,
             app,
             "forge_run_phase_checks",
-            json!({ "workspaceId": workspace_id, "planId": plan_id, "taskId": task_id, "phaseId": phase_id }),
+            json!({
+                "workspaceId": workspace_id,
+                "planId": plan_id,
+                "taskId": task_id,
+                "phaseId": phase_id,
+                "threadId": thread_id,
+            }),
         )
         .await?;
         return serde_json::from_value(response).map_err(|err| err.to_string());
     }
 
     let workspace_root = workspace_root_for_id(&state, &workspace_id).await?;
-    forge_execute_core::forge_run_phase_checks_core(&workspace_root, &plan_id, &task_id, &phase_id)
-        .await
+    let event_sink = TauriEventSink::new(app);
+    forge_execute_core::forge_run_phase_checks_core(
+        &workspace_root,
+        forge_execute_core::ForgeRunPhaseChecksRequest {
+            plan_id: &plan_id,
+            task_id: &task_id,
+            phase_id: &phase_id,
+            thread_id: thread_id.as_deref(),
+        },
+        &workspace_id,
+        &event_sink,
+        &state.forge_execution_cancels,
+    )
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn forge_get_run_history(
+    workspace_id: String,
+    plan_id: String,
+    task_id: Option<String>,
+    limit: Option<u32>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<forge_execute_core::ForgeRunHistoryV1, String> {
+    if remote_backend::is_remote_mode(&state).await {
+        let response = remote_backend::call_remote(
+            &state,
+            app,
+            "forge_get_run_history",
+            json!({
+                "workspaceId": workspace_id,
+                "planId": plan_id,
+                "taskId": task_id,
+                "limit": limit,
+            }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    let workspace_root = workspace_root_for_id(&state, &workspace_id).await?;
+    forge_execute_core::forge_get_run_history_core(
+        &workspace_root,
+        &plan_id,
+        task_id.as_deref(),
+        limit.map(|value| value as usize),
+    )
+}
+
+#[tauri::command]
+pub(crate) async fn forge_rollback_task(
+    workspace_id: String,
+    plan_id: String,
+    task_id: String,
+    cascade: Option<bool>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<forge_execute_core::ForgeTaskRollbackResultV1, String> {
+    if remote_backend::is_remote_mode(&state).await {
+        let response = remote_backend::call_remote(
+            &state,
+            app,
+            "forge_rollback_task",
+            json!({
+                "workspaceId": workspace_id,
+                "planId": plan_id,
+                "taskId": task_id,
+                "cascade": cascade,
+            }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    let workspace_root = workspace_root_for_id(&state, &workspace_id).await?;
+    forge_execute_core::forge_rollback_task_core(
+        &workspace_root,
+        &plan_id,
+        &task_id,
+        cascade.unwrap_or(false),
+    )
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn forge_analyze_plan(
+    workspace_id: String,
+    plan_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<forge_execute_core::ForgePlanAnalysisV1, String> {
+    if remote_backend::is_remote_mode(&state).await {
+        let response = remote_backend::call_remote(
+            &state,
+            app,
+            "forge_analyze_plan",
+            json!({ "workspaceId": workspace_id, "planId": plan_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    let workspace_root = workspace_root_for_id(&state, &workspace_id).await?;
+    forge_execute_core::forge_analyze_plan_core(&workspace_root, &plan_id)
+}
+
+#[tauri::command]
+pub(crate) async fn forge_plan_cost(
+    workspace_id: String,
+    plan_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<forge_execute_core::ForgePlanCostV1, String> {
+    if remote_backend::is_remote_mode(&state).await {
+        let response = remote_backend::call_remote(
+            &state,
+            app,
+            "forge_plan_cost",
+            json!({ "workspaceId": workspace_id, "planId": plan_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    let workspace_root = workspace_root_for_id(&state, &workspace_id).await?;
+    forge_execute_core::forge_plan_cost_core(&state.workspaces, &workspace_root, &plan_id).await
+}
+
+#[tauri::command]
+pub(crate) async fn forge_update_plan(
+    workspace_id: String,
+    plan_id: String,
+    operations: Vec<forge_execute_core::ForgePlanOperationV1>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<forge_execute_core::ForgePlanUpdateResultV1, String> {
+    if remote_backend::is_remote_mode(&state).await {
+        let operations = serde_json::to_value(&operations).map_err(|err| err.to_string())?;
+        let response = remote_backend::call_remote(
+            &state,
+            app,
+            "forge_update_plan",
+            json!({
+                "workspaceId": workspace_id,
+                "planId": plan_id,
+                "operations": operations,
+            }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    let workspace_root = workspace_root_for_id(&state, &workspace_id).await?;
+    forge_execute_core::forge_update_plan_core(&workspace_root, &plan_id, operations).await
+}
+
+#[tauri::command]
+pub(crate) async fn forge_approve_phase(
+    workspace_id: String,
+    plan_id: String,
+    task_id: String,
+    phase_id: String,
+    reviewer: String,
+    notes: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<forge_execute_core::ForgePhaseReviewResultV1, String> {
+    if remote_backend::is_remote_mode(&state).await {
+        let response = remote_backend::call_remote(
+            &state,
+            app,
+            "forge_approve_phase",
+            json!({
+                "workspaceId": workspace_id,
+                "planId": plan_id,
+                "taskId": task_id,
+                "phaseId": phase_id,
+                "reviewer": reviewer,
+                "notes": notes,
+            }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    let workspace_root = workspace_root_for_id(&state, &workspace_id).await?;
+    let event_sink = TauriEventSink::new(app);
+    forge_execute_core::forge_approve_phase_core(
+        &workspace_root,
+        forge_execute_core::ForgePhaseReviewRequest {
+            plan_id: &plan_id,
+            task_id: &task_id,
+            phase_id: &phase_id,
+            reviewer: &reviewer,
+            notes: notes.as_deref(),
+        },
+        &workspace_id,
+        &event_sink,
+        &state.forge_execution_cancels,
+    )
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn forge_reject_phase(
+    workspace_id: String,
+    plan_id: String,
+    task_id: String,
+    phase_id: String,
+    reviewer: String,
+    notes: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<forge_execute_core::ForgePhaseReviewResultV1, String> {
+    if remote_backend::is_remote_mode(&state).await {
+        let response = remote_backend::call_remote(
+            &state,
+            app,
+            "forge_reject_phase",
+            json!({
+                "workspaceId": workspace_id,
+                "planId": plan_id,
+                "taskId": task_id,
+                "phaseId": phase_id,
+                "reviewer": reviewer,
+                "notes": notes,
+            }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    let workspace_root = workspace_root_for_id(&state, &workspace_id).await?;
+    forge_execute_core::forge_reject_phase_core(
+        &workspace_root,
+        forge_execute_core::ForgePhaseReviewRequest {
+            plan_id: &plan_id,
+            task_id: &task_id,
+            phase_id: &phase_id,
+            reviewer: &reviewer,
+            notes: Some(&notes),
+        },
+    )
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn forge_cancel_execution(
+    workspace_id: String,
+    plan_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<forge_execute_core::ForgeCancelExecutionResultV1, String> {
+    if remote_backend::is_remote_mode(&state).await {
+        let response = remote_backend::call_remote(
+            &state,
+            app,
+            "forge_cancel_execution",
+            json!({ "workspaceId": workspace_id, "planId": plan_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    forge_execute_core::forge_cancel_execution_core(
+        &state.forge_execution_cancels,
+        &workspace_id,
+        &plan_id,
+    )
+}
+
+impl forge_execute_core::ForgeRunHost for AppHandle {
+    type Sink = TauriEventSink;
+
+    fn sessions(&self) -> &Mutex<HashMap<String, Arc<WorkspaceSession>>> {
+        &self.state::<AppState>().inner().sessions
+    }
+
+    fn workspaces(&self) -> &Mutex<HashMap<String, WorkspaceEntry>> {
+        &self.state::<AppState>().inner().workspaces
+    }
+
+    fn forge_execution_cancels(&self) -> &forge_execute_core::ForgeExecutionCancels {
+        &self.state::<AppState>().inner().forge_execution_cancels
+    }
+
+    fn forge_runs(&self) -> &forge_execute_core::ForgeRuns {
+        &self.state::<AppState>().inner().forge_runs
+    }
+
+    fn event_sink(&self) -> TauriEventSink {
+        TauriEventSink::new(self.clone())
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn forge_start_run(
+    workspace_id: String,
+    plan_id: String,
+    options: Option<forge_execute_core::ForgeStartRunOptionsV1>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<forge_execute_core::ForgeRunStatusV1, String> {
+    if remote_backend::is_remote_mode(&state).await {
+        let response = remote_backend::call_remote(
+            &state,
+            app,
+            "forge_start_run",
+            json!({
+                "workspaceId": workspace_id,
+                "planId": plan_id,
+                "options": options,
+            }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    let workspace_root = workspace_root_for_id(&state, &workspace_id).await?;
+    forge_execute_core::forge_start_run_core(
+        app,
+        &workspace_id,
+        workspace_root,
+        &plan_id,
+        options.unwrap_or_default(),
+    )
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn forge_stop_run(
+    workspace_id: String,
+    plan_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<forge_execute_core::ForgeRunStatusV1, String> {
+    if remote_backend::is_remote_mode(&state).await {
+        let response = remote_backend::call_remote(
+            &state,
+            app,
+            "forge_stop_run",
+            json!({ "workspaceId": workspace_id, "planId": plan_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    forge_execute_core::forge_stop_run_core(
+        &state.forge_runs,
+        &state.forge_execution_cancels,
+        &workspace_id,
+        &plan_id,
+    )
+}
+
+#[tauri::command]
+pub(crate) async fn forge_run_status(
+    workspace_id: String,
+    plan_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Option<forge_execute_core::ForgeRunStatusV1>, String> {
+    if remote_backend::is_remote_mode(&state).await {
+        let response = remote_backend::call_remote(
+            &state,
+            app,
+            "forge_run_status",
+            json!({ "workspaceId": workspace_id, "planId": plan_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    forge_execute_core::forge_run_status_core(&state.forge_runs, &workspace_id, &plan_id)
+}
+
+#[tauri::command]
+pub(crate) async fn forge_get_runnable_tasks(
+    workspace_id: String,
+    plan_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Vec<forge_execute_core::ForgeRunnableTaskV1>, String> {
+    if remote_backend::is_remote_mode(&state).await {
+        let response = remote_backend::call_remote(
+            &state,
+            app,
+            "forge_get_runnable_tasks",
+            json!({ "workspaceId": workspace_id, "planId": plan_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    let workspace_root = workspace_root_for_id(&state, &workspace_id).await?;
+    forge_execute_core::forge_get_runnable_tasks_core(&workspace_root, &plan_id)
+}
+
+#[tauri::command]
+pub(crate) async fn forge_get_task_phase_prompt(
+    workspace_id: String,
+    plan_id: String,
+    task_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Option<forge_execute_core::ForgeNextPhasePromptV1>, String> {
+    if remote_backend::is_remote_mode(&state).await {
+        let response = remote_backend::call_remote(
+            &state,
+            app,
+            "forge_get_task_phase_prompt",
+            json!({ "workspaceId": workspace_id, "planId": plan_id, "taskId": task_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    let workspace_root = workspace_root_for_id(&state, &workspace_id).await?;
+    let event_sink = TauriEventSink::new(app);
+    forge_execute_core::forge_get_task_phase_prompt_core(
+        &state.workspaces,
+        &workspace_root,
+        &plan_id,
+        &task_id,
+        &workspace_id,
+        &event_sink,
+    )
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn forge_start_task_worktree(
+    workspace_id: String,
+    plan_id: String,
+    task_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<forge_execute_core::ForgeTaskWorktreeV1, String> {
+    if remote_backend::is_remote_mode(&state).await {
+        let response = remote_backend::call_remote(
+            &state,
+            app,
+            "forge_start_task_worktree",
+            json!({ "workspaceId": workspace_id, "planId": plan_id, "taskId": task_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    let workspace_root = workspace_root_for_id(&state, &workspace_id).await?;
+    let branch = forge_execute_core::forge_task_worktree_branch(&plan_id, &task_id)?;
+    forge_execute_core::forge_check_task_worktree_core(&workspace_root, &plan_id, &task_id)?;
+    let worktree = workspaces::add_worktree(
+        workspace_id,
+        branch.clone(),
+        Some(format!("{} {}", plan_id.trim(), task_id.trim())),
+        Some(true),
+        state.clone(),
+        app.clone(),
+    )
+    .await?;
+    let attached = forge_execute_core::forge_attach_task_worktree_core(
+        &workspace_root,
+        &plan_id,
+        &task_id,
+        &worktree.id,
+        &worktree.path,
+        &branch,
+    )
+    .await;
+    if attached.is_err() {
+        if let Err(err) = workspaces::remove_worktree(worktree.id, state.clone(), app).await {
+            eprintln!("forge_start_task_worktree: failed to remove task worktree: {err}");
+        }
+    }
+    attached
+}
+
+#[tauri::command]
+pub(crate) async fn forge_merge_task_worktree(
+    workspace_id: String,
+    plan_id: String,
+    task_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<forge_execute_core::ForgeTaskMergeResultV1, String> {
+    if remote_backend::is_remote_mode(&state).await {
+        let response = remote_backend::call_remote(
+            &state,
+            app,
+            "forge_merge_task_worktree",
+            json!({ "workspaceId": workspace_id, "planId": plan_id, "taskId": task_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    let workspace_root = workspace_root_for_id(&state, &workspace_id).await?;
+    let result =
+        forge_execute_core::forge_merge_task_worktree_core(&workspace_root, &plan_id, &task_id)
+            .await?;
+    if result.ok {
+        if let Some(worktree_id) = result.worktree_workspace_id.clone() {
+            if let Err(err) = workspaces::remove_worktree(worktree_id, state.clone(), app).await {
+                eprintln!("forge_merge_task_worktree: failed to remove task worktree: {err}");
+            }
+        }
+    }
+    Ok(result)
 }
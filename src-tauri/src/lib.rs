@@ -213,6 +213,7 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             settings::get_app_settings,
             settings::update_app_settings,
+            settings::forget_remote_backend_tls_pin,
             settings::get_codex_config_path,
             files::file_read,
             files::file_write,
@@ -322,16 +323,37 @@ pub fn run() {
             tailscale::tailscale_daemon_stop,
             tailscale::tailscale_daemon_status,
             forge::forge_list_bundled_templates,
+            forge::forge_list_template_sources,
+            forge::forge_add_template_source,
+            forge::forge_remove_template_source,
             forge::forge_get_installed_template,
             forge::forge_install_template,
             forge::forge_uninstall_template,
+            forge::forge_template_upgrade_preview,
+            forge::forge_upgrade_template,
             forge::forge_list_plans,
+            forge::forge_validate_plan,
             forge::forge_get_plan_prompt,
             forge::forge_prepare_execution,
             forge::forge_reset_execution_progress,
             forge::forge_get_next_phase_prompt,
             forge::forge_get_phase_status,
             forge::forge_run_phase_checks,
+            forge::forge_cancel_execution,
+            forge::forge_start_run,
+            forge::forge_stop_run,
+            forge::forge_run_status,
+            forge::forge_get_run_history,
+            forge::forge_rollback_task,
+            forge::forge_update_plan,
+            forge::forge_analyze_plan,
+            forge::forge_plan_cost,
+            forge::forge_approve_phase,
+            forge::forge_reject_phase,
+            forge::forge_get_runnable_tasks,
+            forge::forge_get_task_phase_prompt,
+            forge::forge_start_task_worktree,
+            forge::forge_merge_task_worktree,
             is_mobile_runtime
         ])
         .build(tauri::generate_context!())
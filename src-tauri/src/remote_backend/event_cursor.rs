@@ -0,0 +1,94 @@
+use std::sync::Mutex;
+
+use serde_json::{Map, Value};
+
+#[derive(Default)]
+struct CursorState {
+    stream_id: Option<String>,
+    last_seq: u64,
+}
+
+/// The last daemon event the app received. It outlives individual connections so a
+/// reconnect can ask the daemon to replay what was missed.
+#[derive(Default)]
+pub(crate) struct RemoteEventCursor {
+    state: Mutex<CursorState>,
+}
+
+impl RemoteEventCursor {
+    fn lock(&self) -> std::sync::MutexGuard<'_, CursorState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// `streamId`/`sinceSeq` to send with `auth`, once the daemon's stream is known.
+    pub(crate) fn resume_params(&self) -> Map<String, Value> {
+        let state = self.lock();
+        let mut params = Map::new();
+        if let Some(stream_id) = &state.stream_id {
+            params.insert("streamId".to_string(), Value::from(stream_id.clone()));
+            params.insert("sinceSeq".to_string(), Value::from(state.last_seq));
+        }
+        params
+    }
+
+    /// Records the stream a connection was authenticated on. A different stream means the
+    /// daemon restarted or the app now talks to another daemon, so numbering starts over.
+    pub(crate) fn set_stream(&self, stream_id: &str) {
+        let mut state = self.lock();
+        if state.stream_id.as_deref() == Some(stream_id) {
+            return;
+        }
+        if state.stream_id.is_some() {
+            state.last_seq = 0;
+        }
+        state.stream_id = Some(stream_id.to_string());
+    }
+
+    /// Returns `false` for an event that was already delivered, e.g. replayed twice.
+    /// Events from daemons without sequence numbers are always delivered.
+    pub(crate) fn accept(&self, seq: Option<u64>) -> bool {
+        let Some(seq) = seq else {
+            return true;
+        };
+        let mut state = self.lock();
+        if seq <= state.last_seq {
+            return false;
+        }
+        state.last_seq = seq;
+        true
+    }
+
+    pub(crate) fn reset(&self) {
+        *self.lock() = CursorState::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RemoteEventCursor;
+    use serde_json::json;
+
+    #[test]
+    fn resumes_from_the_last_event_of_the_known_stream() {
+        let cursor = RemoteEventCursor::default();
+        assert!(cursor.resume_params().is_empty());
+
+        cursor.set_stream("stream-a");
+        assert!(cursor.accept(Some(1)));
+        assert!(cursor.accept(Some(2)));
+        assert!(!cursor.accept(Some(2)), "replayed events are dropped");
+        assert!(cursor.accept(None));
+        assert_eq!(
+            serde_json::Value::Object(cursor.resume_params()),
+            json!({ "streamId": "stream-a", "sinceSeq": 2 })
+        );
+
+        cursor.set_stream("stream-b");
+        assert!(cursor.accept(Some(1)), "a new stream restarts numbering");
+
+        cursor.reset();
+        assert!(cursor.resume_params().is_empty());
+    }
+}
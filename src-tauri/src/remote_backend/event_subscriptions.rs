@@ -0,0 +1,162 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use serde_json::{json, Value};
+
+/// Workspaces the app has open on the daemon. Each connection subscribes to their events
+/// only, instead of receiving every workspace's activity.
+#[derive(Default)]
+pub(crate) struct RemoteEventSubscriptions {
+    workspace_ids: Mutex<HashSet<String>>,
+}
+
+impl RemoteEventSubscriptions {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashSet<String>> {
+        self.workspace_ids
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// `subscribe` params that restore the subscription on a new connection, or `None` while
+    /// no workspace is open, since an empty list would filter out every event.
+    pub(crate) fn subscribe_params(&self) -> Option<Value> {
+        let mut workspace_ids = self.lock().iter().cloned().collect::<Vec<_>>();
+        if workspace_ids.is_empty() {
+            return None;
+        }
+        workspace_ids.sort();
+        Some(json!({ "workspaceIds": workspace_ids }))
+    }
+
+    /// The workspace to subscribe to before sending `method`, the first time a request
+    /// touches it.
+    pub(crate) fn workspace_to_open(&self, method: &str, params: &Value) -> Option<String> {
+        if is_workspace_removal(method) {
+            return None;
+        }
+        let key = if method == "connect_workspace" {
+            "id"
+        } else {
+            "workspaceId"
+        };
+        let workspace_id = params.get(key).and_then(Value::as_str)?;
+        self.lock()
+            .insert(workspace_id.to_string())
+            .then(|| workspace_id.to_string())
+    }
+
+    /// Workspaces to unsubscribe from once `method` succeeded: a removed workspace, or open
+    /// ones missing from the daemon's workspace list. Workspaces are only ever opened through
+    /// [`Self::workspace_to_open`], so the list never subscribes to anything.
+    pub(crate) fn workspaces_to_close(
+        &self,
+        method: &str,
+        params: &Value,
+        result: &Value,
+    ) -> Vec<String> {
+        let mut open = self.lock();
+        let mut closed = match method {
+            "list_workspaces" => {
+                let listed = result
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|workspace| workspace.get("id").and_then(Value::as_str))
+                    .collect::<HashSet<_>>();
+                open.iter()
+                    .filter(|workspace_id| !listed.contains(workspace_id.as_str()))
+                    .cloned()
+                    .collect::<Vec<_>>()
+            }
+            _ if is_workspace_removal(method) => params
+                .get("id")
+                .and_then(Value::as_str)
+                .map(|workspace_id| vec![workspace_id.to_string()])
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        closed.retain(|workspace_id| open.remove(workspace_id));
+        closed.sort();
+        closed
+    }
+}
+
+fn is_workspace_removal(method: &str) -> bool {
+    matches!(method, "remove_workspace" | "remove_worktree")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RemoteEventSubscriptions;
+    use serde_json::json;
+
+    #[test]
+    fn subscribes_once_per_workspace_the_app_touches() {
+        let subscriptions = RemoteEventSubscriptions::default();
+        assert_eq!(subscriptions.subscribe_params(), None);
+
+        assert_eq!(
+            subscriptions.workspace_to_open("connect_workspace", &json!({ "id": "ws-2" })),
+            Some("ws-2".to_string())
+        );
+        assert_eq!(
+            subscriptions.workspace_to_open("list_threads", &json!({ "workspaceId": "ws-1" })),
+            Some("ws-1".to_string())
+        );
+        assert_eq!(
+            subscriptions.workspace_to_open("list_threads", &json!({ "workspaceId": "ws-1" })),
+            None
+        );
+        assert_eq!(
+            subscriptions.workspace_to_open("list_workspaces", &json!({})),
+            None
+        );
+        assert_eq!(
+            subscriptions.subscribe_params(),
+            Some(json!({ "workspaceIds": ["ws-1", "ws-2"] }))
+        );
+
+        assert_eq!(
+            subscriptions.workspace_to_open("remove_workspace", &json!({ "id": "ws-2" })),
+            None
+        );
+        assert_eq!(
+            subscriptions.workspaces_to_close(
+                "remove_workspace",
+                &json!({ "id": "ws-2" }),
+                &json!(null)
+            ),
+            vec!["ws-2".to_string()]
+        );
+        assert_eq!(
+            subscriptions.subscribe_params(),
+            Some(json!({ "workspaceIds": ["ws-1"] }))
+        );
+    }
+
+    #[test]
+    fn workspace_list_only_drops_workspaces_that_are_gone() {
+        let subscriptions = RemoteEventSubscriptions::default();
+        subscriptions.workspace_to_open("connect_workspace", &json!({ "id": "ws-1" }));
+        subscriptions.workspace_to_open("list_threads", &json!({ "workspaceId": "ws-gone" }));
+
+        let listed = json!([{ "id": "ws-1" }, { "id": "ws-2" }, { "id": "ws-3" }]);
+        assert_eq!(
+            subscriptions.workspaces_to_close("list_workspaces", &json!({}), &listed),
+            vec!["ws-gone".to_string()]
+        );
+        assert_eq!(
+            subscriptions.subscribe_params(),
+            Some(json!({ "workspaceIds": ["ws-1"] }))
+        );
+        assert!(subscriptions
+            .workspaces_to_close("list_workspaces", &json!({}), &listed)
+            .is_empty());
+        assert!(subscriptions
+            .workspaces_to_close("add_workspace", &json!({}), &json!({ "id": "ws-4" }))
+            .is_empty());
+
+        subscriptions.workspaces_to_close("list_workspaces", &json!({}), &json!([]));
+        assert_eq!(subscriptions.subscribe_params(), None);
+    }
+}
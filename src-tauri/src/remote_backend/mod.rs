@@ -1,6 +1,9 @@
+mod event_cursor;
+mod event_subscriptions;
 mod orbit_ws_transport;
 mod protocol;
 mod tcp_transport;
+mod tls;
 mod transport;
 
 use serde_json::{json, Value};
@@ -13,13 +16,18 @@ use tokio::sync::Mutex;
 use tokio::time::timeout;
 
 use crate::state::AppState;
-use crate::types::{BackendMode, RemoteBackendProvider};
+use crate::storage::write_settings;
+use crate::types::{AppSettings, BackendMode, RemoteBackendProvider};
 
 use self::orbit_ws_transport::OrbitWsTransport;
 use self::protocol::{build_request_line, DEFAULT_REMOTE_HOST, DISCONNECTED_MESSAGE};
 use self::tcp_transport::TcpTransport;
 use self::transport::{PendingMap, RemoteTransport, RemoteTransportConfig, RemoteTransportKind};
 
+pub(crate) use self::event_cursor::RemoteEventCursor;
+pub(crate) use self::event_subscriptions::RemoteEventSubscriptions;
+pub(crate) use self::tls::connect_daemon_tls;
+
 const REMOTE_REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
 const REMOTE_SEND_TIMEOUT: Duration = Duration::from_secs(15);
 
@@ -121,27 +129,41 @@ pub(crate) async fn call_remote(
     params: Value,
 ) -> Result<Value, String> {
     let client = ensure_remote_backend(state, app.clone()).await?;
-    match client.call(method, params.clone()).await {
-        Ok(value) => Ok(value),
+    let subscriptions = &state.remote_event_subscriptions;
+    if let Some(workspace_id) = subscriptions.workspace_to_open(method, &params) {
+        // Daemons without subscriptions reject this and keep sending every event.
+        let _ = client
+            .call("subscribe", json!({ "workspaceIds": [workspace_id] }))
+            .await;
+    }
+    let (client, value) = match client.call(method, params.clone()).await {
+        Ok(value) => (client, value),
         Err(err) if err == DISCONNECTED_MESSAGE => {
             *state.remote_backend.lock().await = None;
             if !can_retry_after_disconnect(method) {
                 return Err(err);
             }
             let retry_client = ensure_remote_backend(state, app).await?;
-            match retry_client.call(method, params).await {
-                Ok(value) => Ok(value),
+            match retry_client.call(method, params.clone()).await {
+                Ok(value) => (retry_client, value),
                 Err(retry_err) => {
                     *state.remote_backend.lock().await = None;
-                    Err(retry_err)
+                    return Err(retry_err);
                 }
             }
         }
         Err(err) => {
             *state.remote_backend.lock().await = None;
-            Err(err)
+            return Err(err);
         }
+    };
+    let closed = subscriptions.workspaces_to_close(method, &params, &value);
+    if !closed.is_empty() {
+        let _ = client
+            .call("unsubscribe", json!({ "workspaceIds": closed }))
+            .await;
     }
+    Ok(value)
 }
 
 fn can_retry_after_disconnect(method: &str) -> bool {
@@ -153,17 +175,28 @@ fn can_retry_after_disconnect(method: &str) -> bool {
             | "collaboration_mode_list"
             | "connect_workspace"
             | "file_read"
+            | "forge_analyze_plan"
+            | "forge_cancel_execution"
             | "forge_get_installed_template"
             | "forge_get_plan_prompt"
             | "forge_get_next_phase_prompt"
             | "forge_get_phase_status"
+            | "forge_get_run_history"
+            | "forge_get_runnable_tasks"
+            | "forge_get_task_phase_prompt"
             | "forge_install_template"
             | "forge_list_bundled_templates"
             | "forge_list_plans"
+            | "forge_list_template_sources"
+            | "forge_plan_cost"
             | "forge_prepare_execution"
             | "forge_reset_execution_progress"
             | "forge_run_phase_checks"
+            | "forge_run_status"
+            | "forge_stop_run"
+            | "forge_template_upgrade_preview"
             | "forge_uninstall_template"
+            | "forge_validate_plan"
             | "get_config_model"
             | "get_git_commit_diff"
             | "get_git_diffs"
@@ -204,12 +237,21 @@ async fn ensure_remote_backend(state: &AppState, app: AppHandle) -> Result<Remot
     };
     let transport_kind = transport_config.kind();
     let auth_token = transport_config.auth_token().map(|value| value.to_string());
+    let unpinned_host = match &transport_config {
+        RemoteTransportConfig::Tcp {
+            host,
+            tls_fingerprint: None,
+            ..
+        } => Some(host.clone()),
+        _ => None,
+    };
 
     let transport: Box<dyn RemoteTransport> = match transport_config.kind() {
         RemoteTransportKind::Tcp => Box::new(TcpTransport),
         RemoteTransportKind::OrbitWs => Box::new(OrbitWsTransport),
     };
     let connection = transport.connect(app, transport_config).await?;
+    let tls_fingerprint = connection.tls_fingerprint;
 
     let client = RemoteBackend {
         inner: Arc::new(RemoteBackendInner {
@@ -220,13 +262,36 @@ async fn ensure_remote_backend(state: &AppState, app: AppHandle) -> Result<Remot
         }),
     };
 
-    if matches!(transport_kind, RemoteTransportKind::Tcp) {
-        if let Some(token) = auth_token {
-            client
-                .call("auth", json!({ "token": token }))
-                .await
-                .map(|_| ())?;
+    // `auth` also tells the daemon where the event stream left off, so events emitted while
+    // the app was disconnected are replayed, and which workspaces to replay them for. Orbit
+    // runners accept it without a token.
+    let subscribe_params = state.remote_event_subscriptions.subscribe_params();
+    let mut auth_params = state.remote_event_cursor.resume_params();
+    auth_params.insert(
+        "subscribe".to_string(),
+        subscribe_params.clone().unwrap_or_else(|| json!({})),
+    );
+    let should_auth = match transport_kind {
+        RemoteTransportKind::Tcp => match auth_token {
+            Some(token) => {
+                auth_params.insert("token".to_string(), Value::from(token));
+                true
+            }
+            None => false,
+        },
+        RemoteTransportKind::OrbitWs => true,
+    };
+    if should_auth {
+        let result = client.call("auth", Value::Object(auth_params)).await?;
+        if let Some(stream_id) = result.get("streamId").and_then(Value::as_str) {
+            state.remote_event_cursor.set_stream(stream_id);
         }
+    } else if let Some(subscribe_params) = subscribe_params {
+        // Without `auth` the connection receives every event until it subscribes.
+        let _ = client.call("subscribe", subscribe_params).await;
+    }
+    if let (Some(host), Some(fingerprint)) = (unpinned_host, tls_fingerprint) {
+        pin_remote_backend_certificate(state, &host, &fingerprint).await?;
     }
 
     {
@@ -237,17 +302,39 @@ async fn ensure_remote_backend(state: &AppState, app: AppHandle) -> Result<Remot
     Ok(client)
 }
 
-fn resolve_transport_config(
-    settings: &crate::types::AppSettings,
-) -> Result<RemoteTransportConfig, String> {
+/// Trust on first use: the certificate a TCP host presents on its first authenticated
+/// connection is kept, and later connections to that host must present the same one.
+async fn pin_remote_backend_certificate(
+    state: &AppState,
+    host: &str,
+    fingerprint: &str,
+) -> Result<(), String> {
+    let mut settings = state.app_settings.lock().await;
+    if settings.remote_backend_tls_pins.contains_key(host) {
+        return Ok(());
+    }
+    settings
+        .remote_backend_tls_pins
+        .insert(host.to_string(), fingerprint.to_string());
+    write_settings(&state.settings_path, &settings)
+}
+
+/// The TCP host the app connects to, which is also the key of its pinned certificate.
+pub(crate) fn tcp_remote_host(settings: &AppSettings) -> String {
+    let host = settings.remote_backend_host.trim();
+    if host.is_empty() {
+        DEFAULT_REMOTE_HOST.to_string()
+    } else {
+        host.to_string()
+    }
+}
+
+fn resolve_transport_config(settings: &AppSettings) -> Result<RemoteTransportConfig, String> {
     match settings.remote_backend_provider {
         RemoteBackendProvider::Tcp => {
-            let host = if settings.remote_backend_host.trim().is_empty() {
-                DEFAULT_REMOTE_HOST.to_string()
-            } else {
-                settings.remote_backend_host.clone()
-            };
+            let host = tcp_remote_host(settings);
             Ok(RemoteTransportConfig::Tcp {
+                tls_fingerprint: settings.remote_backend_tls_pins.get(&host).cloned(),
                 host,
                 auth_token: settings.remote_backend_token.clone(),
             })
@@ -286,14 +373,59 @@ mod tests {
         assert_eq!(ws_url, "https://orbit.example/ws/live");
     }
 
+    #[test]
+    fn resolve_tcp_transport_uses_the_pin_of_the_configured_host() {
+        let mut settings = AppSettings::default();
+        settings.remote_backend_host = " macbook.tailnet.ts.net:4732 ".to_string();
+        settings
+            .remote_backend_tls_pins
+            .insert("127.0.0.1:4732".to_string(), "AA:BB".to_string());
+        settings.remote_backend_tls_pins.insert(
+            "macbook.tailnet.ts.net:4732".to_string(),
+            "CC:DD".to_string(),
+        );
+
+        let config = resolve_transport_config(&settings).expect("transport config");
+        let RemoteTransportConfig::Tcp {
+            host,
+            tls_fingerprint,
+            ..
+        } = config
+        else {
+            panic!("expected tcp transport config");
+        };
+        assert_eq!(host, "macbook.tailnet.ts.net:4732");
+        assert_eq!(tls_fingerprint.as_deref(), Some("CC:DD"));
+
+        settings.remote_backend_host = "studio.tailnet.ts.net:4732".to_string();
+        let config = resolve_transport_config(&settings).expect("transport config");
+        let RemoteTransportConfig::Tcp {
+            tls_fingerprint, ..
+        } = config
+        else {
+            panic!("expected tcp transport config");
+        };
+        assert!(tls_fingerprint.is_none());
+    }
+
     #[test]
     fn retries_only_retry_safe_methods_after_disconnect() {
         assert!(can_retry_after_disconnect("resume_thread"));
         assert!(can_retry_after_disconnect("list_threads"));
         assert!(can_retry_after_disconnect("local_usage_snapshot"));
         assert!(can_retry_after_disconnect("forge_list_plans"));
+        assert!(can_retry_after_disconnect("forge_validate_plan"));
         assert!(can_retry_after_disconnect("forge_get_plan_prompt"));
         assert!(can_retry_after_disconnect("forge_reset_execution_progress"));
+        assert!(can_retry_after_disconnect("forge_get_run_history"));
+        assert!(can_retry_after_disconnect("forge_analyze_plan"));
+        assert!(can_retry_after_disconnect("forge_plan_cost"));
+        assert!(!can_retry_after_disconnect("forge_rollback_task"));
+        assert!(!can_retry_after_disconnect("forge_update_plan"));
+        assert!(!can_retry_after_disconnect("forge_approve_phase"));
+        assert!(!can_retry_after_disconnect("forge_reject_phase"));
+        assert!(can_retry_after_disconnect("forge_run_status"));
+        assert!(!can_retry_after_disconnect("forge_start_run"));
         assert!(!can_retry_after_disconnect("send_user_message"));
         assert!(!can_retry_after_disconnect("start_thread"));
         assert!(!can_retry_after_disconnect("remove_workspace"));
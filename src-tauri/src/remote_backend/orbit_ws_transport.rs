@@ -74,6 +74,7 @@ impl RemoteTransport for OrbitWsTransport {
                 out_tx,
                 pending,
                 connected,
+                tls_fingerprint: None,
             })
         })
     }
@@ -1,6 +1,7 @@
 use tauri::AppHandle;
 use tokio::net::TcpStream;
 
+use super::tls::connect_daemon_tls;
 use super::transport::{
     spawn_transport_io, RemoteTransport, RemoteTransportConfig, TransportFuture,
 };
@@ -10,15 +11,24 @@ pub(crate) struct TcpTransport;
 impl RemoteTransport for TcpTransport {
     fn connect(&self, app: AppHandle, config: RemoteTransportConfig) -> TransportFuture {
         Box::pin(async move {
-            let RemoteTransportConfig::Tcp { host, .. } = config else {
+            let RemoteTransportConfig::Tcp {
+                host,
+                tls_fingerprint,
+                ..
+            } = config
+            else {
                 return Err("invalid transport config for tcp transport".to_string());
             };
 
             let stream = TcpStream::connect(host.clone())
                 .await
                 .map_err(|err| format!("Failed to connect to remote backend at {host}: {err}"))?;
-            let (reader, writer) = stream.into_split();
-            Ok(spawn_transport_io(app, reader, writer))
+            let (stream, fingerprint) =
+                connect_daemon_tls(stream, tls_fingerprint.as_deref()).await?;
+            let (reader, writer) = tokio::io::split(stream);
+            let mut connection = spawn_transport_io(app, reader, writer);
+            connection.tls_fingerprint = Some(fingerprint);
+            Ok(connection)
         })
     }
 }
@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{DigitallySignedStruct, SignatureScheme};
+use tokio::net::TcpStream;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::TlsConnector;
+
+use crate::shared::daemon_tls_core::{certificate_fingerprint, daemon_tls_provider};
+
+/// The daemon certificate is self-signed and names no host, so it is trusted by fingerprint
+/// instead of by chain and name. Handshake signatures are still checked, which proves the
+/// daemon holds the key of the certificate that is compared against the pin.
+#[derive(Debug)]
+struct DaemonCertVerifier {
+    provider: Arc<CryptoProvider>,
+}
+
+impl ServerCertVerifier for DaemonCertVerifier {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+/// Runs the TLS handshake with a daemon and returns the stream with the daemon's certificate
+/// fingerprint. With `pinned_fingerprint`, a different certificate fails the call before
+/// anything is sent over the stream.
+pub(crate) async fn connect_daemon_tls(
+    stream: TcpStream,
+    pinned_fingerprint: Option<&str>,
+) -> Result<(TlsStream<TcpStream>, String), String> {
+    let provider = daemon_tls_provider();
+    let config = rustls::ClientConfig::builder_with_provider(Arc::clone(&provider))
+        .with_safe_default_protocol_versions()
+        .map_err(|err| err.to_string())?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(DaemonCertVerifier { provider }))
+        .with_no_client_auth();
+    let server_name =
+        ServerName::try_from("codex-monitor-daemon").map_err(|err| err.to_string())?;
+
+    let stream = TlsConnector::from(Arc::new(config))
+        .connect(server_name, stream)
+        .await
+        .map_err(|err| format!("TLS handshake with the remote backend failed: {err}"))?;
+    let fingerprint = stream
+        .get_ref()
+        .1
+        .peer_certificates()
+        .and_then(|certs| certs.first())
+        .map(|cert| certificate_fingerprint(cert.as_ref()))
+        .ok_or_else(|| "Remote backend did not present a TLS certificate.".to_string())?;
+
+    if let Some(pinned) = pinned_fingerprint {
+        if !pinned.eq_ignore_ascii_case(&fingerprint) {
+            return Err(format!(
+                "Remote backend certificate does not match the pinned fingerprint \
+                 (pinned {pinned}, presented {fingerprint}). If the daemon's certificate was \
+                 regenerated, forget the pinned certificate in Settings > Server."
+            ));
+        }
+    }
+    Ok((stream, fingerprint))
+}
@@ -5,11 +5,12 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use serde_json::Value;
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
 use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
 use tokio::sync::{mpsc, oneshot, Mutex};
 
 use super::protocol::{parse_incoming_line, IncomingMessage, DISCONNECTED_MESSAGE};
+use crate::state::AppState;
 
 pub(crate) type PendingMap = HashMap<u64, oneshot::Sender<Result<Value, String>>>;
 const OUTBOUND_QUEUE_CAPACITY: usize = 512;
@@ -19,6 +20,8 @@ pub(crate) enum RemoteTransportConfig {
     Tcp {
         host: String,
         auth_token: Option<String>,
+        /// Certificate fingerprint pinned for `host`; `None` pins on first connect.
+        tls_fingerprint: Option<String>,
     },
     OrbitWs {
         ws_url: String,
@@ -52,6 +55,8 @@ pub(crate) struct TransportConnection {
     pub(crate) out_tx: mpsc::Sender<String>,
     pub(crate) pending: Arc<Mutex<PendingMap>>,
     pub(crate) connected: Arc<AtomicBool>,
+    /// Fingerprint of the certificate the daemon presented, for transports that use TLS.
+    pub(crate) tls_fingerprint: Option<String>,
 }
 
 pub(crate) type TransportFuture =
@@ -98,6 +103,7 @@ where
         out_tx,
         pending,
         connected,
+        tls_fingerprint: None,
     }
 }
 
@@ -138,18 +144,43 @@ pub(crate) async fn dispatch_incoming_line(
                 let _ = sender.send(payload);
             }
         }
-        IncomingMessage::Notification { method, params } => match method.as_str() {
-            "app-server-event" => {
-                let _ = app.emit("app-server-event", params);
+        IncomingMessage::Notification {
+            method,
+            params,
+            seq,
+        } => {
+            let state = app.state::<AppState>();
+            let cursor = &state.remote_event_cursor;
+            if method == "event-gap" {
+                if let Some(stream_id) = params.get("streamId").and_then(Value::as_str) {
+                    cursor.set_stream(stream_id);
+                }
+                let _ = app.emit("remote-event-gap", params);
+                return;
             }
-            "terminal-output" => {
-                let _ = app.emit("terminal-output", params);
+            if !cursor.accept(seq) {
+                return;
             }
-            "terminal-exit" => {
-                let _ = app.emit("terminal-exit", params);
-            }
-            _ => {}
-        },
+            emit_notification(app, &method, params);
+        }
+    }
+}
+
+fn emit_notification(app: &AppHandle, method: &str, params: Value) {
+    match method {
+        "app-server-event" => {
+            let _ = app.emit("app-server-event", params);
+        }
+        "terminal-output" => {
+            let _ = app.emit("terminal-output", params);
+        }
+        "terminal-exit" => {
+            let _ = app.emit("terminal-exit", params);
+        }
+        "forge-execution-event" => {
+            let _ = app.emit("forge-execution-event", params);
+        }
+        _ => {}
     }
 }
 
@@ -1,9 +1,11 @@
 use tauri::{State, Window};
 
+use crate::remote_backend::tcp_remote_host;
 use crate::shared::settings_core::{
     get_app_settings_core, get_codex_config_path_core, update_app_settings_core,
 };
 use crate::state::AppState;
+use crate::storage::write_settings;
 use crate::types::{AppSettings, BackendMode, RemoteBackendProvider};
 use crate::window;
 
@@ -24,16 +26,39 @@ pub(crate) async fn update_app_settings(
     window: Window,
 ) -> Result<AppSettings, String> {
     let previous = state.app_settings.lock().await.clone();
+    // Certificate pins are written by the remote backend connection, never by the settings UI,
+    // so a settings copy loaded before a pin was recorded must not drop it.
+    let mut settings = settings;
+    settings.remote_backend_tls_pins = previous.remote_backend_tls_pins.clone();
     let updated =
         update_app_settings_core(settings, &state.app_settings, &state.settings_path).await?;
     if should_reset_remote_backend(&previous, &updated) {
         *state.remote_backend.lock().await = None;
+        state.remote_event_cursor.reset();
     }
     ensure_remote_runtime_for_settings(&updated, state).await;
     let _ = window::apply_window_appearance(&window, updated.theme.as_str());
     Ok(updated)
 }
 
+/// Drops the certificate pinned for the configured TCP host, so the next connection pins the
+/// certificate the daemon presents then.
+#[tauri::command]
+pub(crate) async fn forget_remote_backend_tls_pin(
+    state: State<'_, AppState>,
+) -> Result<AppSettings, String> {
+    let updated = {
+        let mut settings = state.app_settings.lock().await;
+        let host = tcp_remote_host(&settings);
+        if settings.remote_backend_tls_pins.remove(&host).is_some() {
+            write_settings(&state.settings_path, &settings)?;
+        }
+        settings.clone()
+    };
+    *state.remote_backend.lock().await = None;
+    Ok(updated)
+}
+
 #[tauri::command]
 pub(crate) async fn get_codex_config_path() -> Result<String, String> {
     get_codex_config_path_core()
@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use rustls::crypto::CryptoProvider;
+use sha2::{Digest, Sha256};
+
+/// The daemon and its clients name the provider explicitly; rustls cannot pick a process default
+/// when other dependencies compile in a second provider.
+pub(crate) fn daemon_tls_provider() -> Arc<CryptoProvider> {
+    Arc::new(rustls::crypto::ring::default_provider())
+}
+
+/// SHA-256 of a DER certificate as colon-separated uppercase hex, the form printed by
+/// `openssl x509 -noout -fingerprint -sha256`.
+pub(crate) fn certificate_fingerprint(der: &[u8]) -> String {
+    Sha256::digest(der)
+        .iter()
+        .map(|byte| format!("{byte:02X}"))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::certificate_fingerprint;
+
+    #[test]
+    fn fingerprint_is_colon_separated_uppercase_sha256() {
+        let fingerprint = certificate_fingerprint(b"abc");
+        assert_eq!(
+            fingerprint,
+            concat!(
+                "BA:78:16:BF:8F:01:CF:EA:41:41:40:DE:5D:AE:22:23:",
+                "B0:03:61:A3:96:17:7A:9C:B4:10:FF:61:F2:00:15:AD"
+            )
+        );
+    }
+}
@@ -1,22 +1,109 @@
 use chrono::Utc;
+use futures_util::future::{join3, select, Either};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::fs;
-use std::process::Output;
+use std::pin::pin;
+use std::process::{Output, Stdio};
 use std::path::{Component, Path, PathBuf};
 use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::sync::Mutex;
 use tokio::time::timeout;
 use uuid::Uuid;
 
-use crate::shared::forge_templates_core::{read_installed_template_lock_core, ForgeTemplateLockV1};
-use crate::shared::process_core::tokio_command;
+use crate::backend::events::{
+    EventSink, ForgeCheckOutputStream, ForgeExecutionEvent, ForgeExecutionEventKind,
+};
+use crate::shared::forge_templates_core::{
+    read_installed_template_lock_core, ForgeTemplateHookV1, ForgeTemplateLockV1,
+    FORGE_BUILTIN_HOOK_INTERPRETER,
+};
+use crate::shared::process_core::{kill_process_group, set_new_process_group, tokio_command};
+use crate::types::WorkspaceEntry;
 use crate::utils::{git_env_path, resolve_git_binary};
 
 const CHECK_TIMEOUT_SECONDS_DEFAULT: u64 = 10 * 60;
 const HOOK_TIMEOUT_SECONDS: u64 = 2 * 60;
 const GIT_COMMAND_TIMEOUT_SECONDS: u64 = 90;
-const AI_REVIEW_REPORT_SCHEMA: &str = "forge-ai-review-v1";
+const MAX_PHASE_RETRY_BACKOFF_SECONDS: u64 = 60 * 60;
+
+#[path = "forge_execute_core/approval.rs"]
+mod approval;
+#[path = "forge_execute_core/artifact_gates.rs"]
+mod artifact_gates;
+#[path = "forge_execute_core/budget.rs"]
+mod budget;
+#[path = "forge_execute_core/builtin_hooks.rs"]
+mod builtin_hooks;
+#[path = "forge_execute_core/cancel.rs"]
+mod cancel;
+#[path = "forge_execute_core/commit_policy.rs"]
+mod commit_policy;
+#[path = "forge_execute_core/cost.rs"]
+mod cost;
+#[path = "forge_execute_core/history.rs"]
+mod history;
+#[path = "forge_execute_core/plan_edit.rs"]
+mod plan_edit;
+#[path = "forge_execute_core/plan_graph.rs"]
+mod plan_graph;
+#[path = "forge_execute_core/rollback.rs"]
+mod rollback;
+#[path = "forge_execute_core/run_driver.rs"]
+mod run_driver;
+#[path = "forge_execute_core/scheduler.rs"]
+mod scheduler;
+#[path = "forge_execute_core/state_store.rs"]
+mod state_store;
+#[path = "forge_execute_core/typed_checks.rs"]
+mod typed_checks;
+
+use approval::{
+    append_review_feedback, awaiting_approval_prompt, is_manual_approval_phase,
+    is_phase_awaiting_approval, require_approval, MANUAL_APPROVAL_PHASE_KIND,
+};
+pub(crate) use approval::{
+    forge_approve_phase_core, forge_reject_phase_core, ForgePhaseReviewRequest,
+    ForgePhaseReviewResultV1,
+};
+use artifact_gates::{parse_artifact_gates, run_artifact_gate};
+pub(crate) use cancel::{
+    forge_cancel_execution_core, ForgeCancelExecutionResultV1, ForgeExecutionCancels,
+};
+use budget::{
+    budget_exceeded_prompt, enforce_plan_budget, is_budget_blocked, restart_run_clock, PlanBudgetV1,
+};
+use cancel::ForgeCancelToken;
+pub(crate) use history::{forge_get_run_history_core, ForgeRunHistoryV1};
+use commit_policy::{
+    create_forge_commit, load_commit_policy, task_commit_shas, ForgeCommitKind,
+    ForgeCommitPolicyV1,
+};
+pub(crate) use cost::{forge_plan_cost_core, ForgePlanCostV1};
+use cost::record_phase_thread;
+use history::{now_rfc3339_millis, ForgeRunLogEntry};
+pub(crate) use plan_edit::{forge_update_plan_core, ForgePlanOperationV1, ForgePlanUpdateResultV1};
+use plan_graph::check_plan_structure;
+pub(crate) use plan_graph::{forge_analyze_plan_core, ForgePlanAnalysisV1};
+pub(crate) use rollback::{forge_rollback_task_core, ForgeTaskRollbackResultV1};
+pub(crate) use run_driver::{
+    forge_run_status_core, forge_start_run_core, forge_stop_run_core, ForgeRunHost, ForgeRuns,
+    ForgeRunStatusV1, ForgeStartRunOptionsV1,
+};
+pub(crate) use scheduler::{
+    forge_attach_task_worktree_core, forge_check_task_worktree_core, forge_get_runnable_tasks_core,
+    forge_get_task_phase_prompt_core, forge_merge_task_worktree_core, forge_task_worktree_branch,
+    ForgeRunnableTaskV1, ForgeTaskMergeResultV1, ForgeTaskWorktreeV1,
+};
+use state_store::{
+    load_state, lock_state, lock_state_blocking, migrate_state_file, update_state,
+    update_task_state, write_file_atomically, write_state_file, CURRENT_STATE_SCHEMA,
+};
+use typed_checks::{
+    parse_typed_check, run_typed_check, ForgeCheckDetailsV1, TypedCheckContext, TypedPhaseCheck,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -26,6 +113,14 @@ pub(crate) struct ForgeNextPhasePromptV1 {
     pub(crate) phase_id: String,
     pub(crate) is_last_phase: bool,
     pub(crate) prompt_text: String,
+    /// The phase is a `manual-approval` phase: no prompt is rendered and execution waits for
+    /// `forge_approve_phase` or `forge_reject_phase`.
+    #[serde(default)]
+    pub(crate) awaiting_approval: bool,
+    /// The plan's `budget` ran out and the task was blocked: no prompt is rendered and
+    /// execution stops. Holds the budget note written to the task.
+    #[serde(default)]
+    pub(crate) budget_exceeded: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +140,18 @@ pub(crate) struct ForgePhaseCheckResultV1 {
     pub(crate) stdout: String,
     pub(crate) stderr: String,
     pub(crate) timed_out: bool,
+    /// Structured outcome of a typed check (`junit`, `coverage`, `diff-limits`, `file-exists`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) details: Option<Box<ForgeCheckDetailsV1>>,
+}
+
+/// The phase whose checks to run, and the agent thread whose changes they verify.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ForgeRunPhaseChecksRequest<'a> {
+    pub(crate) plan_id: &'a str,
+    pub(crate) task_id: &'a str,
+    pub(crate) phase_id: &'a str,
+    pub(crate) thread_id: Option<&'a str>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -52,6 +159,19 @@ pub(crate) struct ForgePhaseCheckResultV1 {
 pub(crate) struct ForgeRunPhaseChecksResponseV1 {
     pub(crate) ok: bool,
     pub(crate) results: Vec<ForgePhaseCheckResultV1>,
+    #[serde(default)]
+    pub(crate) cancelled: bool,
+    /// Check runs recorded for the phase, including this one.
+    #[serde(default)]
+    pub(crate) attempts: i64,
+    #[serde(default)]
+    pub(crate) max_attempts: Option<u32>,
+    /// Delay the phase's backoff asks for before the next attempt.
+    #[serde(default)]
+    pub(crate) retry_after_ms: Option<u64>,
+    /// The phase used its last attempt: it is now `failed` and the task `blocked`.
+    #[serde(default)]
+    pub(crate) exhausted: bool,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -72,9 +192,9 @@ struct ForgeTemplateEntrypointsV1 {
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct ForgeTemplateHooksV1 {
-    post_plan: String,
-    pre_execute: String,
-    post_step: String,
+    post_plan: ForgeTemplateHookV1,
+    pre_execute: ForgeTemplateHookV1,
+    post_step: ForgeTemplateHookV1,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -86,6 +206,8 @@ struct PlanV1 {
     goal: String,
     #[serde(default)]
     tasks: Vec<PlanTaskV1>,
+    #[serde(default)]
+    budget: Option<PlanBudgetV1>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -105,6 +227,9 @@ struct StateV2 {
     summary: String,
     #[serde(default)]
     tasks: Vec<StateTaskV2>,
+    /// Start of the current run's `max_minutes` budget.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    run_started_at: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -117,6 +242,17 @@ struct StateTaskV2 {
     commit_sha: Option<String>,
     #[serde(default)]
     phases: Vec<StatePhaseV2>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    worktree: Option<StateTaskWorktreeV2>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StateTaskWorktreeV2 {
+    workspace_id: String,
+    path: String,
+    branch: String,
+    #[serde(default)]
+    base_sha: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -125,6 +261,31 @@ struct StatePhaseV2 {
     status: String,
     attempts: i64,
     notes: String,
+    /// Set when the commit policy commits each phase separately.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    commit_sha: Option<String>,
+    /// Latest review of a `manual-approval` phase.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    approval: Option<StatePhaseApprovalV2>,
+    /// The agent thread behind each check run, which `forge_plan_cost` joins with session logs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    threads: Vec<StatePhaseThreadV2>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StatePhaseThreadV2 {
+    thread_id: String,
+    /// When the checks started, which is when the agent's turn had ended.
+    checked_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StatePhaseApprovalV2 {
+    /// `approved` or `rejected`.
+    decision: String,
+    reviewer: String,
+    notes: String,
+    decided_at: String,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -132,13 +293,26 @@ struct ForgeTemplatePhasesV1 {
     schema: String,
     #[serde(default)]
     phases: Vec<ForgeTemplatePhaseV1>,
+    #[serde(default)]
+    commit: Option<ForgeCommitPolicyV1>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct ForgeTemplatePhaseV1 {
     id: String,
+    /// `agent` (the default) or `manual-approval`.
+    #[serde(default)]
+    kind: Option<String>,
     #[serde(default)]
     checks: Vec<Value>,
+    /// `None` when the key is absent, which keeps the built-in `ai-review` report gate.
+    #[serde(default)]
+    artifacts: Option<Vec<Value>>,
+    #[serde(default)]
+    max_attempts: Option<u32>,
+    #[serde(default)]
+    backoff_seconds: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -154,6 +328,8 @@ struct ForgeHookContextV1 {
     generated_plan_md_path: String,
     generated_execute_prompt_path: String,
     today_iso: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    task_id: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -168,17 +344,77 @@ struct ForgeExecutionPaths {
     generated_plan_md_path: PathBuf,
     generated_execute_prompt_path: PathBuf,
     phases_path: PathBuf,
-    post_plan_hook_path: PathBuf,
-    pre_execute_hook_path: PathBuf,
-    post_step_hook_path: PathBuf,
+    post_plan_hook: ForgeHookRunner,
+    pre_execute_hook: ForgeHookRunner,
+    post_step_hook: ForgeHookRunner,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ForgeHookSlot {
+    PostPlan,
+    PreExecute,
+    PostStep,
+}
+
+#[derive(Debug, Clone)]
+enum ForgeHookRunner {
+    Builtin(ForgeHookSlot),
+    Command {
+        interpreter: String,
+        script_path: Option<PathBuf>,
+        args: Vec<String>,
+    },
 }
 
 #[derive(Debug, Clone)]
 struct RunnablePhaseCheck {
     id: String,
     title: String,
-    command: String,
-    timeout_seconds: u64,
+    kind: RunnablePhaseCheckKind,
+}
+
+#[derive(Debug, Clone)]
+enum RunnablePhaseCheckKind {
+    Command { command: String, timeout_seconds: u64 },
+    Typed(TypedPhaseCheck),
+}
+
+/// Tags execution progress with the workspace, plan, task and phase it belongs to.
+struct ForgeExecutionEmitter<'a, E: EventSink> {
+    event_sink: &'a E,
+    workspace_id: &'a str,
+    plan_id: &'a str,
+    task_id: &'a str,
+    phase_id: &'a str,
+}
+
+impl<E: EventSink> ForgeExecutionEmitter<'_, E> {
+    fn emit(&self, kind: ForgeExecutionEventKind) {
+        self.event_sink.emit_forge_execution_event(ForgeExecutionEvent {
+            workspace_id: self.workspace_id.to_string(),
+            plan_id: self.plan_id.to_string(),
+            task_id: self.task_id.to_string(),
+            phase_id: self.phase_id.to_string(),
+            kind,
+        });
+    }
+
+    fn check_started(&self, id: &str, title: &str) {
+        self.emit(ForgeExecutionEventKind::CheckStarted {
+            check_id: id.to_string(),
+            title: title.to_string(),
+        });
+    }
+
+    fn check_finished(&self, result: &ForgePhaseCheckResultV1) {
+        self.emit(ForgeExecutionEventKind::CheckFinished {
+            check_id: result.id.clone(),
+            title: result.title.clone(),
+            exit_code: result.exit_code,
+            duration_ms: result.duration_ms,
+            timed_out: result.timed_out,
+        });
+    }
 }
 
 fn is_ascii_lower_alnum(ch: char) -> bool {
@@ -251,6 +487,40 @@ fn read_template_manifest(template_root: &Path) -> Result<ForgeTemplateManifestV
     Ok(manifest)
 }
 
+fn resolve_hook_runner(
+    template_root: &Path,
+    hook: &ForgeTemplateHookV1,
+    slot: ForgeHookSlot,
+) -> Result<ForgeHookRunner, String> {
+    match hook {
+        ForgeTemplateHookV1::Script(script) => Ok(ForgeHookRunner::Command {
+            interpreter: "node".to_string(),
+            script_path: Some(template_root.join(validate_relative_file_path(script)?)),
+            args: Vec::new(),
+        }),
+        ForgeTemplateHookV1::Command(command) => {
+            let interpreter = command.interpreter.trim();
+            if interpreter.is_empty() {
+                return Err("template hook interpreter is empty".to_string());
+            }
+            if interpreter == FORGE_BUILTIN_HOOK_INTERPRETER {
+                return Ok(ForgeHookRunner::Builtin(slot));
+            }
+            let script_path = command
+                .script
+                .as_deref()
+                .map(validate_relative_file_path)
+                .transpose()?
+                .map(|rel| template_root.join(rel));
+            Ok(ForgeHookRunner::Command {
+                interpreter: interpreter.to_string(),
+                script_path,
+                args: command.args.clone(),
+            })
+        }
+    }
+}
+
 fn require_installed_template_lock(workspace_root: &Path) -> Result<ForgeTemplateLockV1, String> {
     read_installed_template_lock_core(workspace_root)?
         .ok_or_else(|| "No Forge template installed.".to_string())
@@ -270,9 +540,11 @@ fn build_execution_paths(workspace_root: &Path, plan_id: &str) -> Result<ForgeEx
     let manifest = read_template_manifest(&template_root)?;
     let phases_rel = validate_relative_file_path(&manifest.entrypoints.phases)?;
     let _execute_prompt_rel = validate_relative_file_path(&manifest.entrypoints.execute_prompt)?;
-    let post_plan_rel = validate_relative_file_path(&manifest.entrypoints.hooks.post_plan)?;
-    let pre_execute_rel = validate_relative_file_path(&manifest.entrypoints.hooks.pre_execute)?;
-    let post_step_rel = validate_relative_file_path(&manifest.entrypoints.hooks.post_step)?;
+    let hooks = &manifest.entrypoints.hooks;
+    let post_plan_hook = resolve_hook_runner(&template_root, &hooks.post_plan, ForgeHookSlot::PostPlan)?;
+    let pre_execute_hook =
+        resolve_hook_runner(&template_root, &hooks.pre_execute, ForgeHookSlot::PreExecute)?;
+    let post_step_hook = resolve_hook_runner(&template_root, &hooks.post_step, ForgeHookSlot::PostStep)?;
 
     let plan_dir = workspace_root.join("plans").join(&normalized_plan_id);
     let plan_path = plan_dir.join("plan.json");
@@ -292,9 +564,9 @@ fn build_execution_paths(workspace_root: &Path, plan_id: &str) -> Result<ForgeEx
         generated_plan_md_path,
         generated_execute_prompt_path,
         phases_path: template_root.join(phases_rel),
-        post_plan_hook_path: template_root.join(post_plan_rel),
-        pre_execute_hook_path: template_root.join(pre_execute_rel),
-        post_step_hook_path: template_root.join(post_step_rel),
+        post_plan_hook,
+        pre_execute_hook,
+        post_step_hook,
     })
 }
 
@@ -323,24 +595,6 @@ fn load_plan(paths: &ForgeExecutionPaths) -> Result<PlanV1, String> {
     Ok(plan)
 }
 
-fn load_state(paths: &ForgeExecutionPaths) -> Result<StateV2, String> {
-    let state = read_json_file::<StateV2>(&paths.state_path)?;
-    if state.schema.trim() != "state-v2" {
-        return Err(format!(
-            "Unsupported state schema in {}: {}",
-            paths.state_path.display(),
-            state.schema
-        ));
-    }
-    if state.plan_id.trim() != paths.plan_id {
-        return Err(format!(
-            "State plan_id mismatch (expected {}, got {})",
-            paths.plan_id, state.plan_id
-        ));
-    }
-    Ok(state)
-}
-
 fn load_template_phases(paths: &ForgeExecutionPaths) -> Result<ForgeTemplatePhasesV1, String> {
     let phases = read_json_file::<ForgeTemplatePhasesV1>(&paths.phases_path)?;
     if phases.schema.trim() != "forge-phases-v1" {
@@ -373,6 +627,12 @@ fn is_task_completed(task: &StateTaskV2) -> bool {
     are_all_task_phases_completed(task)
 }
 
+/// A task running in its own worktree only counts as done for its dependents once its commit has
+/// been merged back onto the plan branch.
+fn is_task_merged(task: &StateTaskV2) -> bool {
+    is_task_completed(task) && task.worktree.is_none()
+}
+
 fn map_state_tasks<'a>(state: &'a StateV2) -> HashMap<&'a str, &'a StateTaskV2> {
     state
         .tasks
@@ -393,29 +653,64 @@ fn is_task_dependencies_satisfied(
         let Some(state_task) = state_tasks_by_id.get(dep_id) else {
             return false;
         };
-        if !is_task_completed(state_task) {
+        if !is_task_merged(state_task) {
             return false;
         }
     }
     true
 }
 
+/// A phase is marked `failed` once it uses up its `maxAttempts`; the task is then skipped.
+fn is_task_retry_exhausted(task: &StateTaskV2) -> bool {
+    task.phases
+        .iter()
+        .any(|phase| phase.status.trim() == "failed")
+}
+
+fn recorded_phase_attempts(task: &StateTaskV2, phase_index: usize) -> i64 {
+    task.phases
+        .get(phase_index)
+        .map(|phase| phase.attempts)
+        .unwrap_or(0)
+}
+
+/// Backoff doubles with each failed attempt, starting from the phase's `backoffSeconds`.
+fn phase_retry_delay_ms(backoff_seconds: u64, attempts: i64) -> u64 {
+    let doublings = attempts.saturating_sub(1).clamp(0, 16) as u32;
+    backoff_seconds
+        .saturating_mul(1 << doublings)
+        .min(MAX_PHASE_RETRY_BACKOFF_SECONDS)
+        .saturating_mul(1000)
+}
+
 fn find_next_runnable_task<'a>(plan: &'a PlanV1, state: &'a StateV2) -> Option<&'a PlanTaskV1> {
     let state_tasks_by_id = map_state_tasks(state);
+    let mut first_blocked = None;
 
     for task in &plan.tasks {
         let Some(state_task) = state_tasks_by_id.get(task.id.as_str()) else {
             continue;
         };
-        if is_task_completed(state_task) {
+        if is_task_completed(state_task) || is_task_retry_exhausted(state_task) {
+            continue;
+        }
+        if !is_task_dependencies_satisfied(task, &state_tasks_by_id) {
+            continue;
+        }
+        // A task the budget blocked waits until nothing else can run. It is picked again then,
+        // so a raised limit lets it continue and an unchanged one reports the budget again.
+        // Tasks blocked for any other reason stay put until someone unblocks them.
+        if is_budget_blocked(state_task) {
+            first_blocked.get_or_insert(task);
             continue;
         }
-        if is_task_dependencies_satisfied(task, &state_tasks_by_id) {
-            return Some(task);
+        if state_task.status.trim() == "blocked" {
+            continue;
         }
+        return Some(task);
     }
 
-    None
+    first_blocked
 }
 
 fn find_next_phase<'a>(task: &'a StateTaskV2) -> Option<(usize, &'a StatePhaseV2)> {
@@ -437,6 +732,7 @@ fn build_hook_context(paths: &ForgeExecutionPaths) -> ForgeHookContextV1 {
         generated_plan_md_path: paths.generated_plan_md_path.to_string_lossy().to_string(),
         generated_execute_prompt_path: paths.generated_execute_prompt_path.to_string_lossy().to_string(),
         today_iso: Utc::now().date_naive().format("%Y-%m-%d").to_string(),
+        task_id: None,
     }
 }
 
@@ -456,10 +752,35 @@ fn format_process_error(stdout: &[u8], stderr: &[u8]) -> String {
 }
 
 async fn run_template_hook(
-    script_path: &Path,
+    hook: &ForgeHookRunner,
     workspace_root: &Path,
     context: &ForgeHookContextV1,
+    cancel: Option<&ForgeCancelToken>,
 ) -> Result<(), String> {
+    let (interpreter, script_path, args) = match hook {
+        ForgeHookRunner::Builtin(slot) => {
+            // Builtin hooks do blocking file work, including waiting for the state lock.
+            let (slot, context) = (*slot, context.clone());
+            return tokio::task::spawn_blocking(move || {
+                builtin_hooks::run_builtin_hook(slot, &context)
+            })
+            .await
+            .map_err(|err| format!("Builtin hook task failed: {err}"))?;
+        }
+        ForgeHookRunner::Command {
+            interpreter,
+            script_path,
+            args,
+        } => (interpreter, script_path, args),
+    };
+    let hook_label = script_path
+        .as_ref()
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|| interpreter.clone());
+    // Script hooks rewrite state.json without a lock of their own, so they run while holding
+    // the plan's state lock.
+    let _state_lock = lock_state(Path::new(&context.workspace_root), &context.plan_id).await?;
+
     let context_json =
         serde_json::to_string_pretty(context).map_err(|err| format!("Failed to serialize hook context: {err}"))?;
     let context_path =
@@ -472,43 +793,66 @@ async fn run_template_hook(
         )
     })?;
 
-    let mut command = tokio_command("node");
+    let mut command = tokio_command(interpreter);
+    if let Some(script_path) = script_path {
+        command.arg(script_path);
+    }
     command
-        .arg(script_path)
+        .args(args)
         .arg("--context")
         .arg(&context_path)
         .current_dir(workspace_root)
-        .env("PATH", git_env_path());
-
-    let output_result = timeout(Duration::from_secs(HOOK_TIMEOUT_SECONDS), command.output()).await;
+        .env("PATH", git_env_path())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    set_new_process_group(&mut command);
+
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+    let outcome = match command.spawn() {
+        Ok(mut child) => Ok(wait_for_forge_process(
+            &mut child,
+            &mut stdout,
+            &mut stderr,
+            HOOK_TIMEOUT_SECONDS,
+            cancel,
+            |_, _| {},
+        )
+        .await),
+        Err(err) => Err(err),
+    };
     let _ = fs::remove_file(&context_path);
 
-    let output = match output_result {
-        Ok(Ok(output)) => output,
-        Ok(Err(err)) => {
-            return Err(format!(
-                "Failed to run hook {}: {err}",
-                script_path.display()
-            ))
-        }
-        Err(_) => {
-            return Err(format!(
-                "Hook timed out after {}s: {}",
-                HOOK_TIMEOUT_SECONDS,
-                script_path.display()
-            ))
+    match outcome {
+        Ok(ForgeProcessOutcome::Exited(status)) if status.success() => Ok(()),
+        Ok(ForgeProcessOutcome::Exited(_)) => Err(format!(
+            "Hook failed ({hook_label}): {}",
+            format_process_error(&stdout, &stderr)
+        )),
+        Ok(ForgeProcessOutcome::WaitFailed(err)) | Err(err) => {
+            Err(format!("Failed to run hook {hook_label}: {err}"))
         }
-    };
-
-    if output.status.success() {
-        return Ok(());
+        Ok(ForgeProcessOutcome::TimedOut) => Err(format!(
+            "Hook timed out after {}s: {hook_label}",
+            HOOK_TIMEOUT_SECONDS
+        )),
+        Ok(ForgeProcessOutcome::Cancelled) => Err(format!("Hook cancelled: {hook_label}")),
     }
+}
 
-    Err(format!(
-        "Hook failed ({}): {}",
-        script_path.display(),
-        format_process_error(&output.stdout, &output.stderr)
-    ))
+fn invalid_check_result(id: String, title: String, stderr: String) -> ForgePhaseCheckResultV1 {
+    ForgePhaseCheckResultV1 {
+        id,
+        title,
+        exit_code: 2,
+        duration_ms: 0,
+        stdout: String::new(),
+        stderr,
+        timed_out: false,
+        details: None,
+    }
 }
 
 fn parse_phase_checks(checks: &[Value]) -> Vec<Result<RunnablePhaseCheck, ForgePhaseCheckResultV1>> {
@@ -520,34 +864,28 @@ fn parse_phase_checks(checks: &[Value]) -> Vec<Result<RunnablePhaseCheck, ForgeP
             if let Some(command) = raw.as_str() {
                 let trimmed = command.trim();
                 if trimmed.is_empty() {
-                    return Err(ForgePhaseCheckResultV1 {
-                        id: fallback_id.clone(),
-                        title: fallback_id,
-                        exit_code: 2,
-                        duration_ms: 0,
-                        stdout: String::new(),
-                        stderr: "Phase check command is empty.".to_string(),
-                        timed_out: false,
-                    });
+                    return Err(invalid_check_result(
+                        fallback_id.clone(),
+                        fallback_id,
+                        "Phase check command is empty.".to_string(),
+                    ));
                 }
                 return Ok(RunnablePhaseCheck {
                     id: fallback_id.clone(),
                     title: fallback_id,
-                    command: trimmed.to_string(),
-                    timeout_seconds: CHECK_TIMEOUT_SECONDS_DEFAULT,
+                    kind: RunnablePhaseCheckKind::Command {
+                        command: trimmed.to_string(),
+                        timeout_seconds: CHECK_TIMEOUT_SECONDS_DEFAULT,
+                    },
                 });
             }
 
             let Some(obj) = raw.as_object() else {
-                return Err(ForgePhaseCheckResultV1 {
-                    id: fallback_id.clone(),
-                    title: fallback_id,
-                    exit_code: 2,
-                    duration_ms: 0,
-                    stdout: String::new(),
-                    stderr: "Invalid phase check entry: expected string or object.".to_string(),
-                    timed_out: false,
-                });
+                return Err(invalid_check_result(
+                    fallback_id.clone(),
+                    fallback_id,
+                    "Invalid phase check entry: expected string or object.".to_string(),
+                ));
             };
 
             let id = obj
@@ -564,6 +902,23 @@ fn parse_phase_checks(checks: &[Value]) -> Vec<Result<RunnablePhaseCheck, ForgeP
                 .filter(|value| !value.is_empty())
                 .unwrap_or(&id)
                 .to_string();
+            let kind = obj
+                .get("kind")
+                .and_then(Value::as_str)
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .unwrap_or("command");
+            if kind != "command" {
+                return match parse_typed_check(kind, obj) {
+                    Ok(typed) => Ok(RunnablePhaseCheck {
+                        id,
+                        title,
+                        kind: RunnablePhaseCheckKind::Typed(typed),
+                    }),
+                    Err(err) => Err(invalid_check_result(id, title, err)),
+                };
+            }
+
             let command = obj
                 .get("command")
                 .and_then(Value::as_str)
@@ -571,15 +926,11 @@ fn parse_phase_checks(checks: &[Value]) -> Vec<Result<RunnablePhaseCheck, ForgeP
                 .unwrap_or("")
                 .to_string();
             if command.is_empty() {
-                return Err(ForgePhaseCheckResultV1 {
+                return Err(invalid_check_result(
                     id,
                     title,
-                    exit_code: 2,
-                    duration_ms: 0,
-                    stdout: String::new(),
-                    stderr: "Phase check object is missing a non-empty command field.".to_string(),
-                    timed_out: false,
-                });
+                    "Phase check object is missing a non-empty command field.".to_string(),
+                ));
             }
             let timeout_seconds = obj
                 .get("timeoutSec")
@@ -590,43 +941,150 @@ fn parse_phase_checks(checks: &[Value]) -> Vec<Result<RunnablePhaseCheck, ForgeP
             Ok(RunnablePhaseCheck {
                 id,
                 title,
-                command,
-                timeout_seconds,
+                kind: RunnablePhaseCheckKind::Command {
+                    command,
+                    timeout_seconds,
+                },
             })
         })
         .collect()
 }
 
-async fn run_phase_check(workspace_root: &Path, check: &RunnablePhaseCheck) -> ForgePhaseCheckResultV1 {
+/// Reads one output pipe of a running check, keeping every byte and forwarding decoded
+/// chunks as they arrive. A UTF-8 sequence split across reads waits for the next read.
+async fn stream_check_output<R: AsyncRead + Unpin>(
+    reader: Option<R>,
+    captured: &mut Vec<u8>,
+    mut on_chunk: impl FnMut(String),
+) {
+    let Some(mut reader) = reader else {
+        return;
+    };
+    let mut buffer = [0u8; 8192];
+    let mut pending: Vec<u8> = Vec::new();
+    loop {
+        let count = match reader.read(&mut buffer).await {
+            Ok(0) | Err(_) => break,
+            Ok(count) => count,
+        };
+        captured.extend_from_slice(&buffer[..count]);
+        pending.extend_from_slice(&buffer[..count]);
+        let ready = match std::str::from_utf8(&pending) {
+            Err(error) if error.error_len().is_none() => error.valid_up_to(),
+            _ => pending.len(),
+        };
+        if ready > 0 {
+            on_chunk(String::from_utf8_lossy(&pending[..ready]).to_string());
+            pending.drain(..ready);
+        }
+    }
+    if !pending.is_empty() {
+        on_chunk(String::from_utf8_lossy(&pending).to_string());
+    }
+}
+
+/// Keeps whatever the process wrote to stderr before it was stopped.
+fn append_process_note(stderr: &[u8], note: &str) -> String {
+    let partial = String::from_utf8_lossy(stderr);
+    if partial.trim().is_empty() {
+        note.to_string()
+    } else {
+        format!("{}\n{note}", partial.trim_end())
+    }
+}
+
+enum ForgeProcessOutcome {
+    Exited(std::process::ExitStatus),
+    WaitFailed(std::io::Error),
+    TimedOut,
+    Cancelled,
+}
+
+/// Drains a check or hook spawned with piped output into `stdout`/`stderr` until it exits.
+/// On timeout or cancellation the child's whole process group is killed.
+async fn wait_for_forge_process(
+    child: &mut tokio::process::Child,
+    stdout: &mut Vec<u8>,
+    stderr: &mut Vec<u8>,
+    timeout_seconds: u64,
+    cancel: Option<&ForgeCancelToken>,
+    on_chunk: impl Fn(ForgeCheckOutputStream, String),
+) -> ForgeProcessOutcome {
+    let stdout_pipe = child.stdout.take();
+    let stderr_pipe = child.stderr.take();
+    let outcome = {
+        let run = pin!(timeout(Duration::from_secs(timeout_seconds), async {
+            let (_, _, status) = join3(
+                stream_check_output(stdout_pipe, stdout, |data| {
+                    on_chunk(ForgeCheckOutputStream::Stdout, data)
+                }),
+                stream_check_output(stderr_pipe, stderr, |data| {
+                    on_chunk(ForgeCheckOutputStream::Stderr, data)
+                }),
+                child.wait(),
+            )
+            .await;
+            status
+        }));
+        let cancelled = pin!(async {
+            match cancel {
+                Some(cancel) => cancel.cancelled().await,
+                None => std::future::pending().await,
+            }
+        });
+        let raced = select(run, cancelled).await;
+        match raced {
+            Either::Left((Ok(Ok(status)), _)) => ForgeProcessOutcome::Exited(status),
+            Either::Left((Ok(Err(err)), _)) => ForgeProcessOutcome::WaitFailed(err),
+            Either::Left((Err(_), _)) => ForgeProcessOutcome::TimedOut,
+            Either::Right(_) => ForgeProcessOutcome::Cancelled,
+        }
+    };
+    if matches!(
+        outcome,
+        ForgeProcessOutcome::TimedOut | ForgeProcessOutcome::Cancelled
+    ) {
+        kill_process_group(child).await;
+    }
+    outcome
+}
+
+async fn run_command_check<E: EventSink>(
+    workspace_root: &Path,
+    check: &RunnablePhaseCheck,
+    command_line: &str,
+    timeout_seconds: u64,
+    events: &ForgeExecutionEmitter<'_, E>,
+    cancel: &ForgeCancelToken,
+) -> ForgePhaseCheckResultV1 {
     let start = Instant::now();
+    events.check_started(&check.id, &check.title);
 
     #[cfg(target_os = "windows")]
     let mut command = {
         let mut command = tokio_command("cmd");
-        command.arg("/C").arg(&check.command);
+        command.arg("/C").arg(command_line);
         command
     };
 
     #[cfg(not(target_os = "windows"))]
     let mut command = {
         let mut command = tokio_command("sh");
-        command.arg("-lc").arg(&check.command);
+        command.arg("-lc").arg(command_line);
         command
     };
 
-    command.current_dir(workspace_root).env("PATH", git_env_path());
-
-    match timeout(Duration::from_secs(check.timeout_seconds), command.output()).await {
-        Ok(Ok(output)) => ForgePhaseCheckResultV1 {
-            id: check.id.clone(),
-            title: check.title.clone(),
-            exit_code: output.status.code().unwrap_or(-1),
-            duration_ms: start.elapsed().as_millis() as i64,
-            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
-            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
-            timed_out: false,
-        },
-        Ok(Err(err)) => ForgePhaseCheckResultV1 {
+    command
+        .current_dir(workspace_root)
+        .env("PATH", git_env_path())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    set_new_process_group(&mut command);
+
+    let result = match command.spawn() {
+        Err(err) => ForgePhaseCheckResultV1 {
             id: check.id.clone(),
             title: check.title.clone(),
             exit_code: -1,
@@ -634,133 +1092,85 @@ async fn run_phase_check(workspace_root: &Path, check: &RunnablePhaseCheck) -> F
             stdout: String::new(),
             stderr: format!("Failed to run phase check command: {err}"),
             timed_out: false,
+            details: None,
         },
-        Err(_) => ForgePhaseCheckResultV1 {
-            id: check.id.clone(),
-            title: check.title.clone(),
-            exit_code: 124,
-            duration_ms: start.elapsed().as_millis() as i64,
-            stdout: String::new(),
-            stderr: format!(
-                "Phase check timed out after {}s.",
-                check.timeout_seconds
-            ),
-            timed_out: true,
-        },
-    }
-}
-
-fn run_ai_review_report_check(paths: &ForgeExecutionPaths, task_id: &str) -> ForgePhaseCheckResultV1 {
-    let start = Instant::now();
-    let report_path = paths
-        .plan_dir
-        .join("ai-review")
-        .join(format!("{task_id}.json"));
-
-    let failure = |stderr: String, duration_ms: i64| ForgePhaseCheckResultV1 {
-        id: "ai-review-report".to_string(),
-        title: "AI review report has zero findings".to_string(),
-        exit_code: 2,
-        duration_ms,
-        stdout: String::new(),
-        stderr,
-        timed_out: false,
-    };
-
-    if !report_path.is_file() {
-        return failure(
-            format!(
-                "Missing AI review report: {}. Expected JSON with findings: [].",
-                report_path.display()
-            ),
-            start.elapsed().as_millis() as i64,
-        );
-    }
-
-    let raw = match fs::read_to_string(&report_path) {
-        Ok(value) => value,
-        Err(err) => {
-            return failure(
-                format!("Failed to read AI review report {}: {err}", report_path.display()),
-                start.elapsed().as_millis() as i64,
-            )
-        }
-    };
-    let report: Value = match serde_json::from_str(&raw) {
-        Ok(value) => value,
-        Err(err) => {
-            return failure(
-                format!("Invalid JSON in AI review report {}: {err}", report_path.display()),
-                start.elapsed().as_millis() as i64,
+        Ok(mut child) => {
+            let mut stdout = Vec::new();
+            let mut stderr = Vec::new();
+            let outcome = wait_for_forge_process(
+                &mut child,
+                &mut stdout,
+                &mut stderr,
+                timeout_seconds,
+                Some(cancel),
+                |stream, data| {
+                    events.emit(ForgeExecutionEventKind::CheckOutput {
+                        check_id: check.id.clone(),
+                        stream,
+                        data,
+                    })
+                },
             )
+            .await;
+
+            let (exit_code, stderr, timed_out) = match outcome {
+                ForgeProcessOutcome::Exited(status) => (
+                    status.code().unwrap_or(-1),
+                    String::from_utf8_lossy(&stderr).to_string(),
+                    false,
+                ),
+                ForgeProcessOutcome::WaitFailed(err) => (
+                    -1,
+                    format!("Failed to run phase check command: {err}"),
+                    false,
+                ),
+                ForgeProcessOutcome::TimedOut => (
+                    124,
+                    append_process_note(
+                        &stderr,
+                        &format!("Phase check timed out after {timeout_seconds}s."),
+                    ),
+                    true,
+                ),
+                ForgeProcessOutcome::Cancelled => (
+                    130,
+                    append_process_note(&stderr, "Phase check cancelled."),
+                    false,
+                ),
+            };
+            ForgePhaseCheckResultV1 {
+                id: check.id.clone(),
+                title: check.title.clone(),
+                exit_code,
+                duration_ms: start.elapsed().as_millis() as i64,
+                stdout: String::from_utf8_lossy(&stdout).to_string(),
+                stderr,
+                timed_out,
+                details: None,
+            }
         }
     };
 
-    let schema = report
-        .get("schema")
-        .and_then(Value::as_str)
-        .map(str::trim)
-        .unwrap_or("");
-    if schema != AI_REVIEW_REPORT_SCHEMA {
-        return failure(
-            format!(
-                "AI review report schema must be {AI_REVIEW_REPORT_SCHEMA} (got: {}).",
-                if schema.is_empty() { "<missing>" } else { schema }
-            ),
-            start.elapsed().as_millis() as i64,
-        );
-    }
-
-    let report_task_id = report
-        .get("taskId")
-        .and_then(Value::as_str)
-        .map(str::trim)
-        .unwrap_or("");
-    if report_task_id != task_id {
-        return failure(
-            format!(
-                "AI review report taskId mismatch (expected {task_id}, got {}).",
-                if report_task_id.is_empty() {
-                    "<missing>"
-                } else {
-                    report_task_id
-                }
-            ),
-            start.elapsed().as_millis() as i64,
-        );
-    }
-
-    let findings = match report.get("findings").and_then(Value::as_array) {
-        Some(value) => value,
-        None => {
-            return failure(
-                "AI review report must include a findings array.".to_string(),
-                start.elapsed().as_millis() as i64,
-            )
-        }
-    };
-    if !findings.is_empty() {
-        return failure(
-            format!(
-                "AI review report contains {} finding(s); resolve all findings before marking ai-review complete.",
-                findings.len()
-            ),
-            start.elapsed().as_millis() as i64,
-        );
-    }
+    events.check_finished(&result);
+    result
+}
 
-    ForgePhaseCheckResultV1 {
-        id: "ai-review-report".to_string(),
-        title: "AI review report has zero findings".to_string(),
-        exit_code: 0,
-        duration_ms: start.elapsed().as_millis() as i64,
-        stdout: format!(
-            "Verified zero findings in {}.",
-            report_path.to_string_lossy()
-        ),
-        stderr: String::new(),
-        timed_out: false,
+fn emit_phase_started<E: EventSink>(
+    event_sink: &E,
+    workspace_id: &str,
+    plan_id: &str,
+    task_id: &str,
+    phase_id: &str,
+    is_last_phase: bool,
+) {
+    ForgeExecutionEmitter {
+        event_sink,
+        workspace_id,
+        plan_id,
+        task_id,
+        phase_id,
     }
+    .emit(ForgeExecutionEventKind::PhaseStarted { is_last_phase });
 }
 
 fn task_has_commit_sha(task: &StateTaskV2) -> bool {
@@ -770,9 +1180,19 @@ fn task_has_commit_sha(task: &StateTaskV2) -> bool {
         .unwrap_or(false)
 }
 
-fn write_state_file(paths: &ForgeExecutionPaths, state: &StateV2) -> Result<(), String> {
-    let state_raw = serde_json::to_string_pretty(state).map_err(|err| err.to_string())?;
-    fs::write(&paths.state_path, format!("{state_raw}\n")).map_err(|err| err.to_string())
+/// Checks and the task commit run inside the task's worktree when the scheduler assigned one.
+fn task_execution_root(paths: &ForgeExecutionPaths, task: &StateTaskV2) -> PathBuf {
+    task.worktree
+        .as_ref()
+        .map(|worktree| PathBuf::from(&worktree.path))
+        .filter(|path| path.is_dir())
+        .unwrap_or_else(|| paths.workspace_root.clone())
+}
+
+/// Pathspec that leaves out the plan folder. Forge keeps rewriting `state.json` and the run
+/// logs there, so rollbacks and diff limits only look at the rest of the tree.
+fn outside_plan_dir_pathspec(plan_id: &str) -> String {
+    format!(":(exclude)plans/{plan_id}")
 }
 
 fn set_task_status_from_phases(task: &mut StateTaskV2) {
@@ -784,14 +1204,6 @@ fn set_task_status_from_phases(task: &mut StateTaskV2) {
     };
 }
 
-fn build_forge_task_commit_message(plan_id: &str, task: &PlanTaskV1) -> String {
-    let normalized_name = task.name.split_whitespace().collect::<Vec<_>>().join(" ");
-    if normalized_name.is_empty() {
-        return format!("forge({plan_id}): {}", task.id);
-    }
-    format!("forge({plan_id}): {} {normalized_name}", task.id)
-}
-
 async fn run_git_command_with_timeout(
     workspace_root: &Path,
     args: &[&str],
@@ -813,130 +1225,38 @@ async fn run_git_command_with_timeout(
     }
 }
 
-async fn forge_create_task_commit(
-    workspace_root: &Path,
-    commit_message: &str,
-) -> Result<(String, String, String, i64), ForgePhaseCheckResultV1> {
-    let start = Instant::now();
-    let add_output = match run_git_command_with_timeout(workspace_root, &["add", "-A"]).await {
-        Ok(output) => output,
-        Err(err) => {
-            return Err(ForgePhaseCheckResultV1 {
-                id: "forge-commit".to_string(),
-                title: "Forge task commit".to_string(),
-                exit_code: -1,
-                duration_ms: start.elapsed().as_millis() as i64,
-                stdout: String::new(),
-                stderr: err,
-                timed_out: false,
-            })
-        }
-    };
-    if !add_output.status.success() {
-        return Err(ForgePhaseCheckResultV1 {
-            id: "forge-commit".to_string(),
-            title: "Forge task commit".to_string(),
-            exit_code: add_output.status.code().unwrap_or(-1),
-            duration_ms: start.elapsed().as_millis() as i64,
-            stdout: String::from_utf8_lossy(&add_output.stdout).to_string(),
-            stderr: String::from_utf8_lossy(&add_output.stderr).to_string(),
-            timed_out: false,
-        });
-    }
-
-    let commit_output = match run_git_command_with_timeout(
-        workspace_root,
-        &["commit", "-m", commit_message],
-    )
-    .await
-    {
-        Ok(output) => output,
-        Err(err) => {
-            return Err(ForgePhaseCheckResultV1 {
-                id: "forge-commit".to_string(),
-                title: "Forge task commit".to_string(),
-                exit_code: -1,
-                duration_ms: start.elapsed().as_millis() as i64,
-                stdout: String::new(),
-                stderr: err,
-                timed_out: false,
-            })
-        }
-    };
-    if !commit_output.status.success() {
-        return Err(ForgePhaseCheckResultV1 {
-            id: "forge-commit".to_string(),
-            title: "Forge task commit".to_string(),
-            exit_code: commit_output.status.code().unwrap_or(-1),
-            duration_ms: start.elapsed().as_millis() as i64,
-            stdout: String::from_utf8_lossy(&commit_output.stdout).to_string(),
-            stderr: String::from_utf8_lossy(&commit_output.stderr).to_string(),
-            timed_out: false,
-        });
-    }
-
-    let sha_output = match run_git_command_with_timeout(workspace_root, &["rev-parse", "HEAD"]).await {
-        Ok(output) => output,
-        Err(err) => {
-            return Err(ForgePhaseCheckResultV1 {
-                id: "forge-commit".to_string(),
-                title: "Forge task commit".to_string(),
-                exit_code: -1,
-                duration_ms: start.elapsed().as_millis() as i64,
-                stdout: String::new(),
-                stderr: err,
-                timed_out: false,
-            })
-        }
-    };
-    if !sha_output.status.success() {
-        return Err(ForgePhaseCheckResultV1 {
-            id: "forge-commit".to_string(),
-            title: "Forge task commit".to_string(),
-            exit_code: sha_output.status.code().unwrap_or(-1),
-            duration_ms: start.elapsed().as_millis() as i64,
-            stdout: String::from_utf8_lossy(&sha_output.stdout).to_string(),
-            stderr: String::from_utf8_lossy(&sha_output.stderr).to_string(),
-            timed_out: false,
-        });
-    }
-
-    let sha = String::from_utf8_lossy(&sha_output.stdout).trim().to_string();
-    if sha.is_empty() {
-        return Err(ForgePhaseCheckResultV1 {
-            id: "forge-commit".to_string(),
-            title: "Forge task commit".to_string(),
-            exit_code: -1,
-            duration_ms: start.elapsed().as_millis() as i64,
-            stdout: String::new(),
-            stderr: "Unable to resolve commit SHA after commit.".to_string(),
-            timed_out: false,
-        });
-    }
-
-    Ok((
-        sha,
-        String::from_utf8_lossy(&commit_output.stdout).to_string(),
-        String::from_utf8_lossy(&commit_output.stderr).to_string(),
-        start.elapsed().as_millis() as i64,
-    ))
-}
-
 pub(crate) async fn forge_prepare_execution_core(
     workspace_root: &Path,
     plan_id: &str,
+    workspace_id: &str,
+    cancels: &ForgeExecutionCancels,
 ) -> Result<(), String> {
     let paths = build_execution_paths(workspace_root, plan_id)?;
     require_plan_file(&paths.plan_path)?;
-    let _ = load_plan(&paths)?;
+    check_plan_structure(&load_plan(&paths)?)?;
     let context = build_hook_context(&paths);
+    let registration = cancels.register(workspace_id, &paths.plan_id);
+    let cancel = registration.token();
 
     if paths.state_path.is_file() {
-        let _ = load_state(&paths)?;
+        migrate_state_file(&paths).await?;
+        restart_run_clock(&paths).await?;
     } else {
-        run_template_hook(&paths.post_plan_hook_path, &paths.workspace_root, &context).await?;
+        run_template_hook(
+            &paths.post_plan_hook,
+            &paths.workspace_root,
+            &context,
+            Some(cancel),
+        )
+        .await?;
     }
-    run_template_hook(&paths.pre_execute_hook_path, &paths.workspace_root, &context).await
+    run_template_hook(
+        &paths.pre_execute_hook,
+        &paths.workspace_root,
+        &context,
+        Some(cancel),
+    )
+    .await
 }
 
 pub(crate) async fn forge_reset_execution_progress_core(
@@ -948,13 +1268,17 @@ pub(crate) async fn forge_reset_execution_progress_core(
     let _ = load_plan(&paths)?;
     let context = build_hook_context(&paths);
 
-    run_template_hook(&paths.post_plan_hook_path, &paths.workspace_root, &context).await?;
-    run_template_hook(&paths.pre_execute_hook_path, &paths.workspace_root, &context).await
+    run_template_hook(&paths.post_plan_hook, &paths.workspace_root, &context, None).await?;
+    run_template_hook(&paths.pre_execute_hook, &paths.workspace_root, &context, None).await
 }
 
-pub(crate) async fn forge_get_next_phase_prompt_core(
+pub(crate) async fn forge_get_next_phase_prompt_core<E: EventSink>(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_root: &Path,
     plan_id: &str,
+    workspace_id: &str,
+    event_sink: &E,
+    cancels: &ForgeExecutionCancels,
 ) -> Result<Option<ForgeNextPhasePromptV1>, String> {
     let paths = build_execution_paths(workspace_root, plan_id)?;
     require_plan_file(&paths.plan_path)?;
@@ -966,6 +1290,7 @@ pub(crate) async fn forge_get_next_phase_prompt_core(
     }
 
     let plan = load_plan(&paths)?;
+    check_plan_structure(&plan)?;
     let state = load_state(&paths)?;
     let Some(task) = find_next_runnable_task(&plan, &state) else {
         return Ok(None);
@@ -975,27 +1300,70 @@ pub(crate) async fn forge_get_next_phase_prompt_core(
         .get(task.id.as_str())
         .ok_or_else(|| format!("state.json missing task entry for {}", task.id))?;
 
-    let (phase_id, is_last_phase) = match find_next_phase(task_state) {
-        Some((index, phase)) => (phase.id.clone(), index + 1 >= task_state.phases.len()),
-        None => ("implementation".to_string(), true),
+    let (phase_index, phase_id, is_last_phase) = match find_next_phase(task_state) {
+        Some((index, phase)) => (index, phase.id.clone(), index + 1 >= task_state.phases.len()),
+        None => (0, "implementation".to_string(), true),
     };
+    let phases = load_template_phases(&paths)?;
+    if is_manual_approval_phase(&phases, &phase_id) {
+        return Ok(Some(awaiting_approval_prompt(
+            event_sink,
+            workspace_id,
+            &paths.plan_id,
+            &task.id,
+            &phase_id,
+            is_last_phase,
+        )));
+    }
+    if let Some(note) =
+        enforce_plan_budget(workspaces, &paths, plan.budget.as_ref(), &state, &task.id).await?
+    {
+        return Ok(Some(budget_exceeded_prompt(
+            event_sink,
+            workspace_id,
+            &paths.plan_id,
+            &task.id,
+            &phase_id,
+            is_last_phase,
+            note,
+        )));
+    }
 
     // Always regenerate execute prompt from current plan/state to avoid stale task/phase instructions.
     let context = build_hook_context(&paths);
-    run_template_hook(&paths.post_step_hook_path, &paths.workspace_root, &context).await?;
+    let registration = cancels.register(workspace_id, &paths.plan_id);
+    run_template_hook(
+        &paths.post_step_hook,
+        &paths.workspace_root,
+        &context,
+        Some(registration.token()),
+    )
+    .await?;
+    drop(registration);
     let prompt_text = fs::read_to_string(&paths.generated_execute_prompt_path).map_err(|err| {
         format!(
             "Unable to read generated execute prompt {}: {err}",
             paths.generated_execute_prompt_path.display()
         )
     })?;
-
+    let prompt_text = append_review_feedback(prompt_text, task_state, phase_index);
+
+    emit_phase_started(
+        event_sink,
+        workspace_id,
+        &paths.plan_id,
+        &task.id,
+        &phase_id,
+        is_last_phase,
+    );
     Ok(Some(ForgeNextPhasePromptV1 {
         plan_id: paths.plan_id,
         task_id: task.id.clone(),
         phase_id,
         is_last_phase,
         prompt_text,
+        awaiting_approval: false,
+        budget_exceeded: None,
     }))
 }
 
@@ -1044,12 +1412,19 @@ pub(crate) fn forge_get_phase_status_core(
     })
 }
 
-pub(crate) async fn forge_run_phase_checks_core(
+pub(crate) async fn forge_run_phase_checks_core<E: EventSink>(
     workspace_root: &Path,
-    plan_id: &str,
-    task_id: &str,
-    phase_id: &str,
+    request: ForgeRunPhaseChecksRequest<'_>,
+    workspace_id: &str,
+    event_sink: &E,
+    cancels: &ForgeExecutionCancels,
 ) -> Result<ForgeRunPhaseChecksResponseV1, String> {
+    let ForgeRunPhaseChecksRequest {
+        plan_id,
+        task_id,
+        phase_id,
+        thread_id,
+    } = request;
     let paths = build_execution_paths(workspace_root, plan_id)?;
     let task_id = task_id.trim();
     let phase_id = phase_id.trim();
@@ -1066,15 +1441,13 @@ pub(crate) async fn forge_run_phase_checks_core(
         .iter()
         .find(|task| task.id.trim() == task_id)
         .ok_or_else(|| format!("Unknown taskId: {task_id}"))?;
-    let mut state = load_state(&paths)?;
-    let task_index = state
-        .tasks
-        .iter()
-        .position(|task| task.id.trim() == task_id)
-        .ok_or_else(|| format!("state.json missing task entry for {task_id}"))?;
+    // Checks can run for minutes, so the state is re-read under the lock for every update
+    // instead of holding on to this snapshot.
+    let state = load_state(&paths)?;
     let task_state = state
         .tasks
-        .get(task_index)
+        .iter()
+        .find(|task| task.id.trim() == task_id)
         .ok_or_else(|| format!("state.json missing task entry for {task_id}"))?;
     let phase_index = task_state
         .phases
@@ -1082,40 +1455,142 @@ pub(crate) async fn forge_run_phase_checks_core(
         .position(|phase| phase.id.trim() == phase_id)
         .ok_or_else(|| format!("Unknown phaseId for task {task_id}: {phase_id}"))?;
     let is_last_phase = phase_index + 1 >= task_state.phases.len();
+    let execution_root = task_execution_root(&paths, task_state);
+    let base_sha = task_state
+        .worktree
+        .as_ref()
+        .and_then(|worktree| worktree.base_sha.as_deref())
+        .map(str::trim)
+        .filter(|sha| !sha.is_empty())
+        .map(str::to_string);
 
     let phases = load_template_phases(&paths)?;
-    let phase_checks = phases
+    require_approval(&phases, task_state, phase_index)?;
+    let template_phase = phases
         .phases
         .iter()
-        .find(|phase| phase.id.trim() == phase_id)
+        .find(|phase| phase.id.trim() == phase_id);
+    let phase_checks = template_phase
         .map(|phase| phase.checks.as_slice())
         .unwrap_or(&[]);
-
+    let max_attempts = template_phase.and_then(|phase| phase.max_attempts);
+    let commit_policy = load_commit_policy(&paths, phases.commit.as_ref())?;
+    let backoff_seconds = template_phase.and_then(|phase| phase.backoff_seconds);
+
+    let events = ForgeExecutionEmitter {
+        event_sink,
+        workspace_id,
+        plan_id: &paths.plan_id,
+        task_id,
+        phase_id,
+    };
+    let run_log = ForgeRunLogEntry {
+        plan_dir: &paths.plan_dir,
+        plan_id: &paths.plan_id,
+        task_id,
+        phase_id,
+        thread_id,
+        started_at: now_rfc3339_millis(),
+    };
+    let typed_context = TypedCheckContext {
+        execution_root: &execution_root,
+        plan_id: &paths.plan_id,
+        task_id,
+        base_sha: base_sha.as_deref(),
+    };
+    let registration = cancels.register(workspace_id, &paths.plan_id);
+    let cancel = registration.token();
     let mut results = Vec::new();
     for parsed in parse_phase_checks(phase_checks) {
+        if cancel.is_cancelled() {
+            break;
+        }
         match parsed {
             Ok(check) => {
-                let result = run_phase_check(&paths.workspace_root, &check).await;
+                let result = match &check.kind {
+                    RunnablePhaseCheckKind::Command {
+                        command,
+                        timeout_seconds,
+                    } => {
+                        run_command_check(
+                            &execution_root,
+                            &check,
+                            command,
+                            *timeout_seconds,
+                            &events,
+                            cancel,
+                        )
+                        .await
+                    }
+                    RunnablePhaseCheckKind::Typed(typed) => {
+                        run_typed_check(&typed_context, &check.id, &check.title, typed, &events)
+                            .await
+                    }
+                };
+                results.push(result);
+            }
+            Err(result) => {
+                events.check_finished(&result);
                 results.push(result);
             }
-            Err(result) => results.push(result),
         }
     }
 
+    if cancel.is_cancelled() {
+        // Nothing was staged or committed yet, so the worktree keeps the agent's edits as-is.
+        // A cancelled run does not count against the phase's attempts.
+        let attempts = update_task_state(&paths, task_id, |task| {
+            task.status = "in_progress".to_string();
+            if let Some(phase) = task.phases.get_mut(phase_index) {
+                record_phase_thread(phase, thread_id, &run_log.started_at);
+                phase.status = "in_progress".to_string();
+                phase.notes = format!(
+                    "Phase checks cancelled at {}.",
+                    Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+                );
+            }
+            Ok(recorded_phase_attempts(task, phase_index))
+        })
+        .await?;
+        let response = ForgeRunPhaseChecksResponseV1 {
+            ok: false,
+            results,
+            cancelled: true,
+            attempts,
+            max_attempts,
+            retry_after_ms: None,
+            exhausted: false,
+        };
+        run_log.append(&response, None)?;
+        events.emit(ForgeExecutionEventKind::Cancelled);
+        return Ok(response);
+    }
+
     let mut ok = results
         .iter()
         .all(|result| !result.timed_out && result.exit_code == 0);
 
-    if phase_id == "ai-review" {
-        let ai_review_result = run_ai_review_report_check(&paths, task_id);
-        if ai_review_result.timed_out || ai_review_result.exit_code != 0 {
+    let artifacts = template_phase.and_then(|phase| phase.artifacts.as_deref());
+    for parsed in parse_artifact_gates(phase_id, artifacts) {
+        let result = match parsed {
+            Ok(gate) => {
+                events.check_started(&gate.id, &gate.title);
+                run_artifact_gate(&paths, task_id, &gate)
+            }
+            Err(result) => result,
+        };
+        events.check_finished(&result);
+        if result.timed_out || result.exit_code != 0 {
             ok = false;
         }
-        results.push(ai_review_result);
+        results.push(result);
     }
 
-    if let Some(task) = state.tasks.get_mut(task_index) {
+    let (attempts, should_commit_task) = update_task_state(&paths, task_id, |task| {
         if let Some(phase) = task.phases.get_mut(phase_index) {
+            record_phase_thread(phase, thread_id, &run_log.started_at);
+            phase.attempts = phase.attempts.saturating_add(1);
+            task.attempts = task.attempts.saturating_add(1);
             if ok {
                 phase.status = "completed".to_string();
                 if is_last_phase && !task_has_commit_sha(task) {
@@ -1127,49 +1602,102 @@ pub(crate) async fn forge_run_phase_checks_core(
                 phase.status = "in_progress".to_string();
                 task.status = "in_progress".to_string();
             }
-            write_state_file(&paths, &state)?;
         }
-    }
-
-    let should_commit_task = if ok && is_last_phase {
-        let task = state
-            .tasks
-            .get(task_index)
-            .ok_or_else(|| format!("state.json missing task entry for {task_id}"))?;
-        are_all_task_phases_completed(task) && !task_has_commit_sha(task)
-    } else {
-        false
-    };
-    if should_commit_task {
-        let commit_message = build_forge_task_commit_message(&paths.plan_id, plan_task);
-        match forge_create_task_commit(&paths.workspace_root, &commit_message).await {
-            Ok((sha, stdout, stderr, duration_ms)) => {
-                if let Some(task) = state.tasks.get_mut(task_index) {
-                    task.commit_sha = Some(sha.clone());
-                    task.status = "completed".to_string();
-                }
-                write_state_file(&paths, &state)?;
-                results.push(ForgePhaseCheckResultV1 {
-                    id: "forge-commit".to_string(),
-                    title: "Forge task commit".to_string(),
-                    exit_code: 0,
-                    duration_ms,
-                    stdout: if stdout.trim().is_empty() {
-                        format!("Created commit {sha}")
-                    } else {
-                        stdout
-                    },
-                    stderr,
-                    timed_out: false,
+        let should_commit_task = ok
+            && is_last_phase
+            && are_all_task_phases_completed(task)
+            && !task_has_commit_sha(task);
+        Ok((recorded_phase_attempts(task, phase_index), should_commit_task))
+    })
+    .await?;
+    let should_commit_phase = ok && !is_last_phase && commit_policy.per_phase();
+    let mut created_commit_sha = None;
+    if should_commit_task || should_commit_phase {
+        let kind = if should_commit_task {
+            ForgeCommitKind::Task
+        } else {
+            ForgeCommitKind::Phase
+        };
+        let message = commit_policy.message_for(&paths.plan_id, plan_task, phase_id, kind);
+        let commit =
+            create_forge_commit(&execution_root, &commit_policy, &paths.plan_id, &message, kind)
+                .await;
+        match commit {
+            Ok(Some(commit)) => {
+                update_task_state(&paths, task_id, |task| {
+                    if commit_policy.per_phase() {
+                        if let Some(phase) = task.phases.get_mut(phase_index) {
+                            phase.commit_sha = Some(commit.sha.clone());
+                        }
+                    }
+                    if should_commit_task {
+                        task.commit_sha = Some(commit.sha.clone());
+                        task.status = "completed".to_string();
+                    }
+                    Ok(())
+                })
+                .await?;
+                events.emit(ForgeExecutionEventKind::CommitCreated {
+                    commit_sha: commit.sha.clone(),
                 });
+                created_commit_sha = Some(commit.sha);
+                results.push(commit.result);
+            }
+            // A phase without changes under the policy has nothing to commit.
+            Ok(None) if !should_commit_task => {}
+            Ok(None) => {
+                // With per-phase commits the last phase may add nothing; the newest phase
+                // commit then stands for the task.
+                let reused_sha = update_task_state(&paths, task_id, |task| {
+                    let sha = task_commit_shas(task).pop();
+                    if let Some(sha) = &sha {
+                        task.commit_sha = Some(sha.clone());
+                        task.status = "completed".to_string();
+                    } else {
+                        task.status = "in_progress".to_string();
+                    }
+                    Ok(sha)
+                })
+                .await?;
+                match reused_sha {
+                    Some(sha) => {
+                        results.push(kind.result(
+                            0,
+                            0,
+                            format!("No changes left to commit; task commit is {sha}."),
+                            String::new(),
+                        ));
+                    }
+                    None => {
+                        let result = kind.result(
+                            1,
+                            0,
+                            String::new(),
+                            "Nothing to commit for this task under the commit policy."
+                                .to_string(),
+                        );
+                        events.check_finished(&result);
+                        results.push(result);
+                        ok = false;
+                    }
+                }
             }
             Err(result) => {
+                events.check_finished(&result);
                 results.push(result);
                 ok = false;
-                if let Some(task) = state.tasks.get_mut(task_index) {
+                update_task_state(&paths, task_id, |task| {
                     task.status = "in_progress".to_string();
-                    write_state_file(&paths, &state)?;
-                }
+                    // A phase whose commit failed runs again rather than leaving its changes
+                    // uncommitted behind a completed status.
+                    if should_commit_phase {
+                        if let Some(phase) = task.phases.get_mut(phase_index) {
+                            phase.status = "in_progress".to_string();
+                        }
+                    }
+                    Ok(())
+                })
+                .await?;
             }
         }
     }
@@ -1178,19 +1706,82 @@ pub(crate) async fn forge_run_phase_checks_core(
         && results
             .iter()
             .all(|result| !result.timed_out && result.exit_code == 0);
+    let exhausted = !ok && max_attempts.is_some_and(|max| attempts >= i64::from(max));
+    let retry_after_ms = if ok || exhausted {
+        None
+    } else {
+        backoff_seconds
+            .map(|seconds| phase_retry_delay_ms(seconds, attempts))
+            .filter(|delay_ms| *delay_ms > 0)
+    };
+    if exhausted {
+        update_task_state(&paths, task_id, |task| {
+            task.status = "blocked".to_string();
+            if let Some(phase) = task.phases.get_mut(phase_index) {
+                phase.status = "failed".to_string();
+                phase.notes = format!("Phase checks failed on all {attempts} attempts.");
+            }
+            Ok(())
+        })
+        .await?;
+    }
+    if !ok {
+        let failed = results
+            .iter()
+            .filter(|result| result.timed_out || result.exit_code != 0)
+            .map(|result| result.title.as_str())
+            .collect::<Vec<_>>();
+        events.emit(if exhausted {
+            ForgeExecutionEventKind::TaskBlocked {
+                reason: format!(
+                    "Retry budget exhausted after {attempts} attempts: {}",
+                    failed.join(", ")
+                ),
+            }
+        } else {
+            ForgeExecutionEventKind::ChecksFailed {
+                reason: format!("Phase checks failed: {}", failed.join(", ")),
+            }
+        });
+    }
 
     let context = build_hook_context(&paths);
-    run_template_hook(&paths.post_step_hook_path, &paths.workspace_root, &context).await?;
+    let hook_result =
+        run_template_hook(&paths.post_step_hook, &paths.workspace_root, &context, Some(cancel)).await;
+    let response = ForgeRunPhaseChecksResponseV1 {
+        ok,
+        results,
+        // A cancelled post-step hook only skips prompt regeneration; the next prompt request
+        // redoes it.
+        cancelled: hook_result.is_err() && cancel.is_cancelled(),
+        attempts,
+        max_attempts,
+        retry_after_ms,
+        exhausted,
+    };
+    run_log.append(&response, created_commit_sha.as_deref())?;
+    if response.cancelled {
+        events.emit(ForgeExecutionEventKind::Cancelled);
+        return Ok(response);
+    }
+    hook_result?;
 
-    Ok(ForgeRunPhaseChecksResponseV1 { ok, results })
+    Ok(response)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::json;
+    use crate::backend::app_server::WorkspaceSession;
+    use crate::backend::events::{AppServerEvent, TerminalExit, TerminalOutput};
+    use super::cost::ForgeUsageV1;
+    use super::plan_graph::ForgeTaskBlockedReasonV1;
     use std::future::Future;
     use std::process::Command;
+    use std::sync::{Arc, Mutex};
+
+    const TEST_WORKSPACE_ID: &str = "workspace-1";
 
     const SIX_PHASE_IDS: [&str; 6] = [
         "test-case-mapping",
@@ -1211,6 +1802,34 @@ mod tests {
         }
     }
 
+    #[derive(Clone, Default)]
+    struct RecordingEventSink {
+        forge_events: Arc<Mutex<Vec<ForgeExecutionEvent>>>,
+    }
+
+    impl RecordingEventSink {
+        fn forge_event_kinds(&self) -> Vec<ForgeExecutionEventKind> {
+            self.forge_events
+                .lock()
+                .expect("events lock")
+                .iter()
+                .map(|event| event.kind.clone())
+                .collect()
+        }
+    }
+
+    impl EventSink for RecordingEventSink {
+        fn emit_app_server_event(&self, _event: AppServerEvent) {}
+
+        fn emit_terminal_output(&self, _event: TerminalOutput) {}
+
+        fn emit_terminal_exit(&self, _event: TerminalExit) {}
+
+        fn emit_forge_execution_event(&self, event: ForgeExecutionEvent) {
+            self.forge_events.lock().expect("events lock").push(event);
+        }
+    }
+
     fn temp_workspace_root() -> PathBuf {
         std::env::temp_dir().join(format!("codex-monitor-forge-execute-test-{}", Uuid::new_v4()))
     }
@@ -1594,10 +2213,17 @@ await fs.writeFile(ctx.generatedExecutePromptPath, 'fresh prompt from post-step\
                 "stale prompt that should not be returned\n",
             );
 
-            let next = forge_get_next_phase_prompt_core(&workspace, "alpha")
-                .await
-                .expect("get next phase prompt")
-                .expect("expected runnable phase");
+            let next = forge_get_next_phase_prompt_core(
+                &tokio::sync::Mutex::default(),
+                &workspace,
+                "alpha",
+                TEST_WORKSPACE_ID,
+                &RecordingEventSink::default(),
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect("get next phase prompt")
+            .expect("expected runnable phase");
             assert_eq!(next.task_id, "task-2");
             assert_eq!(next.phase_id, "implementation");
             assert_eq!(next.prompt_text.trim(), "fresh prompt from post-step");
@@ -1622,10 +2248,17 @@ await fs.writeFile(ctx.generatedExecutePromptPath, 'fresh prompt from post-step\
                 0,
             );
 
-            let next = forge_get_next_phase_prompt_core(&fixture.root, "alpha")
-                .await
-                .expect("get next phase")
-                .expect("expected next phase");
+            let next = forge_get_next_phase_prompt_core(
+                &tokio::sync::Mutex::default(),
+                &fixture.root,
+                "alpha",
+                TEST_WORKSPACE_ID,
+                &RecordingEventSink::default(),
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect("get next phase")
+            .expect("expected next phase");
             assert_eq!(next.task_id, "task-1");
             assert_eq!(next.phase_id, "coverage-hardening");
             assert!(!next.is_last_phase);
@@ -1649,10 +2282,20 @@ await fs.writeFile(ctx.generatedExecutePromptPath, 'fresh prompt from post-step\
                 0,
             );
 
-            let result =
-                forge_run_phase_checks_core(&fixture.root, "alpha", "task-1", "test-case-mapping")
-                    .await
-                    .expect("run phase checks");
+            let result = forge_run_phase_checks_core(
+                &fixture.root,
+                ForgeRunPhaseChecksRequest {
+                    plan_id: "alpha",
+                    task_id: "task-1",
+                    phase_id: "test-case-mapping",
+                    thread_id: None,
+                },
+                TEST_WORKSPACE_ID,
+                &RecordingEventSink::default(),
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect("run phase checks");
             assert!(result.ok);
             assert!(!result.results.iter().any(|check| check.id == "forge-commit"));
 
@@ -1663,10 +2306,17 @@ await fs.writeFile(ctx.generatedExecutePromptPath, 'fresh prompt from post-step\
             assert_eq!(phase_status(&task, "behavioral-tests"), "pending");
             assert_eq!(phase_status(&task, "ai-review"), "pending");
 
-            let next = forge_get_next_phase_prompt_core(&fixture.root, "alpha")
-                .await
-                .expect("get next phase")
-                .expect("expected next phase");
+            let next = forge_get_next_phase_prompt_core(
+                &tokio::sync::Mutex::default(),
+                &fixture.root,
+                "alpha",
+                TEST_WORKSPACE_ID,
+                &RecordingEventSink::default(),
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect("get next phase")
+            .expect("expected next phase");
             assert_eq!(next.task_id, "task-1");
             assert_eq!(next.phase_id, "behavioral-tests");
             assert!(!next.is_last_phase);
@@ -1689,9 +2339,20 @@ await fs.writeFile(ctx.generatedExecutePromptPath, 'fresh prompt from post-step\
                 1,
             );
 
-            let result = forge_run_phase_checks_core(&fixture.root, "alpha", "task-1", "ai-review")
-                .await
-                .expect("run phase checks");
+            let result = forge_run_phase_checks_core(
+                &fixture.root,
+                ForgeRunPhaseChecksRequest {
+                    plan_id: "alpha",
+                    task_id: "task-1",
+                    phase_id: "ai-review",
+                    thread_id: None,
+                },
+                TEST_WORKSPACE_ID,
+                &RecordingEventSink::default(),
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect("run phase checks");
             assert!(!result.ok);
             assert!(result.results.iter().any(|check| check.exit_code != 0));
             assert!(!result.results.iter().any(|check| check.id == "forge-commit"));
@@ -1701,10 +2362,17 @@ await fs.writeFile(ctx.generatedExecutePromptPath, 'fresh prompt from post-step\
             assert_eq!(task.commit_sha, None);
             assert_eq!(phase_status(&task, "ai-review"), "in_progress");
 
-            let next = forge_get_next_phase_prompt_core(&fixture.root, "alpha")
-                .await
-                .expect("get next phase")
-                .expect("expected next phase");
+            let next = forge_get_next_phase_prompt_core(
+                &tokio::sync::Mutex::default(),
+                &fixture.root,
+                "alpha",
+                TEST_WORKSPACE_ID,
+                &RecordingEventSink::default(),
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect("get next phase")
+            .expect("expected next phase");
             assert_eq!(next.task_id, "task-1");
             assert_eq!(next.phase_id, "ai-review");
             assert!(next.is_last_phase);
@@ -1728,9 +2396,20 @@ await fs.writeFile(ctx.generatedExecutePromptPath, 'fresh prompt from post-step\
             );
             init_git_repo(&fixture.root);
 
-            let result = forge_run_phase_checks_core(&fixture.root, "alpha", "task-1", "ai-review")
-                .await
-                .expect("run phase checks");
+            let result = forge_run_phase_checks_core(
+                &fixture.root,
+                ForgeRunPhaseChecksRequest {
+                    plan_id: "alpha",
+                    task_id: "task-1",
+                    phase_id: "ai-review",
+                    thread_id: None,
+                },
+                TEST_WORKSPACE_ID,
+                &RecordingEventSink::default(),
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect("run phase checks");
             assert!(!result.ok);
             assert!(result.results.iter().any(|check| check.id == "ai-review-report"));
 
@@ -1759,9 +2438,20 @@ await fs.writeFile(ctx.generatedExecutePromptPath, 'fresh prompt from post-step\
             init_git_repo(&fixture.root);
             write_ai_review_report(&fixture.root, "alpha", "task-1", &["missing test for edge case"]);
 
-            let result = forge_run_phase_checks_core(&fixture.root, "alpha", "task-1", "ai-review")
-                .await
-                .expect("run phase checks");
+            let result = forge_run_phase_checks_core(
+                &fixture.root,
+                ForgeRunPhaseChecksRequest {
+                    plan_id: "alpha",
+                    task_id: "task-1",
+                    phase_id: "ai-review",
+                    thread_id: None,
+                },
+                TEST_WORKSPACE_ID,
+                &RecordingEventSink::default(),
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect("run phase checks");
             assert!(!result.ok);
             assert!(result.results.iter().any(|check| check.id == "ai-review-report"));
             assert!(!result.results.iter().any(|check| check.id == "forge-commit"));
@@ -1791,9 +2481,21 @@ await fs.writeFile(ctx.generatedExecutePromptPath, 'fresh prompt from post-step\
             init_git_repo(&fixture.root);
             write_ai_review_report(&fixture.root, "alpha", "task-1", &[]);
 
-            let result = forge_run_phase_checks_core(&fixture.root, "alpha", "task-1", "ai-review")
-                .await
-                .expect("run phase checks");
+            let sink = RecordingEventSink::default();
+            let result = forge_run_phase_checks_core(
+                &fixture.root,
+                ForgeRunPhaseChecksRequest {
+                    plan_id: "alpha",
+                    task_id: "task-1",
+                    phase_id: "ai-review",
+                    thread_id: None,
+                },
+                TEST_WORKSPACE_ID,
+                &sink,
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect("run phase checks");
             assert!(result.ok);
             assert!(result
                 .results
@@ -1809,14 +2511,2282 @@ await fs.writeFile(ctx.generatedExecutePromptPath, 'fresh prompt from post-step\
                     .map(|sha| !sha.trim().is_empty())
                     .unwrap_or(false)
             );
+            assert_eq!(
+                sink.forge_event_kinds().last(),
+                Some(&ForgeExecutionEventKind::CommitCreated {
+                    commit_sha: task.commit_sha.clone().unwrap_or_default(),
+                })
+            );
 
-            let next = forge_get_next_phase_prompt_core(&fixture.root, "alpha")
-                .await
-                .expect("get next phase")
-                .expect("expected next phase");
+            let next = forge_get_next_phase_prompt_core(
+                &tokio::sync::Mutex::default(),
+                &fixture.root,
+                "alpha",
+                TEST_WORKSPACE_ID,
+                &RecordingEventSink::default(),
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect("get next phase")
+            .expect("expected next phase");
             assert_eq!(next.task_id, "task-2");
             assert_eq!(next.phase_id, "implementation");
             assert!(next.is_last_phase);
         });
     }
+
+    #[test]
+    fn run_phase_checks_streams_check_output_and_reports_blocked_task() {
+        run_async_test(async {
+            let fixture = setup_six_phase_workspace(
+                "in_progress",
+                [
+                    "completed",
+                    "completed",
+                    "pending",
+                    "pending",
+                    "pending",
+                    "pending",
+                ],
+                0,
+            );
+            write_json(
+                &fixture
+                    .root
+                    .join(".agent")
+                    .join("templates")
+                    .join("test-first-loop")
+                    .join("phases.json"),
+                json!({
+                    "schema": "forge-phases-v1",
+                    "phases": [{
+                        "id": "implementation",
+                        "checks": [{
+                            "id": "unit",
+                            "title": "Unit tests",
+                            "command": "node -e \"process.stdout.write('hello'); process.stderr.write('oops'); process.exitCode = 3\""
+                        }]
+                    }]
+                }),
+            );
+
+            let sink = RecordingEventSink::default();
+            let next = forge_get_next_phase_prompt_core(
+                &tokio::sync::Mutex::default(),
+                &fixture.root,
+                "alpha",
+                TEST_WORKSPACE_ID,
+                &sink,
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect("get next phase")
+            .expect("expected next phase");
+            assert_eq!(next.phase_id, "implementation");
+
+            let result = forge_run_phase_checks_core(
+                &fixture.root,
+                ForgeRunPhaseChecksRequest {
+                    plan_id: "alpha",
+                    task_id: "task-1",
+                    phase_id: "implementation",
+                    thread_id: None,
+                },
+                TEST_WORKSPACE_ID,
+                &sink,
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect("run phase checks");
+            assert!(!result.ok);
+            assert_eq!(result.results[0].stdout, "hello");
+            assert_eq!(result.results[0].stderr, "oops");
+
+            let events = sink.forge_events.lock().expect("events lock").clone();
+            assert!(events.iter().all(|event| event.workspace_id == TEST_WORKSPACE_ID
+                && event.plan_id == "alpha"
+                && event.task_id == "task-1"
+                && event.phase_id == "implementation"));
+
+            let kinds = sink.forge_event_kinds();
+            assert_eq!(
+                kinds.first(),
+                Some(&ForgeExecutionEventKind::PhaseStarted {
+                    is_last_phase: false
+                })
+            );
+            assert_eq!(
+                kinds.get(1),
+                Some(&ForgeExecutionEventKind::CheckStarted {
+                    check_id: "unit".to_string(),
+                    title: "Unit tests".to_string(),
+                })
+            );
+            let streamed = |stream: ForgeCheckOutputStream| {
+                kinds
+                    .iter()
+                    .filter_map(|kind| match kind {
+                        ForgeExecutionEventKind::CheckOutput {
+                            stream: chunk_stream,
+                            data,
+                            ..
+                        } if *chunk_stream == stream => Some(data.as_str()),
+                        _ => None,
+                    })
+                    .collect::<String>()
+            };
+            assert_eq!(streamed(ForgeCheckOutputStream::Stdout), "hello");
+            assert_eq!(streamed(ForgeCheckOutputStream::Stderr), "oops");
+            assert!(kinds.iter().any(|kind| matches!(
+                kind,
+                ForgeExecutionEventKind::CheckFinished { check_id, exit_code: 3, .. }
+                    if check_id == "unit"
+            )));
+            assert_eq!(
+                kinds.last(),
+                Some(&ForgeExecutionEventKind::ChecksFailed {
+                    reason: "Phase checks failed: Unit tests".to_string(),
+                })
+            );
+            assert!(!kinds
+                .iter()
+                .any(|kind| matches!(kind, ForgeExecutionEventKind::TaskBlocked { .. })));
+        });
+    }
+
+    #[test]
+    fn run_phase_checks_blocks_task_once_phase_retry_budget_is_exhausted() {
+        run_async_test(async {
+            let fixture = setup_six_phase_workspace(
+                "in_progress",
+                [
+                    "completed",
+                    "completed",
+                    "in_progress",
+                    "pending",
+                    "pending",
+                    "pending",
+                ],
+                0,
+            );
+            write_json(
+                &fixture
+                    .root
+                    .join(".agent")
+                    .join("templates")
+                    .join("test-first-loop")
+                    .join("phases.json"),
+                json!({
+                    "schema": "forge-phases-v1",
+                    "phases": [{
+                        "id": "implementation",
+                        "maxAttempts": 2,
+                        "backoffSeconds": 5,
+                        "checks": [{
+                            "id": "unit",
+                            "title": "Unit tests",
+                            "command": "node -e \"process.exitCode = 1\""
+                        }]
+                    }]
+                }),
+            );
+
+            let sink = RecordingEventSink::default();
+            let cancels = ForgeExecutionCancels::default();
+            let run_checks = || {
+                forge_run_phase_checks_core(
+                    &fixture.root,
+                    ForgeRunPhaseChecksRequest {
+                        plan_id: "alpha",
+                        task_id: "task-1",
+                        phase_id: "implementation",
+                        thread_id: None,
+                    },
+                    TEST_WORKSPACE_ID,
+                    &sink,
+                    &cancels,
+                )
+            };
+
+            let first = run_checks().await.expect("first run");
+            assert!(!first.ok);
+            assert!(!first.exhausted);
+            assert_eq!(first.attempts, 1);
+            assert_eq!(first.max_attempts, Some(2));
+            assert_eq!(first.retry_after_ms, Some(5_000));
+            assert_eq!(
+                sink.forge_event_kinds().last(),
+                Some(&ForgeExecutionEventKind::ChecksFailed {
+                    reason: "Phase checks failed: Unit tests".to_string(),
+                })
+            );
+            let task = load_state_task(&fixture.root, "alpha", "task-1");
+            assert_eq!(task.status, "in_progress");
+            assert_eq!(task.attempts, 1);
+            assert_eq!(phase_status(&task, "implementation"), "in_progress");
+
+            let second = run_checks().await.expect("second run");
+            assert!(!second.ok);
+            assert!(second.exhausted);
+            assert_eq!(second.attempts, 2);
+            assert_eq!(second.retry_after_ms, None);
+            let task = load_state_task(&fixture.root, "alpha", "task-1");
+            assert_eq!(task.status, "blocked");
+            assert_eq!(task.attempts, 2);
+            assert_eq!(phase_status(&task, "implementation"), "failed");
+            assert_eq!(
+                sink.forge_event_kinds().last(),
+                Some(&ForgeExecutionEventKind::TaskBlocked {
+                    reason: "Retry budget exhausted after 2 attempts: Unit tests".to_string(),
+                })
+            );
+
+            let next = forge_get_next_phase_prompt_core(
+                &tokio::sync::Mutex::default(),
+                &fixture.root,
+                "alpha",
+                TEST_WORKSPACE_ID,
+                &sink,
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect("get next phase");
+            assert!(next.is_none());
+        });
+    }
+
+    #[test]
+    fn run_phase_checks_appends_run_history_for_each_attempt() {
+        run_async_test(async {
+            let fixture = setup_six_phase_workspace(
+                "in_progress",
+                [
+                    "completed",
+                    "completed",
+                    "in_progress",
+                    "pending",
+                    "pending",
+                    "pending",
+                ],
+                0,
+            );
+            write_json(
+                &fixture
+                    .root
+                    .join(".agent")
+                    .join("templates")
+                    .join("test-first-loop")
+                    .join("phases.json"),
+                json!({
+                    "schema": "forge-phases-v1",
+                    "phases": [{
+                        "id": "implementation",
+                        "checks": [{
+                            "id": "unit",
+                            "title": "Unit tests",
+                            "command": "node -e \"process.stdout.write('x'.repeat(20000) + 'END'); process.exitCode = 1\""
+                        }]
+                    }]
+                }),
+            );
+
+            let sink = RecordingEventSink::default();
+            let cancels = ForgeExecutionCancels::default();
+            for thread_id in ["thread-1", "thread-2"] {
+                let result = forge_run_phase_checks_core(
+                    &fixture.root,
+                    ForgeRunPhaseChecksRequest {
+                        plan_id: "alpha",
+                        task_id: "task-1",
+                        phase_id: "implementation",
+                        thread_id: Some(thread_id),
+                    },
+                    TEST_WORKSPACE_ID,
+                    &sink,
+                    &cancels,
+                )
+                .await
+                .expect("run phase checks");
+                assert!(!result.ok);
+            }
+
+            let history = forge_get_run_history_core(&fixture.root, "alpha", None, None)
+                .expect("run history");
+            assert_eq!(history.plan_id, "alpha");
+            assert_eq!(history.runs.len(), 2);
+            let latest = &history.runs[0];
+            assert_eq!(latest.task_id, "task-1");
+            assert_eq!(latest.phase_id, "implementation");
+            assert_eq!(latest.attempt, 2);
+            assert_eq!(latest.thread_id.as_deref(), Some("thread-2"));
+            assert!(!latest.ok);
+            assert!(latest.started_at <= latest.finished_at);
+            assert_eq!(history.runs[1].thread_id.as_deref(), Some("thread-1"));
+
+            let check = &latest.checks[0];
+            assert_eq!(check.id, "unit");
+            assert_eq!(check.exit_code, 1);
+            assert!(check.output_truncated);
+            assert!(check.stdout.starts_with("[truncated] ... "));
+            assert!(check.stdout.ends_with("END"));
+            assert!(check.stdout.len() < 20000);
+
+            let limited =
+                forge_get_run_history_core(&fixture.root, "alpha", Some("task-1"), Some(1))
+                    .expect("limited run history");
+            assert_eq!(limited.runs.len(), 1);
+            assert_eq!(limited.runs[0].attempt, 2);
+            let other_task =
+                forge_get_run_history_core(&fixture.root, "alpha", Some("task-2"), None)
+                    .expect("other task history");
+            assert!(other_task.runs.is_empty());
+        });
+    }
+
+    fn write_implementation_checks(workspace: &Path, checks: serde_json::Value) {
+        write_json(
+            &workspace
+                .join(".agent")
+                .join("templates")
+                .join("test-first-loop")
+                .join("phases.json"),
+            json!({
+                "schema": "forge-phases-v1",
+                "phases": [{ "id": "implementation", "checks": checks }]
+            }),
+        );
+    }
+
+    async fn run_implementation_checks(workspace: &Path) -> ForgeRunPhaseChecksResponseV1 {
+        forge_run_phase_checks_core(
+            workspace,
+            ForgeRunPhaseChecksRequest {
+                plan_id: "alpha",
+                task_id: "task-1",
+                phase_id: "implementation",
+                thread_id: None,
+            },
+            TEST_WORKSPACE_ID,
+            &RecordingEventSink::default(),
+            &ForgeExecutionCancels::default(),
+        )
+        .await
+        .expect("run phase checks")
+    }
+
+    #[test]
+    fn run_phase_checks_reports_structured_details_for_typed_checks() {
+        run_async_test(async {
+            let fixture = setup_six_phase_workspace(
+                "in_progress",
+                [
+                    "completed",
+                    "completed",
+                    "in_progress",
+                    "pending",
+                    "pending",
+                    "pending",
+                ],
+                0,
+            );
+            write_text(
+                &fixture.root.join("reports").join("junit").join("unit.xml"),
+                "<?xml version=\"1.0\"?>\n\
+<!-- <testcase name=\"commented-out\"/> -->\n\
+<testsuite name=\"unit\" tests=\"3\">\n\
+  <testcase classname=\"math\" name=\"adds\" time=\"0.012\"/>\n\
+  <testcase classname=\"math\" name=\"divides\" time=\"0.5\">\n\
+    <failure message=\"expected 2 &gt; 1\"><![CDATA[<stack> & trace]]></failure>\n\
+  </testcase>\n\
+  <testcase classname=\"math\" name=\"rounds\"><skipped/></testcase>\n\
+</testsuite>\n",
+            );
+            write_text(
+                &fixture.root.join("coverage").join("lcov.info"),
+                "TN:\nSF:src/a.rs\nLF:10\nLH:9\nBRF:4\nBRH:2\nend_of_record\n\
+SF:src/b.rs\nLF:10\nLH:7\nend_of_record\n",
+            );
+            write_text(
+                &fixture.root.join("coverage").join("cobertura.xml"),
+                "<coverage lines-valid=\"200\" lines-covered=\"150\" line-rate=\"0.75\">\n\
+</coverage>\n",
+            );
+            write_text(&fixture.root.join("docs").join("task-1.md"), "notes\n");
+            write_implementation_checks(
+                &fixture.root,
+                json!([
+                    { "id": "junit", "kind": "junit", "path": "reports/junit" },
+                    {
+                        "id": "lcov",
+                        "kind": "coverage",
+                        "path": "coverage/lcov.info",
+                        "minLinePercent": 80,
+                        "minBranchPercent": 60
+                    },
+                    {
+                        "id": "cobertura",
+                        "kind": "coverage",
+                        "path": "coverage/cobertura.xml",
+                        "minLinePercent": 70
+                    },
+                    {
+                        "id": "files",
+                        "kind": "file-exists",
+                        "paths": ["docs/{{task_id}}.md", "plans/{{plan_id}}/missing.md"]
+                    },
+                    { "id": "bad", "kind": "diff-limits" },
+                    { "id": "escape", "kind": "file-exists", "paths": ["../outside"] }
+                ]),
+            );
+
+            let result = run_implementation_checks(&fixture.root).await;
+            assert!(!result.ok);
+            let by_id = |id: &str| {
+                result
+                    .results
+                    .iter()
+                    .find(|check| check.id == id)
+                    .unwrap_or_else(|| panic!("missing check {id}"))
+            };
+
+            let junit = by_id("junit");
+            assert_eq!(junit.exit_code, 1);
+            assert!(junit.stderr.contains("failed math::divides: expected 2 > 1"));
+            let Some(ForgeCheckDetailsV1::Junit(details)) = junit.details.as_deref() else {
+                panic!("expected junit details: {:?}", junit.details);
+            };
+            assert_eq!(details.reports, vec!["reports/junit/unit.xml".to_string()]);
+            assert_eq!(
+                (details.tests, details.passed, details.failures, details.skipped),
+                (3, 1, 1, 1)
+            );
+            assert_eq!(details.cases[0].duration_ms, Some(12));
+            assert_eq!(details.cases[1].status, "failed");
+
+            let lcov = by_id("lcov");
+            assert_eq!(lcov.exit_code, 1);
+            let Some(ForgeCheckDetailsV1::Coverage(details)) = lcov.details.as_deref() else {
+                panic!("expected coverage details: {:?}", lcov.details);
+            };
+            assert_eq!(details.format, "lcov");
+            assert_eq!((details.lines_found, details.lines_hit), (20, 16));
+            assert_eq!(details.line_percent, 80.0);
+            assert_eq!(details.branch_percent, Some(50.0));
+            assert!(lcov.stderr.contains("Branch coverage 50% is below"));
+            assert!(!lcov.stderr.contains("Line coverage"));
+
+            let cobertura = by_id("cobertura");
+            assert_eq!(cobertura.exit_code, 0, "stderr: {}", cobertura.stderr);
+            let Some(ForgeCheckDetailsV1::Coverage(details)) = cobertura.details.as_deref() else {
+                panic!("expected coverage details: {:?}", cobertura.details);
+            };
+            assert_eq!(details.format, "cobertura");
+            assert_eq!(details.line_percent, 75.0);
+
+            let files = by_id("files");
+            assert_eq!(files.exit_code, 1);
+            let Some(ForgeCheckDetailsV1::FileExists(details)) = files.details.as_deref() else {
+                panic!("expected file-exists details: {:?}", files.details);
+            };
+            assert_eq!(details.present, vec!["docs/task-1.md".to_string()]);
+            assert_eq!(details.missing, vec!["plans/alpha/missing.md".to_string()]);
+
+            assert_eq!(by_id("bad").exit_code, 2);
+            assert!(by_id("bad").stderr.contains("maxFiles or maxLines"));
+            assert_eq!(by_id("escape").exit_code, 2);
+            assert!(by_id("escape").details.is_none());
+
+            let history = forge_get_run_history_core(&fixture.root, "alpha", None, None)
+                .expect("run history");
+            let recorded = &history.runs[0].checks[0];
+            assert!(matches!(recorded.details.as_deref(), Some(ForgeCheckDetailsV1::Junit(_))));
+            let encoded = serde_json::to_value(recorded).expect("encode check record");
+            assert_eq!(encoded["details"]["kind"], "junit");
+            assert_eq!(encoded["details"]["cases"][1]["classname"], "math");
+        });
+    }
+
+    #[test]
+    fn run_phase_checks_enforces_declared_artifact_gates() {
+        run_async_test(async {
+            let fixture = setup_six_phase_workspace(
+                "in_progress",
+                [
+                    "completed",
+                    "completed",
+                    "in_progress",
+                    "pending",
+                    "pending",
+                    "pending",
+                ],
+                0,
+            );
+            let template_root =
+                fixture.root.join(".agent").join("templates").join("test-first-loop");
+            write_json(
+                &template_root.join("schemas").join("security.schema.json"),
+                json!({
+                    "type": "object",
+                    "required": ["findings"],
+                    "properties": { "findings": { "type": "array" } }
+                }),
+            );
+            write_json(
+                &template_root.join("phases.json"),
+                json!({
+                    "schema": "forge-phases-v1",
+                    "phases": [
+                        {
+                            "id": "implementation",
+                            "checks": [],
+                            "artifacts": [
+                                {
+                                    "id": "security-review",
+                                    "path": "plans/{planId}/security/{taskId}.json",
+                                    "schema": "schemas/security.schema.json",
+                                    "require": [{ "pointer": "/findings", "maxSeverity": "low" }]
+                                },
+                                {
+                                    "id": "design-doc",
+                                    "title": "Design doc approved",
+                                    "path": "docs/{taskId}-design.json",
+                                    "require": [{ "pointer": "/approved", "equals": true }]
+                                },
+                                {
+                                    "id": "broken",
+                                    "path": "docs/x.json",
+                                    "require": [{ "pointer": "/x" }]
+                                }
+                            ]
+                        },
+                        { "id": "ai-review", "checks": [], "artifacts": [] }
+                    ]
+                }),
+            );
+            let security_path = fixture
+                .root
+                .join("plans")
+                .join("alpha")
+                .join("security")
+                .join("task-1.json");
+            write_json(&security_path, json!({ "findings": "none" }));
+
+            let result = run_implementation_checks(&fixture.root).await;
+            assert!(!result.ok);
+            let ids = result
+                .results
+                .iter()
+                .map(|check| (check.id.as_str(), check.exit_code))
+                .collect::<Vec<_>>();
+            assert_eq!(ids, vec![("security-review", 2), ("design-doc", 2), ("broken", 2)]);
+            let Some(ForgeCheckDetailsV1::Artifact(security)) = result.results[0].details.as_deref()
+            else {
+                panic!("expected artifact details: {:?}", result.results[0].details);
+            };
+            assert_eq!(security.path, "plans/alpha/security/task-1.json");
+            assert_eq!(security.schema_errors[0].instance_path, "/findings");
+            let Some(ForgeCheckDetailsV1::Artifact(design)) = result.results[1].details.as_deref()
+            else {
+                panic!("expected artifact details: {:?}", result.results[1].details);
+            };
+            assert!(!design.present);
+            assert_eq!(result.results[1].title, "Design doc approved");
+            assert!(result.results[2].stderr.contains("needs one of"));
+
+            write_json(
+                &security_path,
+                json!({ "findings": [{ "severity": "low" }, { "severity": "High" }] }),
+            );
+            let design_path = fixture.root.join("docs").join("task-1-design.json");
+            write_json(&design_path, json!({ "approved": false }));
+            let result = run_implementation_checks(&fixture.root).await;
+            assert_eq!(result.results[0].exit_code, 1);
+            assert!(result.results[0]
+                .stderr
+                .contains("/findings has 1 item(s) above low severity: high."));
+            assert_eq!(result.results[1].exit_code, 1);
+            assert!(result.results[1].stderr.contains("/approved must equal true (got false)."));
+
+            write_json(&security_path, json!({ "findings": [{ "severity": "info" }] }));
+            write_json(&design_path, json!({ "approved": true }));
+            let result = run_implementation_checks(&fixture.root).await;
+            assert_eq!(result.results[0].exit_code, 0, "stderr: {}", result.results[0].stderr);
+            assert_eq!(result.results[1].exit_code, 0, "stderr: {}", result.results[1].stderr);
+
+            // An explicit empty list turns off the built-in ai-review report gate.
+            let result = forge_run_phase_checks_core(
+                &fixture.root,
+                ForgeRunPhaseChecksRequest {
+                    plan_id: "alpha",
+                    task_id: "task-1",
+                    phase_id: "ai-review",
+                    thread_id: None,
+                },
+                TEST_WORKSPACE_ID,
+                &RecordingEventSink::default(),
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect("run ai-review checks");
+            assert!(!result.results.iter().any(|check| check.id == "ai-review-report"));
+        });
+    }
+
+    #[test]
+    fn run_phase_checks_diff_limits_measure_changes_since_task_base() {
+        run_async_test(async {
+            let fixture = setup_six_phase_workspace(
+                "in_progress",
+                [
+                    "completed",
+                    "completed",
+                    "in_progress",
+                    "pending",
+                    "pending",
+                    "pending",
+                ],
+                0,
+            );
+            write_implementation_checks(
+                &fixture.root,
+                json!([
+                    { "id": "small", "kind": "diff-limits", "maxFiles": 1 },
+                    { "id": "lines", "kind": "diff-limits", "maxFiles": 5, "maxLines": 5 }
+                ]),
+            );
+            write_text(&fixture.root.join("src").join("lib.rs"), "one\ntwo\nthree\n");
+            init_git_repo(&fixture.root);
+            run_git(&fixture.root, &["add", "-A"]);
+            run_git(&fixture.root, &["commit", "-m", "init"]);
+
+            write_text(&fixture.root.join("src").join("lib.rs"), "one\n2\nthree\nfour\n");
+            write_text(&fixture.root.join("src").join("new.rs"), "a\nb");
+
+            let result = run_implementation_checks(&fixture.root).await;
+            let small = &result.results[0];
+            assert_eq!(small.exit_code, 1, "stderr: {}", small.stderr);
+            assert!(small.stderr.contains("2 files changed; the limit is 1."));
+            let Some(ForgeCheckDetailsV1::DiffLimits(details)) = small.details.as_deref() else {
+                panic!("expected diff-limits details: {:?}", small.details);
+            };
+            assert_eq!(details.base_sha, git_head_sha(&fixture.root));
+            assert_eq!(details.files_changed, 2);
+            // lib.rs: +2 -1; new.rs: two lines, the last without a newline. Plan files are ignored.
+            assert_eq!((details.lines_added, details.lines_removed), (4, 1));
+            assert_eq!(
+                details.files,
+                vec!["src/lib.rs".to_string(), "src/new.rs".to_string()]
+            );
+
+            let lines = &result.results[1];
+            assert_eq!(lines.exit_code, 0, "stderr: {}", lines.stderr);
+        });
+    }
+
+    fn git_stdout(workspace: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(workspace)
+            .output()
+            .expect("run git");
+        String::from_utf8_lossy(&output.stdout).to_string()
+    }
+
+    #[test]
+    fn run_phase_checks_task_commit_follows_workspace_commit_policy() {
+        run_async_test(async {
+            let fixture = setup_six_phase_workspace(
+                "in_progress",
+                [
+                    "completed",
+                    "completed",
+                    "completed",
+                    "completed",
+                    "completed",
+                    "pending",
+                ],
+                0,
+            );
+            init_git_repo(&fixture.root);
+            run_git(&fixture.root, &["add", "-A"]);
+            run_git(&fixture.root, &["commit", "-m", "base"]);
+            write_json(
+                &fixture.root.join(".agent").join("forge.json"),
+                json!({
+                    "commit": {
+                        "include": ["src/**"],
+                        "exclude": ["**/*.log"],
+                        "includePlanFiles": false,
+                        "message": "feat({taskId}): {taskName}",
+                        "trailers": ["Forge-Plan: {planId}"],
+                        "signoff": true
+                    }
+                }),
+            );
+            write_text(&fixture.root.join("src").join("app.rs"), "fn main() {}\n");
+            write_text(&fixture.root.join("src").join("debug.log"), "scratch\n");
+            write_text(&fixture.root.join("notes.md"), "not staged\n");
+            write_ai_review_report(&fixture.root, "alpha", "task-1", &[]);
+
+            let result = forge_run_phase_checks_core(
+                &fixture.root,
+                ForgeRunPhaseChecksRequest {
+                    plan_id: "alpha",
+                    task_id: "task-1",
+                    phase_id: "ai-review",
+                    thread_id: None,
+                },
+                TEST_WORKSPACE_ID,
+                &RecordingEventSink::default(),
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect("run phase checks");
+            assert!(result.ok, "{:?}", result.results);
+
+            let task = load_state_task(&fixture.root, "alpha", "task-1");
+            assert_eq!(task.status, "completed");
+            assert_eq!(task.commit_sha.as_deref(), Some(git_head_sha(&fixture.root).as_str()));
+            let committed =
+                git_stdout(&fixture.root, &["show", "--name-only", "--format=", "HEAD"]);
+            assert_eq!(committed.lines().collect::<Vec<_>>(), vec!["src/app.rs"]);
+            let message = git_stdout(&fixture.root, &["log", "-1", "--format=%B"]);
+            assert!(message.starts_with("feat(task-1): Task 1\n\nForge-Plan: alpha\n"));
+            assert!(message.contains("Signed-off-by: Forge Tests <forge-tests@example.com>"));
+        });
+    }
+
+    #[test]
+    fn run_phase_checks_per_phase_policy_commits_each_phase_and_rollback_reverts_them() {
+        run_async_test(async {
+            let fixture = setup_six_phase_workspace(
+                "pending",
+                [
+                    "pending",
+                    "pending",
+                    "pending",
+                    "pending",
+                    "pending",
+                    "pending",
+                ],
+                0,
+            );
+            init_git_repo(&fixture.root);
+            write_json(
+                &fixture.root.join(".agent").join("forge.json"),
+                json!({
+                    "commit": {
+                        "perPhase": true,
+                        "includePlanFiles": false,
+                        "trailers": ["Forge-Task: {taskId}"],
+                        "signoff": true
+                    }
+                }),
+            );
+            run_git(&fixture.root, &["add", "-A"]);
+            run_git(&fixture.root, &["commit", "-m", "base"]);
+            write_ai_review_report(&fixture.root, "alpha", "task-1", &[]);
+
+            for phase_id in SIX_PHASE_IDS {
+                write_text(&fixture.root.join("src").join(format!("{phase_id}.txt")), "x\n");
+                let result = forge_run_phase_checks_core(
+                    &fixture.root,
+                    ForgeRunPhaseChecksRequest {
+                        plan_id: "alpha",
+                        task_id: "task-1",
+                        phase_id,
+                        thread_id: None,
+                    },
+                    TEST_WORKSPACE_ID,
+                    &RecordingEventSink::default(),
+                    &ForgeExecutionCancels::default(),
+                )
+                .await
+                .expect("run phase checks");
+                assert!(result.ok, "{phase_id}: {:?}", result.results);
+            }
+
+            let task = load_state_task(&fixture.root, "alpha", "task-1");
+            assert_eq!(task.status, "completed");
+            let phase_shas = task
+                .phases
+                .iter()
+                .filter_map(|phase| phase.commit_sha.clone())
+                .collect::<Vec<_>>();
+            assert_eq!(phase_shas.len(), SIX_PHASE_IDS.len());
+            assert_eq!(task.commit_sha.as_ref(), phase_shas.last());
+            let subjects = git_stdout(&fixture.root, &["log", "--format=%s", "-2"]);
+            assert_eq!(
+                subjects.lines().collect::<Vec<_>>(),
+                vec!["forge(alpha): task-1 Task 1", "forge(alpha): task-1 documentation"]
+            );
+
+            let rollback = forge_rollback_task_core(&fixture.root, "alpha", "task-1", false)
+                .await
+                .expect("rollback task");
+            let rolled_back = rollback.tasks.last().expect("rolled back task-1");
+            assert_eq!(rolled_back.commit_sha, task.commit_sha);
+            assert!(rolled_back.revert_sha.is_some());
+            for phase_id in SIX_PHASE_IDS {
+                assert!(!fixture.root.join("src").join(format!("{phase_id}.txt")).exists());
+            }
+            let task = load_state_task(&fixture.root, "alpha", "task-1");
+            assert_eq!(task.status, "pending");
+            assert!(task.phases.iter().all(|phase| phase.commit_sha.is_none()));
+            // Reverts follow the same commit policy as the commits they undo.
+            let revert_message = git_stdout(&fixture.root, &["log", "--format=%B", "-1"]);
+            assert!(revert_message.starts_with("forge(alpha): revert task-1 ("));
+            assert!(revert_message.contains("Forge-Task: task-1"), "{revert_message}");
+            assert!(revert_message.contains("Signed-off-by: "), "{revert_message}");
+        });
+    }
+
+    #[test]
+    fn prepare_execution_migrates_state_v1_to_current_schema() {
+        run_async_test(async {
+            let fixture = setup_six_phase_workspace("pending", ["pending"; 6], 0);
+            let state_path = fixture.root.join("plans").join("alpha").join("state.json");
+            write_json(
+                &state_path,
+                json!({
+                    "$schema": "state-v1",
+                    "plan_id": "alpha",
+                    "tasks": [
+                        { "id": "task-1", "status": "completed", "commit_sha": "abc123" },
+                        { "id": "task-2", "status": "pending" }
+                    ]
+                }),
+            );
+
+            forge_prepare_execution_core(
+                &fixture.root,
+                "alpha",
+                TEST_WORKSPACE_ID,
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect("prepare execution");
+
+            let raw = read_json_file::<Value>(&state_path).expect("read migrated state");
+            assert_eq!(raw["$schema"], "state-v2");
+            let task = load_state_task(&fixture.root, "alpha", "task-1");
+            assert_eq!(task.commit_sha.as_deref(), Some("abc123"));
+            assert_eq!(task.phases.len(), SIX_PHASE_IDS.len());
+            assert!(task.phases.iter().all(|phase| phase.status == "completed"));
+            let task = load_state_task(&fixture.root, "alpha", "task-2");
+            assert_eq!(phase_status(&task, "test-case-mapping"), "pending");
+        });
+    }
+
+    #[test]
+    fn concurrent_task_state_updates_are_not_lost() {
+        let fixture = setup_six_phase_workspace("pending", ["pending"; 6], 0);
+        let paths = build_execution_paths(&fixture.root, "alpha").expect("paths");
+        let updates_per_task = 25;
+        std::thread::scope(|scope| {
+            let paths = &paths;
+            scope.spawn(move || {
+                run_async_test(async move {
+                    for _ in 0..updates_per_task {
+                        update_task_state(paths, "task-1", |task| {
+                            task.attempts += 1;
+                            Ok(())
+                        })
+                        .await
+                        .expect("update task state");
+                    }
+                });
+            });
+            // Builtin hooks take the same lock from the blocking pool.
+            scope.spawn(move || {
+                for _ in 0..updates_per_task {
+                    let _lock = lock_state_blocking(&paths.workspace_root, &paths.plan_id)
+                        .expect("lock state");
+                    let mut state = load_state(paths).expect("load state");
+                    let task = state
+                        .tasks
+                        .iter_mut()
+                        .find(|task| task.id == "task-2")
+                        .expect("task-2");
+                    task.attempts += 1;
+                    write_state_file(paths, &state).expect("write state");
+                }
+            });
+        });
+
+        for task_id in ["task-1", "task-2"] {
+            let task = load_state_task(&fixture.root, "alpha", task_id);
+            assert_eq!(task.attempts, updates_per_task);
+        }
+        let leftovers = std::fs::read_dir(&paths.plan_dir)
+            .expect("read plan dir")
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_name().to_string_lossy().ends_with(".tmp"))
+            .count();
+        assert_eq!(leftovers, 0);
+    }
+
+    #[test]
+    fn phase_retry_delay_doubles_per_attempt_up_to_the_cap() {
+        assert_eq!(phase_retry_delay_ms(0, 3), 0);
+        assert_eq!(phase_retry_delay_ms(5, 1), 5_000);
+        assert_eq!(phase_retry_delay_ms(5, 3), 20_000);
+        assert_eq!(
+            phase_retry_delay_ms(5, 40),
+            MAX_PHASE_RETRY_BACKOFF_SECONDS * 1000
+        );
+    }
+
+    #[test]
+    fn cancel_execution_kills_running_check_and_keeps_phase_in_progress() {
+        run_async_test(async {
+            let fixture = setup_six_phase_workspace(
+                "in_progress",
+                [
+                    "completed",
+                    "completed",
+                    "pending",
+                    "pending",
+                    "pending",
+                    "pending",
+                ],
+                0,
+            );
+            write_json(
+                &fixture
+                    .root
+                    .join(".agent")
+                    .join("templates")
+                    .join("test-first-loop")
+                    .join("phases.json"),
+                json!({
+                    "schema": "forge-phases-v1",
+                    "phases": [{
+                        "id": "implementation",
+                        "checks": [
+                            {
+                                "id": "slow",
+                                "command": "node -e \"console.log('started'); setTimeout(() => {}, 60000)\""
+                            },
+                            { "id": "never-runs", "command": "node -e \"process.exitCode = 0\"" }
+                        ]
+                    }]
+                }),
+            );
+
+            let sink = RecordingEventSink::default();
+            let cancels = ForgeExecutionCancels::default();
+            let start = Instant::now();
+            let run = forge_run_phase_checks_core(
+                &fixture.root,
+                ForgeRunPhaseChecksRequest {
+                    plan_id: "alpha",
+                    task_id: "task-1",
+                    phase_id: "implementation",
+                    thread_id: None,
+                },
+                TEST_WORKSPACE_ID,
+                &sink,
+                &cancels,
+            );
+            let cancel = async {
+                while !sink.forge_event_kinds().iter().any(|kind| {
+                    matches!(
+                        kind,
+                        ForgeExecutionEventKind::CheckOutput { check_id, .. } if check_id == "slow"
+                    )
+                }) {
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                }
+                forge_cancel_execution_core(&cancels, TEST_WORKSPACE_ID, "alpha").expect("cancel")
+            };
+            let (result, cancelled) = futures_util::future::join(run, cancel).await;
+            let result = result.expect("run phase checks");
+
+            assert_eq!(cancelled.cancelled_runs, 1);
+            assert!(start.elapsed() < Duration::from_secs(30));
+            assert!(result.cancelled);
+            assert!(!result.ok);
+            assert_eq!(result.results.len(), 1);
+            assert_eq!(result.results[0].exit_code, 130);
+            assert!(result.results[0].stderr.contains("cancelled"));
+            assert_eq!(
+                sink.forge_event_kinds().last(),
+                Some(&ForgeExecutionEventKind::Cancelled)
+            );
+
+            let task = load_state_task(&fixture.root, "alpha", "task-1");
+            assert_eq!(task.status, "in_progress");
+            assert_eq!(phase_status(&task, "implementation"), "in_progress");
+            let phase = task
+                .phases
+                .iter()
+                .find(|phase| phase.id == "implementation")
+                .expect("implementation phase");
+            assert!(phase.notes.starts_with("Phase checks cancelled at "));
+
+            let idle = forge_cancel_execution_core(&cancels, TEST_WORKSPACE_ID, "alpha")
+                .expect("cancel idle plan");
+            assert_eq!(idle.cancelled_runs, 0);
+        });
+    }
+
+    #[derive(Default)]
+    struct TestRunHostState {
+        sessions: tokio::sync::Mutex<HashMap<String, Arc<WorkspaceSession>>>,
+        workspaces: tokio::sync::Mutex<HashMap<String, WorkspaceEntry>>,
+        cancels: ForgeExecutionCancels,
+        runs: ForgeRuns,
+        event_sink: RecordingEventSink,
+    }
+
+    #[derive(Clone, Default)]
+    struct TestRunHost(Arc<TestRunHostState>);
+
+    impl ForgeRunHost for TestRunHost {
+        type Sink = RecordingEventSink;
+
+        fn sessions(&self) -> &tokio::sync::Mutex<HashMap<String, Arc<WorkspaceSession>>> {
+            &self.0.sessions
+        }
+
+        fn workspaces(&self) -> &tokio::sync::Mutex<HashMap<String, WorkspaceEntry>> {
+            &self.0.workspaces
+        }
+
+        fn forge_execution_cancels(&self) -> &ForgeExecutionCancels {
+            &self.0.cancels
+        }
+
+        fn forge_runs(&self) -> &ForgeRuns {
+            &self.0.runs
+        }
+
+        fn event_sink(&self) -> RecordingEventSink {
+            self.0.event_sink.clone()
+        }
+    }
+
+    #[test]
+    fn start_run_requires_a_connected_workspace_and_leaves_no_run_behind() {
+        run_async_test(async {
+            let fixture = setup_six_phase_workspace("pending", ["pending"; 6], 0);
+            let host = TestRunHost::default();
+
+            let err = forge_start_run_core(
+                host.clone(),
+                TEST_WORKSPACE_ID,
+                fixture.root.clone(),
+                "alpha",
+                ForgeStartRunOptionsV1::default(),
+            )
+            .await
+            .expect_err("disconnected workspace");
+            assert_eq!(err, "workspace not connected");
+
+            let status = forge_run_status_core(host.forge_runs(), TEST_WORKSPACE_ID, "alpha")
+                .expect("run status");
+            assert!(status.is_none());
+            let stop_err = forge_stop_run_core(
+                host.forge_runs(),
+                host.forge_execution_cancels(),
+                TEST_WORKSPACE_ID,
+                "alpha",
+            )
+            .expect_err("no run to stop");
+            assert_eq!(stop_err, "Plan alpha has no Forge run.");
+        });
+    }
+
+    fn write_three_task_plan(workspace: &Path) {
+        let plan_dir = workspace.join("plans").join("alpha");
+        write_json(
+            &plan_dir.join("plan.json"),
+            json!({
+                "$schema": "plan-v1",
+                "id": "alpha",
+                "goal": "Test goal",
+                "tasks": [
+                    { "id": "task-1", "name": "Task 1", "depends_on": [] },
+                    { "id": "task-2", "name": "Task 2", "depends_on": ["task-1"] },
+                    { "id": "task-3", "name": "Task 3", "depends_on": [] }
+                ]
+            }),
+        );
+        let task = |id: &str| {
+            json!({
+                "id": id,
+                "status": "pending",
+                "attempts": 0,
+                "notes": "",
+                "commit_sha": null,
+                "phases": build_task_phase_state(["pending"; 6])
+            })
+        };
+        write_json(
+            &plan_dir.join("state.json"),
+            json!({
+                "$schema": "state-v2",
+                "plan_id": "alpha",
+                "iteration": 0,
+                "summary": "",
+                "tasks": [task("task-1"), task("task-2"), task("task-3")]
+            }),
+        );
+    }
+
+    #[test]
+    fn get_runnable_tasks_returns_every_task_with_satisfied_dependencies() {
+        let fixture = setup_six_phase_workspace("pending", ["pending"; 6], 0);
+        write_three_task_plan(&fixture.root);
+
+        let runnable = forge_get_runnable_tasks_core(&fixture.root, "alpha").expect("runnable");
+        let ids = runnable
+            .iter()
+            .map(|task| task.task_id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["task-1", "task-3"]);
+        assert!(runnable
+            .iter()
+            .all(|task| task.phase_id.as_deref() == Some("test-case-mapping")));
+        assert!(runnable.iter().all(|task| !task.awaiting_merge));
+    }
+
+    /// Runs task-1 of the three-task plan to completion in its own worktree, which commits
+    /// `feature.txt` there. Returns the worktree path.
+    async fn complete_task_one_in_worktree(fixture: &TestWorkspace) -> PathBuf {
+        write_three_task_plan(&fixture.root);
+        init_git_repo(&fixture.root);
+        run_git(&fixture.root, &["add", "-A"]);
+        run_git(&fixture.root, &["commit", "-m", "init"]);
+
+        let worktree_root = std::env::temp_dir().join(format!(
+            "codex-monitor-forge-worktree-test-{}",
+            Uuid::new_v4()
+        ));
+        let worktree_root_str = worktree_root.to_string_lossy().to_string();
+        let branch = forge_task_worktree_branch("alpha", "task-1").expect("branch");
+        assert_eq!(branch, "forge/alpha/task-1");
+        run_git(
+            &fixture.root,
+            &["worktree", "add", "-b", &branch, &worktree_root_str],
+        );
+
+        let attached = forge_attach_task_worktree_core(
+            &fixture.root,
+            "alpha",
+            "task-1",
+            "ws-task-1",
+            &worktree_root_str,
+            &branch,
+        )
+        .await
+        .expect("attach worktree");
+        assert!(attached.base_sha.is_some());
+
+        let state_path = fixture.root.join("plans").join("alpha").join("state.json");
+        let mut state = read_json_file::<StateV2>(&state_path).expect("load state");
+        for phase in state.tasks[0].phases.iter_mut().take(5) {
+            phase.status = "completed".to_string();
+        }
+        write_json(
+            &state_path,
+            serde_json::to_value(&state).expect("encode state"),
+        );
+        write_ai_review_report(&fixture.root, "alpha", "task-1", &[]);
+        write_text(&worktree_root.join("feature.txt"), "from worktree\n");
+
+        let checks = forge_run_phase_checks_core(
+            &fixture.root,
+            ForgeRunPhaseChecksRequest {
+                plan_id: "alpha",
+                task_id: "task-1",
+                phase_id: "ai-review",
+                thread_id: None,
+            },
+            TEST_WORKSPACE_ID,
+            &RecordingEventSink::default(),
+            &ForgeExecutionCancels::default(),
+        )
+        .await
+        .expect("run phase checks");
+        assert!(checks.ok);
+        assert!(!fixture.root.join("feature.txt").exists());
+        worktree_root
+    }
+
+    #[test]
+    fn merge_task_worktree_lands_commit_and_unblocks_dependents() {
+        run_async_test(async {
+            let fixture = setup_six_phase_workspace("pending", ["pending"; 6], 0);
+            let worktree_root = complete_task_one_in_worktree(&fixture).await;
+            let worktree_root_str = worktree_root.to_string_lossy().to_string();
+
+            let runnable = forge_get_runnable_tasks_core(&fixture.root, "alpha").expect("runnable");
+            let task_one = runnable
+                .iter()
+                .find(|task| task.task_id == "task-1")
+                .expect("task-1 awaiting merge");
+            assert!(task_one.awaiting_merge);
+            assert!(!runnable.iter().any(|task| task.task_id == "task-2"));
+
+            let merged = forge_merge_task_worktree_core(&fixture.root, "alpha", "task-1")
+                .await
+                .expect("merge worktree");
+            assert!(merged.ok);
+            assert_eq!(merged.worktree_workspace_id.as_deref(), Some("ws-task-1"));
+            assert!(fixture.root.join("feature.txt").is_file());
+
+            let task = load_state_task(&fixture.root, "alpha", "task-1");
+            assert!(task.worktree.is_none());
+            assert_eq!(task.commit_sha, merged.commit_sha);
+
+            let runnable = forge_get_runnable_tasks_core(&fixture.root, "alpha").expect("runnable");
+            let ids = runnable
+                .iter()
+                .map(|task| task.task_id.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(ids, vec!["task-2", "task-3"]);
+
+            run_git(&fixture.root, &["worktree", "remove", "--force", &worktree_root_str]);
+            let _ = std::fs::remove_dir_all(&worktree_root);
+        });
+    }
+
+    #[test]
+    fn conflicting_merge_keeps_task_completed_and_can_be_retried() {
+        run_async_test(async {
+            let fixture = setup_six_phase_workspace("pending", ["pending"; 6], 0);
+            let worktree_root = complete_task_one_in_worktree(&fixture).await;
+            let worktree_root_str = worktree_root.to_string_lossy().to_string();
+            write_text(&fixture.root.join("feature.txt"), "from the plan branch\n");
+            run_git(&fixture.root, &["add", "feature.txt"]);
+            run_git(&fixture.root, &["commit", "-m", "conflicting change"]);
+
+            let conflicted = forge_merge_task_worktree_core(&fixture.root, "alpha", "task-1")
+                .await
+                .expect("merge reports the conflict");
+            assert!(!conflicted.ok);
+            assert!(conflicted.message.contains("forge/alpha/task-1"));
+            assert!(!fixture.root.join(".git").join("CHERRY_PICK_HEAD").exists());
+
+            let task = load_state_task(&fixture.root, "alpha", "task-1");
+            assert_eq!(task.status, "completed");
+            assert!(task.worktree.is_some());
+            assert!(task.notes.contains(&conflicted.message));
+            let runnable = forge_get_runnable_tasks_core(&fixture.root, "alpha").expect("runnable");
+            let task_one = runnable
+                .iter()
+                .find(|task| task.task_id == "task-1")
+                .expect("task-1 still awaits its merge");
+            assert!(task_one.awaiting_merge);
+            assert_eq!(task_one.phase_id, None);
+
+            run_git(&fixture.root, &["revert", "--no-edit", "HEAD"]);
+            let merged = forge_merge_task_worktree_core(&fixture.root, "alpha", "task-1")
+                .await
+                .expect("retry merge");
+            assert!(merged.ok, "{}", merged.message);
+            assert!(load_state_task(&fixture.root, "alpha", "task-1")
+                .worktree
+                .is_none());
+
+            run_git(
+                &fixture.root,
+                &["worktree", "remove", "--force", &worktree_root_str],
+            );
+            let _ = std::fs::remove_dir_all(&worktree_root);
+        });
+    }
+
+    #[test]
+    fn check_task_worktree_refuses_tasks_that_cannot_be_attached() {
+        run_async_test(async {
+            let fixture = setup_six_phase_workspace("pending", ["pending"; 6], 0);
+            write_three_task_plan(&fixture.root);
+
+            forge_check_task_worktree_core(&fixture.root, "alpha", "task-1")
+                .expect("task-1 is runnable");
+            let err = forge_check_task_worktree_core(&fixture.root, "alpha", "task-2")
+                .expect_err("task-2 waits on task-1");
+            assert_eq!(err, "Task task-2 is not runnable yet.");
+
+            forge_attach_task_worktree_core(
+                &fixture.root,
+                "alpha",
+                "task-1",
+                "ws-task-1",
+                "/tmp/forge-task-1",
+                "forge/alpha/task-1",
+            )
+            .await
+            .expect("attach worktree");
+            let err = forge_check_task_worktree_core(&fixture.root, "alpha", "task-1")
+                .expect_err("task-1 already has a worktree");
+            assert_eq!(err, "Task task-1 already runs in worktree /tmp/forge-task-1.");
+        });
+    }
+
+    #[test]
+    fn task_phase_prompt_refuses_plans_with_broken_dependencies() {
+        run_async_test(async {
+            let fixture = setup_six_phase_workspace("pending", ["pending"; 6], 0);
+            write_three_task_plan(&fixture.root);
+            let plan_path = fixture.root.join("plans").join("alpha").join("plan.json");
+            let mut plan = read_json_file::<Value>(&plan_path).expect("load plan");
+            plan["tasks"][2]["depends_on"] = json!(["task-9"]);
+            write_json(&plan_path, plan);
+
+            let err = forge_get_task_phase_prompt_core(
+                &tokio::sync::Mutex::default(),
+                &fixture.root,
+                "alpha",
+                "task-1",
+                TEST_WORKSPACE_ID,
+                &RecordingEventSink::default(),
+            )
+            .await
+            .expect_err("broken plans are not executed");
+            assert!(
+                err.contains("task-3 depends on unknown task task-9"),
+                "unexpected error: {err}"
+            );
+            let err = forge_check_task_worktree_core(&fixture.root, "alpha", "task-1")
+                .expect_err("broken plans get no worktrees");
+            assert!(err.contains("task-9"), "unexpected error: {err}");
+        });
+    }
+
+    fn git_head_sha(workspace: &Path) -> String {
+        let output = Command::new("git")
+            .args(["rev-parse", "HEAD"])
+            .current_dir(workspace)
+            .output()
+            .expect("run git rev-parse");
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    #[test]
+    fn rollback_task_reverts_commits_and_requires_cascade_for_started_dependents() {
+        run_async_test(async {
+            let fixture = setup_six_phase_workspace("pending", ["pending"; 6], 0);
+            write_three_task_plan(&fixture.root);
+            init_git_repo(&fixture.root);
+            run_git(&fixture.root, &["add", "-A"]);
+            run_git(&fixture.root, &["commit", "-m", "init"]);
+
+            let state_path = fixture.root.join("plans").join("alpha").join("state.json");
+            let commit_task = |task_index: usize, file: &str| {
+                write_text(&fixture.root.join(file), "task output\n");
+                let mut state = read_json_file::<StateV2>(&state_path).expect("load state");
+                state.tasks[task_index].status = "completed".to_string();
+                for phase in &mut state.tasks[task_index].phases {
+                    phase.status = "completed".to_string();
+                }
+                write_json(&state_path, serde_json::to_value(&state).expect("encode state"));
+                run_git(&fixture.root, &["add", "-A"]);
+                run_git(&fixture.root, &["commit", "-m", file]);
+                // Forge records the SHA after committing, leaving state.json modified.
+                state.tasks[task_index].commit_sha = Some(git_head_sha(&fixture.root));
+                write_json(&state_path, serde_json::to_value(&state).expect("encode state"));
+            };
+            commit_task(0, "task-1.txt");
+            commit_task(1, "task-2.txt");
+
+            let err = forge_rollback_task_core(&fixture.root, "alpha", "task-1", false)
+                .await
+                .expect_err("started dependent blocks rollback");
+            assert!(err.contains("task-2"), "unexpected error: {err}");
+            assert!(fixture.root.join("task-1.txt").is_file());
+
+            let task_one_sha = load_state_task(&fixture.root, "alpha", "task-1").commit_sha;
+            let result = forge_rollback_task_core(&fixture.root, "alpha", "task-1", true)
+                .await
+                .expect("cascade rollback");
+            let ids = result
+                .tasks
+                .iter()
+                .map(|task| task.task_id.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(ids, vec!["task-2", "task-1"]);
+            assert_eq!(result.tasks[1].commit_sha, task_one_sha);
+            assert!(result.tasks.iter().all(|task| task.revert_sha.is_some()));
+            assert_eq!(result.tasks[1].revert_sha, Some(git_head_sha(&fixture.root)));
+            assert!(!fixture.root.join("task-1.txt").exists());
+            assert!(!fixture.root.join("task-2.txt").exists());
+
+            for task_id in ["task-1", "task-2"] {
+                let task = load_state_task(&fixture.root, "alpha", task_id);
+                assert_eq!(task.status, "pending");
+                assert!(task.commit_sha.is_none());
+                assert!(task.notes.starts_with("Rolled back at "));
+                assert!(task.phases.iter().all(|phase| phase.status == "pending"));
+            }
+            let task_three = load_state_task(&fixture.root, "alpha", "task-3");
+            assert!(task_three.notes.is_empty());
+
+            // A task attached to a worktree comes back detached from it.
+            let worktree_path = fixture.root.join("worktrees").join("task-3");
+            update_task_state(
+                &build_execution_paths(&fixture.root, "alpha").expect("paths"),
+                "task-3",
+                |task| {
+                    task.status = "in_progress".to_string();
+                    task.worktree = Some(StateTaskWorktreeV2 {
+                        workspace_id: "worktree-3".to_string(),
+                        path: worktree_path.to_string_lossy().to_string(),
+                        branch: "forge/alpha/task-3".to_string(),
+                        base_sha: None,
+                    });
+                    Ok(())
+                },
+            )
+            .await
+            .expect("attach worktree");
+            forge_rollback_task_core(&fixture.root, "alpha", "task-3", false)
+                .await
+                .expect("rollback attached task");
+            let task_three = load_state_task(&fixture.root, "alpha", "task-3");
+            assert_eq!(task_three.status, "pending");
+            assert!(task_three.worktree.is_none());
+        });
+    }
+
+    fn mark_manual_approval_phase(workspace: &Path, phase_id: &str) {
+        let phases_path = workspace
+            .join(".agent")
+            .join("templates")
+            .join("test-first-loop")
+            .join("phases.json");
+        let mut phases = read_json_file::<serde_json::Value>(&phases_path).expect("read phases");
+        for phase in phases["phases"].as_array_mut().expect("phase list") {
+            if phase["id"] == phase_id {
+                phase["kind"] = json!("manual-approval");
+            }
+        }
+        write_json(&phases_path, phases);
+    }
+
+    fn review_request<'a>(
+        phase_id: &'a str,
+        notes: Option<&'a str>,
+    ) -> ForgePhaseReviewRequest<'a> {
+        ForgePhaseReviewRequest {
+            plan_id: "alpha",
+            task_id: "task-1",
+            phase_id,
+            reviewer: "dana",
+            notes,
+        }
+    }
+
+    async fn next_phase_prompt(
+        workspace: &Path,
+        sink: &RecordingEventSink,
+    ) -> ForgeNextPhasePromptV1 {
+        let workspaces = tokio::sync::Mutex::default();
+        forge_get_next_phase_prompt_core(
+            &workspaces,
+            workspace,
+            "alpha",
+            TEST_WORKSPACE_ID,
+            sink,
+            &ForgeExecutionCancels::default(),
+        )
+        .await
+        .expect("get next phase")
+        .expect("expected next phase")
+    }
+
+    #[test]
+    fn manual_approval_phase_waits_for_a_reviewer_and_rejection_returns_to_the_agent() {
+        run_async_test(async {
+            let fixture = setup_six_phase_workspace(
+                "in_progress",
+                [
+                    "completed",
+                    "completed",
+                    "completed",
+                    "completed",
+                    "pending",
+                    "pending",
+                ],
+                0,
+            );
+            mark_manual_approval_phase(&fixture.root, "documentation");
+
+            let sink = RecordingEventSink::default();
+            let next = next_phase_prompt(&fixture.root, &sink).await;
+            assert_eq!(next.phase_id, "documentation");
+            assert!(next.awaiting_approval);
+            assert!(next.prompt_text.is_empty());
+            assert_eq!(
+                sink.forge_event_kinds(),
+                vec![ForgeExecutionEventKind::ApprovalRequested]
+            );
+            let runnable = forge_get_runnable_tasks_core(&fixture.root, "alpha").expect("runnable");
+            assert!(runnable[0].awaiting_approval);
+
+            let checks = forge_run_phase_checks_core(
+                &fixture.root,
+                ForgeRunPhaseChecksRequest {
+                    plan_id: "alpha",
+                    task_id: "task-1",
+                    phase_id: "documentation",
+                    thread_id: None,
+                },
+                TEST_WORKSPACE_ID,
+                &RecordingEventSink::default(),
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect_err("checks need an approval");
+            assert!(checks.contains("needs a manual approval"));
+            let rejection =
+                forge_reject_phase_core(&fixture.root, review_request("documentation", None))
+                    .await
+                    .expect_err("notes are required");
+            assert!(rejection.contains("notes are required"));
+
+            let rejected = forge_reject_phase_core(
+                &fixture.root,
+                review_request("documentation", Some("Coverage misses the error paths.")),
+            )
+            .await
+            .expect("reject phase");
+            assert_eq!(
+                rejected.returned_to_phase_id.as_deref(),
+                Some("coverage-hardening")
+            );
+            let task = load_state_task(&fixture.root, "alpha", "task-1");
+            assert_eq!(phase_status(&task, "coverage-hardening"), "pending");
+            let approval = task.phases[4]
+                .approval
+                .as_ref()
+                .expect("recorded rejection");
+            assert_eq!(approval.decision, "rejected");
+            assert_eq!(approval.reviewer, "dana");
+
+            let next = next_phase_prompt(&fixture.root, &RecordingEventSink::default()).await;
+            assert_eq!(next.phase_id, "coverage-hardening");
+            assert!(!next.awaiting_approval);
+            assert!(next.prompt_text.contains("## Reviewer Feedback"));
+            assert!(next
+                .prompt_text
+                .contains("Coverage misses the error paths."));
+        });
+    }
+
+    #[test]
+    fn approving_a_manual_phase_completes_it_and_commits_the_task() {
+        run_async_test(async {
+            let fixture = setup_six_phase_workspace(
+                "in_progress",
+                [
+                    "completed",
+                    "completed",
+                    "completed",
+                    "completed",
+                    "completed",
+                    "pending",
+                ],
+                0,
+            );
+            init_git_repo(&fixture.root);
+            mark_manual_approval_phase(&fixture.root, "ai-review");
+            write_ai_review_report(&fixture.root, "alpha", "task-1", &[]);
+
+            let early = forge_approve_phase_core(
+                &fixture.root,
+                review_request("documentation", None),
+                TEST_WORKSPACE_ID,
+                &RecordingEventSink::default(),
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect_err("only the awaiting phase can be approved");
+            assert!(early.contains("not a manual-approval phase"));
+
+            let approved = forge_approve_phase_core(
+                &fixture.root,
+                review_request("ai-review", Some("Looks good.")),
+                TEST_WORKSPACE_ID,
+                &RecordingEventSink::default(),
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect("approve phase");
+            assert!(approved.checks.as_ref().is_some_and(|checks| checks.ok));
+
+            let task = load_state_task(&fixture.root, "alpha", "task-1");
+            assert_eq!(task.status, "completed");
+            assert!(task.commit_sha.is_some());
+            let approval = task.phases[5].approval.as_ref().expect("recorded approval");
+            assert_eq!(approval.decision, "approved");
+            assert_eq!(approval.notes, "Looks good.");
+
+            let next = next_phase_prompt(&fixture.root, &RecordingEventSink::default()).await;
+            assert_eq!(next.task_id, "task-2");
+        });
+    }
+
+    async fn run_checks_on_thread(workspace: &Path, phase_id: &str, thread_id: &str) {
+        forge_run_phase_checks_core(
+            workspace,
+            ForgeRunPhaseChecksRequest {
+                plan_id: "alpha",
+                task_id: "task-1",
+                phase_id,
+                thread_id: Some(thread_id),
+            },
+            TEST_WORKSPACE_ID,
+            &RecordingEventSink::default(),
+            &ForgeExecutionCancels::default(),
+        )
+        .await
+        .expect("run phase checks");
+    }
+
+    fn token_count_line(timestamp: &str, input: i64, cached: i64, output: i64) -> String {
+        json!({
+            "timestamp": timestamp,
+            "payload": {
+                "type": "token_count",
+                "info": {
+                    "total_token_usage": {
+                        "input_tokens": input,
+                        "cached_input_tokens": cached,
+                        "output_tokens": output
+                    }
+                }
+            }
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn plan_cost_splits_thread_usage_between_phases_at_each_check_run() {
+        run_async_test(async {
+            let fixture = setup_six_phase_workspace("pending", ["pending"; 6], 0);
+            run_checks_on_thread(&fixture.root, "test-case-mapping", "thread-1").await;
+            run_checks_on_thread(&fixture.root, "behavioral-tests", "thread-1").await;
+            run_checks_on_thread(&fixture.root, "implementation", "thread-gone").await;
+
+            let task = load_state_task(&fixture.root, "alpha", "task-1");
+            assert_eq!(task.phases[0].threads[0].thread_id, "thread-1");
+            let paths = build_execution_paths(&fixture.root, "alpha").expect("paths");
+            update_task_state(&paths, "task-1", |task| {
+                task.phases[0].threads[0].checked_at = "2026-03-01T10:01:00.000Z".to_string();
+                task.phases[1].threads[0].checked_at = "2026-03-01T10:02:00.000Z".to_string();
+                Ok(())
+            })
+            .await
+            .expect("pin check times");
+
+            let sessions_root = fixture.root.join("codex-sessions");
+            let day_dir = sessions_root.join("2026").join("03").join("01");
+            write_text(
+                &day_dir.join("rollout-2026-03-01T10-00-00-thread-1.jsonl"),
+                &format!(
+                    "{}\n{}\n{}\n",
+                    token_count_line("2026-03-01T10:00:30.000Z", 100, 40, 10),
+                    token_count_line("2026-03-01T10:01:30.000Z", 250, 100, 30),
+                    token_count_line("2026-03-01T10:02:30.000Z", 300, 100, 35),
+                ),
+            );
+
+            let cost = cost::plan_cost(&paths, &[sessions_root]).expect("plan cost");
+            let task = &cost.tasks[0];
+            assert_eq!(task.task_name.as_deref(), Some("Task 1"));
+            assert_eq!(task.thread_ids, vec!["thread-1", "thread-gone"]);
+            assert_eq!(task.phases[0].usage.input_tokens, 100);
+            assert_eq!(task.phases[0].usage.cached_input_tokens, 40);
+            assert_eq!(task.phases[1].usage.input_tokens, 150);
+            assert_eq!(task.phases[1].usage.total_tokens, 170);
+            assert_eq!(task.phases[1].usage.agent_time_ms, 60_000);
+            assert_eq!(task.phases[2].usage, ForgeUsageV1::default());
+            assert_eq!(task.unchecked_usage.input_tokens, 50);
+            assert_eq!(task.usage.input_tokens, 300);
+            assert_eq!(task.usage.output_tokens, 35);
+            assert_eq!(cost.usage, task.usage);
+            assert_eq!(cost.tasks[1].usage, ForgeUsageV1::default());
+            assert_eq!(cost.missing_thread_ids, vec!["thread-gone"]);
+            assert_eq!(cost.template_id.as_deref(), Some("test-first-loop"));
+        });
+    }
+
+    fn set_plan_budget(workspace: &Path, budget: serde_json::Value) {
+        let plan_path = workspace.join("plans").join("alpha").join("plan.json");
+        let mut plan = read_json_file::<serde_json::Value>(&plan_path).expect("read plan");
+        plan["budget"] = budget;
+        write_json(&plan_path, plan);
+    }
+
+    fn workspaces_with_codex_home(
+        codex_home: &Path,
+    ) -> tokio::sync::Mutex<HashMap<String, WorkspaceEntry>> {
+        let settings = crate::types::WorkspaceSettings {
+            codex_home: Some(codex_home.to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        let entry = WorkspaceEntry {
+            id: TEST_WORKSPACE_ID.to_string(),
+            name: "Workspace".to_string(),
+            path: "/tmp/forge-budget-workspace".to_string(),
+            codex_bin: None,
+            kind: crate::types::WorkspaceKind::Main,
+            parent_id: None,
+            worktree: None,
+            settings,
+        };
+        tokio::sync::Mutex::new(HashMap::from([(entry.id.clone(), entry)]))
+    }
+
+    #[test]
+    fn plan_budget_blocks_the_task_until_the_limit_is_raised() {
+        run_async_test(async {
+            let fixture = setup_six_phase_workspace("in_progress", ["pending"; 6], 0);
+            let codex_home = fixture.root.join("codex-home");
+            write_text(
+                &codex_home
+                    .join("sessions")
+                    .join("2026")
+                    .join("03")
+                    .join("01")
+                    .join("rollout-2026-03-01T10-00-00-thread-1.jsonl"),
+                &format!(
+                    "{}\n",
+                    token_count_line("2026-03-01T10:00:30.000Z", 120, 20, 30)
+                ),
+            );
+            let workspaces = workspaces_with_codex_home(&codex_home);
+            set_plan_budget(&fixture.root, json!({ "max_task_attempts": 1 }));
+            run_checks_on_thread(&fixture.root, "test-case-mapping", "thread-1").await;
+
+            let sink = RecordingEventSink::default();
+            let halted = forge_get_next_phase_prompt_core(
+                &workspaces,
+                &fixture.root,
+                "alpha",
+                TEST_WORKSPACE_ID,
+                &sink,
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect("get next phase")
+            .expect("expected next phase");
+            let attempts_note =
+                "Budget exceeded: task-1 reached 1 check runs (max_task_attempts 1).";
+            assert_eq!(halted.budget_exceeded.as_deref(), Some(attempts_note));
+            assert_eq!(halted.phase_id, "behavioral-tests");
+            assert!(halted.prompt_text.is_empty());
+            assert_eq!(
+                sink.forge_event_kinds().last(),
+                Some(&ForgeExecutionEventKind::TaskBlocked {
+                    reason: attempts_note.to_string(),
+                })
+            );
+            let task = load_state_task(&fixture.root, "alpha", "task-1");
+            assert_eq!(task.status, "blocked");
+            assert_eq!(task.notes, attempts_note);
+
+            set_plan_budget(
+                &fixture.root,
+                json!({ "max_task_attempts": 5, "max_task_tokens": 100 }),
+            );
+            let halted = forge_get_next_phase_prompt_core(
+                &workspaces,
+                &fixture.root,
+                "alpha",
+                TEST_WORKSPACE_ID,
+                &RecordingEventSink::default(),
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect("get next phase")
+            .expect("expected next phase");
+            let tokens_note = "Budget exceeded: task-1 used 150 tokens (max_task_tokens 100).";
+            assert_eq!(halted.budget_exceeded.as_deref(), Some(tokens_note));
+            let task = load_state_task(&fixture.root, "alpha", "task-1");
+            assert_eq!(task.notes, tokens_note);
+
+            set_plan_budget(
+                &fixture.root,
+                json!({ "max_task_attempts": 5, "max_total_tokens": 1000 }),
+            );
+            let next = forge_get_next_phase_prompt_core(
+                &workspaces,
+                &fixture.root,
+                "alpha",
+                TEST_WORKSPACE_ID,
+                &RecordingEventSink::default(),
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect("get next phase")
+            .expect("expected next phase");
+            assert_eq!(next.budget_exceeded, None);
+            assert_eq!(next.phase_id, "behavioral-tests");
+            assert!(!next.prompt_text.is_empty());
+        });
+    }
+
+    #[test]
+    fn budget_blocked_task_lets_independent_tasks_run_first() {
+        run_async_test(async {
+            let fixture = setup_six_phase_workspace("pending", ["pending"; 6], 0);
+            write_three_task_plan(&fixture.root);
+            set_plan_budget(&fixture.root, json!({ "max_task_attempts": 1 }));
+            let paths = build_execution_paths(&fixture.root, "alpha").expect("paths");
+            update_task_state(&paths, "task-1", |task| {
+                task.status = "in_progress".to_string();
+                task.attempts = 1;
+                Ok(())
+            })
+            .await
+            .expect("spend task-1 budget");
+            let next_phase = || async {
+                forge_get_next_phase_prompt_core(
+                    &tokio::sync::Mutex::default(),
+                    &fixture.root,
+                    "alpha",
+                    TEST_WORKSPACE_ID,
+                    &RecordingEventSink::default(),
+                    &ForgeExecutionCancels::default(),
+                )
+                .await
+                .expect("get next phase")
+                .expect("expected next phase")
+            };
+
+            let halted = next_phase().await;
+            assert_eq!(halted.task_id, "task-1");
+            assert!(halted.budget_exceeded.is_some());
+            assert_eq!(load_state_task(&fixture.root, "alpha", "task-1").status, "blocked");
+
+            // task-3 does not depend on the blocked task, so it gets the next prompt.
+            let next = next_phase().await;
+            assert_eq!(next.task_id, "task-3");
+            assert_eq!(next.budget_exceeded, None);
+            assert!(!next.prompt_text.is_empty());
+
+            // Once task-3 is done only task-1 is left, and it reports its budget again.
+            update_task_state(&paths, "task-3", |task| {
+                task.status = "completed".to_string();
+                for phase in &mut task.phases {
+                    phase.status = "completed".to_string();
+                }
+                Ok(())
+            })
+            .await
+            .expect("complete task-3");
+            let halted = next_phase().await;
+            assert_eq!(halted.task_id, "task-1");
+            assert!(halted.budget_exceeded.is_some());
+
+            // A task blocked for another reason is not picked up at all.
+            update_task_state(&paths, "task-1", |task| {
+                task.notes = "Waiting on a design review.".to_string();
+                Ok(())
+            })
+            .await
+            .expect("block task-1 by hand");
+            let next = forge_get_next_phase_prompt_core(
+                &tokio::sync::Mutex::default(),
+                &fixture.root,
+                "alpha",
+                TEST_WORKSPACE_ID,
+                &RecordingEventSink::default(),
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect("get next phase");
+            assert!(next.is_none());
+        });
+    }
+
+    #[test]
+    fn run_clock_budget_counts_from_the_first_prompt_and_restarts_with_each_run() {
+        run_async_test(async {
+            let fixture = setup_six_phase_workspace("in_progress", ["pending"; 6], 0);
+            set_plan_budget(&fixture.root, json!({ "max_minutes": 30 }));
+            let next = next_phase_prompt(&fixture.root, &RecordingEventSink::default()).await;
+            assert_eq!(next.budget_exceeded, None);
+
+            let paths = build_execution_paths(&fixture.root, "alpha").expect("paths");
+            let started_at = load_state(&paths).expect("load state").run_started_at;
+            assert!(started_at.is_some());
+            update_state(&paths, |state| {
+                let two_hours_ago = Utc::now() - chrono::Duration::minutes(120);
+                state.run_started_at =
+                    Some(two_hours_ago.to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+                Ok(())
+            })
+            .await
+            .expect("move the run start back");
+            let halted = next_phase_prompt(&fixture.root, &RecordingEventSink::default()).await;
+            assert_eq!(
+                halted.budget_exceeded.as_deref(),
+                Some("Budget exceeded: the run has lasted 120 minutes (max_minutes 30).")
+            );
+
+            forge_prepare_execution_core(
+                &fixture.root,
+                "alpha",
+                TEST_WORKSPACE_ID,
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect("prepare the next run");
+            assert_eq!(load_state(&paths).expect("load state").run_started_at, None);
+            let next = next_phase_prompt(&fixture.root, &RecordingEventSink::default()).await;
+            assert_eq!(next.budget_exceeded, None);
+        });
+    }
+
+    fn plan_operations(value: serde_json::Value) -> Vec<ForgePlanOperationV1> {
+        serde_json::from_value(value).expect("decode operations")
+    }
+
+    #[test]
+    fn update_plan_renumbers_tasks_and_carries_their_state_and_run_logs() {
+        run_async_test(async {
+            let fixture = setup_six_phase_workspace("pending", ["pending"; 6], 0);
+            write_three_task_plan(&fixture.root);
+            let plan_dir = fixture.root.join("plans").join("alpha");
+            let state_path = plan_dir.join("state.json");
+            let mut state = read_json_file::<StateV2>(&state_path).expect("load state");
+            state.tasks[2].status = "completed".to_string();
+            state.tasks[2].commit_sha = Some("abc123".to_string());
+            for phase in &mut state.tasks[2].phases {
+                phase.status = "completed".to_string();
+            }
+            write_json(
+                &state_path,
+                serde_json::to_value(&state).expect("encode state"),
+            );
+            write_text(
+                &plan_dir.join("runs").join("task-3.jsonl"),
+                &format!(
+                    "{}\n",
+                    json!({
+                        "schema": "forge-run-v1",
+                        "runId": "run-1",
+                        "planId": "alpha",
+                        "taskId": "task-3",
+                        "phaseId": "test-case-mapping",
+                        "attempt": 1,
+                        "startedAt": "2026-01-01T00:00:00.000Z",
+                        "finishedAt": "2026-01-01T00:00:01.000Z",
+                        "ok": true
+                    })
+                ),
+            );
+
+            // Insert a task after task-1, make the old task-2 depend on it too, then move the
+            // old task-3 to the front.
+            let operations = plan_operations(json!([
+                { "op": "addTask", "position": 2, "task": { "name": "Setup" } },
+                { "op": "setDependencies", "taskId": "task-3", "dependsOn": ["task-1", "task-2"] },
+                { "op": "moveTask", "taskId": "task-4", "position": 1 }
+            ]));
+            let result = forge_update_plan_core(&fixture.root, "alpha", operations)
+                .await
+                .expect("update plan");
+            let previous_ids = result
+                .tasks
+                .iter()
+                .map(|task| task.previous_id.as_deref())
+                .collect::<Vec<_>>();
+            assert_eq!(
+                previous_ids,
+                vec![Some("task-3"), Some("task-1"), None, Some("task-2")]
+            );
+            assert!(result.removed_task_ids.is_empty());
+
+            let plan = read_json_file::<serde_json::Value>(&plan_dir.join("plan.json"))
+                .expect("load plan");
+            assert_eq!(plan["tasks"][2]["name"], "Setup");
+            assert_eq!(plan["tasks"][3]["id"], "task-4");
+            assert_eq!(plan["tasks"][3]["depends_on"], json!(["task-2", "task-3"]));
+
+            let moved = load_state_task(&fixture.root, "alpha", "task-1");
+            assert_eq!(moved.status, "completed");
+            assert_eq!(moved.commit_sha.as_deref(), Some("abc123"));
+            assert!(moved.phases.iter().all(|phase| phase.status == "completed"));
+            let added = load_state_task(&fixture.root, "alpha", "task-3");
+            assert_eq!(added.status, "pending");
+            assert_eq!(added.phases.len(), 6);
+
+            let history = forge_get_run_history_core(&fixture.root, "alpha", Some("task-1"), None)
+                .expect("run history");
+            assert_eq!(history.runs.len(), 1);
+            assert_eq!(history.runs[0].task_id, "task-1");
+            assert!(!plan_dir.join("runs").join("task-3.jsonl").exists());
+        });
+    }
+
+    #[test]
+    fn update_plan_rejects_edits_that_break_dependencies() {
+        run_async_test(async {
+            let fixture = setup_six_phase_workspace("pending", ["pending"; 6], 0);
+            write_three_task_plan(&fixture.root);
+            let plan_path = fixture.root.join("plans").join("alpha").join("plan.json");
+            let before = std::fs::read_to_string(&plan_path).expect("read plan");
+
+            let err = forge_update_plan_core(
+                &fixture.root,
+                "alpha",
+                plan_operations(json!([{ "op": "removeTask", "taskId": "task-1" }])),
+            )
+            .await
+            .expect_err("dependent blocks removal");
+            assert!(err.contains("task-2"), "unexpected error: {err}");
+
+            let err = forge_update_plan_core(
+                &fixture.root,
+                "alpha",
+                plan_operations(json!([{ "op": "moveTask", "taskId": "task-2", "position": 1 }])),
+            )
+            .await
+            .expect_err("dependency must stay first");
+            assert!(err.contains("must come after"), "unexpected error: {err}");
+            assert_eq!(
+                std::fs::read_to_string(&plan_path).expect("read plan"),
+                before
+            );
+
+            let result = forge_update_plan_core(
+                &fixture.root,
+                "alpha",
+                plan_operations(json!([
+                    { "op": "setDependencies", "taskId": "task-2", "dependsOn": [] },
+                    { "op": "removeTask", "taskId": "task-1" }
+                ])),
+            )
+            .await
+            .expect("remove task");
+            assert_eq!(result.removed_task_ids, vec!["task-1"]);
+            let state = read_json_file::<StateV2>(
+                &fixture.root.join("plans").join("alpha").join("state.json"),
+            )
+            .expect("load state");
+            let ids = state
+                .tasks
+                .iter()
+                .map(|task| task.id.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(ids, vec!["task-1", "task-2"]);
+        });
+    }
+
+    #[test]
+    fn analyze_plan_reports_cycles_and_unknown_dependencies_and_blocks_execution() {
+        run_async_test(async {
+            let fixture = setup_six_phase_workspace("pending", ["pending"; 6], 0);
+            let plan_dir = fixture.root.join("plans").join("alpha");
+            write_json(
+                &plan_dir.join("plan.json"),
+                json!({
+                    "$schema": "plan-v1",
+                    "id": "alpha",
+                    "goal": "Test goal",
+                    "tasks": [
+                        { "id": "task-1", "name": "Task 1", "depends_on": [] },
+                        { "id": "task-2", "name": "Task 2", "depends_on": ["task-1"] },
+                        { "id": "task-3", "name": "Task 3", "depends_on": ["task-4"] },
+                        { "id": "task-4", "name": "Task 4", "depends_on": ["task-3"] },
+                        { "id": "task-5", "name": "Task 5", "depends_on": ["task-9"] }
+                    ]
+                }),
+            );
+            std::fs::remove_file(plan_dir.join("state.json")).expect("remove state");
+
+            let analysis = forge_analyze_plan_core(&fixture.root, "alpha").expect("analyze plan");
+            assert_eq!(analysis.cycles, vec![vec!["task-3", "task-4"]]);
+            assert_eq!(analysis.dangling_dependencies.len(), 1);
+            assert_eq!(analysis.dangling_dependencies[0].task_id, "task-5");
+            assert_eq!(analysis.dangling_dependencies[0].depends_on, "task-9");
+            assert_eq!(analysis.critical_path, vec!["task-1", "task-2"]);
+            assert_eq!(analysis.tasks[0].dependents, vec!["task-2"]);
+            assert!(analysis.tasks[0].runnable);
+            assert!(analysis.tasks[0].status.is_none());
+            let reasons = analysis
+                .tasks
+                .iter()
+                .map(|task| task.blocked_reason.clone())
+                .collect::<Vec<_>>();
+            assert_eq!(
+                reasons[1],
+                Some(ForgeTaskBlockedReasonV1::WaitingOnDependencies {
+                    task_ids: vec!["task-1".to_string()]
+                })
+            );
+            assert!(matches!(
+                reasons[2],
+                Some(ForgeTaskBlockedReasonV1::InCycle { .. })
+            ));
+            assert_eq!(
+                reasons[4],
+                Some(ForgeTaskBlockedReasonV1::UnknownDependencies {
+                    task_ids: vec!["task-9".to_string()]
+                })
+            );
+
+            let err = forge_prepare_execution_core(
+                &fixture.root,
+                "alpha",
+                TEST_WORKSPACE_ID,
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect_err("structural errors block execution");
+            assert!(
+                err.contains("dependency cycle between task-3, task-4")
+                    && err.contains("task-5 depends on unknown task task-9"),
+                "unexpected error: {err}"
+            );
+            assert!(!plan_dir.join("state.json").exists());
+        });
+    }
+
+    #[test]
+    fn analyze_plan_explains_tasks_blocked_by_execution_state() {
+        let fixture = setup_six_phase_workspace("pending", ["pending"; 6], 0);
+        write_three_task_plan(&fixture.root);
+        let state_path = fixture.root.join("plans").join("alpha").join("state.json");
+        let mut state = read_json_file::<StateV2>(&state_path).expect("load state");
+        state.tasks[0].status = "in_progress".to_string();
+        state.tasks[0].phases[1].status = "failed".to_string();
+        state.tasks[2].status = "completed".to_string();
+        for phase in &mut state.tasks[2].phases {
+            phase.status = "completed".to_string();
+        }
+        write_json(&state_path, serde_json::to_value(&state).expect("encode state"));
+
+        let analysis = forge_analyze_plan_core(&fixture.root, "alpha").expect("analyze plan");
+        let reasons = analysis
+            .tasks
+            .iter()
+            .map(|task| task.blocked_reason.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            reasons,
+            vec![
+                Some(ForgeTaskBlockedReasonV1::RetryExhausted {
+                    phase_ids: vec!["behavioral-tests".to_string()]
+                }),
+                Some(ForgeTaskBlockedReasonV1::DependencyFailed {
+                    task_ids: vec!["task-1".to_string()]
+                }),
+                Some(ForgeTaskBlockedReasonV1::Completed),
+            ]
+        );
+        assert!(analysis.tasks.iter().all(|task| !task.runnable));
+        assert_eq!(analysis.tasks[2].status.as_deref(), Some("completed"));
+        assert_eq!(analysis.critical_path, vec!["task-1", "task-2"]);
+        assert!(analysis.cycles.is_empty());
+    }
+
+    fn install_bundled_template_with_plan(template_id: &str) -> TestWorkspace {
+        let workspace = TestWorkspace {
+            root: temp_workspace_root(),
+        };
+        std::fs::create_dir_all(&workspace.root).expect("create workspace");
+        let bundled_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("resources")
+            .join("forge")
+            .join("templates");
+        crate::shared::forge_templates_core::install_bundled_template_core(
+            &bundled_root,
+            &workspace.root,
+            template_id,
+        )
+        .expect("install bundled template");
+
+        write_json(
+            &workspace.root.join("plans").join("alpha").join("plan.json"),
+            json!({
+                "$schema": "plan-v1",
+                "id": "alpha",
+                "goal": "Ship the alpha feature end to end.",
+                "context": { "tech_stack": ["Rust"], "constraints": [] },
+                "tasks": [
+                    {
+                        "id": "task-1",
+                        "name": "Scaffold module",
+                        "description": "Create the module skeleton and wire it in.",
+                        "depends_on": [],
+                        "files": ["src/alpha.rs"],
+                        "verification": ["cargo test"]
+                    }
+                ]
+            }),
+        );
+        workspace
+    }
+
+    #[test]
+    fn builtin_hooks_prepare_bundled_template_and_render_prompt() {
+        run_async_test(async {
+            let workspace = install_bundled_template_with_plan("ralph-loop");
+
+            forge_prepare_execution_core(
+                &workspace.root,
+                "alpha",
+                TEST_WORKSPACE_ID,
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect("prepare execution with builtin hooks");
+            let task = load_state_task(&workspace.root, "alpha", "task-1");
+            assert_eq!(task.status, "pending");
+            assert_eq!(phase_status(&task, "implementation"), "pending");
+
+            let next = forge_get_next_phase_prompt_core(
+                &tokio::sync::Mutex::default(),
+                &workspace.root,
+                "alpha",
+                TEST_WORKSPACE_ID,
+                &RecordingEventSink::default(),
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect("render next phase prompt")
+            .expect("task-1 is runnable");
+            assert_eq!(next.phase_id, "implementation");
+            assert!(next
+                .prompt_text
+                .contains("## YOUR TASK: task-1 - Scaffold module"));
+            assert!(!next.prompt_text.contains("{{"));
+        });
+    }
+
+    #[test]
+    fn builtin_hooks_accept_bundled_test_first_loop_phases() {
+        run_async_test(async {
+            let workspace = install_bundled_template_with_plan("test-first-loop");
+
+            forge_prepare_execution_core(
+                &workspace.root,
+                "alpha",
+                TEST_WORKSPACE_ID,
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect("prepare execution with builtin hooks");
+            let next = forge_get_next_phase_prompt_core(
+                &tokio::sync::Mutex::default(),
+                &workspace.root,
+                "alpha",
+                TEST_WORKSPACE_ID,
+                &RecordingEventSink::default(),
+                &ForgeExecutionCancels::default(),
+            )
+            .await
+            .expect("render next phase prompt")
+            .expect("task-1 is runnable");
+            assert_eq!(next.phase_id, "test-case-mapping");
+        });
+    }
 }
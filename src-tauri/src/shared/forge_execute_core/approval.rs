@@ -0,0 +1,275 @@
+use super::*;
+
+pub(super) const MANUAL_APPROVAL_PHASE_KIND: &str = "manual-approval";
+const APPROVED_DECISION: &str = "approved";
+const REJECTED_DECISION: &str = "rejected";
+
+/// A reviewer's decision on a `manual-approval` phase.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ForgePhaseReviewRequest<'a> {
+    pub(crate) plan_id: &'a str,
+    pub(crate) task_id: &'a str,
+    pub(crate) phase_id: &'a str,
+    pub(crate) reviewer: &'a str,
+    pub(crate) notes: Option<&'a str>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgePhaseReviewResultV1 {
+    pub(crate) plan_id: String,
+    pub(crate) task_id: String,
+    pub(crate) phase_id: String,
+    /// `approved` or `rejected`.
+    pub(crate) decision: String,
+    /// The agent phase a rejection sent the task back to.
+    pub(crate) returned_to_phase_id: Option<String>,
+    /// Checks run after an approval. They complete the phase and may commit the task.
+    pub(crate) checks: Option<ForgeRunPhaseChecksResponseV1>,
+}
+
+pub(super) fn is_manual_approval_phase(phases: &ForgeTemplatePhasesV1, phase_id: &str) -> bool {
+    phases
+        .phases
+        .iter()
+        .find(|phase| phase.id.trim() == phase_id.trim())
+        .and_then(|phase| phase.kind.as_deref())
+        .is_some_and(|kind| kind.trim() == MANUAL_APPROVAL_PHASE_KIND)
+}
+
+/// Reported instead of a prompt when the task's next phase waits for a reviewer.
+pub(super) fn awaiting_approval_prompt<E: EventSink>(
+    event_sink: &E,
+    workspace_id: &str,
+    plan_id: &str,
+    task_id: &str,
+    phase_id: &str,
+    is_last_phase: bool,
+) -> ForgeNextPhasePromptV1 {
+    ForgeExecutionEmitter {
+        event_sink,
+        workspace_id,
+        plan_id,
+        task_id,
+        phase_id,
+    }
+    .emit(ForgeExecutionEventKind::ApprovalRequested);
+    ForgeNextPhasePromptV1 {
+        plan_id: plan_id.to_string(),
+        task_id: task_id.to_string(),
+        phase_id: phase_id.to_string(),
+        is_last_phase,
+        prompt_text: String::new(),
+        awaiting_approval: true,
+        budget_exceeded: None,
+    }
+}
+
+/// Appends the notes of every later review that rejected the task, so the agent sees why it
+/// is redoing the phase.
+pub(super) fn append_review_feedback(
+    prompt_text: String,
+    task: &StateTaskV2,
+    phase_index: usize,
+) -> String {
+    let feedback = task
+        .phases
+        .iter()
+        .skip(phase_index + 1)
+        .filter(|phase| !is_completed_status(&phase.status))
+        .filter_map(|phase| {
+            let approval = phase.approval.as_ref()?;
+            (approval.decision == REJECTED_DECISION).then(|| {
+                format!(
+                    "The `{}` review by {} ({}) rejected this task:\n\n{}",
+                    phase.id.trim(),
+                    approval.reviewer,
+                    approval.decided_at,
+                    approval.notes.trim()
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+    if feedback.is_empty() {
+        return prompt_text;
+    }
+    format!(
+        "{}\n\n## Reviewer Feedback\n\nAddress this feedback before completing the phase.\n\n{}\n",
+        prompt_text.trim_end(),
+        feedback.join("\n\n")
+    )
+}
+
+/// Checks of a `manual-approval` phase only run once a reviewer approved it.
+pub(super) fn require_approval(
+    phases: &ForgeTemplatePhasesV1,
+    task: &StateTaskV2,
+    phase_index: usize,
+) -> Result<(), String> {
+    let Some(phase) = task.phases.get(phase_index) else {
+        return Ok(());
+    };
+    let approved = phase
+        .approval
+        .as_ref()
+        .is_some_and(|approval| approval.decision == APPROVED_DECISION);
+    if is_manual_approval_phase(phases, &phase.id) && !approved {
+        return Err(format!(
+            "Phase {}/{} needs a manual approval. Use forge_approve_phase or forge_reject_phase.",
+            task.id.trim(),
+            phase.id.trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Index of `phase_id` when it is the task's next phase and a `manual-approval` phase.
+fn awaiting_phase_index(
+    phases: &ForgeTemplatePhasesV1,
+    task: &StateTaskV2,
+    phase_id: &str,
+) -> Result<usize, String> {
+    if !is_manual_approval_phase(phases, phase_id) {
+        return Err(format!(
+            "Phase {}/{phase_id} is not a manual-approval phase.",
+            task.id.trim()
+        ));
+    }
+    match find_next_phase(task) {
+        Some((index, phase)) if phase.id.trim() == phase_id && !is_task_completed(task) => {
+            Ok(index)
+        }
+        _ => Err(format!(
+            "Phase {}/{phase_id} is not awaiting approval.",
+            task.id.trim()
+        )),
+    }
+}
+
+/// Whether the run driver should keep waiting on this phase.
+pub(super) fn is_phase_awaiting_approval(
+    workspace_root: &Path,
+    plan_id: &str,
+    task_id: &str,
+    phase_id: &str,
+) -> Result<bool, String> {
+    let paths = build_execution_paths(workspace_root, plan_id)?;
+    let phases = load_template_phases(&paths)?;
+    let state = load_state(&paths)?;
+    Ok(state
+        .tasks
+        .iter()
+        .find(|task| task.id.trim() == task_id.trim())
+        .is_some_and(|task| awaiting_phase_index(&phases, task, phase_id.trim()).is_ok()))
+}
+
+fn validate_review_request(request: &ForgePhaseReviewRequest<'_>) -> Result<(), String> {
+    if request.task_id.trim().is_empty() {
+        return Err("taskId is required".to_string());
+    }
+    if request.phase_id.trim().is_empty() {
+        return Err("phaseId is required".to_string());
+    }
+    if request.reviewer.trim().is_empty() {
+        return Err("reviewer is required".to_string());
+    }
+    Ok(())
+}
+
+fn record_approval(
+    phase: &mut StatePhaseV2,
+    decision: &str,
+    request: &ForgePhaseReviewRequest<'_>,
+) {
+    phase.approval = Some(StatePhaseApprovalV2 {
+        decision: decision.to_string(),
+        reviewer: request.reviewer.trim().to_string(),
+        notes: request.notes.unwrap_or_default().trim().to_string(),
+        decided_at: Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+    });
+}
+
+/// Records the approval, then runs the phase's checks, which complete the phase and commit the
+/// task when it is the last one.
+pub(crate) async fn forge_approve_phase_core<E: EventSink>(
+    workspace_root: &Path,
+    request: ForgePhaseReviewRequest<'_>,
+    workspace_id: &str,
+    event_sink: &E,
+    cancels: &ForgeExecutionCancels,
+) -> Result<ForgePhaseReviewResultV1, String> {
+    validate_review_request(&request)?;
+    let paths = build_execution_paths(workspace_root, request.plan_id)?;
+    let task_id = request.task_id.trim();
+    let phase_id = request.phase_id.trim();
+    let phases = load_template_phases(&paths)?;
+    update_task_state(&paths, task_id, |task| {
+        let index = awaiting_phase_index(&phases, task, phase_id)?;
+        record_approval(&mut task.phases[index], APPROVED_DECISION, &request);
+        Ok(())
+    })
+    .await?;
+
+    let checks = forge_run_phase_checks_core(
+        workspace_root,
+        ForgeRunPhaseChecksRequest {
+            plan_id: &paths.plan_id,
+            task_id,
+            phase_id,
+            thread_id: None,
+        },
+        workspace_id,
+        event_sink,
+        cancels,
+    )
+    .await?;
+    Ok(ForgePhaseReviewResultV1 {
+        plan_id: paths.plan_id,
+        task_id: task_id.to_string(),
+        phase_id: phase_id.to_string(),
+        decision: APPROVED_DECISION.to_string(),
+        returned_to_phase_id: None,
+        checks: Some(checks),
+    })
+}
+
+/// Records the rejection and sends the task back to the closest agent phase before the review.
+/// That phase and the review run again, and the agent prompt carries the reviewer's notes.
+pub(crate) async fn forge_reject_phase_core(
+    workspace_root: &Path,
+    request: ForgePhaseReviewRequest<'_>,
+) -> Result<ForgePhaseReviewResultV1, String> {
+    validate_review_request(&request)?;
+    if request.notes.is_none_or(|notes| notes.trim().is_empty()) {
+        return Err("notes are required to reject a phase".to_string());
+    }
+    let paths = build_execution_paths(workspace_root, request.plan_id)?;
+    let task_id = request.task_id.trim();
+    let phase_id = request.phase_id.trim();
+    let phases = load_template_phases(&paths)?;
+    let returned_to = update_task_state(&paths, task_id, |task| {
+        let index = awaiting_phase_index(&phases, task, phase_id)?;
+        let target = (0..index)
+            .rev()
+            .find(|&earlier| !is_manual_approval_phase(&phases, &task.phases[earlier].id))
+            .ok_or_else(|| {
+                format!("Phase {task_id}/{phase_id} has no agent phase before it to return to.")
+            })?;
+        record_approval(&mut task.phases[index], REJECTED_DECISION, &request);
+        for phase in &mut task.phases[target..=index] {
+            phase.status = "pending".to_string();
+        }
+        task.status = "in_progress".to_string();
+        Ok(task.phases[target].id.clone())
+    })
+    .await?;
+
+    Ok(ForgePhaseReviewResultV1 {
+        plan_id: paths.plan_id,
+        task_id: task_id.to_string(),
+        phase_id: phase_id.to_string(),
+        decision: REJECTED_DECISION.to_string(),
+        returned_to_phase_id: Some(returned_to),
+        checks: None,
+    })
+}
@@ -0,0 +1,380 @@
+use super::*;
+
+use serde_json::{json, Map};
+
+use crate::shared::forge_schema_core::{validate_json_against_schema, ForgeSchemaErrorV1};
+
+const AI_REVIEW_REPORT_SCHEMA: &str = "forge-ai-review-v1";
+const DEFAULT_SEVERITY_LEVELS: [&str; 5] = ["info", "low", "medium", "high", "critical"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeArtifactDetailsV1 {
+    pub(crate) path: String,
+    pub(crate) present: bool,
+    #[serde(default)]
+    pub(crate) schema_errors: Vec<ForgeSchemaErrorV1>,
+    /// One message per `require` rule the artifact does not meet.
+    #[serde(default)]
+    pub(crate) failed_rules: Vec<String>,
+}
+
+/// A JSON artifact a phase must produce before it can complete, declared under `artifacts` in
+/// `phases.json`.
+#[derive(Debug, Clone)]
+pub(super) struct ArtifactGate {
+    pub(super) id: String,
+    pub(super) title: String,
+    path: String,
+    schema: Option<ArtifactSchema>,
+    rules: Vec<ArtifactRule>,
+}
+
+#[derive(Debug, Clone)]
+enum ArtifactSchema {
+    /// Path relative to the installed template folder.
+    File(String),
+    Inline(Value),
+}
+
+#[derive(Debug, Clone)]
+struct ArtifactRule {
+    pointer: String,
+    predicate: ArtifactPredicate,
+}
+
+#[derive(Debug, Clone)]
+enum ArtifactPredicate {
+    Empty,
+    Equals(Value),
+    MaxSeverity {
+        max: String,
+        field: String,
+        levels: Vec<String>,
+    },
+}
+
+/// Gate used by an `ai-review` phase that declares no `artifacts`, so templates written before
+/// artifact gates keep their zero-findings rule.
+fn default_ai_review_gate() -> Value {
+    json!({
+        "id": "ai-review-report",
+        "title": "AI review report has zero findings",
+        "path": "plans/{planId}/ai-review/{taskId}.json",
+        "schema": {
+            "type": "object",
+            "required": ["schema", "taskId", "findings"],
+            "properties": {
+                "schema": { "const": AI_REVIEW_REPORT_SCHEMA },
+                "taskId": { "type": "string" },
+                "findings": { "type": "array" }
+            }
+        },
+        "require": [
+            { "pointer": "/taskId", "equals": "{taskId}" },
+            { "pointer": "/findings", "empty": true }
+        ]
+    })
+}
+
+fn fill_placeholders(raw: &str, plan_id: &str, task_id: &str) -> String {
+    raw.replace("{planId}", plan_id).replace("{taskId}", task_id)
+}
+
+fn parse_rule(raw: &Value) -> Result<ArtifactRule, String> {
+    let obj = raw
+        .as_object()
+        .ok_or_else(|| "Artifact require entries must be objects.".to_string())?;
+    let pointer = obj
+        .get("pointer")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .unwrap_or("")
+        .to_string();
+    if !pointer.is_empty() && !pointer.starts_with('/') {
+        return Err(format!("Artifact rule pointer must start with '/': {pointer}"));
+    }
+
+    let predicate = if obj.get("empty").and_then(Value::as_bool) == Some(true) {
+        ArtifactPredicate::Empty
+    } else if let Some(expected) = obj.get("equals") {
+        ArtifactPredicate::Equals(expected.clone())
+    } else if let Some(max) = obj.get("maxSeverity").and_then(Value::as_str) {
+        let levels = match obj.get("severityLevels").and_then(Value::as_array) {
+            Some(items) => items
+                .iter()
+                .filter_map(Value::as_str)
+                .map(|level| level.trim().to_ascii_lowercase())
+                .collect::<Vec<_>>(),
+            None => DEFAULT_SEVERITY_LEVELS.iter().map(|level| level.to_string()).collect(),
+        };
+        let max = max.trim().to_ascii_lowercase();
+        if !levels.contains(&max) {
+            return Err(format!(
+                "maxSeverity {max} is not one of the severity levels: {}.",
+                levels.join(", ")
+            ));
+        }
+        ArtifactPredicate::MaxSeverity {
+            max,
+            field: obj
+                .get("severityField")
+                .and_then(Value::as_str)
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .unwrap_or("severity")
+                .to_string(),
+            levels,
+        }
+    } else {
+        return Err(
+            "Artifact rule needs one of empty: true, equals, or maxSeverity.".to_string(),
+        );
+    };
+    Ok(ArtifactRule { pointer, predicate })
+}
+
+fn parse_gate(obj: &Map<String, Value>, id: String, title: String) -> Result<ArtifactGate, String> {
+    let path = obj
+        .get("path")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| "Artifact gate is missing a non-empty path field.".to_string())?
+        .to_string();
+    let schema = match obj.get("schema") {
+        None | Some(Value::Null) => None,
+        Some(Value::String(rel)) => Some(ArtifactSchema::File(rel.trim().to_string())),
+        Some(inline @ Value::Object(_)) => Some(ArtifactSchema::Inline(inline.clone())),
+        Some(_) => {
+            return Err(
+                "Artifact schema must be a template file path or a schema object.".to_string(),
+            )
+        }
+    };
+    let rules = match obj.get("require") {
+        None | Some(Value::Null) => Vec::new(),
+        Some(Value::Array(items)) => items.iter().map(parse_rule).collect::<Result<_, _>>()?,
+        Some(_) => return Err("Artifact require must be an array of rules.".to_string()),
+    };
+    Ok(ArtifactGate {
+        id,
+        title,
+        path,
+        schema,
+        rules,
+    })
+}
+
+/// Reads the phase's `artifacts` entries. A phase without the key gets the built-in `ai-review`
+/// report gate when it is the `ai-review` phase, and no gates otherwise.
+pub(super) fn parse_artifact_gates(
+    phase_id: &str,
+    artifacts: Option<&[Value]>,
+) -> Vec<Result<ArtifactGate, ForgePhaseCheckResultV1>> {
+    let defaults;
+    let entries = match artifacts {
+        Some(entries) => entries,
+        None if phase_id == "ai-review" => {
+            defaults = [default_ai_review_gate()];
+            &defaults[..]
+        }
+        None => &[],
+    };
+    entries
+        .iter()
+        .enumerate()
+        .map(|(index, raw)| {
+            let fallback_id = format!("artifact-{}", index + 1);
+            let Some(obj) = raw.as_object() else {
+                return Err(invalid_check_result(
+                    fallback_id.clone(),
+                    fallback_id,
+                    "Invalid artifact gate entry: expected object.".to_string(),
+                ));
+            };
+            let id = obj
+                .get("id")
+                .and_then(Value::as_str)
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .unwrap_or(&fallback_id)
+                .to_string();
+            let title = obj
+                .get("title")
+                .and_then(Value::as_str)
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .unwrap_or(&id)
+                .to_string();
+            parse_gate(obj, id.clone(), title.clone())
+                .map_err(|err| invalid_check_result(id, title, err))
+        })
+        .collect()
+}
+
+fn describe_value(value: &Value) -> String {
+    match value {
+        Value::Array(items) => format!("{} item(s)", items.len()),
+        Value::Object(map) => format!("{} key(s)", map.len()),
+        other => other.to_string(),
+    }
+}
+
+fn severity_of<'a>(item: &'a Value, field: &str) -> Option<&'a str> {
+    match item {
+        Value::String(level) => Some(level),
+        Value::Object(map) => map.get(field).and_then(Value::as_str),
+        _ => None,
+    }
+}
+
+/// Returns a failure message when the artifact does not meet the rule.
+fn check_rule(
+    rule: &ArtifactRule,
+    artifact: &Value,
+    plan_id: &str,
+    task_id: &str,
+) -> Option<String> {
+    let label = if rule.pointer.is_empty() { "/" } else { &rule.pointer };
+    let Some(target) = artifact.pointer(&rule.pointer) else {
+        return Some(format!("{label} is missing."));
+    };
+    match &rule.predicate {
+        ArtifactPredicate::Empty => {
+            let is_empty = match target {
+                Value::Null => true,
+                Value::Array(items) => items.is_empty(),
+                Value::Object(map) => map.is_empty(),
+                Value::String(text) => text.trim().is_empty(),
+                _ => false,
+            };
+            (!is_empty).then(|| format!("{label} must be empty (has {}).", describe_value(target)))
+        }
+        ArtifactPredicate::Equals(expected) => {
+            let expected = match expected {
+                Value::String(text) => Value::String(fill_placeholders(text, plan_id, task_id)),
+                other => other.clone(),
+            };
+            (target != &expected).then(|| format!("{label} must equal {expected} (got {target})."))
+        }
+        ArtifactPredicate::MaxSeverity { max, field, levels } => {
+            let max_rank = levels.iter().position(|level| level == max).unwrap_or(0);
+            let items = match target {
+                Value::Array(items) => items.iter().collect::<Vec<_>>(),
+                other => vec![other],
+            };
+            let above = items
+                .iter()
+                .map(|item| {
+                    severity_of(item, field)
+                        .map(|level| level.trim().to_ascii_lowercase())
+                        .unwrap_or_else(|| "<missing>".to_string())
+                })
+                .filter(|level| {
+                    levels
+                        .iter()
+                        .position(|known| known == level)
+                        .is_none_or(|rank| rank > max_rank)
+                })
+                .collect::<Vec<_>>();
+            (!above.is_empty()).then(|| {
+                format!(
+                    "{label} has {} item(s) above {max} severity: {}.",
+                    above.len(),
+                    above.join(", ")
+                )
+            })
+        }
+    }
+}
+
+fn load_schema(paths: &ForgeExecutionPaths, schema: &ArtifactSchema) -> Result<Value, String> {
+    match schema {
+        ArtifactSchema::Inline(value) => Ok(value.clone()),
+        ArtifactSchema::File(rel) => {
+            let path = paths.template_root.join(validate_relative_file_path(rel)?);
+            read_json_file(&path).map_err(|err| format!("Invalid artifact schema {rel}: {err}"))
+        }
+    }
+}
+
+/// Checks one artifact gate. Exit code `2` covers a missing, unreadable or schema-invalid
+/// artifact, and `1` an artifact that fails its `require` rules.
+pub(super) fn run_artifact_gate(
+    paths: &ForgeExecutionPaths,
+    task_id: &str,
+    gate: &ArtifactGate,
+) -> ForgePhaseCheckResultV1 {
+    let start = Instant::now();
+    let display = fill_placeholders(&gate.path, &paths.plan_id, task_id);
+    let mut details = ForgeArtifactDetailsV1 {
+        path: display.clone(),
+        present: false,
+        schema_errors: Vec::new(),
+        failed_rules: Vec::new(),
+    };
+    let finish = |exit_code: i32, stdout: String, stderr: String, details| {
+        ForgePhaseCheckResultV1 {
+            id: gate.id.clone(),
+            title: gate.title.clone(),
+            exit_code,
+            duration_ms: start.elapsed().as_millis() as i64,
+            stdout,
+            stderr,
+            timed_out: false,
+            details: Some(Box::new(ForgeCheckDetailsV1::Artifact(details))),
+        }
+    };
+
+    let artifact_path = match validate_relative_file_path(&display) {
+        Ok(rel) => paths.workspace_root.join(rel),
+        Err(err) => return invalid_check_result(gate.id.clone(), gate.title.clone(), err),
+    };
+    if !artifact_path.is_file() {
+        return finish(2, String::new(), format!("Missing artifact: {display}"), details);
+    }
+    details.present = true;
+    let artifact: Value = match read_json_file(&artifact_path) {
+        Ok(value) => value,
+        Err(err) => {
+            return finish(2, String::new(), format!("Invalid artifact {display}: {err}"), details)
+        }
+    };
+
+    if let Some(schema) = &gate.schema {
+        let schema = match load_schema(paths, schema) {
+            Ok(schema) => schema,
+            Err(err) => return finish(2, String::new(), err, details),
+        };
+        details.schema_errors = validate_json_against_schema(&schema, &artifact);
+        if !details.schema_errors.is_empty() {
+            let stderr = details
+                .schema_errors
+                .iter()
+                .map(|error| {
+                    let at = match error.instance_path.as_str() {
+                        "" => "/",
+                        path => path,
+                    };
+                    format!("{display} {at}: {}", error.message)
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            return finish(2, String::new(), stderr, details);
+        }
+    }
+
+    details.failed_rules = gate
+        .rules
+        .iter()
+        .filter_map(|rule| check_rule(rule, &artifact, &paths.plan_id, task_id))
+        .collect();
+    if details.failed_rules.is_empty() {
+        let stdout = format!("Verified {display} ({} rule(s)).", gate.rules.len());
+        finish(0, stdout, String::new(), details)
+    } else {
+        let stderr = details.failed_rules.join("\n");
+        finish(1, String::new(), stderr, details)
+    }
+}
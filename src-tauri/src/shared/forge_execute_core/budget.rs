@@ -0,0 +1,209 @@
+use super::*;
+
+use super::cost::plan_cost;
+use crate::shared::local_usage_core::resolve_sessions_roots;
+
+const BUDGET_NOTE_PREFIX: &str = "Budget exceeded:";
+
+/// Optional `budget` of `plan.json`. The limits are checked before each phase prompt, so a
+/// phase that is running always gets to finish.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub(super) struct PlanBudgetV1 {
+    /// Input plus output tokens on the task's threads.
+    #[serde(default)]
+    max_task_tokens: Option<i64>,
+    /// Input plus output tokens on every thread of the plan.
+    #[serde(default)]
+    max_total_tokens: Option<i64>,
+    /// Check runs of the task, as counted by its `attempts`.
+    #[serde(default)]
+    max_task_attempts: Option<i64>,
+    /// Minutes since the first phase prompt of the current run.
+    #[serde(default)]
+    max_minutes: Option<i64>,
+}
+
+impl PlanBudgetV1 {
+    fn limits_tokens(&self) -> bool {
+        self.max_task_tokens.is_some() || self.max_total_tokens.is_some()
+    }
+}
+
+/// Reported instead of a prompt when the task ran out of budget. The task is already blocked.
+pub(super) fn budget_exceeded_prompt<E: EventSink>(
+    event_sink: &E,
+    workspace_id: &str,
+    plan_id: &str,
+    task_id: &str,
+    phase_id: &str,
+    is_last_phase: bool,
+    note: String,
+) -> ForgeNextPhasePromptV1 {
+    ForgeExecutionEmitter {
+        event_sink,
+        workspace_id,
+        plan_id,
+        task_id,
+        phase_id,
+    }
+    .emit(ForgeExecutionEventKind::TaskBlocked {
+        reason: note.clone(),
+    });
+    ForgeNextPhasePromptV1 {
+        plan_id: plan_id.to_string(),
+        task_id: task_id.to_string(),
+        phase_id: phase_id.to_string(),
+        is_last_phase,
+        prompt_text: String::new(),
+        awaiting_approval: false,
+        budget_exceeded: Some(note),
+    }
+}
+
+/// Both drivers prepare the plan when they start, so each run gets the full `max_minutes`.
+pub(super) async fn restart_run_clock(paths: &ForgeExecutionPaths) -> Result<(), String> {
+    if load_state(paths)?.run_started_at.is_none() {
+        return Ok(());
+    }
+    update_state(paths, |state| {
+        state.run_started_at = None;
+        Ok(())
+    })
+    .await
+}
+
+/// The run clock starts at the run's first phase prompt.
+async fn run_started_at(paths: &ForgeExecutionPaths, state: &StateV2) -> Result<String, String> {
+    if let Some(started_at) = &state.run_started_at {
+        return Ok(started_at.clone());
+    }
+    update_state(paths, |state| {
+        Ok(state
+            .run_started_at
+            .get_or_insert_with(|| Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+            .clone())
+    })
+    .await
+}
+
+/// Describes the first limit the task or the plan has reached.
+fn exceeded_limit(
+    budget: &PlanBudgetV1,
+    task: &StateTaskV2,
+    started_at: Option<&str>,
+    cost: Option<&ForgePlanCostV1>,
+) -> Option<String> {
+    let task_id = task.id.trim();
+    if let Some(max) = budget.max_task_attempts {
+        if task.attempts >= max {
+            return Some(format!(
+                "{BUDGET_NOTE_PREFIX} {task_id} reached {} check runs (max_task_attempts {max}).",
+                task.attempts
+            ));
+        }
+    }
+    if let (Some(max), Some(started_at)) = (budget.max_minutes, started_at) {
+        let elapsed_minutes = chrono::DateTime::parse_from_rfc3339(started_at)
+            .map(|started_at| (Utc::now() - started_at.with_timezone(&Utc)).num_minutes())
+            .unwrap_or_default();
+        if elapsed_minutes >= max {
+            return Some(format!(
+                "{BUDGET_NOTE_PREFIX} the run has lasted {elapsed_minutes} minutes \
+                 (max_minutes {max})."
+            ));
+        }
+    }
+    let cost = cost?;
+    if let Some(max) = budget.max_task_tokens {
+        let used = cost
+            .tasks
+            .iter()
+            .find(|entry| entry.task_id.trim() == task_id)
+            .map_or(0, |entry| entry.usage.total_tokens);
+        if used >= max {
+            return Some(format!(
+                "{BUDGET_NOTE_PREFIX} {task_id} used {used} tokens (max_task_tokens {max})."
+            ));
+        }
+    }
+    if let Some(max) = budget.max_total_tokens {
+        let used = cost.usage.total_tokens;
+        if used >= max {
+            return Some(format!(
+                "{BUDGET_NOTE_PREFIX} the plan used {used} tokens (max_total_tokens {max})."
+            ));
+        }
+    }
+    None
+}
+
+/// Marks the task `blocked` with the budget note, replacing the note of an earlier budget stop.
+/// Blocked by [`enforce_plan_budget`] rather than by a hook or by hand.
+pub(super) fn is_budget_blocked(task: &StateTaskV2) -> bool {
+    task.status.trim() == "blocked"
+        && task
+            .notes
+            .lines()
+            .any(|line| line.starts_with(BUDGET_NOTE_PREFIX))
+}
+
+async fn block_task(paths: &ForgeExecutionPaths, task_id: &str, note: &str) -> Result<(), String> {
+    update_task_state(paths, task_id, |task| {
+        task.status = "blocked".to_string();
+        let kept = task
+            .notes
+            .lines()
+            .filter(|line| !line.starts_with(BUDGET_NOTE_PREFIX))
+            .collect::<Vec<_>>()
+            .join("\n");
+        task.notes = if kept.trim().is_empty() {
+            note.to_string()
+        } else {
+            format!("{}\n{note}", kept.trim_end())
+        };
+        Ok(())
+    })
+    .await
+}
+
+/// Checks the plan's `budget` before the task gets its next prompt. When a limit is reached
+/// the task is blocked and the note is returned; raising the limit lets the task run again.
+pub(super) async fn enforce_plan_budget(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    paths: &ForgeExecutionPaths,
+    budget: Option<&PlanBudgetV1>,
+    state: &StateV2,
+    task_id: &str,
+) -> Result<Option<String>, String> {
+    let Some(budget) = budget else {
+        return Ok(None);
+    };
+    let task = state
+        .tasks
+        .iter()
+        .find(|task| task.id.trim() == task_id)
+        .ok_or_else(|| format!("state.json missing task entry for {task_id}"))?;
+    let started_at = match budget.max_minutes {
+        Some(_) => Some(run_started_at(paths, state).await?),
+        None => None,
+    };
+    let cost = if budget.limits_tokens() {
+        let sessions_roots = {
+            let workspaces = workspaces.lock().await;
+            resolve_sessions_roots(&workspaces, None)
+        };
+        let paths = paths.clone();
+        let cost = tokio::task::spawn_blocking(move || plan_cost(&paths, &sessions_roots))
+            .await
+            .map_err(|err| err.to_string())??;
+        Some(cost)
+    } else {
+        None
+    };
+
+    let Some(note) = exceeded_limit(budget, task, started_at.as_deref(), cost.as_ref()) else {
+        return Ok(None);
+    };
+    block_task(paths, task_id, &note).await?;
+    Ok(Some(note))
+}
@@ -0,0 +1,1400 @@
+//! Native implementation of the stock template hooks (`post-plan`, `pre-execute`, `post-step`).
+//!
+//! Mirrors `scripts/*.mjs` in the bundled templates so a template that declares
+//! `{ "interpreter": "builtin" }` runs without Node.js on the host.
+
+use super::*;
+
+const STATE_SUMMARY_MAX_LENGTH: usize = 300;
+const TASK_NOTES_MAX_LENGTH: usize = 2000;
+const PHASE_NOTES_MAX_LENGTH: usize = 800;
+const NOTES_TRUNCATED_SUFFIX: &str = " ... [truncated]";
+const TASK_STATUSES: [&str; 5] = ["pending", "in_progress", "completed", "blocked", "failed"];
+const PLAN_ID_PATTERN: &str = "/^[a-z0-9][a-z0-9-]*[a-z0-9]$/";
+const TASK_ID_PATTERN: &str = "/^task-[0-9]+$/";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct BuiltinPlan {
+    id: String,
+    #[serde(default)]
+    goal: String,
+    #[serde(default)]
+    context: BuiltinPlanContext,
+    #[serde(default)]
+    tasks: Vec<BuiltinPlanTask>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct BuiltinPlanContext {
+    #[serde(default)]
+    tech_stack: Vec<String>,
+    #[serde(default)]
+    constraints: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct BuiltinPlanTask {
+    id: String,
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    depends_on: Vec<String>,
+    #[serde(default)]
+    files: Vec<String>,
+    #[serde(default)]
+    verification: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct BuiltinTemplatePhase {
+    id: String,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    goal: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+struct BuiltinTemplate {
+    phases_raw: Vec<Value>,
+    phases: Vec<BuiltinTemplatePhase>,
+    execute_prompt: String,
+}
+
+pub(super) fn run_builtin_hook(
+    slot: ForgeHookSlot,
+    context: &ForgeHookContextV1,
+) -> Result<(), String> {
+    match slot {
+        ForgeHookSlot::PostPlan => run_post_plan(context),
+        ForgeHookSlot::PreExecute => run_pre_execute(context),
+        ForgeHookSlot::PostStep => run_post_step(context),
+    }
+}
+
+fn run_post_plan(context: &ForgeHookContextV1) -> Result<(), String> {
+    let plan_raw = read_json_value(Path::new(&context.plan_path))?;
+    let plan = parse_validated_plan(&plan_raw)?;
+    let template = load_builtin_template(Path::new(&context.template_root))?;
+
+    let state = build_initial_state(&plan, &template.phases);
+    {
+        let _lock = lock_state_blocking(Path::new(&context.workspace_root), &context.plan_id)?;
+        write_state_output(context, &state)?;
+    }
+    write_text_output(
+        Path::new(&context.generated_plan_md_path),
+        &plan_state_to_markdown(&plan, &state, &template.phases),
+    )?;
+
+    let progress_path = Path::new(&context.progress_path);
+    if !progress_path.exists() {
+        write_text_output(progress_path, "")?;
+    }
+
+    let prompt = render_execute_prompt(
+        &template,
+        &plan,
+        &state,
+        &read_progress_notes(context),
+        context,
+    );
+    write_text_output(Path::new(&context.generated_execute_prompt_path), &prompt)
+}
+
+fn run_pre_execute(context: &ForgeHookContextV1) -> Result<(), String> {
+    let template_root = Path::new(&context.template_root);
+    let manifest = read_json_value(&template_root.join("template.json"))?;
+    for rel in string_array(manifest.get("files")) {
+        let rel_path = validate_relative_file_path(&rel)?;
+        if !template_root.join(rel_path).is_file() {
+            return Err(format!("Template file missing: {rel}"));
+        }
+    }
+
+    let required_skills = string_array(
+        manifest
+            .get("entrypoints")
+            .and_then(|entrypoints| entrypoints.get("requiredSkills")),
+    );
+    for skill in required_skills {
+        let skill_path = Path::new(&context.workspace_root)
+            .join(".agent")
+            .join("skills")
+            .join(validate_relative_file_path(&skill)?)
+            .join("SKILL.md");
+        if !skill_path.is_file() {
+            return Err(format!(
+                "Missing required workspace skill: {}",
+                skill_path.display()
+            ));
+        }
+    }
+
+    if !Path::new(&context.plan_path).is_file() {
+        return Err(format!("Missing plan.json: {}", context.plan_path));
+    }
+    if !Path::new(&context.state_path).is_file() {
+        return Err(format!("Missing state.json: {}", context.state_path));
+    }
+    Ok(())
+}
+
+fn run_post_step(context: &ForgeHookContextV1) -> Result<(), String> {
+    let plan_raw = read_json_value(Path::new(&context.plan_path))?;
+    let plan = parse_validated_plan(&plan_raw)?;
+    let template = load_builtin_template(Path::new(&context.template_root))?;
+
+    let state = {
+        // Held while notes are trimmed so a concurrent update is not overwritten.
+        let _lock = lock_state_blocking(Path::new(&context.workspace_root), &context.plan_id)?;
+        let mut state_raw = read_json_value(Path::new(&context.state_path))?;
+        let notes_were_truncated = normalize_state_notes(&mut state_raw);
+        let errors = validate_state_against_plan(&state_raw, &plan_raw, &template.phases_raw);
+        if !errors.is_empty() {
+            return Err(format!("state.json is invalid:\n{}", errors.join("\n")));
+        }
+        let state = serde_json::from_value::<StateV2>(state_raw)
+            .map_err(|err| format!("state.json is invalid: {err}"))?;
+        if notes_were_truncated {
+            write_state_output(context, &state)?;
+        }
+        state
+    };
+
+    write_text_output(
+        Path::new(&context.generated_plan_md_path),
+        &plan_state_to_markdown(&plan, &state, &template.phases),
+    )?;
+    let prompt = render_execute_prompt(
+        &template,
+        &plan,
+        &state,
+        &read_progress_notes(context),
+        context,
+    );
+    write_text_output(Path::new(&context.generated_execute_prompt_path), &prompt)
+}
+
+fn read_json_value(path: &Path) -> Result<Value, String> {
+    let raw = fs::read_to_string(path)
+        .map_err(|err| format!("Unable to read {}: {err}", path.display()))?;
+    serde_json::from_str(&raw).map_err(|err| format!("Invalid JSON: {} ({err})", path.display()))
+}
+
+fn write_text_output(path: &Path, contents: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    fs::write(path, contents).map_err(|err| format!("Unable to write {}: {err}", path.display()))
+}
+
+fn write_state_output(context: &ForgeHookContextV1, state: &StateV2) -> Result<(), String> {
+    let raw = serde_json::to_string_pretty(state).map_err(|err| err.to_string())?;
+    write_file_atomically(Path::new(&context.state_path), &format!("{raw}\n"))
+}
+
+fn read_progress_notes(context: &ForgeHookContextV1) -> String {
+    fs::read_to_string(&context.progress_path).unwrap_or_default()
+}
+
+fn string_array(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn load_builtin_template(template_root: &Path) -> Result<BuiltinTemplate, String> {
+    let manifest = read_template_manifest(template_root)?;
+    let phases_path =
+        template_root.join(validate_relative_file_path(&manifest.entrypoints.phases)?);
+    let prompt_path = template_root.join(validate_relative_file_path(
+        &manifest.entrypoints.execute_prompt,
+    )?);
+
+    let phases_raw = read_json_value(&phases_path)?
+        .get("phases")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    let phases = phases_raw
+        .iter()
+        .filter_map(|phase| serde_json::from_value::<BuiltinTemplatePhase>(phase.clone()).ok())
+        .collect();
+    let execute_prompt = fs::read_to_string(&prompt_path)
+        .map_err(|err| format!("Unable to read {}: {err}", prompt_path.display()))?;
+
+    Ok(BuiltinTemplate {
+        phases_raw,
+        phases,
+        execute_prompt,
+    })
+}
+
+fn build_initial_state(plan: &BuiltinPlan, phases: &[BuiltinTemplatePhase]) -> StateV2 {
+    StateV2 {
+        schema: CURRENT_STATE_SCHEMA.to_string(),
+        plan_id: plan.id.clone(),
+        iteration: 0,
+        summary: String::new(),
+        tasks: plan
+            .tasks
+            .iter()
+            .map(|task| StateTaskV2 {
+                id: task.id.clone(),
+                status: "pending".to_string(),
+                attempts: 0,
+                notes: String::new(),
+                commit_sha: None,
+                phases: phases
+                    .iter()
+                    .map(|phase| StatePhaseV2 {
+                        id: phase.id.clone(),
+                        status: "pending".to_string(),
+                        attempts: 0,
+                        notes: String::new(),
+                        commit_sha: None,
+                        approval: None,
+                        threads: Vec::new(),
+                    })
+                    .collect(),
+                worktree: None,
+            })
+            .collect(),
+        run_started_at: None,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Validation (mirrors scripts/lib/plan.mjs)
+// ---------------------------------------------------------------------------
+
+fn push_error(errors: &mut Vec<String>, path: &str, message: &str) {
+    errors.push(format!("{path}: {message}"));
+}
+
+fn expect_no_extra_keys(errors: &mut Vec<String>, value: &Value, allowed: &[&str], path: &str) {
+    let Some(obj) = value.as_object() else {
+        return;
+    };
+    for key in obj.keys() {
+        if !allowed.contains(&key.as_str()) {
+            push_error(errors, path, &format!("Unexpected property: {key}"));
+        }
+    }
+}
+
+fn expect_string<'a>(
+    errors: &mut Vec<String>,
+    value: Option<&'a Value>,
+    path: &str,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+) -> Option<&'a str> {
+    let Some(text) = value.and_then(Value::as_str) else {
+        push_error(errors, path, "Expected string");
+        return None;
+    };
+    let length = text.chars().count();
+    if let Some(min) = min_length.filter(|min| length < *min) {
+        push_error(errors, path, &format!("Too short (minLength {min})"));
+    }
+    if let Some(max) = max_length.filter(|max| length > *max) {
+        push_error(errors, path, &format!("Too long (maxLength {max})"));
+    }
+    Some(text)
+}
+
+fn expect_array<'a>(
+    errors: &mut Vec<String>,
+    value: Option<&'a Value>,
+    path: &str,
+    min_items: Option<usize>,
+) -> Option<&'a Vec<Value>> {
+    let Some(items) = value.and_then(Value::as_array) else {
+        push_error(errors, path, "Expected array");
+        return None;
+    };
+    if let Some(min) = min_items.filter(|min| items.len() < *min) {
+        push_error(errors, path, &format!("Too few items (minItems {min})"));
+    }
+    Some(items)
+}
+
+fn expect_array_of_strings(
+    errors: &mut Vec<String>,
+    value: Option<&Value>,
+    path: &str,
+    min_items: Option<usize>,
+) {
+    let Some(items) = expect_array(errors, value, path, min_items) else {
+        return;
+    };
+    for (index, item) in items.iter().enumerate() {
+        if !item.is_string() {
+            push_error(errors, &format!("{path}[{index}]"), "Expected string");
+        }
+    }
+}
+
+fn expect_non_negative_integer(errors: &mut Vec<String>, value: Option<&Value>, path: &str) {
+    if value.is_none_or(|value| value.as_u64().is_none()) {
+        push_error(errors, path, "Expected integer >= 0");
+    }
+}
+
+fn matches_plan_id_pattern(id: &str) -> bool {
+    let (Some(first), Some(last)) = (id.chars().next(), id.chars().last()) else {
+        return false;
+    };
+    id.len() >= 2
+        && is_ascii_lower_alnum(first)
+        && is_ascii_lower_alnum(last)
+        && id.chars().all(|ch| is_ascii_lower_alnum(ch) || ch == '-')
+}
+
+fn parse_task_number(id: &str) -> Option<u64> {
+    let digits = id.strip_prefix("task-")?;
+    if digits.is_empty() || !digits.chars().all(|ch| ch.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse::<u64>().ok()
+}
+
+fn validate_task_dag(
+    errors: &mut Vec<String>,
+    order: &[String],
+    deps_by_id: &HashMap<String, Vec<String>>,
+) {
+    fn visit(
+        id: &str,
+        deps_by_id: &HashMap<String, Vec<String>>,
+        visiting: &mut Vec<String>,
+        visited: &mut Vec<String>,
+        errors: &mut Vec<String>,
+    ) {
+        if visited.iter().any(|entry| entry == id) {
+            return;
+        }
+        if visiting.iter().any(|entry| entry == id) {
+            push_error(errors, "tasks", &format!("Cycle detected at {id}"));
+            return;
+        }
+        visiting.push(id.to_string());
+        for dep in deps_by_id.get(id).into_iter().flatten() {
+            if deps_by_id.contains_key(dep) {
+                visit(dep, deps_by_id, visiting, visited, errors);
+            }
+        }
+        visiting.retain(|entry| entry != id);
+        visited.push(id.to_string());
+    }
+
+    let mut visiting = Vec::new();
+    let mut visited = Vec::new();
+    for id in order {
+        visit(id, deps_by_id, &mut visiting, &mut visited, errors);
+    }
+}
+
+fn validate_plan_value(plan: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    if !plan.is_object() {
+        push_error(&mut errors, "plan", "Expected object");
+        return errors;
+    }
+
+    expect_no_extra_keys(
+        &mut errors,
+        plan,
+        &["$schema", "id", "title", "goal", "context", "tasks", "budget"],
+        "plan",
+    );
+    if plan.get("$schema").and_then(Value::as_str) != Some("plan-v1") {
+        push_error(&mut errors, "plan.$schema", "Expected \"plan-v1\"");
+    }
+    if let Some(id) = expect_string(&mut errors, plan.get("id"), "plan.id", None, Some(64)) {
+        if !matches_plan_id_pattern(id) {
+            push_error(
+                &mut errors,
+                "plan.id",
+                &format!("Does not match pattern {PLAN_ID_PATTERN}"),
+            );
+        }
+    }
+    if plan.get("title").is_some_and(|title| !title.is_null()) {
+        expect_string(
+            &mut errors,
+            plan.get("title"),
+            "plan.title",
+            Some(3),
+            Some(80),
+        );
+    }
+    expect_string(
+        &mut errors,
+        plan.get("goal"),
+        "plan.goal",
+        Some(10),
+        Some(500),
+    );
+
+    match plan.get("context").filter(|context| context.is_object()) {
+        None => push_error(&mut errors, "plan.context", "Expected object"),
+        Some(context) => {
+            expect_no_extra_keys(
+                &mut errors,
+                context,
+                &["tech_stack", "constraints", "references"],
+                "plan.context",
+            );
+            expect_array_of_strings(
+                &mut errors,
+                context.get("tech_stack"),
+                "plan.context.tech_stack",
+                Some(1),
+            );
+            expect_array_of_strings(
+                &mut errors,
+                context.get("constraints"),
+                "plan.context.constraints",
+                None,
+            );
+            if context
+                .get("references")
+                .is_some_and(|refs| !refs.is_null())
+            {
+                if let Some(references) = expect_array(
+                    &mut errors,
+                    context.get("references"),
+                    "plan.context.references",
+                    None,
+                ) {
+                    for (index, reference) in references.iter().enumerate() {
+                        let ref_path = format!("plan.context.references[{index}]");
+                        if !reference.is_object() {
+                            push_error(&mut errors, &ref_path, "Expected object");
+                            continue;
+                        }
+                        expect_no_extra_keys(
+                            &mut errors,
+                            reference,
+                            &["path", "description"],
+                            &ref_path,
+                        );
+                        expect_string(
+                            &mut errors,
+                            reference.get("path"),
+                            &format!("{ref_path}.path"),
+                            None,
+                            None,
+                        );
+                        expect_string(
+                            &mut errors,
+                            reference.get("description"),
+                            &format!("{ref_path}.description"),
+                            None,
+                            None,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(budget) = plan.get("budget") {
+        validate_plan_budget(&mut errors, budget);
+    }
+
+    let mut task_order: Vec<String> = Vec::new();
+    let mut deps_by_id: HashMap<String, Vec<String>> = HashMap::new();
+    let mut has_entry_point = false;
+
+    if let Some(tasks) = expect_array(&mut errors, plan.get("tasks"), "plan.tasks", Some(1)) {
+        for (index, task) in tasks.iter().enumerate() {
+            let task_path = format!("plan.tasks[{index}]");
+            if !task.is_object() {
+                push_error(&mut errors, &task_path, "Expected object");
+                continue;
+            }
+            expect_no_extra_keys(
+                &mut errors,
+                task,
+                &[
+                    "id",
+                    "name",
+                    "description",
+                    "depends_on",
+                    "files",
+                    "verification",
+                ],
+                &task_path,
+            );
+
+            let id_path = format!("{task_path}.id");
+            let task_id = expect_string(&mut errors, task.get("id"), &id_path, None, None);
+            if task_id.is_some_and(|id| parse_task_number(id).is_none()) {
+                push_error(
+                    &mut errors,
+                    &id_path,
+                    &format!("Does not match pattern {TASK_ID_PATTERN}"),
+                );
+            }
+            expect_string(
+                &mut errors,
+                task.get("name"),
+                &format!("{task_path}.name"),
+                None,
+                Some(80),
+            );
+            expect_string(
+                &mut errors,
+                task.get("description"),
+                &format!("{task_path}.description"),
+                Some(20),
+                None,
+            );
+            expect_array_of_strings(
+                &mut errors,
+                task.get("depends_on"),
+                &format!("{task_path}.depends_on"),
+                None,
+            );
+            expect_array_of_strings(
+                &mut errors,
+                task.get("files"),
+                &format!("{task_path}.files"),
+                Some(1),
+            );
+            expect_array_of_strings(
+                &mut errors,
+                task.get("verification"),
+                &format!("{task_path}.verification"),
+                Some(1),
+            );
+
+            let depends_on = string_array(task.get("depends_on"));
+            if task
+                .get("depends_on")
+                .and_then(Value::as_array)
+                .is_some_and(|deps| deps.is_empty())
+            {
+                has_entry_point = true;
+            }
+
+            let Some(task_id) = task_id else {
+                continue;
+            };
+            if deps_by_id.contains_key(task_id) {
+                push_error(
+                    &mut errors,
+                    &id_path,
+                    &format!("Duplicate task id: {task_id}"),
+                );
+            } else {
+                task_order.push(task_id.to_string());
+                deps_by_id.insert(task_id.to_string(), depends_on);
+            }
+            let expected_id = format!("task-{}", index + 1);
+            match parse_task_number(task_id) {
+                Some(number) if number >= 1 => {
+                    if task_id != expected_id {
+                        push_error(
+                            &mut errors,
+                            &id_path,
+                            &format!("Task ids must match array order (expected {expected_id})"),
+                        );
+                    }
+                }
+                _ => push_error(
+                    &mut errors,
+                    &id_path,
+                    "Task id must be task-<n> with n >= 1",
+                ),
+            }
+        }
+    }
+
+    if !has_entry_point {
+        push_error(
+            &mut errors,
+            "plan.tasks",
+            "At least one task must have depends_on: []",
+        );
+    }
+
+    for id in &task_order {
+        for dep in deps_by_id.get(id).into_iter().flatten() {
+            let dep_path = format!("task:{id}.depends_on");
+            if dep == id {
+                push_error(&mut errors, &dep_path, "Task cannot depend on itself");
+            } else if !deps_by_id.contains_key(dep) {
+                push_error(
+                    &mut errors,
+                    &dep_path,
+                    &format!("Unknown dependency: {dep}"),
+                );
+            }
+        }
+    }
+
+    validate_task_dag(&mut errors, &task_order, &deps_by_id);
+    errors
+}
+
+const PLAN_BUDGET_KEYS: [&str; 4] = [
+    "max_task_tokens",
+    "max_total_tokens",
+    "max_task_attempts",
+    "max_minutes",
+];
+
+fn validate_plan_budget(errors: &mut Vec<String>, budget: &Value) {
+    if !budget.is_object() {
+        push_error(errors, "plan.budget", "Expected object");
+        return;
+    }
+    expect_no_extra_keys(errors, budget, &PLAN_BUDGET_KEYS, "plan.budget");
+    for key in PLAN_BUDGET_KEYS {
+        if budget
+            .get(key)
+            .is_some_and(|value| value.as_u64().is_none_or(|value| value < 1))
+        {
+            push_error(errors, &format!("plan.budget.{key}"), "Expected integer >= 1");
+        }
+    }
+}
+
+fn parse_validated_plan(plan: &Value) -> Result<BuiltinPlan, String> {
+    let errors = validate_plan_value(plan);
+    if !errors.is_empty() {
+        return Err(format!("plan.json is invalid:\n{}", errors.join("\n")));
+    }
+    serde_json::from_value::<BuiltinPlan>(plan.clone())
+        .map_err(|err| format!("plan.json is invalid: {err}"))
+}
+
+fn validate_template_phase_list(errors: &mut Vec<String>, phases: &[Value]) {
+    if phases.is_empty() {
+        push_error(errors, "templatePhases", "Too few items (minItems 1)");
+    }
+    for (index, phase) in phases.iter().enumerate() {
+        let phase_path = format!("templatePhases[{index}]");
+        if !phase.is_object() {
+            push_error(errors, &phase_path, "Expected object");
+            continue;
+        }
+        expect_no_extra_keys(
+            errors,
+            phase,
+            &[
+                "id",
+                "title",
+                "order",
+                "iconId",
+                "goal",
+                "description",
+                "checks",
+                "artifacts",
+                "maxAttempts",
+                "backoffSeconds",
+                "kind",
+            ],
+            &phase_path,
+        );
+        expect_string(
+            errors,
+            phase.get("id"),
+            &format!("{phase_path}.id"),
+            Some(1),
+            Some(64),
+        );
+        expect_string(
+            errors,
+            phase.get("title"),
+            &format!("{phase_path}.title"),
+            Some(1),
+            Some(80),
+        );
+        if let Some(kind) = phase.get("kind") {
+            if !matches!(kind.as_str(), Some("agent" | MANUAL_APPROVAL_PHASE_KIND)) {
+                push_error(errors, &format!("{phase_path}.kind"), "Invalid kind");
+            }
+        }
+        if let Some(max_attempts) = phase.get("maxAttempts") {
+            if max_attempts.as_u64().is_none_or(|value| value < 1) {
+                push_error(errors, &format!("{phase_path}.maxAttempts"), "Expected integer >= 1");
+            }
+        }
+        if let Some(backoff_seconds) = phase.get("backoffSeconds") {
+            expect_non_negative_integer(
+                errors,
+                Some(backoff_seconds),
+                &format!("{phase_path}.backoffSeconds"),
+            );
+        }
+    }
+}
+
+fn truncate_notes(value: &mut Value, max_length: usize) -> bool {
+    let Some(text) = value.as_str() else {
+        return false;
+    };
+    if text.chars().count() <= max_length {
+        return false;
+    }
+    let suffix = if max_length > NOTES_TRUNCATED_SUFFIX.len() {
+        NOTES_TRUNCATED_SUFFIX
+    } else {
+        ""
+    };
+    let keep = max_length.saturating_sub(suffix.len());
+    let truncated: String = text.chars().take(keep).collect();
+    *value = Value::String(format!("{truncated}{suffix}"));
+    true
+}
+
+fn normalize_state_notes(state: &mut Value) -> bool {
+    let mut changed = false;
+    let Some(tasks) = state.get_mut("tasks").and_then(Value::as_array_mut) else {
+        return changed;
+    };
+    for task in tasks.iter_mut().filter(|task| task.is_object()) {
+        if let Some(notes) = task.get_mut("notes") {
+            changed |= truncate_notes(notes, TASK_NOTES_MAX_LENGTH);
+        }
+        let Some(phases) = task.get_mut("phases").and_then(Value::as_array_mut) else {
+            continue;
+        };
+        for phase in phases.iter_mut() {
+            if let Some(notes) = phase.get_mut("notes") {
+                changed |= truncate_notes(notes, PHASE_NOTES_MAX_LENGTH);
+            }
+        }
+    }
+    changed
+}
+
+fn validate_task_status(errors: &mut Vec<String>, value: Option<&Value>, path: &str) {
+    let valid = value
+        .and_then(Value::as_str)
+        .is_some_and(|status| TASK_STATUSES.contains(&status));
+    if !valid {
+        push_error(errors, path, "Invalid status");
+    }
+}
+
+fn validate_state_worktree(errors: &mut Vec<String>, worktree: &Value, path: &str) {
+    if !worktree.is_object() {
+        push_error(errors, path, "Expected object");
+        return;
+    }
+    expect_no_extra_keys(
+        errors,
+        worktree,
+        &["workspace_id", "path", "branch", "base_sha"],
+        path,
+    );
+    for field in ["workspace_id", "path", "branch"] {
+        expect_string(
+            errors,
+            worktree.get(field),
+            &format!("{path}.{field}"),
+            Some(1),
+            None,
+        );
+    }
+    if worktree
+        .get("base_sha")
+        .is_some_and(|sha| !sha.is_null() && !sha.is_string())
+    {
+        push_error(
+            errors,
+            &format!("{path}.base_sha"),
+            "Expected string or null",
+        );
+    }
+}
+
+fn validate_state_against_plan(
+    state: &Value,
+    plan: &Value,
+    template_phases: &[Value],
+) -> Vec<String> {
+    let mut errors = Vec::new();
+    if !state.is_object() {
+        push_error(&mut errors, "state", "Expected object");
+        return errors;
+    }
+
+    expect_no_extra_keys(
+        &mut errors,
+        state,
+        &["$schema", "plan_id", "iteration", "summary", "tasks", "run_started_at"],
+        "state",
+    );
+    if state.get("$schema").and_then(Value::as_str) != Some(CURRENT_STATE_SCHEMA) {
+        push_error(&mut errors, "state.$schema", "Expected \"state-v2\"");
+    }
+    let plan_id = plan.get("id").and_then(Value::as_str).unwrap_or("");
+    if state.get("plan_id").and_then(Value::as_str) != Some(plan_id) {
+        push_error(&mut errors, "state.plan_id", &format!("Expected {plan_id}"));
+    }
+    expect_non_negative_integer(&mut errors, state.get("iteration"), "state.iteration");
+    expect_string(
+        &mut errors,
+        state.get("summary"),
+        "state.summary",
+        None,
+        Some(STATE_SUMMARY_MAX_LENGTH),
+    );
+    if state.get("run_started_at").is_some() {
+        expect_string(
+            &mut errors,
+            state.get("run_started_at"),
+            "state.run_started_at",
+            Some(1),
+            None,
+        );
+    }
+
+    let state_tasks = expect_array(&mut errors, state.get("tasks"), "state.tasks", Some(1));
+    let plan_tasks = plan
+        .get("tasks")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    if state_tasks.is_some_and(|tasks| tasks.len() != plan_tasks.len()) {
+        push_error(&mut errors, "state.tasks", "Must match plan.tasks length");
+    }
+
+    validate_template_phase_list(&mut errors, template_phases);
+    let expected_phase_ids: Vec<&str> = template_phases
+        .iter()
+        .filter_map(|phase| phase.get("id").and_then(Value::as_str))
+        .filter(|id| !id.is_empty())
+        .collect();
+
+    for (index, entry) in state_tasks.into_iter().flatten().enumerate() {
+        let entry_path = format!("state.tasks[{index}]");
+        if !entry.is_object() {
+            push_error(&mut errors, &entry_path, "Expected object");
+            continue;
+        }
+        expect_no_extra_keys(
+            &mut errors,
+            entry,
+            &[
+                "id",
+                "status",
+                "attempts",
+                "notes",
+                "commit_sha",
+                "phases",
+                "worktree",
+            ],
+            &entry_path,
+        );
+        let entry_id = expect_string(
+            &mut errors,
+            entry.get("id"),
+            &format!("{entry_path}.id"),
+            None,
+            None,
+        );
+        if entry_id.is_some_and(|id| parse_task_number(id).is_none()) {
+            push_error(
+                &mut errors,
+                &format!("{entry_path}.id"),
+                &format!("Does not match pattern {TASK_ID_PATTERN}"),
+            );
+        }
+        validate_task_status(
+            &mut errors,
+            entry.get("status"),
+            &format!("{entry_path}.status"),
+        );
+        expect_non_negative_integer(
+            &mut errors,
+            entry.get("attempts"),
+            &format!("{entry_path}.attempts"),
+        );
+        expect_string(
+            &mut errors,
+            entry.get("notes"),
+            &format!("{entry_path}.notes"),
+            None,
+            Some(TASK_NOTES_MAX_LENGTH),
+        );
+        if entry
+            .get("commit_sha")
+            .is_some_and(|sha| !sha.is_null() && !sha.is_string())
+        {
+            push_error(
+                &mut errors,
+                &format!("{entry_path}.commit_sha"),
+                "Expected string or null",
+            );
+        }
+        if let Some(worktree) = entry.get("worktree").filter(|worktree| !worktree.is_null()) {
+            validate_state_worktree(&mut errors, worktree, &format!("{entry_path}.worktree"));
+        }
+
+        let expected_task_id = plan_tasks
+            .get(index)
+            .and_then(|task| task.get("id"))
+            .and_then(Value::as_str);
+        if let Some(expected) = expected_task_id {
+            if entry_id != Some(expected) {
+                push_error(
+                    &mut errors,
+                    &entry_path,
+                    &format!("Task id mismatch at index {index} (expected {expected})"),
+                );
+            }
+        }
+
+        let phases_path = format!("{entry_path}.phases");
+        let Some(phases) = expect_array(&mut errors, entry.get("phases"), &phases_path, Some(1))
+        else {
+            continue;
+        };
+        if !expected_phase_ids.is_empty() && phases.len() != expected_phase_ids.len() {
+            push_error(
+                &mut errors,
+                &phases_path,
+                "Must match templatePhases length",
+            );
+        }
+        for (phase_index, phase) in phases.iter().enumerate() {
+            let phase_path = format!("{phases_path}[{phase_index}]");
+            if !phase.is_object() {
+                push_error(&mut errors, &phase_path, "Expected object");
+                continue;
+            }
+            expect_no_extra_keys(
+                &mut errors,
+                phase,
+                &[
+                    "id",
+                    "status",
+                    "attempts",
+                    "notes",
+                    "commit_sha",
+                    "approval",
+                    "threads",
+                ],
+                &phase_path,
+            );
+            let phase_id = expect_string(
+                &mut errors,
+                phase.get("id"),
+                &format!("{phase_path}.id"),
+                Some(1),
+                Some(64),
+            );
+            if let Some(expected) = expected_phase_ids.get(phase_index) {
+                if phase_id != Some(*expected) {
+                    push_error(
+                        &mut errors,
+                        &format!("{phase_path}.id"),
+                        &format!("Phase id mismatch at index {phase_index} (expected {expected})"),
+                    );
+                }
+            }
+            validate_task_status(
+                &mut errors,
+                phase.get("status"),
+                &format!("{phase_path}.status"),
+            );
+            expect_non_negative_integer(
+                &mut errors,
+                phase.get("attempts"),
+                &format!("{phase_path}.attempts"),
+            );
+            expect_string(
+                &mut errors,
+                phase.get("notes"),
+                &format!("{phase_path}.notes"),
+                None,
+                Some(PHASE_NOTES_MAX_LENGTH),
+            );
+            if phase
+                .get("commit_sha")
+                .is_some_and(|sha| !sha.is_null() && !sha.is_string())
+            {
+                push_error(
+                    &mut errors,
+                    &format!("{phase_path}.commit_sha"),
+                    "Expected string or null",
+                );
+            }
+            if let Some(approval) = phase.get("approval") {
+                validate_state_approval(&mut errors, approval, &format!("{phase_path}.approval"));
+            }
+            if let Some(threads) = phase.get("threads") {
+                validate_state_threads(&mut errors, threads, &format!("{phase_path}.threads"));
+            }
+        }
+    }
+
+    errors
+}
+
+fn validate_state_approval(errors: &mut Vec<String>, approval: &Value, path: &str) {
+    if !approval.is_object() {
+        push_error(errors, path, "Expected object");
+        return;
+    }
+    expect_no_extra_keys(
+        errors,
+        approval,
+        &["decision", "reviewer", "notes", "decided_at"],
+        path,
+    );
+    let decision = approval.get("decision").and_then(Value::as_str);
+    if !matches!(decision, Some("approved" | "rejected")) {
+        push_error(errors, &format!("{path}.decision"), "Invalid decision");
+    }
+    for (field, min_length) in [("reviewer", Some(1)), ("notes", None), ("decided_at", Some(1))] {
+        expect_string(
+            errors,
+            approval.get(field),
+            &format!("{path}.{field}"),
+            min_length,
+            None,
+        );
+    }
+}
+
+fn validate_state_threads(errors: &mut Vec<String>, threads: &Value, path: &str) {
+    let Some(threads) = threads.as_array() else {
+        push_error(errors, path, "Expected array");
+        return;
+    };
+    for (index, thread) in threads.iter().enumerate() {
+        let thread_path = format!("{path}[{index}]");
+        if !thread.is_object() {
+            push_error(errors, &thread_path, "Expected object");
+            continue;
+        }
+        expect_no_extra_keys(errors, thread, &["thread_id", "checked_at"], &thread_path);
+        for field in ["thread_id", "checked_at"] {
+            expect_string(
+                errors,
+                thread.get(field),
+                &format!("{thread_path}.{field}"),
+                Some(1),
+                None,
+            );
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Rendering (mirrors scripts/lib/markdown.mjs and scripts/lib/execute.mjs)
+// ---------------------------------------------------------------------------
+
+fn status_mark(status: &str) -> &'static str {
+    match status.trim() {
+        "completed" => "[x]",
+        "in_progress" => "[*]",
+        "blocked" => "[!]",
+        "failed" => "[~]",
+        _ => "[ ]",
+    }
+}
+
+fn format_bullet_list(items: &[String]) -> String {
+    items
+        .iter()
+        .map(|item| format!("- {item}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn render_template(template_text: &str, values: &[(&str, String)]) -> String {
+    let mut output = template_text.to_string();
+    for (key, value) in values {
+        output = output.replace(&format!("{{{{{key}}}}}"), value);
+    }
+    output
+}
+
+fn plan_state_to_markdown(
+    plan: &BuiltinPlan,
+    state: &StateV2,
+    phases: &[BuiltinTemplatePhase],
+) -> String {
+    let state_by_id = map_state_tasks(state);
+    let mut lines: Vec<String> = vec![
+        format!("# Plan: {}", plan.id),
+        String::new(),
+        "## Goal".to_string(),
+        String::new(),
+        plan.goal.clone(),
+        String::new(),
+        "## Execution State".to_string(),
+        String::new(),
+        format!("- Iteration: {}", state.iteration),
+        format!("- Summary: {}", state.summary.trim()),
+        String::new(),
+        "## Tasks".to_string(),
+        String::new(),
+    ];
+
+    for task in &plan.tasks {
+        let task_state = state_by_id.get(task.id.as_str());
+        let status = task_state
+            .map(|entry| entry.status.as_str())
+            .unwrap_or("pending");
+        lines.push(format!(
+            "### {} {}: {}",
+            status_mark(status),
+            task.id,
+            task.name
+        ));
+        lines.push(String::new());
+
+        let notes = task_state.map(|entry| entry.notes.trim()).unwrap_or("");
+        if !notes.is_empty() {
+            let note_lines = notes
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect::<Vec<_>>();
+            lines.push("Notes:".to_string());
+            lines.push(format_bullet_list(&note_lines));
+            lines.push(String::new());
+        }
+
+        if !phases.is_empty() {
+            lines.push("Phases:".to_string());
+            for phase in phases {
+                let phase_status = task_state
+                    .and_then(|entry| entry.phases.iter().find(|p| p.id == phase.id))
+                    .map(|p| p.status.as_str())
+                    .unwrap_or("pending");
+                let title = phase.title.as_deref().unwrap_or(&phase.id);
+                lines.push(format!(
+                    "- {} {}: {title}",
+                    status_mark(phase_status),
+                    phase.id
+                ));
+            }
+            lines.push(String::new());
+        }
+    }
+
+    format!("{}\n", lines.join("\n").trim_end())
+}
+
+/// The templates treat a task as done once every phase is completed, independent of task status.
+fn are_phases_done(task: &StateTaskV2) -> bool {
+    !task.phases.is_empty()
+        && task
+            .phases
+            .iter()
+            .all(|phase| is_completed_status(&phase.status))
+}
+
+fn find_prompt_task<'a>(
+    plan: &'a BuiltinPlan,
+    state: &StateV2,
+    focus_task_id: Option<&str>,
+) -> Option<&'a BuiltinPlanTask> {
+    let state_by_id = map_state_tasks(state);
+    if let Some(focus) = focus_task_id.map(str::trim).filter(|id| !id.is_empty()) {
+        if let Some(task) = plan
+            .tasks
+            .iter()
+            .find(|task| task.id == focus && state_by_id.contains_key(task.id.as_str()))
+        {
+            return Some(task);
+        }
+    }
+
+    plan.tasks.iter().find(|task| {
+        let Some(task_state) = state_by_id.get(task.id.as_str()) else {
+            return false;
+        };
+        if are_phases_done(task_state) || is_task_retry_exhausted(task_state) {
+            return false;
+        }
+        task.depends_on.iter().all(|dep| {
+            state_by_id
+                .get(dep.as_str())
+                .is_some_and(|dep_state| are_phases_done(dep_state) && dep_state.worktree.is_none())
+        })
+    })
+}
+
+fn render_execute_prompt(
+    template: &BuiltinTemplate,
+    plan: &BuiltinPlan,
+    state: &StateV2,
+    progress_notes: &str,
+    context: &ForgeHookContextV1,
+) -> String {
+    let state_by_id = map_state_tasks(state);
+    let mut values: Vec<(&str, String)> = vec![
+        ("plan_id", plan.id.clone()),
+        ("goal", plan.goal.clone()),
+        ("tech_stack", plan.context.tech_stack.join(", ")),
+        ("constraints", format_bullet_list(&plan.context.constraints)),
+        ("iteration", state.iteration.to_string()),
+        ("summary", state.summary.clone()),
+        ("task_list", build_task_list(plan, &state_by_id)),
+        ("progress_notes", progress_notes.to_string()),
+    ];
+
+    let Some(current) = find_prompt_task(plan, state, context.task_id.as_deref()) else {
+        values.extend([
+            ("current_task_id", "(none)".to_string()),
+            ("current_task_name", "All tasks completed".to_string()),
+            (
+                "current_task_description",
+                "No runnable pending task found. The plan may be complete.".to_string(),
+            ),
+            ("current_phase_id", "(none)".to_string()),
+            ("current_phase_title", String::new()),
+            ("current_phase_goal", String::new()),
+            ("current_phase_description", String::new()),
+            ("current_task_files", String::new()),
+            ("current_task_verification", String::new()),
+            ("current_task_attempts", "0".to_string()),
+            ("current_task_previous_notes", String::new()),
+            ("dependency_notes", String::new()),
+            ("date", context.today_iso.clone()),
+        ]);
+        return render_template(&template.execute_prompt, &values);
+    };
+
+    let task_state = state_by_id.get(current.id.as_str()).copied();
+    let phase = task_state.and_then(|entry| {
+        entry
+            .phases
+            .iter()
+            .find(|phase| !is_completed_status(&phase.status))
+    });
+    let phase_id = phase
+        .map(|phase| phase.id.as_str())
+        .unwrap_or("implementation");
+    let phase_meta = template.phases.iter().find(|meta| meta.id == phase_id);
+
+    let mut notes_blocks = Vec::new();
+    let task_notes = task_state.map(|entry| entry.notes.trim()).unwrap_or("");
+    if !task_notes.is_empty() {
+        notes_blocks.push(format!("Task notes:\n{task_notes}"));
+    }
+    let phase_notes = phase.map(|phase| phase.notes.trim()).unwrap_or("");
+    if !phase_notes.is_empty() {
+        notes_blocks.push(format!("Phase notes:\n{phase_notes}"));
+    }
+    let previous_notes = if notes_blocks.is_empty() {
+        String::new()
+    } else {
+        format!("\nPrevious notes:\n{}", notes_blocks.join("\n\n"))
+    };
+
+    values.extend([
+        ("current_task_id", current.id.clone()),
+        ("current_task_name", current.name.clone()),
+        ("current_task_description", current.description.clone()),
+        ("current_phase_id", phase_id.to_string()),
+        (
+            "current_phase_title",
+            phase_meta
+                .and_then(|meta| meta.title.clone())
+                .unwrap_or_else(|| phase_id.to_string()),
+        ),
+        (
+            "current_phase_goal",
+            phase_meta
+                .and_then(|meta| meta.goal.clone())
+                .unwrap_or_default(),
+        ),
+        (
+            "current_phase_description",
+            phase_meta
+                .and_then(|meta| meta.description.clone())
+                .unwrap_or_default(),
+        ),
+        (
+            "current_task_files",
+            format!("\n{}", format_bullet_list(&current.files)),
+        ),
+        (
+            "current_task_verification",
+            format_bullet_list(&current.verification),
+        ),
+        (
+            "current_task_attempts",
+            phase.map(|phase| phase.attempts).unwrap_or(0).to_string(),
+        ),
+        ("current_task_previous_notes", previous_notes),
+        (
+            "dependency_notes",
+            dependency_notes_for_task(current, &state_by_id),
+        ),
+        ("date", context.today_iso.clone()),
+    ]);
+
+    render_template(&template.execute_prompt, &values)
+}
+
+fn build_task_list(plan: &BuiltinPlan, state_by_id: &HashMap<&str, &StateTaskV2>) -> String {
+    plan.tasks
+        .iter()
+        .map(|task| {
+            let task_state = state_by_id.get(task.id.as_str());
+            let status = task_state
+                .map(|entry| entry.status.as_str())
+                .unwrap_or("pending");
+            let notes = task_state.map(|entry| entry.notes.trim()).unwrap_or("");
+            let note_suffix = if notes.is_empty() {
+                String::new()
+            } else {
+                format!(" - {}", notes.chars().take(120).collect::<String>())
+            };
+            format!(
+                "{} {} {}{note_suffix}",
+                status_mark(status),
+                task.id,
+                task.name
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn dependency_notes_for_task(
+    task: &BuiltinPlanTask,
+    state_by_id: &HashMap<&str, &StateTaskV2>,
+) -> String {
+    if task.depends_on.is_empty() {
+        return "(none)".to_string();
+    }
+    task.depends_on
+        .iter()
+        .map(|dep| {
+            let notes = state_by_id
+                .get(dep.as_str())
+                .map(|entry| entry.notes.trim())
+                .filter(|notes| !notes.is_empty())
+                .unwrap_or("(no notes)");
+            format!("- {dep}: {notes}")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
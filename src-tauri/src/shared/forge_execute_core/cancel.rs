@@ -0,0 +1,124 @@
+use super::*;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+type ForgeCancelKey = (String, String);
+
+/// Cancel signals for the Forge checks and hooks currently running, keyed by workspace and plan.
+#[derive(Default)]
+pub(crate) struct ForgeExecutionCancels {
+    running: Mutex<HashMap<ForgeCancelKey, Vec<Arc<ForgeCancelToken>>>>,
+}
+
+#[derive(Default)]
+pub(crate) struct ForgeCancelToken {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+/// Keeps a token registered while a run is in flight and unregisters it on drop.
+pub(crate) struct ForgeCancelRegistration<'a> {
+    cancels: &'a ForgeExecutionCancels,
+    key: ForgeCancelKey,
+    token: Arc<ForgeCancelToken>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeCancelExecutionResultV1 {
+    pub(crate) cancelled_runs: usize,
+}
+
+fn cancel_key(workspace_id: &str, plan_id: &str) -> ForgeCancelKey {
+    (workspace_id.trim().to_string(), plan_id.trim().to_string())
+}
+
+impl ForgeCancelToken {
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    pub(super) fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        self.notify.notify_waiters();
+    }
+
+    pub(crate) async fn cancelled(&self) {
+        loop {
+            let notified = self.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+impl ForgeExecutionCancels {
+    pub(crate) fn register(
+        &self,
+        workspace_id: &str,
+        plan_id: &str,
+    ) -> ForgeCancelRegistration<'_> {
+        let key = cancel_key(workspace_id, plan_id);
+        let token = Arc::new(ForgeCancelToken::default());
+        if let Ok(mut running) = self.running.lock() {
+            running.entry(key.clone()).or_default().push(Arc::clone(&token));
+        }
+        ForgeCancelRegistration {
+            cancels: self,
+            key,
+            token,
+        }
+    }
+
+    fn cancel(&self, workspace_id: &str, plan_id: &str) -> usize {
+        let key = cancel_key(workspace_id, plan_id);
+        let Ok(running) = self.running.lock() else {
+            return 0;
+        };
+        let tokens = running.get(&key).map(Vec::as_slice).unwrap_or(&[]);
+        for token in tokens {
+            token.cancel();
+        }
+        tokens.len()
+    }
+}
+
+impl ForgeCancelRegistration<'_> {
+    pub(crate) fn token(&self) -> &ForgeCancelToken {
+        &self.token
+    }
+}
+
+impl Drop for ForgeCancelRegistration<'_> {
+    fn drop(&mut self) {
+        let Ok(mut running) = self.cancels.running.lock() else {
+            return;
+        };
+        if let Some(tokens) = running.get_mut(&self.key) {
+            tokens.retain(|token| !Arc::ptr_eq(token, &self.token));
+            if tokens.is_empty() {
+                running.remove(&self.key);
+            }
+        }
+    }
+}
+
+/// Signals every check or hook running for the plan. Each run kills its process tree,
+/// leaves the phase `in_progress` with a cancellation note and skips the task commit.
+pub(crate) fn forge_cancel_execution_core(
+    cancels: &ForgeExecutionCancels,
+    workspace_id: &str,
+    plan_id: &str,
+) -> Result<ForgeCancelExecutionResultV1, String> {
+    let plan_id = validate_plan_id(plan_id)?;
+    if workspace_id.trim().is_empty() {
+        return Err("workspaceId is required".to_string());
+    }
+    Ok(ForgeCancelExecutionResultV1 {
+        cancelled_runs: cancels.cancel(workspace_id, &plan_id),
+    })
+}
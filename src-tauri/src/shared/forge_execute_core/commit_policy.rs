@@ -0,0 +1,361 @@
+use super::*;
+
+use std::collections::HashSet;
+
+const WORKSPACE_CONFIG_FILE: &str = "forge.json";
+/// Paths passed to one `git add` call, keeping the command line short on large change sets.
+const STAGE_PATHS_PER_CALL: usize = 200;
+
+/// How Forge stages and commits task work. Set under `commit` in the template `phases.json` or
+/// in the workspace `.agent/forge.json`; workspace values win field by field.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ForgeCommitPolicyV1 {
+    /// Git glob pathspecs to stage. Everything is staged when unset or empty.
+    #[serde(default)]
+    include: Option<Vec<String>>,
+    #[serde(default)]
+    exclude: Option<Vec<String>>,
+    /// Whether `plans/<planId>/` (plan, state, run logs) goes into the commit. Defaults to `true`.
+    #[serde(default)]
+    include_plan_files: Option<bool>,
+    /// Subject and body with `{planId}`, `{taskId}`, `{taskName}` and `{phaseId}` placeholders.
+    #[serde(default)]
+    message: Option<String>,
+    /// Trailer lines appended to the message, with the same placeholders.
+    #[serde(default)]
+    trailers: Option<Vec<String>>,
+    #[serde(default)]
+    signoff: Option<bool>,
+    /// `true` signs with the configured key, `false` disables signing; unset follows git config.
+    #[serde(default)]
+    gpg_sign: Option<bool>,
+    #[serde(default)]
+    signing_key: Option<String>,
+    /// Commit after every phase that passes its checks instead of once per task.
+    #[serde(default)]
+    per_phase: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ForgeWorkspaceConfigV1 {
+    #[serde(default)]
+    commit: Option<ForgeCommitPolicyV1>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum ForgeCommitKind {
+    Task,
+    Phase,
+}
+
+/// A commit Forge created, with the check result that reports it.
+pub(super) struct ForgeCommitOutcome {
+    pub(super) sha: String,
+    pub(super) result: ForgePhaseCheckResultV1,
+}
+
+impl ForgeCommitKind {
+    fn check_id(self) -> &'static str {
+        match self {
+            Self::Task => "forge-commit",
+            Self::Phase => "forge-phase-commit",
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Self::Task => "Forge task commit",
+            Self::Phase => "Forge phase commit",
+        }
+    }
+
+    /// Check result reporting this commit step.
+    pub(super) fn result(
+        self,
+        exit_code: i32,
+        duration_ms: i64,
+        stdout: String,
+        stderr: String,
+    ) -> ForgePhaseCheckResultV1 {
+        ForgePhaseCheckResultV1 {
+            id: self.check_id().to_string(),
+            title: self.title().to_string(),
+            exit_code,
+            duration_ms,
+            stdout,
+            stderr,
+            timed_out: false,
+            details: None,
+        }
+    }
+}
+
+impl ForgeCommitPolicyV1 {
+    fn merged_over(self, base: Self) -> Self {
+        Self {
+            include: self.include.or(base.include),
+            exclude: self.exclude.or(base.exclude),
+            include_plan_files: self.include_plan_files.or(base.include_plan_files),
+            message: self.message.or(base.message),
+            trailers: self.trailers.or(base.trailers),
+            signoff: self.signoff.or(base.signoff),
+            gpg_sign: self.gpg_sign.or(base.gpg_sign),
+            signing_key: self.signing_key.or(base.signing_key),
+            per_phase: self.per_phase.or(base.per_phase),
+        }
+    }
+
+    pub(super) fn per_phase(&self) -> bool {
+        self.per_phase.unwrap_or(false)
+    }
+
+    /// Builds the commit message. Without a `message` template the subject keeps the
+    /// `forge(<planId>): <taskId> <name>` form, with the phase id in place of the name for
+    /// phase commits.
+    pub(super) fn message_for(
+        &self,
+        plan_id: &str,
+        task: &PlanTaskV1,
+        phase_id: &str,
+        kind: ForgeCommitKind,
+    ) -> String {
+        let task_name = task.name.split_whitespace().collect::<Vec<_>>().join(" ");
+        let fill = |raw: &str| {
+            raw.replace("{planId}", plan_id)
+                .replace("{taskId}", task.id.trim())
+                .replace("{taskName}", &task_name)
+                .replace("{phaseId}", phase_id)
+        };
+        let mut message = match (self.message.as_deref().map(str::trim), kind) {
+            (Some(template), _) if !template.is_empty() => fill(template),
+            (_, ForgeCommitKind::Phase) => format!("forge({plan_id}): {} {phase_id}", task.id),
+            (_, ForgeCommitKind::Task) if task_name.is_empty() => {
+                format!("forge({plan_id}): {}", task.id)
+            }
+            (_, ForgeCommitKind::Task) => format!("forge({plan_id}): {} {task_name}", task.id),
+        };
+        self.append_trailers(&mut message, fill);
+        message
+    }
+
+    /// Message for a rollback commit. The subject is fixed; the policy trailers still apply,
+    /// with an empty `{phaseId}`.
+    pub(super) fn revert_message_for(&self, plan_id: &str, task: &PlanTaskV1, sha: &str) -> String {
+        let task_name = task.name.split_whitespace().collect::<Vec<_>>().join(" ");
+        let fill = |raw: &str| {
+            raw.replace("{planId}", plan_id)
+                .replace("{taskId}", task.id.trim())
+                .replace("{taskName}", &task_name)
+                .replace("{phaseId}", "")
+        };
+        let mut message = format!("forge({plan_id}): revert {} ({sha})", task.id.trim());
+        self.append_trailers(&mut message, fill);
+        message
+    }
+
+    fn append_trailers(&self, message: &mut String, fill: impl Fn(&str) -> String) {
+        let trailers = self
+            .trailers
+            .iter()
+            .flatten()
+            .map(|line| fill(line.trim()))
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        if !trailers.is_empty() {
+            message.push_str("\n\n");
+            message.push_str(&trailers.join("\n"));
+        }
+    }
+
+    fn staging_pathspecs(&self, plan_id: &str) -> Vec<String> {
+        let globs = |items: &Option<Vec<String>>| {
+            items
+                .iter()
+                .flatten()
+                .map(|glob| glob.trim().to_string())
+                .filter(|glob| !glob.is_empty())
+                .collect::<Vec<_>>()
+        };
+        let include = globs(&self.include);
+        let mut specs = if include.is_empty() {
+            vec![".".to_string()]
+        } else {
+            include
+                .into_iter()
+                .map(|glob| format!(":(glob){glob}"))
+                .collect()
+        };
+        specs.extend(
+            globs(&self.exclude)
+                .into_iter()
+                .map(|glob| format!(":(glob,exclude){glob}")),
+        );
+        if !self.include_plan_files.unwrap_or(true) {
+            specs.push(outside_plan_dir_pathspec(plan_id));
+        }
+        specs
+    }
+
+    pub(super) fn commit_flags(&self) -> Vec<String> {
+        let mut flags = Vec::new();
+        if self.signoff.unwrap_or(false) {
+            flags.push("--signoff".to_string());
+        }
+        let signing_key = self
+            .signing_key
+            .as_deref()
+            .map(str::trim)
+            .filter(|key| !key.is_empty());
+        match (self.gpg_sign, signing_key) {
+            (Some(false), _) => flags.push("--no-gpg-sign".to_string()),
+            (_, Some(key)) => flags.push(format!("--gpg-sign={key}")),
+            (Some(true), None) => flags.push("--gpg-sign".to_string()),
+            (None, None) => {}
+        }
+        flags
+    }
+}
+
+/// Reads the template policy merged with the workspace `.agent/forge.json`, if present.
+pub(super) fn load_commit_policy(
+    paths: &ForgeExecutionPaths,
+    template_policy: Option<&ForgeCommitPolicyV1>,
+) -> Result<ForgeCommitPolicyV1, String> {
+    let config_path = paths
+        .workspace_root
+        .join(".agent")
+        .join(WORKSPACE_CONFIG_FILE);
+    let workspace_policy = if config_path.is_file() {
+        read_json_file::<ForgeWorkspaceConfigV1>(&config_path)?
+            .commit
+            .unwrap_or_default()
+    } else {
+        ForgeCommitPolicyV1::default()
+    };
+    Ok(workspace_policy.merged_over(template_policy.cloned().unwrap_or_default()))
+}
+
+async fn git_output(repo_root: &Path, args: &[&str]) -> Result<Output, String> {
+    let output = run_git_command_with_timeout(repo_root, args).await?;
+    if !output.status.success() {
+        return Err(format_process_error(&output.stdout, &output.stderr));
+    }
+    Ok(output)
+}
+
+/// Stages the changed paths matched by the policy. Paths are listed first because `git add`
+/// rejects an include glob that matches nothing.
+async fn stage_policy_paths(
+    repo_root: &Path,
+    policy: &ForgeCommitPolicyV1,
+    plan_id: &str,
+) -> Result<(), String> {
+    let specs = policy.staging_pathspecs(plan_id);
+    let mut args = vec![
+        "ls-files",
+        "-z",
+        "--others",
+        "--modified",
+        "--deleted",
+        "--exclude-standard",
+        "--",
+    ];
+    args.extend(specs.iter().map(String::as_str));
+    let listed = git_output(repo_root, &args).await?;
+
+    let mut seen = HashSet::new();
+    let paths = String::from_utf8_lossy(&listed.stdout)
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .filter(|path| seen.insert(path.to_string()))
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    for chunk in paths.chunks(STAGE_PATHS_PER_CALL) {
+        let mut args = vec!["--literal-pathspecs", "add", "-A", "--"];
+        args.extend(chunk.iter().map(String::as_str));
+        git_output(repo_root, &args).await?;
+    }
+    Ok(())
+}
+
+async fn has_staged_changes(repo_root: &Path) -> Result<bool, String> {
+    let output = run_git_command_with_timeout(repo_root, &["diff", "--cached", "--quiet"]).await?;
+    match output.status.code() {
+        Some(0) => Ok(false),
+        Some(1) => Ok(true),
+        _ => Err(format_process_error(&output.stdout, &output.stderr)),
+    }
+}
+
+/// Stages and commits under `policy`. Returns `Ok(None)` when the policy leaves nothing to
+/// commit, so the caller decides whether an empty step is an error.
+pub(super) async fn create_forge_commit(
+    repo_root: &Path,
+    policy: &ForgeCommitPolicyV1,
+    plan_id: &str,
+    message: &str,
+    kind: ForgeCommitKind,
+) -> Result<Option<ForgeCommitOutcome>, ForgePhaseCheckResultV1> {
+    let start = Instant::now();
+    let fail = |stderr: String| {
+        kind.result(-1, start.elapsed().as_millis() as i64, String::new(), stderr)
+    };
+
+    stage_policy_paths(repo_root, policy, plan_id)
+        .await
+        .map_err(|err| fail(format!("Staging failed: {err}")))?;
+    if !has_staged_changes(repo_root).await.map_err(fail)? {
+        return Ok(None);
+    }
+
+    let flags = policy.commit_flags();
+    let mut args = vec!["commit", "-m", message];
+    args.extend(flags.iter().map(String::as_str));
+    let commit_output = run_git_command_with_timeout(repo_root, &args)
+        .await
+        .map_err(fail)?;
+    if !commit_output.status.success() {
+        return Err(kind.result(
+            commit_output.status.code().unwrap_or(-1),
+            start.elapsed().as_millis() as i64,
+            String::from_utf8_lossy(&commit_output.stdout).to_string(),
+            String::from_utf8_lossy(&commit_output.stderr).to_string(),
+        ));
+    }
+
+    let sha_output = git_output(repo_root, &["rev-parse", "HEAD"])
+        .await
+        .map_err(fail)?;
+    let sha = String::from_utf8_lossy(&sha_output.stdout).trim().to_string();
+    if sha.is_empty() {
+        return Err(fail("Unable to resolve commit SHA after commit.".to_string()));
+    }
+
+    let stdout = String::from_utf8_lossy(&commit_output.stdout).to_string();
+    let result = kind.result(
+        0,
+        start.elapsed().as_millis() as i64,
+        if stdout.trim().is_empty() {
+            format!("Created commit {sha}")
+        } else {
+            stdout
+        },
+        String::from_utf8_lossy(&commit_output.stderr).to_string(),
+    );
+    Ok(Some(ForgeCommitOutcome { sha, result }))
+}
+
+/// Commits recorded for a task, oldest first: phase commits in phase order, then the task
+/// commit when it is not one of them.
+pub(super) fn task_commit_shas(task: &StateTaskV2) -> Vec<String> {
+    let mut shas = Vec::new();
+    let phase_shas = task.phases.iter().filter_map(|phase| phase.commit_sha.as_deref());
+    for sha in phase_shas.chain(task.commit_sha.as_deref()) {
+        let sha = sha.trim();
+        if !sha.is_empty() && !shas.iter().any(|known| known == sha) {
+            shas.push(sha.to_string());
+        }
+    }
+    shas
+}
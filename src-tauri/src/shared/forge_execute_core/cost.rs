@@ -0,0 +1,225 @@
+use super::*;
+
+use std::collections::{BTreeMap, HashSet};
+
+use crate::shared::local_usage_core::{
+    resolve_sessions_roots, scan_thread_usage, SessionUsageEntry,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeUsageV1 {
+    pub(crate) input_tokens: i64,
+    pub(crate) cached_input_tokens: i64,
+    pub(crate) output_tokens: i64,
+    /// Input plus output tokens, counted like the local usage snapshot.
+    pub(crate) total_tokens: i64,
+    pub(crate) agent_time_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgePhaseCostV1 {
+    pub(crate) phase_id: String,
+    pub(crate) thread_ids: Vec<String>,
+    pub(crate) usage: ForgeUsageV1,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeTaskCostV1 {
+    pub(crate) task_id: String,
+    pub(crate) task_name: Option<String>,
+    pub(crate) thread_ids: Vec<String>,
+    /// Phase usage plus `uncheckedUsage`.
+    pub(crate) usage: ForgeUsageV1,
+    /// Usage on the task's threads after their last check run, from a phase still in progress.
+    pub(crate) unchecked_usage: ForgeUsageV1,
+    pub(crate) phases: Vec<ForgePhaseCostV1>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgePlanCostV1 {
+    pub(crate) plan_id: String,
+    pub(crate) template_id: Option<String>,
+    pub(crate) template_version: Option<String>,
+    pub(crate) usage: ForgeUsageV1,
+    pub(crate) tasks: Vec<ForgeTaskCostV1>,
+    /// Recorded threads without a session log on this machine. Their usage is not counted.
+    pub(crate) missing_thread_ids: Vec<String>,
+}
+
+/// A check run on a thread. The thread's usage up to `checked_at_ms`, and after the previous
+/// check run on it, belongs to this phase.
+struct ThreadSlice {
+    checked_at_ms: i64,
+    task_index: usize,
+    phase_index: usize,
+}
+
+impl ForgeUsageV1 {
+    fn add_entry(&mut self, entry: &SessionUsageEntry) {
+        self.input_tokens += entry.input_tokens;
+        self.cached_input_tokens += entry.cached_input_tokens;
+        self.output_tokens += entry.output_tokens;
+        self.total_tokens += entry.input_tokens + entry.output_tokens;
+        self.agent_time_ms += entry.agent_time_ms;
+    }
+
+    fn add(&mut self, other: &ForgeUsageV1) {
+        self.input_tokens += other.input_tokens;
+        self.cached_input_tokens += other.cached_input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.total_tokens += other.total_tokens;
+        self.agent_time_ms += other.agent_time_ms;
+    }
+}
+
+pub(super) fn record_phase_thread(
+    phase: &mut StatePhaseV2,
+    thread_id: Option<&str>,
+    checked_at: &str,
+) {
+    let Some(thread_id) = thread_id.map(str::trim).filter(|value| !value.is_empty()) else {
+        return;
+    };
+    phase.threads.push(StatePhaseThreadV2 {
+        thread_id: thread_id.to_string(),
+        checked_at: checked_at.to_string(),
+    });
+}
+
+fn unique_thread_ids<'a>(threads: impl Iterator<Item = &'a StatePhaseThreadV2>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    threads
+        .filter(|thread| seen.insert(thread.thread_id.as_str()))
+        .map(|thread| thread.thread_id.clone())
+        .collect()
+}
+
+pub(super) fn plan_cost(
+    paths: &ForgeExecutionPaths,
+    sessions_roots: &[PathBuf],
+) -> Result<ForgePlanCostV1, String> {
+    require_plan_file(&paths.plan_path)?;
+    if !paths.state_path.is_file() {
+        return Err(format!(
+            "Missing state.json: {}. Run forge_prepare_execution first.",
+            paths.state_path.display()
+        ));
+    }
+    let plan = load_plan(paths)?;
+    let state = load_state(paths)?;
+
+    let mut slices_by_thread = BTreeMap::<String, Vec<ThreadSlice>>::new();
+    for (task_index, task) in state.tasks.iter().enumerate() {
+        for (phase_index, phase) in task.phases.iter().enumerate() {
+            for thread in &phase.threads {
+                let Ok(checked_at) = chrono::DateTime::parse_from_rfc3339(&thread.checked_at)
+                else {
+                    continue;
+                };
+                slices_by_thread
+                    .entry(thread.thread_id.clone())
+                    .or_default()
+                    .push(ThreadSlice {
+                        checked_at_ms: checked_at.timestamp_millis(),
+                        task_index,
+                        phase_index,
+                    });
+            }
+        }
+    }
+    for slices in slices_by_thread.values_mut() {
+        slices.sort_by_key(|slice| slice.checked_at_ms);
+    }
+
+    let mut tasks = state
+        .tasks
+        .iter()
+        .map(|task| ForgeTaskCostV1 {
+            task_id: task.id.clone(),
+            task_name: plan
+                .tasks
+                .iter()
+                .find(|plan_task| plan_task.id == task.id)
+                .map(|plan_task| plan_task.name.clone()),
+            thread_ids: unique_thread_ids(task.phases.iter().flat_map(|phase| &phase.threads)),
+            usage: ForgeUsageV1::default(),
+            unchecked_usage: ForgeUsageV1::default(),
+            phases: task
+                .phases
+                .iter()
+                .map(|phase| ForgePhaseCostV1 {
+                    phase_id: phase.id.clone(),
+                    thread_ids: unique_thread_ids(phase.threads.iter()),
+                    usage: ForgeUsageV1::default(),
+                })
+                .collect(),
+        })
+        .collect::<Vec<_>>();
+
+    let thread_ids = slices_by_thread.keys().cloned().collect::<HashSet<_>>();
+    let usage_by_thread = scan_thread_usage(sessions_roots, &thread_ids);
+    let mut missing_thread_ids = Vec::new();
+    for (thread_id, slices) in &slices_by_thread {
+        let (Some(entries), Some(last_slice)) = (usage_by_thread.get(thread_id), slices.last())
+        else {
+            missing_thread_ids.push(thread_id.clone());
+            continue;
+        };
+        for entry in entries {
+            match slices
+                .iter()
+                .find(|slice| entry.timestamp_ms <= slice.checked_at_ms)
+            {
+                Some(slice) => tasks[slice.task_index].phases[slice.phase_index]
+                    .usage
+                    .add_entry(entry),
+                None => tasks[last_slice.task_index]
+                    .unchecked_usage
+                    .add_entry(entry),
+            }
+        }
+    }
+
+    let mut usage = ForgeUsageV1::default();
+    for task in &mut tasks {
+        let mut task_usage = task.unchecked_usage;
+        for phase in &task.phases {
+            task_usage.add(&phase.usage);
+        }
+        task.usage = task_usage;
+        usage.add(&task_usage);
+    }
+
+    let lock = read_installed_template_lock_core(&paths.workspace_root)
+        .ok()
+        .flatten();
+    Ok(ForgePlanCostV1 {
+        plan_id: paths.plan_id.clone(),
+        template_id: lock.as_ref().map(|lock| lock.installed_template_id.clone()),
+        template_version: lock.map(|lock| lock.installed_template_version),
+        usage,
+        tasks,
+        missing_thread_ids,
+    })
+}
+
+/// Joins the threads recorded by `forge_run_phase_checks` with the Codex session logs of every
+/// known Codex home, and sums tokens and agent time per phase, task and plan.
+pub(crate) async fn forge_plan_cost_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_root: &Path,
+    plan_id: &str,
+) -> Result<ForgePlanCostV1, String> {
+    let paths = build_execution_paths(workspace_root, plan_id)?;
+    let sessions_roots = {
+        let workspaces = workspaces.lock().await;
+        resolve_sessions_roots(&workspaces, None)
+    };
+    tokio::task::spawn_blocking(move || plan_cost(&paths, &sessions_roots))
+        .await
+        .map_err(|err| err.to_string())?
+}
@@ -0,0 +1,266 @@
+use super::*;
+
+use std::io::Write;
+
+const RUN_RECORD_SCHEMA: &str = "forge-run-v1";
+const RUN_OUTPUT_MAX_CHARS: usize = 16 * 1024;
+const RUN_OUTPUT_TRUNCATED_PREFIX: &str = "[truncated] ... ";
+
+/// One `forge_run_phase_checks` call, appended to `plans/<planId>/runs/<taskId>.jsonl`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeRunRecordV1 {
+    pub(crate) schema: String,
+    pub(crate) run_id: String,
+    pub(crate) plan_id: String,
+    pub(crate) task_id: String,
+    pub(crate) phase_id: String,
+    pub(crate) attempt: i64,
+    #[serde(default)]
+    pub(crate) thread_id: Option<String>,
+    pub(crate) started_at: String,
+    pub(crate) finished_at: String,
+    pub(crate) ok: bool,
+    #[serde(default)]
+    pub(crate) cancelled: bool,
+    #[serde(default)]
+    pub(crate) exhausted: bool,
+    #[serde(default)]
+    pub(crate) commit_sha: Option<String>,
+    #[serde(default)]
+    pub(crate) checks: Vec<ForgeRunCheckRecordV1>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeRunCheckRecordV1 {
+    pub(crate) id: String,
+    pub(crate) title: String,
+    pub(crate) exit_code: i32,
+    pub(crate) duration_ms: i64,
+    pub(crate) timed_out: bool,
+    pub(crate) stdout: String,
+    pub(crate) stderr: String,
+    /// Set when stdout or stderr was cut down to its last `RUN_OUTPUT_MAX_CHARS` characters.
+    #[serde(default)]
+    pub(crate) output_truncated: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) details: Option<Box<ForgeCheckDetailsV1>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeRunHistoryV1 {
+    pub(crate) plan_id: String,
+    pub(crate) runs: Vec<ForgeRunRecordV1>,
+}
+
+/// Context of the check run being recorded, captured before the checks start.
+pub(super) struct ForgeRunLogEntry<'a> {
+    pub(super) plan_dir: &'a Path,
+    pub(super) plan_id: &'a str,
+    pub(super) task_id: &'a str,
+    pub(super) phase_id: &'a str,
+    pub(super) thread_id: Option<&'a str>,
+    pub(super) started_at: String,
+}
+
+fn runs_dir(plan_dir: &Path) -> PathBuf {
+    plan_dir.join("runs")
+}
+
+fn run_log_path(plan_dir: &Path, task_id: &str) -> Result<PathBuf, String> {
+    let task_id = task_id.trim();
+    let is_safe = !task_id.is_empty()
+        && task_id
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_');
+    if !is_safe {
+        return Err(format!("Invalid taskId for run history: {task_id}"));
+    }
+    Ok(runs_dir(plan_dir).join(format!("{task_id}.jsonl")))
+}
+
+/// Keeps the tail of long output, where test runners print their failures.
+fn truncate_run_output(output: &str) -> (String, bool) {
+    let char_count = output.chars().count();
+    if char_count <= RUN_OUTPUT_MAX_CHARS {
+        return (output.to_string(), false);
+    }
+    let tail = output
+        .chars()
+        .skip(char_count - RUN_OUTPUT_MAX_CHARS)
+        .collect::<String>();
+    (format!("{RUN_OUTPUT_TRUNCATED_PREFIX}{tail}"), true)
+}
+
+fn build_check_record(result: &ForgePhaseCheckResultV1) -> ForgeRunCheckRecordV1 {
+    let (stdout, stdout_truncated) = truncate_run_output(&result.stdout);
+    let (stderr, stderr_truncated) = truncate_run_output(&result.stderr);
+    ForgeRunCheckRecordV1 {
+        id: result.id.clone(),
+        title: result.title.clone(),
+        exit_code: result.exit_code,
+        duration_ms: result.duration_ms,
+        timed_out: result.timed_out,
+        stdout,
+        stderr,
+        output_truncated: stdout_truncated || stderr_truncated,
+        details: result.details.clone(),
+    }
+}
+
+pub(super) fn now_rfc3339_millis() -> String {
+    Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
+impl ForgeRunLogEntry<'_> {
+    pub(super) fn append(
+        &self,
+        response: &ForgeRunPhaseChecksResponseV1,
+        commit_sha: Option<&str>,
+    ) -> Result<(), String> {
+        let record = ForgeRunRecordV1 {
+            schema: RUN_RECORD_SCHEMA.to_string(),
+            run_id: Uuid::new_v4().to_string(),
+            plan_id: self.plan_id.to_string(),
+            task_id: self.task_id.to_string(),
+            phase_id: self.phase_id.to_string(),
+            attempt: response.attempts,
+            thread_id: self
+                .thread_id
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string),
+            started_at: self.started_at.clone(),
+            finished_at: now_rfc3339_millis(),
+            ok: response.ok,
+            cancelled: response.cancelled,
+            exhausted: response.exhausted,
+            commit_sha: commit_sha.map(str::to_string),
+            checks: response.results.iter().map(build_check_record).collect(),
+        };
+        let path = run_log_path(self.plan_dir, self.task_id)?;
+        fs::create_dir_all(runs_dir(self.plan_dir)).map_err(|err| err.to_string())?;
+        let line = serde_json::to_string(&record).map_err(|err| err.to_string())?;
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|err| format!("Unable to open run log {}: {err}", path.display()))?;
+        writeln!(file, "{line}")
+            .map_err(|err| format!("Unable to append run log {}: {err}", path.display()))
+    }
+}
+
+/// Moves run logs after tasks were renumbered. `renames` maps old to new task ids; logs of
+/// `removed` tasks go to `runs/removed/`, which the history does not read, so a task that later
+/// takes the same id starts with an empty log.
+pub(super) fn remap_run_logs(
+    plan_dir: &Path,
+    renames: &[(String, String)],
+    removed: &[String],
+) -> Result<(), String> {
+    let dir = runs_dir(plan_dir);
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    let removed_at = Utc::now().format("%Y%m%dT%H%M%SZ");
+    for task_id in removed {
+        let path = run_log_path(plan_dir, task_id)?;
+        if path.is_file() {
+            let archive_dir = dir.join("removed");
+            fs::create_dir_all(&archive_dir).map_err(|err| err.to_string())?;
+            fs::rename(
+                &path,
+                archive_dir.join(format!("{task_id}-{removed_at}.jsonl")),
+            )
+            .map_err(|err| format!("Unable to archive run log {}: {err}", path.display()))?;
+        }
+    }
+
+    // Ids can swap (task-2 and task-3), so every log moves aside before any takes its new name.
+    let mut staged = Vec::new();
+    for (old_id, new_id) in renames.iter().filter(|(old_id, new_id)| old_id != new_id) {
+        let path = run_log_path(plan_dir, old_id)?;
+        if path.is_file() {
+            let staged_path = dir.join(format!(".{old_id}.{}.renaming", Uuid::new_v4()));
+            fs::rename(&path, &staged_path).map_err(|err| err.to_string())?;
+            staged.push((staged_path, new_id));
+        }
+    }
+    for (staged_path, new_id) in staged {
+        let raw = fs::read_to_string(&staged_path).map_err(|err| err.to_string())?;
+        let lines = raw
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(
+                |line| match serde_json::from_str::<ForgeRunRecordV1>(line) {
+                    Ok(mut record) => {
+                        record.task_id = new_id.clone();
+                        serde_json::to_string(&record).map_err(|err| err.to_string())
+                    }
+                    Err(_) => Ok(line.to_string()),
+                },
+            )
+            .collect::<Result<Vec<_>, _>>()?;
+        let target = run_log_path(plan_dir, new_id)?;
+        fs::write(&target, format!("{}\n", lines.join("\n")))
+            .map_err(|err| format!("Unable to write run log {}: {err}", target.display()))?;
+        let _ = fs::remove_file(&staged_path);
+    }
+    Ok(())
+}
+
+fn read_run_log(path: &Path, runs: &mut Vec<ForgeRunRecordV1>) -> Result<(), String> {
+    let raw = fs::read_to_string(path)
+        .map_err(|err| format!("Unable to read run log {}: {err}", path.display()))?;
+    // A line cut short by a crash mid-append is skipped rather than failing the whole history.
+    runs.extend(
+        raw.lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| serde_json::from_str::<ForgeRunRecordV1>(line).ok()),
+    );
+    Ok(())
+}
+
+/// Returns recorded check runs for a plan, newest first. `task_id` narrows the result to one
+/// task's log and `limit` caps the number of runs returned.
+pub(crate) fn forge_get_run_history_core(
+    workspace_root: &Path,
+    plan_id: &str,
+    task_id: Option<&str>,
+    limit: Option<usize>,
+) -> Result<ForgeRunHistoryV1, String> {
+    let plan_id = validate_plan_id(plan_id)?;
+    let plan_dir = workspace_root.join("plans").join(&plan_id);
+    let mut runs = Vec::new();
+
+    match task_id.map(str::trim).filter(|value| !value.is_empty()) {
+        Some(task_id) => {
+            let path = run_log_path(&plan_dir, task_id)?;
+            if path.is_file() {
+                read_run_log(&path, &mut runs)?;
+            }
+        }
+        None => {
+            let dir = runs_dir(&plan_dir);
+            if dir.is_dir() {
+                let entries = fs::read_dir(&dir).map_err(|err| err.to_string())?;
+                for entry in entries {
+                    let path = entry.map_err(|err| err.to_string())?.path();
+                    if path.extension().is_some_and(|ext| ext == "jsonl") && path.is_file() {
+                        read_run_log(&path, &mut runs)?;
+                    }
+                }
+            }
+        }
+    }
+
+    // RFC 3339 timestamps in UTC with fixed precision sort chronologically as strings.
+    runs.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    if let Some(limit) = limit {
+        runs.truncate(limit);
+    }
+    Ok(ForgeRunHistoryV1 { plan_id, runs })
+}
@@ -0,0 +1,456 @@
+use super::*;
+
+use std::collections::HashSet;
+
+use serde_json::Map;
+
+use super::history::remap_run_logs;
+use crate::shared::forge_plans_core::validate_plan_json_core;
+
+/// One edit made by `forge_update_plan`. Every operation renumbers the tasks to
+/// `task-1..task-n`, so task ids refer to the plan as left by the previous operation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub(crate) enum ForgePlanOperationV1 {
+    /// Inserts a task at the 1-based `position`, or at the end. `task` holds every plan field
+    /// except `id`.
+    AddTask {
+        task: Value,
+        #[serde(default)]
+        position: Option<usize>,
+    },
+    RemoveTask {
+        task_id: String,
+    },
+    MoveTask {
+        task_id: String,
+        position: usize,
+    },
+    RenameTask {
+        task_id: String,
+        name: String,
+    },
+    SetDependencies {
+        task_id: String,
+        depends_on: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgePlanUpdatedTaskV1 {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    /// The id before the update, or `None` for an added task.
+    pub(crate) previous_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgePlanUpdateResultV1 {
+    pub(crate) plan_id: String,
+    pub(crate) tasks: Vec<ForgePlanUpdatedTaskV1>,
+    /// Ids of removed tasks, as they were before the update.
+    pub(crate) removed_task_ids: Vec<String>,
+}
+
+/// A task being edited. Dependencies point at `key`s, which stay fixed while positions move.
+struct EditedTask {
+    key: usize,
+    previous_id: Option<String>,
+    fields: Map<String, Value>,
+    depends_on: Vec<usize>,
+}
+
+struct PlanEdit {
+    tasks: Vec<EditedTask>,
+    next_key: usize,
+}
+
+fn task_name(fields: &Map<String, Value>) -> String {
+    fields
+        .get("name")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+impl PlanEdit {
+    fn from_plan(plan_raw: &Value) -> Result<Self, String> {
+        let raw_tasks = plan_raw
+            .get("tasks")
+            .and_then(Value::as_array)
+            .ok_or_else(|| "plan.json has no tasks array".to_string())?;
+        let mut keys_by_id = HashMap::new();
+        for (key, task) in raw_tasks.iter().enumerate() {
+            let id = task
+                .get("id")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .trim();
+            keys_by_id.insert(id.to_string(), key);
+        }
+        let mut tasks = Vec::new();
+        for (key, task) in raw_tasks.iter().enumerate() {
+            let mut fields = task
+                .as_object()
+                .cloned()
+                .ok_or_else(|| format!("plan.json task {} is not an object", key + 1))?;
+            let id = fields
+                .remove("id")
+                .and_then(|id| id.as_str().map(|id| id.trim().to_string()))
+                .unwrap_or_default();
+            let depends_on = dependency_ids(fields.remove("depends_on"))
+                .iter()
+                .map(|dep| {
+                    keys_by_id
+                        .get(dep)
+                        .copied()
+                        .ok_or_else(|| format!("Task {id} depends on unknown task {dep}."))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            tasks.push(EditedTask {
+                key,
+                previous_id: Some(id),
+                fields,
+                depends_on,
+            });
+        }
+        Ok(Self {
+            next_key: tasks.len(),
+            tasks,
+        })
+    }
+
+    /// Position of `task_id` under the current numbering.
+    fn index_of(&self, task_id: &str) -> Result<usize, String> {
+        let task_id = task_id.trim();
+        task_id
+            .strip_prefix("task-")
+            .and_then(|number| number.parse::<usize>().ok())
+            .filter(|number| (1..=self.tasks.len()).contains(number))
+            .map(|number| number - 1)
+            .ok_or_else(|| format!("Unknown taskId: {task_id}"))
+    }
+
+    fn key_of(&self, task_id: &str) -> Result<usize, String> {
+        self.index_of(task_id).map(|index| self.tasks[index].key)
+    }
+
+    fn current_id(&self, key: usize) -> String {
+        let index = self
+            .tasks
+            .iter()
+            .position(|task| task.key == key)
+            .unwrap_or_default();
+        format!("task-{}", index + 1)
+    }
+
+    fn resolve_dependencies(
+        &self,
+        task_id: Option<&str>,
+        deps: &[String],
+    ) -> Result<Vec<usize>, String> {
+        let mut keys = Vec::new();
+        for dep in deps {
+            if task_id.is_some_and(|id| id.trim() == dep.trim()) {
+                return Err(format!("Task {} cannot depend on itself.", dep.trim()));
+            }
+            let key = self.key_of(dep)?;
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        Ok(keys)
+    }
+
+    fn apply(
+        &mut self,
+        operation: ForgePlanOperationV1,
+        state: Option<&StateV2>,
+    ) -> Result<(), String> {
+        match operation {
+            ForgePlanOperationV1::AddTask { task, position } => {
+                let mut fields = task
+                    .as_object()
+                    .cloned()
+                    .ok_or_else(|| "addTask needs a task object.".to_string())?;
+                if fields.contains_key("id") {
+                    return Err("addTask assigns the task id; leave `id` out.".to_string());
+                }
+                let name = fields
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .ok_or_else(|| "addTask needs a task name.".to_string())?
+                    .to_string();
+                fields.insert("name".to_string(), Value::from(name));
+                let deps = dependency_ids(fields.remove("depends_on"));
+                let depends_on = self.resolve_dependencies(None, &deps)?;
+                let index = match position {
+                    Some(position) if (1..=self.tasks.len() + 1).contains(&position) => {
+                        position - 1
+                    }
+                    Some(position) => return Err(format!("Invalid position: {position}")),
+                    None => self.tasks.len(),
+                };
+                self.tasks.insert(
+                    index,
+                    EditedTask {
+                        key: self.next_key,
+                        previous_id: None,
+                        fields,
+                        depends_on,
+                    },
+                );
+                self.next_key += 1;
+            }
+            ForgePlanOperationV1::RemoveTask { task_id } => {
+                let index = self.index_of(&task_id)?;
+                let key = self.tasks[index].key;
+                let dependents = self
+                    .tasks
+                    .iter()
+                    .filter(|task| task.depends_on.contains(&key))
+                    .map(|task| self.current_id(task.key))
+                    .collect::<Vec<_>>();
+                if !dependents.is_empty() {
+                    return Err(format!(
+                        "Tasks {} depend on {}. Update their dependencies first.",
+                        dependents.join(", "),
+                        task_id.trim()
+                    ));
+                }
+                let has_worktree = self.tasks[index]
+                    .previous_id
+                    .as_deref()
+                    .and_then(|id| state?.tasks.iter().find(|task| task.id.trim() == id))
+                    .is_some_and(|task| task.worktree.is_some());
+                if has_worktree {
+                    return Err(format!(
+                        "Task {} runs in a worktree. Merge or discard it first.",
+                        task_id.trim()
+                    ));
+                }
+                self.tasks.remove(index);
+            }
+            ForgePlanOperationV1::MoveTask { task_id, position } => {
+                let index = self.index_of(&task_id)?;
+                if !(1..=self.tasks.len()).contains(&position) {
+                    return Err(format!("Invalid position: {position}"));
+                }
+                let task = self.tasks.remove(index);
+                self.tasks.insert(position - 1, task);
+            }
+            ForgePlanOperationV1::RenameTask { task_id, name } => {
+                let index = self.index_of(&task_id)?;
+                let name = name.trim();
+                if name.is_empty() {
+                    return Err("renameTask needs a non-empty name.".to_string());
+                }
+                self.tasks[index]
+                    .fields
+                    .insert("name".to_string(), Value::from(name));
+            }
+            ForgePlanOperationV1::SetDependencies {
+                task_id,
+                depends_on,
+            } => {
+                let index = self.index_of(&task_id)?;
+                self.tasks[index].depends_on =
+                    self.resolve_dependencies(Some(&task_id), &depends_on)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Dependencies have to come before their dependents, which also rules out cycles.
+    fn check_order(&self) -> Result<(), String> {
+        if self.tasks.is_empty() {
+            return Err("A plan needs at least one task.".to_string());
+        }
+        let mut seen = HashSet::new();
+        for task in &self.tasks {
+            if let Some(dep) = task.depends_on.iter().find(|dep| !seen.contains(*dep)) {
+                return Err(format!(
+                    "Task {} must come after its dependency {}.",
+                    self.current_id(task.key),
+                    self.current_id(*dep)
+                ));
+            }
+            seen.insert(task.key);
+        }
+        Ok(())
+    }
+
+    fn plan_tasks(&self) -> Vec<Value> {
+        self.tasks
+            .iter()
+            .enumerate()
+            .map(|(index, task)| {
+                let mut fields = task.fields.clone();
+                fields.insert("id".to_string(), Value::from(format!("task-{}", index + 1)));
+                let depends_on = task
+                    .depends_on
+                    .iter()
+                    .map(|dep| Value::from(self.current_id(*dep)))
+                    .collect();
+                fields.insert("depends_on".to_string(), Value::Array(depends_on));
+                Value::Object(fields)
+            })
+            .collect()
+    }
+}
+
+fn dependency_ids(value: Option<Value>) -> Vec<String> {
+    value
+        .as_ref()
+        .and_then(Value::as_array)
+        .map(|deps| {
+            deps.iter()
+                .filter_map(Value::as_str)
+                .map(|dep| dep.trim().to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn new_task_state(id: String, phase_ids: &[String]) -> StateTaskV2 {
+    StateTaskV2 {
+        id,
+        status: "pending".to_string(),
+        attempts: 0,
+        notes: String::new(),
+        commit_sha: None,
+        phases: phase_ids
+            .iter()
+            .map(|phase_id| StatePhaseV2 {
+                id: phase_id.clone(),
+                status: "pending".to_string(),
+                attempts: 0,
+                notes: String::new(),
+                commit_sha: None,
+                approval: None,
+                threads: Vec::new(),
+            })
+            .collect(),
+        worktree: None,
+    }
+}
+
+/// Applies `operations` to `plan.json` in order and renumbers the tasks. Matching `state.json`
+/// entries, with their phases and commits, and the per-task run logs follow their tasks to the
+/// new ids; added tasks start `pending`.
+pub(crate) async fn forge_update_plan_core(
+    workspace_root: &Path,
+    plan_id: &str,
+    operations: Vec<ForgePlanOperationV1>,
+) -> Result<ForgePlanUpdateResultV1, String> {
+    let paths = build_execution_paths(workspace_root, plan_id)?;
+    require_plan_file(&paths.plan_path)?;
+    load_plan(&paths)?;
+    if operations.is_empty() {
+        return Err("operations must not be empty".to_string());
+    }
+    let phase_ids = load_template_phases(&paths)?
+        .phases
+        .iter()
+        .map(|phase| phase.id.trim().to_string())
+        .collect::<Vec<_>>();
+
+    let (tasks, removed_task_ids) = {
+        let _lock = lock_state(&paths.workspace_root, &paths.plan_id).await?;
+        let mut plan_raw = read_json_file::<Value>(&paths.plan_path)?;
+        let state = if paths.state_path.is_file() {
+            Some(load_state(&paths)?)
+        } else {
+            None
+        };
+
+        let mut edit = PlanEdit::from_plan(&plan_raw)?;
+        let previous_ids = edit
+            .tasks
+            .iter()
+            .filter_map(|task| task.previous_id.clone())
+            .collect::<Vec<_>>();
+        for operation in operations {
+            edit.apply(operation, state.as_ref())?;
+        }
+        edit.check_order()?;
+
+        if let Some(plan) = plan_raw.as_object_mut() {
+            plan.insert("tasks".to_string(), Value::Array(edit.plan_tasks()));
+        }
+        let errors = validate_plan_json_core(&paths.workspace_root, &plan_raw)?;
+        if !errors.is_empty() {
+            let details = errors
+                .iter()
+                .map(|error| format!("{} {}", error.instance_path, error.message))
+                .collect::<Vec<_>>();
+            return Err(format!("Updated plan is invalid: {}", details.join("; ")));
+        }
+
+        let tasks = edit
+            .tasks
+            .iter()
+            .enumerate()
+            .map(|(index, task)| ForgePlanUpdatedTaskV1 {
+                id: format!("task-{}", index + 1),
+                name: task_name(&task.fields),
+                previous_id: task.previous_id.clone(),
+            })
+            .collect::<Vec<_>>();
+        let kept = tasks
+            .iter()
+            .filter_map(|task| task.previous_id.as_deref())
+            .collect::<HashSet<_>>();
+        let removed_task_ids = previous_ids
+            .into_iter()
+            .filter(|id| !kept.contains(id.as_str()))
+            .collect::<Vec<_>>();
+
+        if let Some(mut state) = state {
+            let mut previous_tasks = std::mem::take(&mut state.tasks);
+            state.tasks = tasks
+                .iter()
+                .map(|task| {
+                    let previous = task.previous_id.as_deref().and_then(|previous_id| {
+                        previous_tasks
+                            .iter()
+                            .position(|entry| entry.id.trim() == previous_id)
+                            .map(|position| previous_tasks.swap_remove(position))
+                    });
+                    match previous {
+                        Some(mut entry) => {
+                            entry.id = task.id.clone();
+                            entry
+                        }
+                        None => new_task_state(task.id.clone(), &phase_ids),
+                    }
+                })
+                .collect();
+            write_state_file(&paths, &state)?;
+        }
+        let plan_out = serde_json::to_string_pretty(&plan_raw).map_err(|err| err.to_string())?;
+        write_file_atomically(&paths.plan_path, &format!("{plan_out}\n"))?;
+        (tasks, removed_task_ids)
+    };
+
+    let renames = tasks
+        .iter()
+        .filter_map(|task| Some((task.previous_id.clone()?, task.id.clone())))
+        .collect::<Vec<_>>();
+    remap_run_logs(&paths.plan_dir, &renames, &removed_task_ids)?;
+
+    if paths.state_path.is_file() {
+        let context = build_hook_context(&paths);
+        run_template_hook(&paths.post_step_hook, &paths.workspace_root, &context, None).await?;
+    }
+
+    Ok(ForgePlanUpdateResultV1 {
+        plan_id: paths.plan_id,
+        tasks,
+        removed_task_ids,
+    })
+}
@@ -0,0 +1,407 @@
+use super::*;
+
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeDanglingDependencyV1 {
+    pub(crate) task_id: String,
+    pub(crate) depends_on: String,
+}
+
+/// Why a task cannot be picked up right now, in the order the checks are made.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub(crate) enum ForgeTaskBlockedReasonV1 {
+    /// `state.json` exists but has no entry for the task.
+    MissingState,
+    Completed,
+    RetryExhausted {
+        phase_ids: Vec<String>,
+    },
+    InCycle {
+        task_ids: Vec<String>,
+    },
+    UnknownDependencies {
+        task_ids: Vec<String>,
+    },
+    /// A dependency ran out of retries, so the task can never start without a rollback.
+    DependencyFailed {
+        task_ids: Vec<String>,
+    },
+    /// Dependencies that are not completed and merged yet.
+    WaitingOnDependencies {
+        task_ids: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgePlanGraphTaskV1 {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    /// Task status from `state.json`, or `None` before the plan is prepared.
+    pub(crate) status: Option<String>,
+    pub(crate) depends_on: Vec<String>,
+    pub(crate) dependents: Vec<String>,
+    pub(crate) runnable: bool,
+    pub(crate) blocked_reason: Option<ForgeTaskBlockedReasonV1>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgePlanAnalysisV1 {
+    pub(crate) plan_id: String,
+    pub(crate) tasks: Vec<ForgePlanGraphTaskV1>,
+    /// Each cycle lists its tasks in plan order. A task that depends on itself is a cycle of one.
+    pub(crate) cycles: Vec<Vec<String>>,
+    pub(crate) dangling_dependencies: Vec<ForgeDanglingDependencyV1>,
+    /// Longest dependency chain among the tasks still to be merged, first task first.
+    pub(crate) critical_path: Vec<String>,
+}
+
+fn task_dependencies(task: &PlanTaskV1) -> impl Iterator<Item = &str> {
+    task.depends_on
+        .iter()
+        .map(|dep| dep.trim())
+        .filter(|dep| !dep.is_empty())
+}
+
+/// Tarjan's strongly connected components over the known dependency edges.
+struct CycleFinder<'a> {
+    edges: Vec<Vec<usize>>,
+    index: Vec<Option<usize>>,
+    low_link: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    next_index: usize,
+    components: Vec<Vec<usize>>,
+    plan: &'a PlanV1,
+}
+
+impl<'a> CycleFinder<'a> {
+    fn new(plan: &'a PlanV1) -> Self {
+        let positions = plan
+            .tasks
+            .iter()
+            .enumerate()
+            .map(|(position, task)| (task.id.trim(), position))
+            .collect::<HashMap<_, _>>();
+        let edges = plan
+            .tasks
+            .iter()
+            .map(|task| {
+                task_dependencies(task)
+                    .filter_map(|dep| positions.get(dep).copied())
+                    .collect()
+            })
+            .collect();
+        let count = plan.tasks.len();
+        Self {
+            edges,
+            index: vec![None; count],
+            low_link: vec![0; count],
+            on_stack: vec![false; count],
+            stack: Vec::new(),
+            next_index: 0,
+            components: Vec::new(),
+            plan,
+        }
+    }
+
+    fn visit(&mut self, node: usize) {
+        self.index[node] = Some(self.next_index);
+        self.low_link[node] = self.next_index;
+        self.next_index += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+
+        for next in self.edges[node].clone() {
+            match self.index[next] {
+                None => {
+                    self.visit(next);
+                    self.low_link[node] = self.low_link[node].min(self.low_link[next]);
+                }
+                Some(next_index) if self.on_stack[next] => {
+                    self.low_link[node] = self.low_link[node].min(next_index);
+                }
+                Some(_) => {}
+            }
+        }
+
+        if Some(self.low_link[node]) == self.index[node] {
+            let mut component = Vec::new();
+            while let Some(member) = self.stack.pop() {
+                self.on_stack[member] = false;
+                component.push(member);
+                if member == node {
+                    break;
+                }
+            }
+            let is_cycle = component.len() > 1 || self.edges[node].contains(&node);
+            if is_cycle {
+                component.sort_unstable();
+                self.components.push(component);
+            }
+        }
+    }
+
+    fn find(mut self) -> Vec<Vec<String>> {
+        for node in 0..self.plan.tasks.len() {
+            if self.index[node].is_none() {
+                self.visit(node);
+            }
+        }
+        self.components.sort_by_key(|component| component[0]);
+        self.components
+            .iter()
+            .map(|component| {
+                component
+                    .iter()
+                    .map(|&node| self.plan.tasks[node].id.trim().to_string())
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+fn find_dependency_cycles(plan: &PlanV1) -> Vec<Vec<String>> {
+    CycleFinder::new(plan).find()
+}
+
+fn find_dangling_dependencies(plan: &PlanV1) -> Vec<ForgeDanglingDependencyV1> {
+    let task_ids = plan
+        .tasks
+        .iter()
+        .map(|task| task.id.trim())
+        .collect::<HashSet<_>>();
+    plan.tasks
+        .iter()
+        .flat_map(|task| {
+            task_dependencies(task)
+                .filter(|dep| !task_ids.contains(dep))
+                .map(|dep| ForgeDanglingDependencyV1 {
+                    task_id: task.id.trim().to_string(),
+                    depends_on: dep.to_string(),
+                })
+        })
+        .collect()
+}
+
+/// Fails when `depends_on` forms a cycle or names an unknown task. Such a plan would otherwise
+/// stop with tasks left pending and no error.
+pub(super) fn check_plan_structure(plan: &PlanV1) -> Result<(), String> {
+    let mut errors = find_dependency_cycles(plan)
+        .into_iter()
+        .map(|cycle| match cycle.as_slice() {
+            [task_id] => format!("{task_id} depends on itself"),
+            _ => format!("dependency cycle between {}", cycle.join(", ")),
+        })
+        .collect::<Vec<_>>();
+    errors.extend(
+        find_dangling_dependencies(plan)
+            .into_iter()
+            .map(|dangling| {
+                format!(
+                    "{} depends on unknown task {}",
+                    dangling.task_id, dangling.depends_on
+                )
+            }),
+    );
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(format!(
+        "Plan {} has dependency errors: {}. Run forge_analyze_plan for details.",
+        plan.id.trim(),
+        errors.join("; ")
+    ))
+}
+
+/// Longest chain of unmerged tasks, skipping tasks in a cycle. Ties go to the task that comes
+/// first in the plan.
+fn find_critical_path(
+    plan: &PlanV1,
+    remaining: &HashSet<&str>,
+    in_cycle: &HashSet<&str>,
+) -> Vec<String> {
+    fn chain_length<'a>(
+        task_id: &'a str,
+        tasks_by_id: &HashMap<&'a str, &'a PlanTaskV1>,
+        lengths: &mut HashMap<&'a str, (usize, Option<&'a str>)>,
+    ) -> usize {
+        if let Some((length, _)) = lengths.get(task_id) {
+            return *length;
+        }
+        let mut best = (1, None);
+        for dep in task_dependencies(tasks_by_id[task_id]) {
+            if !tasks_by_id.contains_key(dep) {
+                continue;
+            }
+            let length = chain_length(dep, tasks_by_id, lengths) + 1;
+            if length > best.0 {
+                best = (length, Some(dep));
+            }
+        }
+        lengths.insert(task_id, best);
+        best.0
+    }
+
+    let tasks_by_id = plan
+        .tasks
+        .iter()
+        .filter(|task| remaining.contains(task.id.trim()) && !in_cycle.contains(task.id.trim()))
+        .map(|task| (task.id.trim(), task))
+        .collect::<HashMap<_, _>>();
+    let mut lengths = HashMap::new();
+    let mut end: Option<(usize, &str)> = None;
+    for task in &plan.tasks {
+        let task_id = task.id.trim();
+        if !tasks_by_id.contains_key(task_id) {
+            continue;
+        }
+        let length = chain_length(task_id, &tasks_by_id, &mut lengths);
+        if end.is_none_or(|(best, _)| length > best) {
+            end = Some((length, task_id));
+        }
+    }
+
+    let mut path = Vec::new();
+    let mut next = end.map(|(_, task_id)| task_id);
+    while let Some(task_id) = next {
+        path.push(task_id.to_string());
+        next = lengths.get(task_id).and_then(|(_, dep)| *dep);
+    }
+    path.reverse();
+    path
+}
+
+/// Describes the plan's dependency graph and why each task is or is not runnable. Works before
+/// `forge_prepare_execution`, in which case every task counts as pending.
+pub(crate) fn forge_analyze_plan_core(
+    workspace_root: &Path,
+    plan_id: &str,
+) -> Result<ForgePlanAnalysisV1, String> {
+    let paths = build_execution_paths(workspace_root, plan_id)?;
+    require_plan_file(&paths.plan_path)?;
+    let plan = load_plan(&paths)?;
+    let state = if paths.state_path.is_file() {
+        Some(load_state(&paths)?)
+    } else {
+        None
+    };
+    let state_tasks_by_id = state.as_ref().map(map_state_tasks).unwrap_or_default();
+
+    let cycles = find_dependency_cycles(&plan);
+    let dangling_dependencies = find_dangling_dependencies(&plan);
+    let in_cycle = cycles
+        .iter()
+        .flatten()
+        .map(String::as_str)
+        .collect::<HashSet<_>>();
+    let remaining = plan
+        .tasks
+        .iter()
+        .map(|task| task.id.trim())
+        .filter(|task_id| {
+            !state_tasks_by_id
+                .get(task_id)
+                .is_some_and(|state_task| is_task_merged(state_task))
+        })
+        .collect::<HashSet<_>>();
+    let critical_path = find_critical_path(&plan, &remaining, &in_cycle);
+
+    let blocked_reason = |task: &PlanTaskV1| -> Option<ForgeTaskBlockedReasonV1> {
+        let task_id = task.id.trim();
+        let state_task = state_tasks_by_id.get(task_id);
+        if state.is_some() {
+            let Some(state_task) = state_task else {
+                return Some(ForgeTaskBlockedReasonV1::MissingState);
+            };
+            if is_task_merged(state_task) {
+                return Some(ForgeTaskBlockedReasonV1::Completed);
+            }
+            if is_task_retry_exhausted(state_task) {
+                let phase_ids = state_task
+                    .phases
+                    .iter()
+                    .filter(|phase| phase.status.trim() == "failed")
+                    .map(|phase| phase.id.clone())
+                    .collect();
+                return Some(ForgeTaskBlockedReasonV1::RetryExhausted { phase_ids });
+            }
+        }
+        if let Some(cycle) = cycles
+            .iter()
+            .find(|cycle| cycle.iter().any(|id| id == task_id))
+        {
+            return Some(ForgeTaskBlockedReasonV1::InCycle {
+                task_ids: cycle.clone(),
+            });
+        }
+        let unknown = dangling_dependencies
+            .iter()
+            .filter(|dangling| dangling.task_id == task_id)
+            .map(|dangling| dangling.depends_on.clone())
+            .collect::<Vec<_>>();
+        if !unknown.is_empty() {
+            return Some(ForgeTaskBlockedReasonV1::UnknownDependencies { task_ids: unknown });
+        }
+        let failed = task_dependencies(task)
+            .filter(|dep| {
+                state_tasks_by_id
+                    .get(dep)
+                    .is_some_and(|state_task| is_task_retry_exhausted(state_task))
+            })
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        if !failed.is_empty() {
+            return Some(ForgeTaskBlockedReasonV1::DependencyFailed { task_ids: failed });
+        }
+        let waiting = task_dependencies(task)
+            .filter(|dep| remaining.contains(dep))
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        if !waiting.is_empty() {
+            return Some(ForgeTaskBlockedReasonV1::WaitingOnDependencies { task_ids: waiting });
+        }
+        None
+    };
+
+    let tasks = plan
+        .tasks
+        .iter()
+        .map(|task| {
+            let task_id = task.id.trim();
+            let reason = blocked_reason(task);
+            ForgePlanGraphTaskV1 {
+                id: task_id.to_string(),
+                name: task.name.clone(),
+                status: state_tasks_by_id
+                    .get(task_id)
+                    .map(|state_task| state_task.status.clone()),
+                depends_on: task_dependencies(task).map(str::to_string).collect(),
+                dependents: plan
+                    .tasks
+                    .iter()
+                    .filter(|other| task_dependencies(other).any(|dep| dep == task_id))
+                    .map(|other| other.id.trim().to_string())
+                    .collect(),
+                runnable: reason.is_none(),
+                blocked_reason: reason,
+            }
+        })
+        .collect();
+
+    Ok(ForgePlanAnalysisV1 {
+        plan_id: paths.plan_id,
+        tasks,
+        cycles,
+        dangling_dependencies,
+        critical_path,
+    })
+}
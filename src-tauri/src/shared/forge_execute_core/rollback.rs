@@ -0,0 +1,270 @@
+use super::*;
+
+use std::collections::HashSet;
+
+use super::scheduler::{require_task_id, resolve_head_sha};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeRolledBackTaskV1 {
+    pub(crate) task_id: String,
+    /// The newest task or phase commit recorded in `state.json` before the rollback.
+    pub(crate) commit_sha: Option<String>,
+    /// The last commit undoing the task's changes, when there was something to revert. Tasks
+    /// committed per phase get one revert per phase commit.
+    pub(crate) revert_sha: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeTaskRollbackResultV1 {
+    pub(crate) plan_id: String,
+    pub(crate) task_id: String,
+    /// Rolled-back tasks in the order they were undone: dependents first, the target task last.
+    pub(crate) tasks: Vec<ForgeRolledBackTaskV1>,
+}
+
+/// Tasks that depend on `task_id` directly or through other tasks, in plan order.
+fn collect_dependent_task_ids(plan: &PlanV1, task_id: &str) -> Vec<String> {
+    let mut affected = HashSet::from([task_id.to_string()]);
+    let mut dependents = Vec::new();
+    // Plan validation keeps dependencies ahead of their dependents, so one pass is enough.
+    for task in &plan.tasks {
+        let id = task.id.trim();
+        if affected.contains(id) {
+            continue;
+        }
+        if task
+            .depends_on
+            .iter()
+            .any(|dep| affected.contains(dep.trim()))
+        {
+            affected.insert(id.to_string());
+            dependents.push(id.to_string());
+        }
+    }
+    dependents
+}
+
+fn task_has_progress(task: &StateTaskV2) -> bool {
+    !task_commit_shas(task).is_empty()
+        || task.worktree.is_some()
+        || task.status.trim() != "pending"
+        || task
+            .phases
+            .iter()
+            .any(|phase| phase.status.trim() != "pending")
+}
+
+fn reset_task_state(task: &mut StateTaskV2, note: String) {
+    task.status = "pending".to_string();
+    task.attempts = 0;
+    task.notes = note;
+    task.commit_sha = None;
+    // The worktree holds the reverted branch; the next run picks or creates one afresh.
+    task.worktree = None;
+    for phase in &mut task.phases {
+        phase.status = "pending".to_string();
+        phase.attempts = 0;
+        phase.notes = String::new();
+        phase.commit_sha = None;
+        phase.approval = None;
+    }
+}
+
+async fn is_commit_in_history(repo_root: &Path, sha: &str) -> Result<bool, String> {
+    let output =
+        run_git_command_with_timeout(repo_root, &["merge-base", "--is-ancestor", sha, "HEAD"])
+            .await?;
+    match output.status.code() {
+        Some(0) => Ok(true),
+        Some(1) => Ok(false),
+        _ => Err(format_process_error(&output.stdout, &output.stderr)),
+    }
+}
+
+async fn require_clean_index(repo_root: &Path, plan_id: &str) -> Result<(), String> {
+    let exclude = outside_plan_dir_pathspec(plan_id);
+    let output = run_git_command_with_timeout(
+        repo_root,
+        &["status", "--porcelain", "--untracked-files=no", "--", ".", &exclude],
+    )
+    .await?;
+    if !output.status.success() {
+        return Err(format_process_error(&output.stdout, &output.stderr));
+    }
+    if !String::from_utf8_lossy(&output.stdout).trim().is_empty() {
+        return Err(format!(
+            "{} has uncommitted changes. Commit or stash them before rolling back.",
+            repo_root.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Commits the inverse of the task commit outside the plan folder, with the commit policy's
+/// trailers, sign-off and signing. Returns `None` when the commit is not in the checked-out
+/// history or only touched plan files.
+async fn revert_task_commit(
+    repo_root: &Path,
+    plan_id: &str,
+    task: &PlanTaskV1,
+    sha: &str,
+    policy: &ForgeCommitPolicyV1,
+) -> Result<Option<String>, String> {
+    if !is_commit_in_history(repo_root, sha).await? {
+        return Ok(None);
+    }
+    let exclude = outside_plan_dir_pathspec(plan_id);
+    let show_output = run_git_command_with_timeout(
+        repo_root,
+        &[
+            "show", "--binary", "--no-color", "--no-ext-diff", "--format=", sha, "--", ".",
+            &exclude,
+        ],
+    )
+    .await?;
+    if !show_output.status.success() {
+        return Err(format_process_error(&show_output.stdout, &show_output.stderr));
+    }
+    if show_output.stdout.iter().all(u8::is_ascii_whitespace) {
+        return Ok(None);
+    }
+
+    let patch_path =
+        std::env::temp_dir().join(format!("codex-monitor-forge-rollback-{}.patch", Uuid::new_v4()));
+    fs::write(&patch_path, &show_output.stdout).map_err(|err| err.to_string())?;
+    let patch_arg = patch_path.to_string_lossy().to_string();
+    let apply_output =
+        run_git_command_with_timeout(repo_root, &["apply", "-R", "--index", &patch_arg]).await;
+    let _ = fs::remove_file(&patch_path);
+    let apply_output = apply_output?;
+    if !apply_output.status.success() {
+        let detail = format_process_error(&apply_output.stdout, &apply_output.stderr);
+        return Err(format!("Reverting {sha} failed: {detail}"));
+    }
+
+    let message = policy.revert_message_for(plan_id, task, sha);
+    let flags = policy.commit_flags();
+    let mut args = vec!["commit", "-m", &message];
+    args.extend(flags.iter().map(String::as_str));
+    let commit_output = run_git_command_with_timeout(repo_root, &args).await?;
+    if !commit_output.status.success() {
+        let detail = format_process_error(&commit_output.stdout, &commit_output.stderr);
+        return Err(format!("Committing the revert of {sha} failed: {detail}"));
+    }
+    resolve_head_sha(repo_root).await.map(Some)
+}
+
+/// Reverts the task's recorded commit and resets the task to `pending`. Dependents that already
+/// started are rolled back first when `cascade` is set; otherwise their progress blocks the call.
+pub(crate) async fn forge_rollback_task_core(
+    workspace_root: &Path,
+    plan_id: &str,
+    task_id: &str,
+    cascade: bool,
+) -> Result<ForgeTaskRollbackResultV1, String> {
+    let paths = build_execution_paths(workspace_root, plan_id)?;
+    let task_id = require_task_id(task_id)?;
+    require_plan_file(&paths.plan_path)?;
+    if !paths.state_path.is_file() {
+        return Err(format!("Missing state.json: {}", paths.state_path.display()));
+    }
+
+    let plan = load_plan(&paths)?;
+    if !plan.tasks.iter().any(|task| task.id.trim() == task_id) {
+        return Err(format!("Unknown taskId: {task_id}"));
+    }
+    let state = load_state(&paths)?;
+    let dependents = collect_dependent_task_ids(&plan, task_id);
+    let started_dependents = dependents
+        .iter()
+        .filter(|id| {
+            state
+                .tasks
+                .iter()
+                .any(|task| task.id.trim() == id.as_str() && task_has_progress(task))
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    if !cascade && !started_dependents.is_empty() {
+        return Err(format!(
+            "Tasks {} build on {task_id}. Roll them back first or pass cascade.",
+            started_dependents.join(", ")
+        ));
+    }
+
+    // Newest work is undone first so each revert applies on top of an unchanged tree.
+    let mut rollback_order = dependents;
+    rollback_order.reverse();
+    rollback_order.push(task_id.to_string());
+
+    let mut checked_roots = HashSet::new();
+    for id in &rollback_order {
+        let Some(task) = state.tasks.iter().find(|task| task.id.trim() == id.as_str()) else {
+            continue;
+        };
+        if !task_commit_shas(task).is_empty() {
+            let root = task_execution_root(&paths, task);
+            if checked_roots.insert(root.clone()) {
+                require_clean_index(&root, &paths.plan_id).await?;
+            }
+        }
+    }
+
+    let commit_policy = load_commit_policy(&paths, load_template_phases(&paths)?.commit.as_ref())?;
+    let rolled_back_at = Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    let mut tasks = Vec::new();
+    for id in &rollback_order {
+        let Some(task) = state.tasks.iter().find(|task| task.id.trim() == id.as_str()) else {
+            continue;
+        };
+        let Some(plan_task) = plan.tasks.iter().find(|task| task.id.trim() == id.as_str()) else {
+            continue;
+        };
+        let shas = task_commit_shas(task);
+        let commit_sha = shas.last().cloned();
+        let root = task_execution_root(&paths, task);
+        let mut reverted = Vec::new();
+        let mut revert_sha = None;
+        for sha in shas.iter().rev() {
+            // Tasks reverted earlier were already written back, so an error here leaves
+            // `state.json` matching the commits that landed.
+            let revert =
+                revert_task_commit(&root, &paths.plan_id, plan_task, sha, &commit_policy).await?;
+            if let Some(revert) = revert {
+                reverted.push(sha.clone());
+                revert_sha = Some(revert);
+            }
+        }
+        let note = match (&commit_sha, &revert_sha) {
+            (_, Some(revert)) => format!(
+                "Rolled back at {rolled_back_at}: reverted {} in {revert}.",
+                reverted.join(", ")
+            ),
+            (Some(sha), None) => {
+                format!("Rolled back at {rolled_back_at}: nothing to revert for {sha}.")
+            }
+            (None, _) => format!("Rolled back at {rolled_back_at}."),
+        };
+        update_task_state(&paths, id, |task| {
+            reset_task_state(task, note);
+            Ok(())
+        })
+        .await?;
+        tasks.push(ForgeRolledBackTaskV1 {
+            task_id: id.clone(),
+            commit_sha,
+            revert_sha,
+        });
+    }
+
+    let context = build_hook_context(&paths);
+    run_template_hook(&paths.post_step_hook, &paths.workspace_root, &context, None).await?;
+
+    Ok(ForgeTaskRollbackResultV1 {
+        plan_id: paths.plan_id,
+        task_id: task_id.to_string(),
+        tasks,
+    })
+}
@@ -0,0 +1,544 @@
+use super::*;
+
+use std::sync::{Arc, Mutex as StdMutex};
+use tokio::sync::{mpsc, Mutex};
+
+use crate::backend::app_server::WorkspaceSession;
+use crate::shared::codex_core;
+
+const TURN_TIMEOUT_SECONDS: u64 = 30 * 60;
+const CHECK_RETRY_DELAY_MS: u64 = 1200;
+const APPROVAL_POLL_INTERVAL_MS: u64 = 2000;
+
+type ForgeRunKey = (String, String);
+
+/// App or daemon state the driver reaches from its background task, after the start request
+/// has returned.
+pub(crate) trait ForgeRunHost: Clone + Send + Sync + 'static {
+    type Sink: EventSink;
+
+    fn sessions(&self) -> &Mutex<HashMap<String, Arc<WorkspaceSession>>>;
+    fn workspaces(&self) -> &Mutex<HashMap<String, WorkspaceEntry>>;
+    fn forge_execution_cancels(&self) -> &ForgeExecutionCancels;
+    fn forge_runs(&self) -> &ForgeRuns;
+    fn event_sink(&self) -> Self::Sink;
+}
+
+/// Backend-driven runs keyed by workspace and plan. A finished run stays visible to
+/// `forge_run_status` until the plan is started again.
+#[derive(Default)]
+pub(crate) struct ForgeRuns {
+    runs: StdMutex<HashMap<ForgeRunKey, Arc<ForgeRunHandle>>>,
+}
+
+struct ForgeRunHandle {
+    status: StdMutex<ForgeRunStatusV1>,
+    stop: ForgeCancelToken,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeStartRunOptionsV1 {
+    /// Sandbox and approval mode for every turn. Unattended runs want `full-access`; other
+    /// modes stop at approval requests until a client answers them.
+    #[serde(default)]
+    pub(crate) access_mode: Option<String>,
+    #[serde(default)]
+    pub(crate) model: Option<String>,
+    #[serde(default)]
+    pub(crate) effort: Option<String>,
+    #[serde(default)]
+    pub(crate) collaboration_mode: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeRunStatusV1 {
+    pub(crate) workspace_id: String,
+    pub(crate) plan_id: String,
+    /// `running`, `stopping`, `stopped`, `completed`, `halted` or `failed`. A run is `halted`
+    /// when the plan's budget ran out; `error` then holds the budget note.
+    pub(crate) state: String,
+    pub(crate) task_id: Option<String>,
+    pub(crate) phase_id: Option<String>,
+    pub(crate) thread_id: Option<String>,
+    pub(crate) turn_id: Option<String>,
+    pub(crate) completed_phases: u32,
+    /// The run is paused on a `manual-approval` phase until a reviewer decides.
+    #[serde(default)]
+    pub(crate) awaiting_approval: bool,
+    pub(crate) started_at: String,
+    pub(crate) updated_at: String,
+    pub(crate) finished_at: Option<String>,
+    pub(crate) error: Option<String>,
+}
+
+enum ForgeRunOutcome {
+    Completed,
+    Stopped,
+    Halted(String),
+}
+
+struct ForgeRunContext {
+    workspace_id: String,
+    workspace_root: PathBuf,
+    plan_id: String,
+    options: ForgeStartRunOptionsV1,
+}
+
+fn run_key(workspace_id: &str, plan_id: &str) -> ForgeRunKey {
+    (workspace_id.trim().to_string(), plan_id.trim().to_string())
+}
+
+fn now_rfc3339() -> String {
+    Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+fn is_active_run_state(state: &str) -> bool {
+    matches!(state, "running" | "stopping")
+}
+
+fn is_failed_phase_status(status: &str) -> bool {
+    matches!(
+        status.trim().to_ascii_lowercase().as_str(),
+        "failed" | "blocked" | "error" | "canceled" | "cancelled"
+    )
+}
+
+impl ForgeRuns {
+    fn get(&self, workspace_id: &str, plan_id: &str) -> Option<Arc<ForgeRunHandle>> {
+        let runs = self.runs.lock().ok()?;
+        runs.get(&run_key(workspace_id, plan_id)).cloned()
+    }
+
+    fn start(&self, workspace_id: &str, plan_id: &str) -> Result<Arc<ForgeRunHandle>, String> {
+        let mut runs = self
+            .runs
+            .lock()
+            .map_err(|_| "Forge run registry is unavailable".to_string())?;
+        let key = run_key(workspace_id, plan_id);
+        if runs
+            .get(&key)
+            .is_some_and(|run| is_active_run_state(&run.snapshot().state))
+        {
+            return Err(format!("Plan {plan_id} is already running."));
+        }
+        let now = now_rfc3339();
+        let handle = Arc::new(ForgeRunHandle {
+            status: StdMutex::new(ForgeRunStatusV1 {
+                workspace_id: key.0.clone(),
+                plan_id: key.1.clone(),
+                state: "running".to_string(),
+                task_id: None,
+                phase_id: None,
+                thread_id: None,
+                turn_id: None,
+                completed_phases: 0,
+                awaiting_approval: false,
+                started_at: now.clone(),
+                updated_at: now,
+                finished_at: None,
+                error: None,
+            }),
+            stop: ForgeCancelToken::default(),
+        });
+        runs.insert(key, Arc::clone(&handle));
+        Ok(handle)
+    }
+}
+
+impl ForgeRunHandle {
+    fn snapshot(&self) -> ForgeRunStatusV1 {
+        match self.status.lock() {
+            Ok(status) => status.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    fn update(&self, apply: impl FnOnce(&mut ForgeRunStatusV1)) {
+        if let Ok(mut status) = self.status.lock() {
+            apply(&mut status);
+            status.updated_at = now_rfc3339();
+        }
+    }
+
+    fn finish(&self, result: Result<ForgeRunOutcome, String>) {
+        self.update(|status| {
+            match result {
+                Ok(ForgeRunOutcome::Completed) => status.state = "completed".to_string(),
+                Ok(ForgeRunOutcome::Stopped) => status.state = "stopped".to_string(),
+                Ok(ForgeRunOutcome::Halted(note)) => {
+                    status.state = "halted".to_string();
+                    status.error = Some(note);
+                }
+                Err(error) => {
+                    status.state = "failed".to_string();
+                    status.error = Some(error);
+                }
+            }
+            status.turn_id = None;
+            status.finished_at = Some(now_rfc3339());
+        });
+    }
+}
+
+fn extract_started_thread_id(response: &Value) -> Result<String, String> {
+    let result = response.get("result").unwrap_or(response);
+    result
+        .get("threadId")
+        .or_else(|| result.get("thread").and_then(|thread| thread.get("id")))
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| format!("thread/start response is missing a thread id: {response}"))
+}
+
+fn extract_turn_id(response: &Value) -> Option<String> {
+    let result = response.get("result").unwrap_or(response);
+    result
+        .get("turn")
+        .and_then(|turn| turn.get("id"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+fn response_error(response: &Value, fallback: &str) -> Option<String> {
+    let error = response.get("error")?;
+    Some(
+        error
+            .get("message")
+            .and_then(Value::as_str)
+            .unwrap_or(fallback)
+            .to_string(),
+    )
+}
+
+async fn wait_for_turn_end(events: &mut mpsc::UnboundedReceiver<Value>) -> Result<(), String> {
+    while let Some(event) = events.recv().await {
+        match event.get("method").and_then(Value::as_str) {
+            Some("turn/completed") => return Ok(()),
+            Some("turn/error") => {
+                let message = event
+                    .get("params")
+                    .and_then(|params| params.get("error"))
+                    .and_then(Value::as_str)
+                    .unwrap_or("Turn failed");
+                return Err(message.to_string());
+            }
+            _ => {}
+        }
+    }
+    Err("App-server event stream closed before the turn finished.".to_string())
+}
+
+/// Sleeps for `delay_ms` unless the run is stopped first. Returns `true` when stopped.
+async fn wait_unless_stopped(stop: &ForgeCancelToken, delay_ms: u64) -> bool {
+    let delay = pin!(tokio::time::sleep(Duration::from_millis(delay_ms)));
+    let stopped = pin!(stop.cancelled());
+    matches!(select(delay, stopped).await, Either::Right(_))
+}
+
+/// Sends the phase prompt and waits for the turn to end. Returns `true` when the run was
+/// stopped mid-turn, after interrupting the turn.
+async fn run_phase_turn<H: ForgeRunHost>(
+    host: &H,
+    run: &ForgeRunHandle,
+    context: &ForgeRunContext,
+    thread_id: &str,
+    prompt_text: String,
+) -> Result<bool, String> {
+    let session = host
+        .sessions()
+        .lock()
+        .await
+        .get(&context.workspace_id)
+        .cloned()
+        .ok_or_else(|| "workspace not connected".to_string())?;
+    let (events_tx, mut events_rx) = mpsc::unbounded_channel::<Value>();
+    session
+        .thread_observers
+        .lock()
+        .await
+        .insert(thread_id.to_string(), events_tx);
+
+    let result = async {
+        let options = &context.options;
+        let response = codex_core::send_user_message_core(
+            host.sessions(),
+            context.workspace_id.clone(),
+            thread_id.to_string(),
+            prompt_text,
+            options.model.clone(),
+            options.effort.clone(),
+            options.access_mode.clone(),
+            None,
+            None,
+            options.collaboration_mode.clone(),
+        )
+        .await?;
+        if let Some(error) = response_error(&response, "Failed to start the phase turn") {
+            return Err(error);
+        }
+        let turn_id = extract_turn_id(&response);
+        run.update(|status| status.turn_id = turn_id.clone());
+
+        let turn_end = pin!(timeout(
+            Duration::from_secs(TURN_TIMEOUT_SECONDS),
+            wait_for_turn_end(&mut events_rx),
+        ));
+        let stopped = pin!(run.stop.cancelled());
+        match select(turn_end, stopped).await {
+            Either::Left((Ok(turn_result), _)) => turn_result.map(|()| false),
+            Either::Left((Err(_), _)) => Err(format!(
+                "Turn on thread {thread_id} did not finish within {} minutes.",
+                TURN_TIMEOUT_SECONDS / 60
+            )),
+            Either::Right(((), _)) => {
+                if let Some(turn_id) = turn_id {
+                    let _ = codex_core::turn_interrupt_core(
+                        host.sessions(),
+                        context.workspace_id.clone(),
+                        thread_id.to_string(),
+                        turn_id,
+                    )
+                    .await;
+                }
+                Ok(true)
+            }
+        }
+    }
+    .await;
+
+    session.thread_observers.lock().await.remove(thread_id);
+    run.update(|status| status.turn_id = None);
+    result
+}
+
+async fn drive_run_phases<H: ForgeRunHost>(
+    host: &H,
+    run: &ForgeRunHandle,
+    context: &ForgeRunContext,
+) -> Result<ForgeRunOutcome, String> {
+    let event_sink = host.event_sink();
+    let mut threads_by_task = HashMap::<String, String>::new();
+
+    loop {
+        if run.stop.is_cancelled() {
+            return Ok(ForgeRunOutcome::Stopped);
+        }
+        let next = forge_get_next_phase_prompt_core(
+            host.workspaces(),
+            &context.workspace_root,
+            &context.plan_id,
+            &context.workspace_id,
+            &event_sink,
+            host.forge_execution_cancels(),
+        )
+        .await?;
+        let Some(phase) = next else {
+            return Ok(ForgeRunOutcome::Completed);
+        };
+        let task_id = phase.task_id.trim().to_string();
+        let phase_id = phase.phase_id.trim().to_string();
+        if phase.awaiting_approval {
+            run.update(|status| {
+                status.task_id = Some(task_id.clone());
+                status.phase_id = Some(phase_id.clone());
+                status.awaiting_approval = true;
+            });
+            // The approve and reject commands move the task on; the run only waits for them.
+            while is_phase_awaiting_approval(
+                &context.workspace_root,
+                &context.plan_id,
+                &task_id,
+                &phase_id,
+            )? {
+                if wait_unless_stopped(&run.stop, APPROVAL_POLL_INTERVAL_MS).await {
+                    return Ok(ForgeRunOutcome::Stopped);
+                }
+            }
+            run.update(|status| status.awaiting_approval = false);
+            continue;
+        }
+        if let Some(note) = phase.budget_exceeded {
+            run.update(|status| {
+                status.task_id = Some(task_id.clone());
+                status.phase_id = Some(phase_id.clone());
+            });
+            return Ok(ForgeRunOutcome::Halted(note));
+        }
+
+        // Each task gets its own thread; later phases of the task continue the conversation.
+        let thread_id = match threads_by_task.get(&task_id) {
+            Some(thread_id) => thread_id.clone(),
+            None => {
+                let response =
+                    codex_core::start_thread_core(host.sessions(), context.workspace_id.clone())
+                        .await?;
+                if let Some(error) = response_error(&response, "Failed to start a thread") {
+                    return Err(error);
+                }
+                let thread_id = extract_started_thread_id(&response)?;
+                threads_by_task.insert(task_id.clone(), thread_id.clone());
+                thread_id
+            }
+        };
+        run.update(|status| {
+            status.task_id = Some(task_id.clone());
+            status.phase_id = Some(phase_id.clone());
+            status.thread_id = Some(thread_id.clone());
+        });
+
+        if run_phase_turn(host, run, context, &thread_id, phase.prompt_text).await? {
+            return Ok(ForgeRunOutcome::Stopped);
+        }
+
+        let phase_status = forge_get_phase_status_core(
+            &context.workspace_root,
+            &context.plan_id,
+            &task_id,
+            &phase_id,
+        )?;
+        if is_failed_phase_status(&phase_status.status) {
+            return Err(format!(
+                "Phase {task_id}/{phase_id} reached terminal status \"{}\" before completion.",
+                phase_status.status
+            ));
+        }
+
+        let checks = forge_run_phase_checks_core(
+            &context.workspace_root,
+            ForgeRunPhaseChecksRequest {
+                plan_id: &context.plan_id,
+                task_id: &task_id,
+                phase_id: &phase_id,
+                thread_id: Some(&thread_id),
+            },
+            &context.workspace_id,
+            &event_sink,
+            host.forge_execution_cancels(),
+        )
+        .await?;
+        if checks.cancelled || run.stop.is_cancelled() {
+            return Ok(ForgeRunOutcome::Stopped);
+        }
+        if checks.exhausted {
+            return Err(format!(
+                "Phase {task_id}/{phase_id} exhausted its retry budget after {} attempts.",
+                checks.attempts
+            ));
+        }
+        if checks.ok {
+            run.update(|status| status.completed_phases += 1);
+            continue;
+        }
+        // The phase's `maxAttempts` bounds the retries; without one the run keeps retrying.
+        if is_task_blocked(&context.workspace_root, &context.plan_id, &task_id)? {
+            return Err(format!(
+                "Task {task_id} became blocked after phase {phase_id} failed its checks."
+            ));
+        }
+        let delay_ms = checks
+            .retry_after_ms
+            .unwrap_or(0)
+            .max(CHECK_RETRY_DELAY_MS);
+        if wait_unless_stopped(&run.stop, delay_ms).await {
+            return Ok(ForgeRunOutcome::Stopped);
+        }
+    }
+}
+
+fn is_task_blocked(workspace_root: &Path, plan_id: &str, task_id: &str) -> Result<bool, String> {
+    let paths = build_execution_paths(workspace_root, plan_id)?;
+    let state = load_state(&paths)?;
+    Ok(state
+        .tasks
+        .iter()
+        .find(|task| task.id.trim() == task_id)
+        .is_some_and(|task| task.status.trim() == "blocked"))
+}
+
+/// Starts executing the plan in a background task that outlives the calling client: each
+/// runnable phase gets its prompt sent on the task's thread, then its checks run once the
+/// turn ends. Fails when the workspace is not connected or the plan is already running.
+pub(crate) async fn forge_start_run_core<H: ForgeRunHost>(
+    host: H,
+    workspace_id: &str,
+    workspace_root: PathBuf,
+    plan_id: &str,
+    options: ForgeStartRunOptionsV1,
+) -> Result<ForgeRunStatusV1, String> {
+    let plan_id = validate_plan_id(plan_id)?;
+    let workspace_id = workspace_id.trim().to_string();
+    if workspace_id.is_empty() {
+        return Err("workspaceId is required".to_string());
+    }
+    if !host.sessions().lock().await.contains_key(&workspace_id) {
+        return Err("workspace not connected".to_string());
+    }
+
+    let run = host.forge_runs().start(&workspace_id, &plan_id)?;
+    if let Err(error) = forge_prepare_execution_core(
+        &workspace_root,
+        &plan_id,
+        &workspace_id,
+        host.forge_execution_cancels(),
+    )
+    .await
+    {
+        run.finish(Err(error.clone()));
+        return Err(error);
+    }
+
+    let context = ForgeRunContext {
+        workspace_id,
+        workspace_root,
+        plan_id,
+        options,
+    };
+    let driver_run = Arc::clone(&run);
+    tokio::spawn(async move {
+        let result = drive_run_phases(&host, &driver_run, &context).await;
+        driver_run.finish(result);
+    });
+    Ok(run.snapshot())
+}
+
+/// Asks the plan's run to stop: the current turn is interrupted and running checks are
+/// cancelled. The run reports `stopped` once the driver has wound down.
+pub(crate) fn forge_stop_run_core(
+    runs: &ForgeRuns,
+    cancels: &ForgeExecutionCancels,
+    workspace_id: &str,
+    plan_id: &str,
+) -> Result<ForgeRunStatusV1, String> {
+    let plan_id = validate_plan_id(plan_id)?;
+    let run = runs
+        .get(workspace_id, &plan_id)
+        .ok_or_else(|| format!("Plan {plan_id} has no Forge run."))?;
+    let mut was_active = false;
+    run.update(|status| {
+        if is_active_run_state(&status.state) {
+            status.state = "stopping".to_string();
+            was_active = true;
+        }
+    });
+    if was_active {
+        run.stop.cancel();
+        forge_cancel_execution_core(cancels, workspace_id, &plan_id)?;
+    }
+    Ok(run.snapshot())
+}
+
+/// Returns the plan's current or most recent backend run, if one was started since launch.
+pub(crate) fn forge_run_status_core(
+    runs: &ForgeRuns,
+    workspace_id: &str,
+    plan_id: &str,
+) -> Result<Option<ForgeRunStatusV1>, String> {
+    let plan_id = validate_plan_id(plan_id)?;
+    Ok(runs
+        .get(workspace_id, &plan_id)
+        .map(|run| run.snapshot()))
+}
@@ -0,0 +1,433 @@
+use super::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeTaskWorktreeV1 {
+    pub(crate) workspace_id: String,
+    pub(crate) path: String,
+    pub(crate) branch: String,
+    pub(crate) base_sha: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeRunnableTaskV1 {
+    pub(crate) task_id: String,
+    pub(crate) task_name: String,
+    pub(crate) phase_id: Option<String>,
+    pub(crate) is_last_phase: bool,
+    pub(crate) awaiting_merge: bool,
+    /// The next phase is a `manual-approval` phase no reviewer has signed off yet.
+    #[serde(default)]
+    pub(crate) awaiting_approval: bool,
+    pub(crate) commit_sha: Option<String>,
+    pub(crate) worktree: Option<ForgeTaskWorktreeV1>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeTaskMergeResultV1 {
+    pub(crate) ok: bool,
+    pub(crate) task_id: String,
+    pub(crate) commit_sha: Option<String>,
+    pub(crate) worktree_workspace_id: Option<String>,
+    pub(crate) message: String,
+}
+
+impl From<&StateTaskWorktreeV2> for ForgeTaskWorktreeV1 {
+    fn from(value: &StateTaskWorktreeV2) -> Self {
+        Self {
+            workspace_id: value.workspace_id.clone(),
+            path: value.path.clone(),
+            branch: value.branch.clone(),
+            base_sha: value.base_sha.clone(),
+        }
+    }
+}
+
+/// Every task whose dependencies are merged, including tasks that finished in a worktree and
+/// are waiting for their commit to land on the plan branch. Tasks out of retries are left out.
+fn find_runnable_tasks<'a>(plan: &'a PlanV1, state: &'a StateV2) -> Vec<&'a PlanTaskV1> {
+    let state_tasks_by_id = map_state_tasks(state);
+
+    plan.tasks
+        .iter()
+        .filter(|task| {
+            let Some(state_task) = state_tasks_by_id.get(task.id.as_str()) else {
+                return false;
+            };
+            !is_task_merged(state_task)
+                && !is_task_retry_exhausted(state_task)
+                && is_task_dependencies_satisfied(task, &state_tasks_by_id)
+        })
+        .collect()
+}
+
+pub(super) fn require_task_id(task_id: &str) -> Result<&str, String> {
+    let task_id = task_id.trim();
+    if task_id.is_empty() {
+        return Err("taskId is required".to_string());
+    }
+    Ok(task_id)
+}
+
+fn require_state_file(paths: &ForgeExecutionPaths) -> Result<(), String> {
+    if !paths.state_path.is_file() {
+        return Err(format!(
+            "Missing state.json: {}. Run forge_prepare_execution first.",
+            paths.state_path.display()
+        ));
+    }
+    Ok(())
+}
+
+fn build_task_hook_context(paths: &ForgeExecutionPaths, task_id: &str) -> ForgeHookContextV1 {
+    let mut context = build_hook_context(paths);
+    context.generated_execute_prompt_path = task_execute_prompt_path(paths, task_id)
+        .to_string_lossy()
+        .to_string();
+    context.task_id = Some(task_id.to_string());
+    context
+}
+
+/// Parallel tasks render into their own prompt file so concurrent runs don't clobber each other.
+fn task_execute_prompt_path(paths: &ForgeExecutionPaths, task_id: &str) -> PathBuf {
+    paths.plan_dir.join(format!("execute-prompt.{task_id}.md"))
+}
+
+pub(super) async fn resolve_head_sha(repo_root: &Path) -> Result<String, String> {
+    let output = run_git_command_with_timeout(repo_root, &["rev-parse", "HEAD"]).await?;
+    if !output.status.success() {
+        return Err(format_process_error(&output.stdout, &output.stderr));
+    }
+    let sha = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if sha.is_empty() {
+        return Err("Unable to resolve HEAD commit.".to_string());
+    }
+    Ok(sha)
+}
+
+/// Why the task cannot get a worktree: a broken plan, a task that is not runnable yet, or
+/// one that already runs elsewhere.
+fn check_task_attachable(
+    plan: &PlanV1,
+    state: &StateV2,
+    task_id: &str,
+    worktree_workspace_id: Option<&str>,
+) -> Result<(), String> {
+    check_plan_structure(plan)?;
+    if !find_runnable_tasks(plan, state)
+        .iter()
+        .any(|task| task.id.trim() == task_id)
+    {
+        return Err(format!("Task {task_id} is not runnable yet."));
+    }
+    let existing = state
+        .tasks
+        .iter()
+        .find(|task| task.id.trim() == task_id)
+        .and_then(|task| task.worktree.as_ref());
+    if let Some(existing) = existing {
+        if Some(existing.workspace_id.as_str()) != worktree_workspace_id {
+            return Err(format!(
+                "Task {task_id} already runs in worktree {}.",
+                existing.path
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the task can get a worktree before one is created for it, so a refused
+/// attach doesn't leave a worktree and branch behind.
+pub(crate) fn forge_check_task_worktree_core(
+    workspace_root: &Path,
+    plan_id: &str,
+    task_id: &str,
+) -> Result<(), String> {
+    let paths = build_execution_paths(workspace_root, plan_id)?;
+    let task_id = require_task_id(task_id)?;
+    require_plan_file(&paths.plan_path)?;
+    require_state_file(&paths)?;
+
+    check_task_attachable(&load_plan(&paths)?, &load_state(&paths)?, task_id, None)
+}
+
+pub(crate) fn forge_task_worktree_branch(plan_id: &str, task_id: &str) -> Result<String, String> {
+    let plan_id = validate_plan_id(plan_id)?;
+    let task_id = require_task_id(task_id)?;
+    Ok(format!("forge/{plan_id}/{task_id}"))
+}
+
+pub(crate) fn forge_get_runnable_tasks_core(
+    workspace_root: &Path,
+    plan_id: &str,
+) -> Result<Vec<ForgeRunnableTaskV1>, String> {
+    let paths = build_execution_paths(workspace_root, plan_id)?;
+    require_plan_file(&paths.plan_path)?;
+    require_state_file(&paths)?;
+
+    let plan = load_plan(&paths)?;
+    check_plan_structure(&plan)?;
+    let state = load_state(&paths)?;
+    let state_tasks_by_id = map_state_tasks(&state);
+    let phases = load_template_phases(&paths)?;
+
+    let runnable = find_runnable_tasks(&plan, &state)
+        .into_iter()
+        .filter_map(|task| {
+            let task_state = state_tasks_by_id.get(task.id.as_str())?;
+            let awaiting_merge = is_task_completed(task_state);
+            let (phase_id, is_last_phase) = match find_next_phase(task_state) {
+                Some((index, phase)) => {
+                    (Some(phase.id.clone()), index + 1 >= task_state.phases.len())
+                }
+                None if awaiting_merge => (None, true),
+                None => (Some("implementation".to_string()), true),
+            };
+            let awaiting_approval = phase_id
+                .as_deref()
+                .is_some_and(|phase_id| is_manual_approval_phase(&phases, phase_id));
+            Some(ForgeRunnableTaskV1 {
+                task_id: task.id.clone(),
+                task_name: task.name.clone(),
+                phase_id,
+                is_last_phase,
+                awaiting_merge,
+                awaiting_approval,
+                commit_sha: task_state.commit_sha.clone(),
+                worktree: task_state.worktree.as_ref().map(ForgeTaskWorktreeV1::from),
+            })
+        })
+        .collect();
+
+    Ok(runnable)
+}
+
+pub(crate) async fn forge_get_task_phase_prompt_core<E: EventSink>(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_root: &Path,
+    plan_id: &str,
+    task_id: &str,
+    workspace_id: &str,
+    event_sink: &E,
+) -> Result<Option<ForgeNextPhasePromptV1>, String> {
+    let paths = build_execution_paths(workspace_root, plan_id)?;
+    let task_id = require_task_id(task_id)?;
+    require_plan_file(&paths.plan_path)?;
+    require_state_file(&paths)?;
+
+    let plan = load_plan(&paths)?;
+    check_plan_structure(&plan)?;
+    let state = load_state(&paths)?;
+    let Some(task) = find_runnable_tasks(&plan, &state)
+        .into_iter()
+        .find(|task| task.id.trim() == task_id)
+    else {
+        return Ok(None);
+    };
+    let state_tasks_by_id = map_state_tasks(&state);
+    let task_state = state_tasks_by_id
+        .get(task.id.as_str())
+        .ok_or_else(|| format!("state.json missing task entry for {}", task.id))?;
+    if is_task_completed(task_state) {
+        return Ok(None);
+    }
+
+    let (phase_index, phase_id, is_last_phase) = match find_next_phase(task_state) {
+        Some((index, phase)) => (index, phase.id.clone(), index + 1 >= task_state.phases.len()),
+        None => (0, "implementation".to_string(), true),
+    };
+    if is_manual_approval_phase(&load_template_phases(&paths)?, &phase_id) {
+        return Ok(Some(awaiting_approval_prompt(
+            event_sink,
+            workspace_id,
+            &paths.plan_id,
+            &task.id,
+            &phase_id,
+            is_last_phase,
+        )));
+    }
+    if let Some(note) =
+        enforce_plan_budget(workspaces, &paths, plan.budget.as_ref(), &state, &task.id).await?
+    {
+        return Ok(Some(budget_exceeded_prompt(
+            event_sink,
+            workspace_id,
+            &paths.plan_id,
+            &task.id,
+            &phase_id,
+            is_last_phase,
+            note,
+        )));
+    }
+
+    let context = build_task_hook_context(&paths, &task.id);
+    run_template_hook(&paths.post_step_hook, &paths.workspace_root, &context, None).await?;
+    let prompt_path = task_execute_prompt_path(&paths, &task.id);
+    let prompt_text = fs::read_to_string(&prompt_path).map_err(|err| {
+        format!(
+            "Unable to read generated execute prompt {}: {err}",
+            prompt_path.display()
+        )
+    })?;
+    let prompt_text = append_review_feedback(prompt_text, task_state, phase_index);
+
+    emit_phase_started(
+        event_sink,
+        workspace_id,
+        &paths.plan_id,
+        &task.id,
+        &phase_id,
+        is_last_phase,
+    );
+    Ok(Some(ForgeNextPhasePromptV1 {
+        plan_id: paths.plan_id,
+        task_id: task.id.clone(),
+        phase_id,
+        is_last_phase,
+        prompt_text,
+        awaiting_approval: false,
+        budget_exceeded: None,
+    }))
+}
+
+pub(crate) async fn forge_attach_task_worktree_core(
+    workspace_root: &Path,
+    plan_id: &str,
+    task_id: &str,
+    worktree_workspace_id: &str,
+    worktree_path: &str,
+    branch: &str,
+) -> Result<ForgeTaskWorktreeV1, String> {
+    let paths = build_execution_paths(workspace_root, plan_id)?;
+    let task_id = require_task_id(task_id)?;
+    require_state_file(&paths)?;
+
+    let plan = load_plan(&paths)?;
+    let state = load_state(&paths)?;
+    check_task_attachable(&plan, &state, task_id, Some(worktree_workspace_id))?;
+
+    let base_sha = resolve_head_sha(&paths.workspace_root).await.ok();
+    update_task_state(&paths, task_id, |task| {
+        if let Some(existing) = task.worktree.as_ref() {
+            if existing.workspace_id != worktree_workspace_id {
+                return Err(format!(
+                    "Task {task_id} already runs in worktree {}.",
+                    existing.path
+                ));
+            }
+        }
+
+        let worktree = StateTaskWorktreeV2 {
+            workspace_id: worktree_workspace_id.to_string(),
+            path: worktree_path.to_string(),
+            branch: branch.to_string(),
+            base_sha,
+        };
+        let response = ForgeTaskWorktreeV1::from(&worktree);
+        task.worktree = Some(worktree);
+        if task.status.trim() == "pending" {
+            task.status = "in_progress".to_string();
+        }
+        Ok(response)
+    })
+    .await
+}
+
+pub(crate) async fn forge_merge_task_worktree_core(
+    workspace_root: &Path,
+    plan_id: &str,
+    task_id: &str,
+) -> Result<ForgeTaskMergeResultV1, String> {
+    let paths = build_execution_paths(workspace_root, plan_id)?;
+    let task_id = require_task_id(task_id)?;
+    require_state_file(&paths)?;
+
+    let state = load_state(&paths)?;
+    let task = state
+        .tasks
+        .iter()
+        .find(|task| task.id.trim() == task_id)
+        .ok_or_else(|| format!("Unknown taskId: {task_id}"))?;
+    let Some(worktree) = task.worktree.clone() else {
+        return Err(format!("Task {task_id} is not running in a worktree."));
+    };
+    if !is_task_completed(task) || !task_has_commit_sha(task) {
+        return Err(format!(
+            "Task {task_id} has no completed commit to merge yet."
+        ));
+    }
+    let task_sha = task.commit_sha.clone().unwrap_or_default();
+    let picks = task_commit_shas(task);
+    let pre_merge_head = resolve_head_sha(&paths.workspace_root).await?;
+
+    // Per-phase policies leave several commits; each is picked in order and remapped.
+    let mut merged_shas = Vec::new();
+    for sha in &picks {
+        let pick_output =
+            run_git_command_with_timeout(&paths.workspace_root, &["cherry-pick", sha]).await?;
+        if pick_output.status.success() {
+            merged_shas.push((sha.clone(), resolve_head_sha(&paths.workspace_root).await?));
+            continue;
+        }
+        let detail = format_process_error(&pick_output.stdout, &pick_output.stderr);
+        let _ =
+            run_git_command_with_timeout(&paths.workspace_root, &["cherry-pick", "--abort"]).await;
+        if !merged_shas.is_empty() {
+            let _ = run_git_command_with_timeout(
+                &paths.workspace_root,
+                &["reset", "--keep", &pre_merge_head],
+            )
+            .await;
+        }
+        let message = format!(
+            "Merging {sha} from {} onto the plan branch failed: {detail}",
+            worktree.branch
+        );
+        // The task stays completed and in its worktree so the merge can be retried once the
+        // conflict is resolved.
+        update_task_state(&paths, task_id, |task| {
+            task.notes = if task.notes.trim().is_empty() {
+                message.clone()
+            } else {
+                format!("{}\n{message}", task.notes.trim_end())
+            };
+            Ok(())
+        })
+        .await?;
+        return Ok(ForgeTaskMergeResultV1 {
+            ok: false,
+            task_id: task_id.to_string(),
+            commit_sha: Some(task_sha),
+            worktree_workspace_id: Some(worktree.workspace_id),
+            message,
+        });
+    }
+
+    let merged_sha = resolve_head_sha(&paths.workspace_root).await?;
+    let remap = |sha: &mut Option<String>| {
+        let old = sha.as_deref().map(str::trim).unwrap_or_default();
+        if let Some((_, new)) = merged_shas.iter().find(|(picked, _)| picked == old) {
+            *sha = Some(new.clone());
+        }
+    };
+    update_task_state(&paths, task_id, |task| {
+        for phase in &mut task.phases {
+            remap(&mut phase.commit_sha);
+        }
+        task.commit_sha = Some(merged_sha.clone());
+        task.worktree = None;
+        task.status = "completed".to_string();
+        Ok(())
+    })
+    .await?;
+
+    Ok(ForgeTaskMergeResultV1 {
+        ok: true,
+        task_id: task_id.to_string(),
+        commit_sha: Some(merged_sha.clone()),
+        worktree_workspace_id: Some(worktree.workspace_id),
+        message: format!("Merged {} as {merged_sha}.", picks.join(", ")),
+    })
+}
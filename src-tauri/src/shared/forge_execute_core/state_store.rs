@@ -0,0 +1,255 @@
+//! Locked, crash-safe access to `plans/<planId>/state.json`.
+//!
+//! Every mutation reloads the file under an exclusive advisory lock, applies the change and
+//! replaces the file through a rename, so a crash never leaves a truncated `state.json` and
+//! concurrent runs on different tasks do not overwrite each other.
+
+use super::*;
+
+use std::io::Write;
+use std::thread;
+
+pub(super) const CURRENT_STATE_SCHEMA: &str = "state-v2";
+/// Script hooks hold the lock for their whole run, so a waiter has to outlast the longest hook.
+const STATE_LOCK_TIMEOUT: Duration = Duration::from_secs(HOOK_TIMEOUT_SECONDS + 30);
+const STATE_LOCK_RETRY: Duration = Duration::from_millis(25);
+
+type StateMigration = fn(&ForgeExecutionPaths, Value) -> Result<Value, String>;
+
+/// Steps from each older `$schema` to the next one, applied in order until the state reaches
+/// `CURRENT_STATE_SCHEMA`.
+const STATE_MIGRATIONS: &[(&str, StateMigration)] = &[("state-v1", migrate_state_v1)];
+
+/// Exclusive lock on one plan's state, released when dropped.
+pub(super) struct StateLock {
+    _file: fs::File,
+}
+
+/// Lives under `.agent/`, which Forge keeps out of git, so the lock never ends up in a commit.
+fn state_lock_path(workspace_root: &Path, plan_id: &str) -> PathBuf {
+    workspace_root
+        .join(".agent")
+        .join("locks")
+        .join(format!("{plan_id}.state.lock"))
+}
+
+fn open_state_lock(workspace_root: &Path, plan_id: &str) -> Result<(fs::File, PathBuf), String> {
+    let path = state_lock_path(workspace_root, plan_id);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+        .map_err(|err| format!("Unable to open state lock {}: {err}", path.display()))?;
+    Ok((file, path))
+}
+
+/// One attempt at the lock: `Ok(false)` means another holder has it and the caller should
+/// retry. Most holders only load, patch and rewrite the file, but a script hook keeps the lock
+/// until it exits or is killed at `HOOK_TIMEOUT_SECONDS`. The timeout only guards against a
+/// holder that never lets go.
+fn try_state_lock(
+    file: &fs::File,
+    path: &Path,
+    plan_id: &str,
+    start: Instant,
+) -> Result<bool, String> {
+    match file.try_lock() {
+        Ok(()) => Ok(true),
+        Err(fs::TryLockError::WouldBlock) if start.elapsed() < STATE_LOCK_TIMEOUT => Ok(false),
+        Err(fs::TryLockError::WouldBlock) => Err(format!(
+            "state.json for plan {plan_id} is locked by another Forge run: {}",
+            path.display()
+        )),
+        Err(fs::TryLockError::Error(err)) => {
+            Err(format!("Unable to lock {}: {err}", path.display()))
+        }
+    }
+}
+
+pub(super) async fn lock_state(workspace_root: &Path, plan_id: &str) -> Result<StateLock, String> {
+    let (file, path) = open_state_lock(workspace_root, plan_id)?;
+    let start = Instant::now();
+    while !try_state_lock(&file, &path, plan_id, start)? {
+        tokio::time::sleep(STATE_LOCK_RETRY).await;
+    }
+    Ok(StateLock { _file: file })
+}
+
+/// [`lock_state`] for code off the async runtime, such as the builtin hooks on the blocking pool.
+pub(super) fn lock_state_blocking(
+    workspace_root: &Path,
+    plan_id: &str,
+) -> Result<StateLock, String> {
+    let (file, path) = open_state_lock(workspace_root, plan_id)?;
+    let start = Instant::now();
+    while !try_state_lock(&file, &path, plan_id, start)? {
+        thread::sleep(STATE_LOCK_RETRY);
+    }
+    Ok(StateLock { _file: file })
+}
+
+/// Writes `contents` to a temporary sibling and renames it over `path`. Readers see either the
+/// old or the new file, never a partial one.
+pub(super) fn write_file_atomically(path: &Path, contents: &str) -> Result<(), String> {
+    let dir = path
+        .parent()
+        .ok_or_else(|| format!("Invalid path: {}", path.display()))?;
+    fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    // Dot-prefixed so plan discovery skips a temp file left by a crash.
+    let temp_path = dir.join(format!(".{file_name}.{}.tmp", Uuid::new_v4()));
+    let written = fs::File::create(&temp_path)
+        .and_then(|mut file| {
+            file.write_all(contents.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|()| fs::rename(&temp_path, path));
+    if let Err(err) = written {
+        let _ = fs::remove_file(&temp_path);
+        return Err(format!("Unable to write {}: {err}", path.display()));
+    }
+    Ok(())
+}
+
+pub(super) fn write_state_file(paths: &ForgeExecutionPaths, state: &StateV2) -> Result<(), String> {
+    let state_raw = serde_json::to_string_pretty(state).map_err(|err| err.to_string())?;
+    write_file_atomically(&paths.state_path, &format!("{state_raw}\n"))
+}
+
+fn schema_of(raw: &Value) -> String {
+    raw.get("$schema")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+/// Loads `state.json`, migrating older schemas in memory. The flag reports whether a migration
+/// ran.
+fn read_state(paths: &ForgeExecutionPaths) -> Result<(StateV2, bool), String> {
+    let mut raw = read_json_file::<Value>(&paths.state_path)?;
+    let mut migrated = false;
+    loop {
+        let schema = schema_of(&raw);
+        if schema == CURRENT_STATE_SCHEMA {
+            break;
+        }
+        let Some((_, migrate)) = STATE_MIGRATIONS.iter().find(|(from, _)| *from == schema) else {
+            return Err(format!(
+                "Unsupported state schema in {}: {schema}",
+                paths.state_path.display()
+            ));
+        };
+        raw = migrate(paths, raw)?;
+        if schema_of(&raw) == schema {
+            return Err(format!("State migration from {schema} did not advance the schema."));
+        }
+        migrated = true;
+    }
+
+    let state = serde_json::from_value::<StateV2>(raw)
+        .map_err(|err| format!("Invalid JSON in {}: {err}", paths.state_path.display()))?;
+    if state.plan_id.trim() != paths.plan_id {
+        return Err(format!(
+            "State plan_id mismatch (expected {}, got {})",
+            paths.plan_id, state.plan_id
+        ));
+    }
+    Ok((state, migrated))
+}
+
+pub(super) fn load_state(paths: &ForgeExecutionPaths) -> Result<StateV2, String> {
+    read_state(paths).map(|(state, _)| state)
+}
+
+/// Rewrites a state file saved under an older schema, so hooks that only read the current
+/// schema can use it. Returns whether the file changed.
+pub(super) async fn migrate_state_file(paths: &ForgeExecutionPaths) -> Result<bool, String> {
+    let _lock = lock_state(&paths.workspace_root, &paths.plan_id).await?;
+    let (state, migrated) = read_state(paths)?;
+    if migrated {
+        write_state_file(paths, &state)?;
+    }
+    Ok(migrated)
+}
+
+/// Applies `mutate` to the current state under the plan's state lock and writes it back.
+pub(super) async fn update_state<R>(
+    paths: &ForgeExecutionPaths,
+    mutate: impl FnOnce(&mut StateV2) -> Result<R, String>,
+) -> Result<R, String> {
+    let _lock = lock_state(&paths.workspace_root, &paths.plan_id).await?;
+    let mut state = load_state(paths)?;
+    let result = mutate(&mut state)?;
+    write_state_file(paths, &state)?;
+    Ok(result)
+}
+
+/// [`update_state`] for a single task, leaving the other tasks as they are on disk.
+pub(super) async fn update_task_state<R>(
+    paths: &ForgeExecutionPaths,
+    task_id: &str,
+    mutate: impl FnOnce(&mut StateTaskV2) -> Result<R, String>,
+) -> Result<R, String> {
+    update_state(paths, |state| {
+        let task = state
+            .tasks
+            .iter_mut()
+            .find(|task| task.id.trim() == task_id)
+            .ok_or_else(|| format!("state.json missing task entry for {task_id}"))?;
+        mutate(task)
+    })
+    .await
+}
+
+/// `state-v1` tracked tasks without phases. Each task gets the template's phases, all
+/// `completed` for a completed task and `pending` otherwise.
+fn migrate_state_v1(paths: &ForgeExecutionPaths, mut raw: Value) -> Result<Value, String> {
+    let phase_ids = load_template_phases(paths)?
+        .phases
+        .iter()
+        .map(|phase| phase.id.trim().to_string())
+        .collect::<Vec<_>>();
+    let object = raw
+        .as_object_mut()
+        .ok_or_else(|| "state.json must be an object".to_string())?;
+    object.insert("$schema".to_string(), Value::from("state-v2"));
+    object.entry("iteration").or_insert(Value::from(0));
+    object.entry("summary").or_insert(Value::from(""));
+    let tasks = object
+        .get_mut("tasks")
+        .and_then(Value::as_array_mut)
+        .map(|tasks| tasks.iter_mut().filter_map(Value::as_object_mut));
+    for task in tasks.into_iter().flatten() {
+        task.entry("attempts").or_insert(Value::from(0));
+        task.entry("notes").or_insert(Value::from(""));
+        task.entry("commit_sha").or_insert(Value::Null);
+        if task.contains_key("phases") {
+            continue;
+        }
+        let phase_status = match task.get("status").and_then(Value::as_str) {
+            Some("completed") => "completed",
+            _ => "pending",
+        };
+        let phases = phase_ids
+            .iter()
+            .map(|id| {
+                serde_json::json!({
+                    "id": id,
+                    "status": phase_status,
+                    "attempts": 0,
+                    "notes": ""
+                })
+            })
+            .collect::<Vec<_>>();
+        task.insert("phases".to_string(), Value::Array(phases));
+    }
+    Ok(raw)
+}
@@ -0,0 +1,796 @@
+use super::*;
+
+use serde_json::Map;
+
+use super::artifact_gates::ForgeArtifactDetailsV1;
+
+const MAX_JUNIT_CASES: usize = 500;
+const MAX_DIFF_FILES_LISTED: usize = 200;
+
+/// Structured outcome of a typed check or artifact gate, tagged with its kind.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub(crate) enum ForgeCheckDetailsV1 {
+    Junit(ForgeJunitDetailsV1),
+    Coverage(ForgeCoverageDetailsV1),
+    DiffLimits(ForgeDiffLimitsDetailsV1),
+    FileExists(ForgeFileExistsDetailsV1),
+    Artifact(ForgeArtifactDetailsV1),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeJunitDetailsV1 {
+    pub(crate) reports: Vec<String>,
+    pub(crate) tests: u32,
+    pub(crate) passed: u32,
+    pub(crate) failures: u32,
+    pub(crate) errors: u32,
+    pub(crate) skipped: u32,
+    /// Failing cases come first when the list is cut to `MAX_JUNIT_CASES`.
+    pub(crate) cases: Vec<ForgeJunitCaseV1>,
+    pub(crate) cases_truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeJunitCaseV1 {
+    pub(crate) name: String,
+    pub(crate) classname: Option<String>,
+    /// `passed`, `failed`, `error` or `skipped`.
+    pub(crate) status: String,
+    pub(crate) message: Option<String>,
+    pub(crate) duration_ms: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeCoverageDetailsV1 {
+    pub(crate) report: String,
+    /// `lcov` or `cobertura`.
+    pub(crate) format: String,
+    pub(crate) lines_found: u64,
+    pub(crate) lines_hit: u64,
+    pub(crate) line_percent: f64,
+    pub(crate) min_line_percent: f64,
+    pub(crate) branches_found: u64,
+    pub(crate) branches_hit: u64,
+    pub(crate) branch_percent: Option<f64>,
+    pub(crate) min_branch_percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeDiffLimitsDetailsV1 {
+    pub(crate) base_sha: String,
+    pub(crate) files_changed: u32,
+    pub(crate) lines_added: u64,
+    pub(crate) lines_removed: u64,
+    pub(crate) max_files: Option<u32>,
+    pub(crate) max_lines: Option<u64>,
+    /// Changed paths, cut to the first `MAX_DIFF_FILES_LISTED`.
+    pub(crate) files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeFileExistsDetailsV1 {
+    pub(crate) present: Vec<String>,
+    pub(crate) missing: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) enum TypedPhaseCheck {
+    Junit {
+        path: String,
+    },
+    Coverage {
+        path: String,
+        format: Option<CoverageFormat>,
+        min_line_percent: f64,
+        min_branch_percent: Option<f64>,
+    },
+    DiffLimits {
+        max_files: Option<u32>,
+        max_lines: Option<u64>,
+    },
+    FileExists {
+        paths: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum CoverageFormat {
+    Lcov,
+    Cobertura,
+}
+
+/// Where a typed check reads its inputs: the task's execution root and the commit its diff
+/// is measured from.
+pub(super) struct TypedCheckContext<'a> {
+    pub(super) execution_root: &'a Path,
+    pub(super) plan_id: &'a str,
+    pub(super) task_id: &'a str,
+    pub(super) base_sha: Option<&'a str>,
+}
+
+struct TypedCheckOutcome {
+    summary: String,
+    problems: Vec<String>,
+    details: ForgeCheckDetailsV1,
+}
+
+impl CoverageFormat {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Lcov => "lcov",
+            Self::Cobertura => "cobertura",
+        }
+    }
+}
+
+fn required_path(obj: &Map<String, Value>, kind: &str) -> Result<String, String> {
+    obj.get("path")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| format!("{kind} check is missing a non-empty path field."))
+}
+
+fn optional_percent(obj: &Map<String, Value>, key: &str) -> Result<Option<f64>, String> {
+    match obj.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value
+            .as_f64()
+            .filter(|percent| (0.0..=100.0).contains(percent))
+            .map(Some)
+            .ok_or_else(|| format!("{key} must be a number between 0 and 100.")),
+    }
+}
+
+fn optional_limit(obj: &Map<String, Value>, key: &str) -> Result<Option<u64>, String> {
+    match obj.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value
+            .as_u64()
+            .map(Some)
+            .ok_or_else(|| format!("{key} must be a non-negative integer.")),
+    }
+}
+
+/// Parses a `phases.json` check entry whose `kind` is not `command`.
+pub(super) fn parse_typed_check(
+    kind: &str,
+    obj: &Map<String, Value>,
+) -> Result<TypedPhaseCheck, String> {
+    match kind {
+        "junit" => Ok(TypedPhaseCheck::Junit {
+            path: required_path(obj, kind)?,
+        }),
+        "coverage" => {
+            let format = match obj.get("format").and_then(Value::as_str).map(str::trim) {
+                None | Some("") => None,
+                Some("lcov") => Some(CoverageFormat::Lcov),
+                Some("cobertura") => Some(CoverageFormat::Cobertura),
+                Some(other) => {
+                    return Err(format!(
+                        "Unknown coverage format: {other}. Expected lcov or cobertura."
+                    ))
+                }
+            };
+            let min_line_percent = optional_percent(obj, "minLinePercent")?
+                .ok_or_else(|| "coverage check is missing minLinePercent.".to_string())?;
+            Ok(TypedPhaseCheck::Coverage {
+                path: required_path(obj, kind)?,
+                format,
+                min_line_percent,
+                min_branch_percent: optional_percent(obj, "minBranchPercent")?,
+            })
+        }
+        "diff-limits" => {
+            let max_files = optional_limit(obj, "maxFiles")?
+                .map(|value| u32::try_from(value).unwrap_or(u32::MAX));
+            let max_lines = optional_limit(obj, "maxLines")?;
+            if max_files.is_none() && max_lines.is_none() {
+                return Err("diff-limits check needs maxFiles or maxLines.".to_string());
+            }
+            Ok(TypedPhaseCheck::DiffLimits {
+                max_files,
+                max_lines,
+            })
+        }
+        "file-exists" => {
+            let paths = obj
+                .get("paths")
+                .and_then(Value::as_array)
+                .map(|items| {
+                    items
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .map(str::trim)
+                        .filter(|value| !value.is_empty())
+                        .map(str::to_string)
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            if paths.is_empty() {
+                return Err("file-exists check needs a non-empty paths array.".to_string());
+            }
+            Ok(TypedPhaseCheck::FileExists { paths })
+        }
+        other => Err(format!(
+            "Unknown phase check kind: {other}. Expected command, junit, coverage, \
+             diff-limits or file-exists."
+        )),
+    }
+}
+
+/// Resolves a configured path against the execution root after filling in `{{plan_id}}` and
+/// `{{task_id}}`.
+fn resolve_check_path(
+    context: &TypedCheckContext<'_>,
+    raw: &str,
+) -> Result<(String, PathBuf), String> {
+    let display = raw
+        .replace("{{plan_id}}", context.plan_id)
+        .replace("{{task_id}}", context.task_id);
+    let relative = validate_relative_file_path(&display)?;
+    Ok((display, context.execution_root.join(relative)))
+}
+
+fn read_report(path: &Path, display: &str) -> Result<String, String> {
+    if !path.is_file() {
+        return Err(format!("Report not found: {display}"));
+    }
+    fs::read_to_string(path).map_err(|err| format!("Unable to read {display}: {err}"))
+}
+
+fn percent(hit: u64, found: u64) -> f64 {
+    if found == 0 {
+        return 0.0;
+    }
+    let value = hit as f64 * 100.0 / found as f64;
+    (value * 100.0).round() / 100.0
+}
+
+fn decode_xml_entities(raw: &str) -> String {
+    if !raw.contains('&') {
+        return raw.to_string();
+    }
+    let mut decoded = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        let Some(end) = tail.find(';') else {
+            decoded.push_str(tail);
+            return decoded;
+        };
+        let entity = &tail[1..end];
+        let replacement = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match replacement {
+            Some(ch) => decoded.push(ch),
+            None => decoded.push_str(&tail[..=end]),
+        }
+        rest = &tail[end + 1..];
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+struct XmlTag<'a> {
+    name: &'a str,
+    closing: bool,
+    self_closing: bool,
+    attrs: Vec<(&'a str, String)>,
+}
+
+impl XmlTag<'_> {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Lists the element tags of a report in document order. Text, comments, CDATA sections and
+/// declarations are skipped; the reports Forge reads keep everything it needs in attributes.
+fn scan_xml_tags(xml: &str) -> Vec<XmlTag<'_>> {
+    let mut tags = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start..];
+        let skip_until = if rest.starts_with("<!--") {
+            Some("-->")
+        } else if rest.starts_with("<![CDATA[") {
+            Some("]]>")
+        } else if rest.starts_with("<?") || rest.starts_with("<!") {
+            Some(">")
+        } else {
+            None
+        };
+        if let Some(terminator) = skip_until {
+            match rest.find(terminator) {
+                Some(end) => {
+                    rest = &rest[end + terminator.len()..];
+                    continue;
+                }
+                None => break,
+            }
+        }
+
+        let body = &rest[1..];
+        let (closing, body) = match body.strip_prefix('/') {
+            Some(body) => (true, body),
+            None => (false, body),
+        };
+        let name_len = body
+            .find(|ch: char| ch.is_whitespace() || ch == '/' || ch == '>')
+            .unwrap_or(body.len());
+        let name = &body[..name_len];
+        let mut cursor = &body[name_len..];
+        let mut attrs = Vec::new();
+        let mut self_closing = false;
+        loop {
+            cursor = cursor.trim_start();
+            if let Some(after) = cursor.strip_prefix("/>") {
+                self_closing = true;
+                cursor = after;
+                break;
+            }
+            if let Some(after) = cursor.strip_prefix('>') {
+                cursor = after;
+                break;
+            }
+            let Some(eq) = cursor.find('=') else {
+                cursor = "";
+                break;
+            };
+            let key = cursor[..eq].trim();
+            let value_part = cursor[eq + 1..].trim_start();
+            let Some(quote) = value_part.chars().next().filter(|ch| *ch == '"' || *ch == '\'')
+            else {
+                cursor = "";
+                break;
+            };
+            let Some(value_end) = value_part[1..].find(quote) else {
+                cursor = "";
+                break;
+            };
+            attrs.push((key, decode_xml_entities(&value_part[1..=value_end])));
+            cursor = &value_part[value_end + 2..];
+        }
+        if !name.is_empty() {
+            tags.push(XmlTag {
+                name,
+                closing,
+                self_closing,
+                attrs,
+            });
+        }
+        rest = cursor;
+    }
+    tags
+}
+
+fn parse_junit_cases(xml: &str, cases: &mut Vec<ForgeJunitCaseV1>) {
+    let mut current: Option<ForgeJunitCaseV1> = None;
+    for tag in scan_xml_tags(xml) {
+        match (tag.name, tag.closing) {
+            ("testcase", false) => {
+                let case = ForgeJunitCaseV1 {
+                    name: tag.attr("name").unwrap_or("").to_string(),
+                    classname: tag.attr("classname").map(str::to_string),
+                    status: "passed".to_string(),
+                    message: None,
+                    duration_ms: tag
+                        .attr("time")
+                        .and_then(|value| value.trim().parse::<f64>().ok())
+                        .map(|seconds| (seconds * 1000.0).round() as i64),
+                };
+                if tag.self_closing {
+                    cases.push(case);
+                } else {
+                    current = Some(case);
+                }
+            }
+            ("testcase", true) => {
+                if let Some(case) = current.take() {
+                    cases.push(case);
+                }
+            }
+            ("failure" | "error" | "skipped", false) => {
+                let Some(case) = current.as_mut() else {
+                    continue;
+                };
+                // A failure outranks a skip when a runner reports both.
+                if case.status == "failed" || case.status == "error" {
+                    continue;
+                }
+                case.status = match tag.name {
+                    "failure" => "failed",
+                    "error" => "error",
+                    _ => "skipped",
+                }
+                .to_string();
+                case.message = tag
+                    .attr("message")
+                    .or_else(|| tag.attr("type"))
+                    .map(str::to_string);
+            }
+            _ => {}
+        }
+    }
+    if let Some(case) = current {
+        cases.push(case);
+    }
+}
+
+fn junit_report_files(path: &Path, display: &str) -> Result<Vec<PathBuf>, String> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    if !path.is_dir() {
+        return Err(format!("JUnit report not found: {display}"));
+    }
+    let mut files = fs::read_dir(path)
+        .map_err(|err| format!("Unable to read {display}: {err}"))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|file| file.is_file() && file.extension().is_some_and(|ext| ext == "xml"))
+        .collect::<Vec<_>>();
+    files.sort();
+    if files.is_empty() {
+        return Err(format!("No JUnit XML reports in {display}"));
+    }
+    Ok(files)
+}
+
+fn run_junit_check(
+    context: &TypedCheckContext<'_>,
+    raw_path: &str,
+) -> Result<TypedCheckOutcome, String> {
+    let (display, path) = resolve_check_path(context, raw_path)?;
+    let mut reports = Vec::new();
+    let mut cases = Vec::new();
+    for file in junit_report_files(&path, &display)? {
+        let report_display = file
+            .strip_prefix(context.execution_root)
+            .unwrap_or(&file)
+            .to_string_lossy()
+            .to_string();
+        let xml = read_report(&file, &report_display)?;
+        parse_junit_cases(&xml, &mut cases);
+        reports.push(report_display);
+    }
+
+    let count = |status: &str| cases.iter().filter(|case| case.status == status).count() as u32;
+    let tests = cases.len() as u32;
+    let (passed, failures, errors, skipped) =
+        (count("passed"), count("failed"), count("error"), count("skipped"));
+    let mut problems = Vec::new();
+    if tests == 0 {
+        problems.push(format!("No test cases found in {display}."));
+    }
+    for case in cases
+        .iter()
+        .filter(|case| case.status == "failed" || case.status == "error")
+    {
+        let name = match &case.classname {
+            Some(classname) if !classname.is_empty() => format!("{classname}::{}", case.name),
+            _ => case.name.clone(),
+        };
+        match &case.message {
+            Some(message) => problems.push(format!("{} {name}: {message}", case.status)),
+            None => problems.push(format!("{} {name}", case.status)),
+        }
+    }
+
+    let cases_truncated = cases.len() > MAX_JUNIT_CASES;
+    if cases_truncated {
+        cases.sort_by_key(|case| case.status == "passed");
+        cases.truncate(MAX_JUNIT_CASES);
+    }
+    Ok(TypedCheckOutcome {
+        summary: format!(
+            "{tests} tests: {passed} passed, {failures} failed, {errors} errors, {skipped} skipped"
+        ),
+        problems,
+        details: ForgeCheckDetailsV1::Junit(ForgeJunitDetailsV1 {
+            reports,
+            tests,
+            passed,
+            failures,
+            errors,
+            skipped,
+            cases,
+            cases_truncated,
+        }),
+    })
+}
+
+/// Sums the `LF`/`LH` and `BRF`/`BRH` records of every file section.
+fn parse_lcov_totals(report: &str) -> (u64, u64, u64, u64) {
+    let mut totals = (0, 0, 0, 0);
+    for line in report.lines() {
+        let Some((key, value)) = line.trim().split_once(':') else {
+            continue;
+        };
+        let Ok(value) = value.trim().parse::<u64>() else {
+            continue;
+        };
+        match key {
+            "LF" => totals.0 += value,
+            "LH" => totals.1 += value,
+            "BRF" => totals.2 += value,
+            "BRH" => totals.3 += value,
+            _ => {}
+        }
+    }
+    totals
+}
+
+/// Reads the totals on the root `<coverage>` element.
+fn parse_cobertura_totals(report: &str) -> Result<(u64, u64, u64, u64), String> {
+    let tags = scan_xml_tags(report);
+    let coverage = tags
+        .iter()
+        .find(|tag| tag.name == "coverage" && !tag.closing)
+        .ok_or_else(|| "Cobertura report has no <coverage> element.".to_string())?;
+    let number = |key: &str| {
+        coverage
+            .attr(key)
+            .and_then(|value| value.trim().parse::<u64>().ok())
+    };
+    match (number("lines-valid"), number("lines-covered")) {
+        (Some(lines_found), Some(lines_hit)) => Ok((
+            lines_found,
+            lines_hit,
+            number("branches-valid").unwrap_or(0),
+            number("branches-covered").unwrap_or(0),
+        )),
+        _ => Err("Cobertura report is missing lines-valid/lines-covered totals.".to_string()),
+    }
+}
+
+fn run_coverage_check(
+    context: &TypedCheckContext<'_>,
+    raw_path: &str,
+    format: Option<CoverageFormat>,
+    min_line_percent: f64,
+    min_branch_percent: Option<f64>,
+) -> Result<TypedCheckOutcome, String> {
+    let (display, path) = resolve_check_path(context, raw_path)?;
+    let report = read_report(&path, &display)?;
+    let format = format.unwrap_or(if path.extension().is_some_and(|ext| ext == "xml") {
+        CoverageFormat::Cobertura
+    } else {
+        CoverageFormat::Lcov
+    });
+    let (lines_found, lines_hit, branches_found, branches_hit) = match format {
+        CoverageFormat::Lcov => parse_lcov_totals(&report),
+        CoverageFormat::Cobertura => parse_cobertura_totals(&report)?,
+    };
+
+    let line_percent = percent(lines_hit, lines_found);
+    let branch_percent = (branches_found > 0).then(|| percent(branches_hit, branches_found));
+    let mut problems = Vec::new();
+    if lines_found == 0 {
+        problems.push(format!("{display} reports no instrumented lines."));
+    } else if line_percent < min_line_percent {
+        problems.push(format!(
+            "Line coverage {line_percent}% is below the {min_line_percent}% minimum."
+        ));
+    }
+    if let Some(min_branch_percent) = min_branch_percent {
+        match branch_percent {
+            Some(branch_percent) if branch_percent < min_branch_percent => {
+                problems.push(format!(
+                    "Branch coverage {branch_percent}% is below the {min_branch_percent}% minimum."
+                ));
+            }
+            Some(_) => {}
+            None => problems.push(format!("{display} reports no branch data.")),
+        }
+    }
+
+    let mut summary = format!("Line coverage {line_percent}% ({lines_hit}/{lines_found})");
+    if let Some(branch_percent) = branch_percent {
+        summary.push_str(&format!(
+            ", branch coverage {branch_percent}% ({branches_hit}/{branches_found})"
+        ));
+    }
+    Ok(TypedCheckOutcome {
+        summary,
+        problems,
+        details: ForgeCheckDetailsV1::Coverage(ForgeCoverageDetailsV1 {
+            report: display,
+            format: format.as_str().to_string(),
+            lines_found,
+            lines_hit,
+            line_percent,
+            min_line_percent,
+            branches_found,
+            branches_hit,
+            branch_percent,
+            min_branch_percent,
+        }),
+    })
+}
+
+async fn git_stdout(repo_root: &Path, args: &[&str]) -> Result<String, String> {
+    let output = run_git_command_with_timeout(repo_root, args).await?;
+    if !output.status.success() {
+        return Err(format_process_error(&output.stdout, &output.stderr));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Measures committed, staged, unstaged and untracked changes against the task's base. The
+/// plan folder is left out because Forge itself rewrites `state.json` during the run.
+async fn run_diff_limits_check(
+    context: &TypedCheckContext<'_>,
+    max_files: Option<u32>,
+    max_lines: Option<u64>,
+) -> Result<TypedCheckOutcome, String> {
+    let root = context.execution_root;
+    let base_sha = match context.base_sha {
+        Some(sha) => sha.to_string(),
+        None => git_stdout(root, &["rev-parse", "HEAD"]).await?.trim().to_string(),
+    };
+    let exclude = outside_plan_dir_pathspec(context.plan_id);
+
+    let numstat = git_stdout(
+        root,
+        &["diff", "--numstat", "--no-renames", &base_sha, "--", ".", &exclude],
+    )
+    .await?;
+    let mut files = Vec::new();
+    let (mut lines_added, mut lines_removed) = (0u64, 0u64);
+    for line in numstat.lines() {
+        let mut fields = line.splitn(3, '\t');
+        let (Some(added), Some(removed), Some(path)) = (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        // Binary files report `-` and count as a changed file without lines.
+        lines_added += added.parse::<u64>().unwrap_or(0);
+        lines_removed += removed.parse::<u64>().unwrap_or(0);
+        files.push(path.to_string());
+    }
+
+    let untracked = git_stdout(
+        root,
+        &["ls-files", "--others", "--exclude-standard", "--", ".", &exclude],
+    )
+    .await?;
+    for path in untracked.lines().filter(|line| !line.trim().is_empty()) {
+        if let Ok(contents) = fs::read(root.join(path)) {
+            if !contents.contains(&0) {
+                let newlines = contents.iter().filter(|byte| **byte == b'\n').count() as u64;
+                let unterminated = u64::from(contents.last().is_some_and(|byte| *byte != b'\n'));
+                lines_added += newlines + unterminated;
+            }
+        }
+        files.push(path.to_string());
+    }
+
+    let files_changed = files.len() as u32;
+    let lines_changed = lines_added + lines_removed;
+    let mut problems = Vec::new();
+    if let Some(max_files) = max_files.filter(|max| files_changed > *max) {
+        problems.push(format!("{files_changed} files changed; the limit is {max_files}."));
+    }
+    if let Some(max_lines) = max_lines.filter(|max| lines_changed > *max) {
+        problems.push(format!("{lines_changed} lines changed; the limit is {max_lines}."));
+    }
+    files.truncate(MAX_DIFF_FILES_LISTED);
+    Ok(TypedCheckOutcome {
+        summary: format!(
+            "{files_changed} files changed, +{lines_added} -{lines_removed} since {base_sha}"
+        ),
+        problems,
+        details: ForgeCheckDetailsV1::DiffLimits(ForgeDiffLimitsDetailsV1 {
+            base_sha,
+            files_changed,
+            lines_added,
+            lines_removed,
+            max_files,
+            max_lines,
+            files,
+        }),
+    })
+}
+
+fn run_file_exists_check(
+    context: &TypedCheckContext<'_>,
+    paths: &[String],
+) -> Result<TypedCheckOutcome, String> {
+    let mut present = Vec::new();
+    let mut missing = Vec::new();
+    for raw in paths {
+        let (display, path) = resolve_check_path(context, raw)?;
+        if path.exists() {
+            present.push(display);
+        } else {
+            missing.push(display);
+        }
+    }
+    let problems = missing
+        .iter()
+        .map(|path| format!("Missing: {path}"))
+        .collect::<Vec<_>>();
+    Ok(TypedCheckOutcome {
+        summary: format!("{} of {} paths present", present.len(), paths.len()),
+        problems,
+        details: ForgeCheckDetailsV1::FileExists(ForgeFileExistsDetailsV1 { present, missing }),
+    })
+}
+
+/// Runs a typed check. It passes with exit code `0`, fails on its own criteria with `1`, and
+/// reports `2` when the report cannot be read or the check is misconfigured.
+pub(super) async fn run_typed_check<E: EventSink>(
+    context: &TypedCheckContext<'_>,
+    id: &str,
+    title: &str,
+    check: &TypedPhaseCheck,
+    events: &ForgeExecutionEmitter<'_, E>,
+) -> ForgePhaseCheckResultV1 {
+    let start = Instant::now();
+    events.check_started(id, title);
+    let outcome = match check {
+        TypedPhaseCheck::Junit { path } => run_junit_check(context, path),
+        TypedPhaseCheck::Coverage {
+            path,
+            format,
+            min_line_percent,
+            min_branch_percent,
+        } => run_coverage_check(
+            context,
+            path,
+            *format,
+            *min_line_percent,
+            *min_branch_percent,
+        ),
+        TypedPhaseCheck::DiffLimits {
+            max_files,
+            max_lines,
+        } => run_diff_limits_check(context, *max_files, *max_lines).await,
+        TypedPhaseCheck::FileExists { paths } => run_file_exists_check(context, paths),
+    };
+
+    let (exit_code, stdout, stderr, details) = match outcome {
+        Ok(outcome) => (
+            i32::from(!outcome.problems.is_empty()),
+            outcome.summary,
+            outcome.problems.join("\n"),
+            Some(Box::new(outcome.details)),
+        ),
+        Err(message) => (2, String::new(), message, None),
+    };
+    let result = ForgePhaseCheckResultV1 {
+        id: id.to_string(),
+        title: title.to_string(),
+        exit_code,
+        duration_ms: start.elapsed().as_millis() as i64,
+        stdout,
+        stderr,
+        timed_out: false,
+        details,
+    };
+    events.check_finished(&result);
+    result
+}
@@ -5,6 +5,11 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
+use crate::shared::forge_schema_core::{validate_json_against_schema, ForgeSchemaErrorV1};
+use crate::shared::forge_templates_core::{
+    read_installed_template_schemas_core, ForgeInstalledTemplateSchemas,
+};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct ForgePlanTaskV1 {
@@ -24,15 +29,21 @@ pub(crate) struct ForgeWorkspacePlanV1 {
     pub(crate) current_task_id: Option<String>,
     pub(crate) plan_path: String,
     pub(crate) updated_at_ms: i64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) plan_errors: Vec<ForgeSchemaErrorV1>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) state_errors: Vec<ForgeSchemaErrorV1>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
-struct PlanV1 {
-    id: String,
-    #[serde(default)]
-    title: Option<String>,
-    goal: String,
-    tasks: Vec<PlanTaskV1>,
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgePlanValidationV1 {
+    pub(crate) plan_id: String,
+    pub(crate) plan_path: String,
+    pub(crate) state_path: Option<String>,
+    pub(crate) valid: bool,
+    pub(crate) plan_errors: Vec<ForgeSchemaErrorV1>,
+    pub(crate) state_errors: Vec<ForgeSchemaErrorV1>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -109,69 +120,255 @@ fn resolve_state_path(plans_dir: &Path, plan_path: &Path, plan_id: &str) -> Opti
     candidates.into_iter().find(|path| path.is_file())
 }
 
-pub(crate) fn list_plans_core(workspace_root: &Path) -> Result<Vec<ForgeWorkspacePlanV1>, String> {
-    let plans_dir = workspace_root.join("plans");
-    if !plans_dir.is_dir() {
-        return Ok(Vec::new());
+fn json_pointer_index(parent: &str, index: usize, field: &str) -> String {
+    format!("{parent}/{index}/{field}")
+}
+
+fn structure_error(instance_path: String, keyword: &str, message: String) -> ForgeSchemaErrorV1 {
+    ForgeSchemaErrorV1 {
+        instance_path,
+        schema_path: String::new(),
+        keyword: keyword.to_string(),
+        message,
     }
+}
 
-    let mut plans = Vec::new();
-    for path in collect_json_files(&plans_dir) {
-        let raw = match fs::read_to_string(&path) {
-            Ok(raw) => raw,
-            Err(_) => continue,
-        };
-        let value: serde_json::Value = match serde_json::from_str(&raw) {
-            Ok(value) => value,
-            Err(_) => continue,
-        };
+/// Checks that `field` of the object at `path` is a string; a missing or null optional
+/// field passes.
+fn check_string_field(
+    object: &serde_json::Value,
+    path: &str,
+    field: &str,
+    required: bool,
+    errors: &mut Vec<ForgeSchemaErrorV1>,
+) {
+    match object.get(field) {
+        Some(serde_json::Value::String(_)) => {}
+        None | Some(serde_json::Value::Null) if !required => {}
+        None => errors.push(structure_error(
+            path.to_string(),
+            "required",
+            format!("missing required property `{field}`"),
+        )),
+        Some(_) => errors.push(structure_error(
+            format!("{path}/{field}"),
+            "type",
+            format!("`{field}` must be a string"),
+        )),
+    }
+}
 
-        let schema = value
-            .get("$schema")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .trim();
-        if schema != "plan-v1" {
+/// The plan-v1 shape the listing relies on, for workspaces without a template schema.
+fn check_legacy_plan_shape(value: &serde_json::Value, errors: &mut Vec<ForgeSchemaErrorV1>) {
+    check_string_field(value, "", "id", true, errors);
+    check_string_field(value, "", "title", false, errors);
+    check_string_field(value, "", "goal", true, errors);
+    let tasks = match value.get("tasks") {
+        Some(serde_json::Value::Array(tasks)) => tasks,
+        None => {
+            errors.push(structure_error(
+                String::new(),
+                "required",
+                "missing required property `tasks`".to_string(),
+            ));
+            return;
+        }
+        Some(_) => {
+            errors.push(structure_error(
+                "/tasks".to_string(),
+                "type",
+                "`tasks` must be an array".to_string(),
+            ));
+            return;
+        }
+    };
+    for (index, task) in tasks.iter().enumerate() {
+        let path = format!("/tasks/{index}");
+        if !task.is_object() {
+            errors.push(structure_error(
+                path,
+                "type",
+                "task must be an object".to_string(),
+            ));
             continue;
         }
+        check_string_field(task, &path, "id", true, errors);
+        check_string_field(task, &path, "name", true, errors);
+    }
+}
 
+/// Rules the template schemas can't express (task order) plus the legacy-shape checks used when
+/// no template is installed to supply a schema.
+fn check_plan_structure(value: &serde_json::Value, has_schema: bool) -> Vec<ForgeSchemaErrorV1> {
+    let mut errors = Vec::new();
+    let tasks = value.get("tasks").and_then(|v| v.as_array());
+
+    if !has_schema {
         // plan-v1 is tasks-only: reject phase-based legacy plans.
         if value.get("phases").is_some() {
+            errors.push(structure_error(
+                "/phases".to_string(),
+                "legacyPhases",
+                "plan-v1 is tasks-only; phase-based plans are not supported".to_string(),
+            ));
+        }
+        for (index, task) in tasks.into_iter().flatten().enumerate() {
+            if task.get("phase").is_some() {
+                errors.push(structure_error(
+                    json_pointer_index("/tasks", index, "phase"),
+                    "legacyPhases",
+                    "plan-v1 tasks may not declare a phase".to_string(),
+                ));
+            }
+        }
+        check_legacy_plan_shape(value, &mut errors);
+    }
+
+    // Task ids must match array order: task-1..task-n.
+    for (index, task) in tasks.into_iter().flatten().enumerate() {
+        let expected = format!("task-{}", index + 1);
+        let id = task.get("id").and_then(|v| v.as_str()).unwrap_or("").trim();
+        if id != expected {
+            errors.push(structure_error(
+                json_pointer_index("/tasks", index, "id"),
+                "taskOrder",
+                format!("task ids must match array order (expected {expected})"),
+            ));
+        }
+    }
+
+    errors
+}
+
+fn validate_plan_value(
+    value: &serde_json::Value,
+    schemas: Option<&ForgeInstalledTemplateSchemas>,
+) -> Vec<ForgeSchemaErrorV1> {
+    let mut errors = schemas
+        .map(|schemas| validate_json_against_schema(&schemas.plan_schema, value))
+        .unwrap_or_default();
+    errors.extend(check_plan_structure(value, schemas.is_some()));
+    errors
+}
+
+fn validate_state_file(
+    state_path: &Path,
+    plan_id: &str,
+    schemas: Option<&ForgeInstalledTemplateSchemas>,
+) -> Vec<ForgeSchemaErrorV1> {
+    let value = match fs::read_to_string(state_path)
+        .map_err(|err| err.to_string())
+        .and_then(|raw| {
+            serde_json::from_str::<serde_json::Value>(&raw).map_err(|err| err.to_string())
+        }) {
+        Ok(value) => value,
+        Err(err) => return vec![structure_error(String::new(), "parse", err)],
+    };
+
+    let mut errors = schemas
+        .map(|schemas| validate_json_against_schema(&schemas.state_schema, &value))
+        .unwrap_or_default();
+    let state_plan_id = value.get("plan_id").and_then(|v| v.as_str()).unwrap_or("");
+    if state_plan_id.trim() != plan_id {
+        errors.push(structure_error(
+            "/plan_id".to_string(),
+            "planId",
+            format!("must match plan id {plan_id}"),
+        ));
+    }
+    errors
+}
+
+fn read_state_task_statuses(state_path: &Path) -> HashMap<String, String> {
+    let Some(state) = fs::read_to_string(state_path)
+        .ok()
+        .and_then(|raw| serde_json::from_str::<StateV1>(&raw).ok())
+    else {
+        return HashMap::new();
+    };
+    if state.schema.trim() != "state-v2" {
+        return HashMap::new();
+    }
+
+    let mut map = HashMap::new();
+    for task in state.tasks {
+        let id = task.id.trim();
+        if id.is_empty() {
             continue;
         }
-        let has_task_phase = value
-            .get("tasks")
-            .and_then(|v| v.as_array())
-            .map(|tasks| {
-                tasks.iter().any(|task| {
-                    task.as_object()
-                        .map(|obj| obj.contains_key("phase"))
-                        .unwrap_or(false)
-                })
-            })
-            .unwrap_or(false);
-        if has_task_phase {
+        let status = task.status.trim();
+        if status.is_empty() {
             continue;
         }
+        map.insert(id.to_string(), status.to_string());
+    }
+    map
+}
 
-        let parsed: PlanV1 = match serde_json::from_value(value) {
-            Ok(parsed) => parsed,
-            Err(_) => continue,
+/// Falls back to the file (or, for `plan.json`, folder) name when the plan has no usable id.
+fn fallback_plan_id(path: &Path) -> String {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+    if stem == "plan" {
+        if let Some(dir_name) = path
+            .parent()
+            .and_then(|dir| dir.file_name())
+            .and_then(|name| name.to_str())
+        {
+            return dir_name.to_string();
+        }
+    }
+    stem.to_string()
+}
+
+fn read_plan_v1_value(path: &Path) -> Option<serde_json::Value> {
+    let raw = fs::read_to_string(path).ok()?;
+    let value: serde_json::Value = serde_json::from_str(&raw).ok()?;
+    let schema = value
+        .get("$schema")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .trim();
+    if schema != "plan-v1" {
+        return None;
+    }
+    Some(value)
+}
+
+fn relative_plan_path(workspace_root: &Path, path: &Path) -> String {
+    path.strip_prefix(workspace_root)
+        .ok()
+        .map(|rel| rel.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string_lossy().to_string())
+}
+
+pub(crate) fn list_plans_core(workspace_root: &Path) -> Result<Vec<ForgeWorkspacePlanV1>, String> {
+    let plans_dir = workspace_root.join("plans");
+    if !plans_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    // A broken template shouldn't hide plans; they are listed without schema checks instead.
+    let schemas = read_installed_template_schemas_core(workspace_root)
+        .ok()
+        .flatten();
+
+    let mut plans = Vec::new();
+    for path in collect_json_files(&plans_dir) {
+        let Some(value) = read_plan_v1_value(&path) else {
+            continue;
         };
 
-        let plan_id = parsed.id.trim();
+        let plan_errors = validate_plan_value(&value, schemas.as_ref());
+        let plan_id = value
+            .get("id")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| fallback_plan_id(&path));
         if plan_id.is_empty() {
             continue;
         }
-        // Task ids must match array order: task-1..task-n.
-        if parsed
-            .tasks
-            .iter()
-            .enumerate()
-            .any(|(i, task)| task.id.trim() != format!("task-{}", i + 1))
-        {
-            continue;
-        }
 
         let updated_at_ms = fs::metadata(&path)
             .ok()
@@ -179,73 +376,59 @@ pub(crate) fn list_plans_core(workspace_root: &Path) -> Result<Vec<ForgeWorkspac
             .map(system_time_to_unix_ms)
             .unwrap_or(0);
 
-        let state_path = resolve_state_path(&plans_dir, &path, plan_id);
-        let task_status_by_id = if let Some(state_path) = state_path {
-            let raw = fs::read_to_string(&state_path).ok();
-            if let Some(raw) = raw {
-                if let Ok(state) = serde_json::from_str::<StateV1>(&raw) {
-                    if state.schema.trim() == "state-v2" {
-                        let mut map = HashMap::new();
-                        for task in state.tasks {
-                            let id = task.id.trim();
-                            if id.is_empty() {
-                                continue;
-                            }
-                            let status = task.status.trim();
-                            if status.is_empty() {
-                                continue;
-                            }
-                            map.insert(id.to_string(), status.to_string());
-                        }
-                        map
-                    } else {
-                        HashMap::new()
-                    }
-                } else {
-                    HashMap::new()
-                }
-            } else {
-                HashMap::new()
-            }
-        } else {
-            HashMap::new()
+        let state_path = resolve_state_path(&plans_dir, &path, &plan_id);
+        let (task_status_by_id, state_errors) = match state_path {
+            Some(state_path) => (
+                read_state_task_statuses(&state_path),
+                validate_state_file(&state_path, &plan_id, schemas.as_ref()),
+            ),
+            None => (HashMap::new(), Vec::new()),
         };
 
-        let tasks = parsed
-            .tasks
-            .into_iter()
-            .map(|task| {
-                let status = task_status_by_id
-                    .get(task.id.trim())
-                    .cloned()
-                    .unwrap_or_else(|| "pending".to_string());
-                ForgePlanTaskV1 {
-                    id: task.id,
-                    name: task.name,
-                    status,
-                }
+        // Invalid plans are still listed (with their errors) using whatever fields parse.
+        let tasks = value
+            .get("tasks")
+            .and_then(|v| v.as_array())
+            .map(|tasks| {
+                tasks
+                    .iter()
+                    .filter_map(|task| serde_json::from_value::<PlanTaskV1>(task.clone()).ok())
+                    .map(|task| {
+                        let status = task_status_by_id
+                            .get(task.id.trim())
+                            .cloned()
+                            .unwrap_or_else(|| "pending".to_string());
+                        ForgePlanTaskV1 {
+                            id: task.id,
+                            name: task.name,
+                            status,
+                        }
+                    })
+                    .collect::<Vec<_>>()
             })
-            .collect::<Vec<_>>();
+            .unwrap_or_default();
 
-        let plan_path = path
-            .strip_prefix(workspace_root)
-            .ok()
-            .map(|rel| rel.to_string_lossy().to_string())
-            .unwrap_or_else(|| path.to_string_lossy().to_string());
-
-        let title = parsed
-            .title
+        let title = value
+            .get("title")
+            .and_then(|v| v.as_str())
             .map(|value| value.trim().to_string())
             .filter(|value| !value.is_empty());
+        let goal = value
+            .get("goal")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string();
 
         plans.push(ForgeWorkspacePlanV1 {
-            id: plan_id.to_string(),
+            id: plan_id,
             title,
-            goal: parsed.goal,
+            goal,
             tasks,
             current_task_id: None,
-            plan_path,
+            plan_path: relative_plan_path(workspace_root, &path),
             updated_at_ms,
+            plan_errors,
+            state_errors,
         });
     }
 
@@ -267,6 +450,66 @@ pub(crate) fn list_plans_core(workspace_root: &Path) -> Result<Vec<ForgeWorkspac
     Ok(plans)
 }
 
+/// Checks a plan value before it is written, against the installed template schema when there
+/// is one.
+pub(crate) fn validate_plan_json_core(
+    workspace_root: &Path,
+    value: &serde_json::Value,
+) -> Result<Vec<ForgeSchemaErrorV1>, String> {
+    let schemas = read_installed_template_schemas_core(workspace_root)?;
+    Ok(validate_plan_value(value, schemas.as_ref()))
+}
+
+pub(crate) fn forge_validate_plan_core(
+    workspace_root: &Path,
+    plan_id: &str,
+) -> Result<ForgePlanValidationV1, String> {
+    let plan_id = plan_id.trim();
+    if plan_id.is_empty() {
+        return Err("planId is required".to_string());
+    }
+    let schemas = read_installed_template_schemas_core(workspace_root)?
+        .ok_or_else(|| "No Forge template installed.".to_string())?;
+
+    let plans_dir = workspace_root.join("plans");
+    let mut candidates = vec![
+        plans_dir.join(plan_id).join("plan.json"),
+        plans_dir.join(format!("{plan_id}.json")),
+    ];
+    candidates.extend(collect_json_files(&plans_dir));
+    let (plan_path, value) = candidates
+        .into_iter()
+        .filter(|path| path.is_file())
+        .filter_map(|path| read_plan_v1_value(&path).map(|value| (path, value)))
+        .find(|(path, value)| {
+            let id = value
+                .get("id")
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(str::to_string)
+                .unwrap_or_else(|| fallback_plan_id(path));
+            id == plan_id
+        })
+        .ok_or_else(|| format!("Plan not found: {plan_id}"))?;
+
+    let plan_errors = validate_plan_value(&value, Some(&schemas));
+    let state_path = resolve_state_path(&plans_dir, &plan_path, plan_id);
+    let state_errors = state_path
+        .as_deref()
+        .map(|state_path| validate_state_file(state_path, plan_id, Some(&schemas)))
+        .unwrap_or_default();
+
+    Ok(ForgePlanValidationV1 {
+        plan_id: plan_id.to_string(),
+        plan_path: relative_plan_path(workspace_root, &plan_path),
+        state_path: state_path.map(|path| relative_plan_path(workspace_root, &path)),
+        valid: plan_errors.is_empty() && state_errors.is_empty(),
+        plan_errors,
+        state_errors,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -357,4 +600,110 @@ mod tests {
         assert_eq!(nested.goal, "Nested goal");
         assert_eq!(nested.tasks[0].status, "completed");
     }
+
+    #[test]
+    fn invalid_plans_are_listed_with_schema_errors_when_template_installed() {
+        let root = std::env::temp_dir().join(format!(
+            "codex-monitor-forge-plans-core-test-{}",
+            Uuid::new_v4()
+        ));
+        std::fs::create_dir_all(root.join("plans").join("broken")).expect("create plans dir");
+        let templates = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("resources")
+            .join("forge")
+            .join("templates");
+        crate::shared::forge_templates_core::install_bundled_template_core(
+            &templates,
+            &root,
+            "ralph-loop",
+        )
+        .expect("install template");
+
+        // Out-of-order task ids and a too-short description used to hide the plan entirely.
+        write_json(
+            &root.join("plans").join("broken").join("plan.json"),
+            serde_json::json!({
+              "$schema": "plan-v1",
+              "id": "broken",
+              "goal": "Exercise plan validation",
+              "context": { "tech_stack": ["rust"], "constraints": [] },
+              "tasks": [{ "id": "task-2", "name": "T2", "description": "d", "depends_on": [], "files": ["a"], "verification": ["v"] }]
+            }),
+        );
+        write_json(
+            &root.join("plans").join("broken").join("state.json"),
+            serde_json::json!({ "$schema": "state-v2", "plan_id": "other", "tasks": [] }),
+        );
+
+        let plans = list_plans_core(&root).expect("list plans");
+        assert_eq!(plans.len(), 1);
+        let broken = &plans[0];
+        assert_eq!(broken.id, "broken");
+        assert_eq!(broken.tasks.len(), 1);
+        assert!(broken
+            .plan_errors
+            .iter()
+            .any(|err| err.instance_path == "/tasks/0/description" && err.keyword == "minLength"));
+        assert!(broken
+            .plan_errors
+            .iter()
+            .any(|err| err.instance_path == "/tasks/0/id" && err.keyword == "taskOrder"));
+        assert!(broken
+            .state_errors
+            .iter()
+            .any(|err| err.instance_path == "/plan_id" && err.keyword == "planId"));
+
+        let validation = forge_validate_plan_core(&root, "broken").expect("validate plan");
+        assert!(!validation.valid);
+        assert_eq!(validation.plan_path, "plans/broken/plan.json");
+        assert_eq!(
+            validation.state_path.as_deref(),
+            Some("plans/broken/state.json")
+        );
+        assert_eq!(validation.plan_errors.len(), broken.plan_errors.len());
+        assert!(validation
+            .state_errors
+            .iter()
+            .any(|err| err.instance_path.is_empty() && err.keyword == "required"));
+
+        assert!(forge_validate_plan_core(&root, "missing")
+            .unwrap_err()
+            .contains("Plan not found"));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn plans_without_template_report_shape_errors_by_field() {
+        let root = std::env::temp_dir().join(format!(
+            "codex-monitor-forge-plans-core-test-{}",
+            Uuid::new_v4()
+        ));
+        std::fs::create_dir_all(root.join("plans")).expect("create plans dir");
+        write_json(
+            &root.join("plans").join("shape.json"),
+            serde_json::json!({
+              "$schema": "plan-v1",
+              "id": "shape",
+              "title": 7,
+              "tasks": [{ "id": "task-1" }, "task-2"]
+            }),
+        );
+
+        let plans = list_plans_core(&root).expect("list plans");
+        assert_eq!(plans.len(), 1);
+        let errors = &plans[0].plan_errors;
+        let has = |path: &str, keyword: &str| {
+            errors
+                .iter()
+                .any(|err| err.instance_path == path && err.keyword == keyword)
+        };
+        assert!(has("", "required"));
+        assert!(has("/title", "type"));
+        assert!(has("/tasks/0", "required"));
+        assert!(has("/tasks/1", "type"));
+        assert_eq!(errors.len(), 5, "{errors:?}");
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
 }
@@ -0,0 +1,578 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// One schema violation. `instance_path` and `schema_path` are JSON pointers ("" is the root).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeSchemaErrorV1 {
+    pub(crate) instance_path: String,
+    pub(crate) schema_path: String,
+    pub(crate) keyword: String,
+    pub(crate) message: String,
+}
+
+/// Validates `instance` against a draft-07 JSON Schema.
+///
+/// Covers the keywords Forge templates use (`type`, `enum`, `const`, string/number/array/object
+/// bounds, `pattern`, `properties`, `patternProperties`, `additionalProperties`, `items`, local
+/// `$ref`, and the `allOf`/`anyOf`/`oneOf`/`not` combinators). Unknown keywords are ignored.
+pub(crate) fn validate_json_against_schema(schema: &Value, instance: &Value) -> Vec<ForgeSchemaErrorV1> {
+    let mut validator = SchemaValidator {
+        root: schema,
+        patterns: HashMap::new(),
+        errors: Vec::new(),
+    };
+    validator.validate(schema, instance, "", "");
+    validator.errors
+}
+
+fn escape_json_pointer_segment(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+struct SchemaValidator<'a> {
+    root: &'a Value,
+    patterns: HashMap<String, Option<Regex>>,
+    errors: Vec<ForgeSchemaErrorV1>,
+}
+
+impl<'a> SchemaValidator<'a> {
+    fn push(&mut self, instance_path: &str, schema_path: &str, keyword: &str, message: String) {
+        self.errors.push(ForgeSchemaErrorV1 {
+            instance_path: instance_path.to_string(),
+            schema_path: format!("{schema_path}/{keyword}"),
+            keyword: keyword.to_string(),
+            message,
+        });
+    }
+
+    fn is_valid(&mut self, schema: &'a Value, instance: &Value) -> bool {
+        let before = self.errors.len();
+        self.validate(schema, instance, "", "");
+        let valid = self.errors.len() == before;
+        self.errors.truncate(before);
+        valid
+    }
+
+    fn regex(&mut self, pattern: &str) -> Option<&Regex> {
+        self.patterns
+            .entry(pattern.to_string())
+            .or_insert_with(|| Regex::new(pattern).ok())
+            .as_ref()
+    }
+
+    fn validate(&mut self, schema: &'a Value, instance: &Value, instance_path: &str, schema_path: &str) {
+        let schema_obj = match schema {
+            Value::Bool(true) => return,
+            Value::Bool(false) => {
+                self.errors.push(ForgeSchemaErrorV1 {
+                    instance_path: instance_path.to_string(),
+                    schema_path: schema_path.to_string(),
+                    keyword: "false".to_string(),
+                    message: "no value is allowed here".to_string(),
+                });
+                return;
+            }
+            Value::Object(obj) => obj,
+            _ => return,
+        };
+
+        if let Some(reference) = schema_obj.get("$ref").and_then(Value::as_str) {
+            // Draft-07: `$ref` overrides every sibling keyword.
+            match resolve_local_ref(self.root, reference) {
+                Some(target) => {
+                    self.validate(target, instance, instance_path, &format!("{schema_path}/$ref"))
+                }
+                None => self.push(
+                    instance_path,
+                    schema_path,
+                    "$ref",
+                    format!("unresolvable reference {reference}"),
+                ),
+            }
+            return;
+        }
+
+        if let Some(expected) = schema_obj.get("type") {
+            let matches = match expected {
+                Value::String(name) => matches_type(name, instance),
+                Value::Array(names) => names
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .any(|name| matches_type(name, instance)),
+                _ => true,
+            };
+            if !matches {
+                self.push(
+                    instance_path,
+                    schema_path,
+                    "type",
+                    format!("expected {}, got {}", describe_type(expected), type_name(instance)),
+                );
+            }
+        }
+        if let Some(allowed) = schema_obj.get("enum").and_then(Value::as_array) {
+            if !allowed.iter().any(|candidate| candidate == instance) {
+                let options = allowed
+                    .iter()
+                    .map(Value::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                self.push(
+                    instance_path,
+                    schema_path,
+                    "enum",
+                    format!("must be one of {options}"),
+                );
+            }
+        }
+        if let Some(expected) = schema_obj.get("const") {
+            if expected != instance {
+                self.push(
+                    instance_path,
+                    schema_path,
+                    "const",
+                    format!("must equal {expected}"),
+                );
+            }
+        }
+
+        match instance {
+            Value::String(text) => self.validate_string(schema_obj, text, instance_path, schema_path),
+            Value::Number(_) => self.validate_number(schema_obj, instance, instance_path, schema_path),
+            Value::Array(items) => self.validate_array(schema_obj, items, instance_path, schema_path),
+            Value::Object(obj) => self.validate_object(schema_obj, obj, instance_path, schema_path),
+            _ => {}
+        }
+
+        self.validate_combinators(schema_obj, instance, instance_path, schema_path);
+    }
+
+    fn validate_string(
+        &mut self,
+        schema: &'a Map<String, Value>,
+        text: &str,
+        instance_path: &str,
+        schema_path: &str,
+    ) {
+        let length = text.chars().count() as u64;
+        if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+            if length < min {
+                self.push(
+                    instance_path,
+                    schema_path,
+                    "minLength",
+                    format!("must be at least {min} characters"),
+                );
+            }
+        }
+        if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+            if length > max {
+                self.push(
+                    instance_path,
+                    schema_path,
+                    "maxLength",
+                    format!("must be at most {max} characters"),
+                );
+            }
+        }
+        if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+            let message = match self.regex(pattern) {
+                Some(regex) if regex.is_match(text) => None,
+                Some(_) => Some(format!("must match pattern {pattern}")),
+                None => Some(format!("schema pattern is not a valid regex: {pattern}")),
+            };
+            if let Some(message) = message {
+                self.push(instance_path, schema_path, "pattern", message);
+            }
+        }
+    }
+
+    fn validate_number(
+        &mut self,
+        schema: &'a Map<String, Value>,
+        instance: &Value,
+        instance_path: &str,
+        schema_path: &str,
+    ) {
+        let Some(value) = instance.as_f64() else {
+            return;
+        };
+        let bounds = [
+            ("minimum", ">="),
+            ("maximum", "<="),
+            ("exclusiveMinimum", ">"),
+            ("exclusiveMaximum", "<"),
+        ];
+        for (keyword, operator) in bounds {
+            if let Some(limit) = schema.get(keyword).and_then(Value::as_f64) {
+                let holds = match operator {
+                    ">=" => value >= limit,
+                    "<=" => value <= limit,
+                    ">" => value > limit,
+                    _ => value < limit,
+                };
+                if !holds {
+                    self.push(
+                        instance_path,
+                        schema_path,
+                        keyword,
+                        format!("must be {operator} {limit}"),
+                    );
+                }
+            }
+        }
+    }
+
+    fn validate_array(
+        &mut self,
+        schema: &'a Map<String, Value>,
+        items: &[Value],
+        instance_path: &str,
+        schema_path: &str,
+    ) {
+        let count = items.len() as u64;
+        if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+            if count < min {
+                self.push(
+                    instance_path,
+                    schema_path,
+                    "minItems",
+                    format!("must contain at least {min} items"),
+                );
+            }
+        }
+        if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+            if count > max {
+                self.push(
+                    instance_path,
+                    schema_path,
+                    "maxItems",
+                    format!("must contain at most {max} items"),
+                );
+            }
+        }
+        if schema.get("uniqueItems").and_then(Value::as_bool) == Some(true) {
+            let has_duplicate = items
+                .iter()
+                .enumerate()
+                .any(|(index, item)| items[..index].contains(item));
+            if has_duplicate {
+                self.push(
+                    instance_path,
+                    schema_path,
+                    "uniqueItems",
+                    "must not contain duplicate items".to_string(),
+                );
+            }
+        }
+
+        match schema.get("items") {
+            Some(Value::Array(tuple)) => {
+                for (index, (item_schema, item)) in tuple.iter().zip(items).enumerate() {
+                    self.validate(
+                        item_schema,
+                        item,
+                        &format!("{instance_path}/{index}"),
+                        &format!("{schema_path}/items/{index}"),
+                    );
+                }
+            }
+            Some(item_schema) => {
+                for (index, item) in items.iter().enumerate() {
+                    self.validate(
+                        item_schema,
+                        item,
+                        &format!("{instance_path}/{index}"),
+                        &format!("{schema_path}/items"),
+                    );
+                }
+            }
+            None => {}
+        }
+    }
+
+    fn validate_object(
+        &mut self,
+        schema: &'a Map<String, Value>,
+        obj: &Map<String, Value>,
+        instance_path: &str,
+        schema_path: &str,
+    ) {
+        if let Some(required) = schema.get("required").and_then(Value::as_array) {
+            for key in required.iter().filter_map(Value::as_str) {
+                if !obj.contains_key(key) {
+                    self.push(
+                        instance_path,
+                        schema_path,
+                        "required",
+                        format!("missing required property \"{key}\""),
+                    );
+                }
+            }
+        }
+        let count = obj.len() as u64;
+        if let Some(min) = schema.get("minProperties").and_then(Value::as_u64) {
+            if count < min {
+                self.push(
+                    instance_path,
+                    schema_path,
+                    "minProperties",
+                    format!("must have at least {min} properties"),
+                );
+            }
+        }
+        if let Some(max) = schema.get("maxProperties").and_then(Value::as_u64) {
+            if count > max {
+                self.push(
+                    instance_path,
+                    schema_path,
+                    "maxProperties",
+                    format!("must have at most {max} properties"),
+                );
+            }
+        }
+
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let pattern_properties = schema.get("patternProperties").and_then(Value::as_object);
+        for (key, value) in obj {
+            let child_path = format!("{instance_path}/{}", escape_json_pointer_segment(key));
+            let mut matched = false;
+
+            if let Some(property_schema) = properties.and_then(|props| props.get(key)) {
+                matched = true;
+                self.validate(
+                    property_schema,
+                    value,
+                    &child_path,
+                    &format!("{schema_path}/properties/{}", escape_json_pointer_segment(key)),
+                );
+            }
+            for (pattern, property_schema) in pattern_properties.into_iter().flatten() {
+                if !self.regex(pattern).is_some_and(|regex| regex.is_match(key)) {
+                    continue;
+                }
+                matched = true;
+                self.validate(
+                    property_schema,
+                    value,
+                    &child_path,
+                    &format!(
+                        "{schema_path}/patternProperties/{}",
+                        escape_json_pointer_segment(pattern)
+                    ),
+                );
+            }
+
+            if matched {
+                continue;
+            }
+            match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => self.push(
+                    &child_path,
+                    schema_path,
+                    "additionalProperties",
+                    format!("unexpected property \"{key}\""),
+                ),
+                Some(additional) if additional.is_object() => self.validate(
+                    additional,
+                    value,
+                    &child_path,
+                    &format!("{schema_path}/additionalProperties"),
+                ),
+                _ => {}
+            }
+        }
+    }
+
+    fn validate_combinators(
+        &mut self,
+        schema: &'a Map<String, Value>,
+        instance: &Value,
+        instance_path: &str,
+        schema_path: &str,
+    ) {
+        if let Some(all_of) = schema.get("allOf").and_then(Value::as_array) {
+            for (index, sub_schema) in all_of.iter().enumerate() {
+                self.validate(
+                    sub_schema,
+                    instance,
+                    instance_path,
+                    &format!("{schema_path}/allOf/{index}"),
+                );
+            }
+        }
+        if let Some(any_of) = schema.get("anyOf").and_then(Value::as_array) {
+            if !any_of.iter().any(|sub_schema| self.is_valid(sub_schema, instance)) {
+                self.push(
+                    instance_path,
+                    schema_path,
+                    "anyOf",
+                    "must match at least one allowed schema".to_string(),
+                );
+            }
+        }
+        if let Some(one_of) = schema.get("oneOf").and_then(Value::as_array) {
+            let matches = one_of
+                .iter()
+                .filter(|sub_schema| self.is_valid(sub_schema, instance))
+                .count();
+            if matches != 1 {
+                self.push(
+                    instance_path,
+                    schema_path,
+                    "oneOf",
+                    format!("must match exactly one allowed schema (matched {matches})"),
+                );
+            }
+        }
+        if let Some(not_schema) = schema.get("not") {
+            if self.is_valid(not_schema, instance) {
+                self.push(
+                    instance_path,
+                    schema_path,
+                    "not",
+                    "must not match the disallowed schema".to_string(),
+                );
+            }
+        }
+    }
+}
+
+fn resolve_local_ref<'a>(root: &'a Value, reference: &str) -> Option<&'a Value> {
+    let pointer = reference.strip_prefix('#')?;
+    if pointer.is_empty() {
+        return Some(root);
+    }
+    root.pointer(pointer)
+}
+
+fn matches_type(name: &str, instance: &Value) -> bool {
+    match name {
+        "null" => instance.is_null(),
+        "boolean" => instance.is_boolean(),
+        "object" => instance.is_object(),
+        "array" => instance.is_array(),
+        "string" => instance.is_string(),
+        "number" => instance.is_number(),
+        "integer" => {
+            instance.is_i64()
+                || instance.is_u64()
+                || instance.as_f64().is_some_and(|value| value.fract() == 0.0)
+        }
+        _ => true,
+    }
+}
+
+fn type_name(instance: &Value) -> &'static str {
+    match instance {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn describe_type(expected: &Value) -> String {
+    match expected {
+        Value::Array(names) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(" or "),
+        Value::String(name) => name.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn pointers(errors: &[ForgeSchemaErrorV1]) -> Vec<(&str, &str)> {
+        errors
+            .iter()
+            .map(|error| (error.instance_path.as_str(), error.keyword.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn reports_nested_violations_with_json_pointers() {
+        let schema = json!({
+            "type": "object",
+            "required": ["id", "tasks"],
+            "additionalProperties": false,
+            "properties": {
+                "id": { "type": "string", "pattern": "^[a-z]+$" },
+                "tasks": {
+                    "type": "array",
+                    "minItems": 1,
+                    "items": {
+                        "type": "object",
+                        "required": ["name"],
+                        "properties": {
+                            "name": { "type": "string", "maxLength": 3 },
+                            "status": { "enum": ["pending", "completed"] }
+                        }
+                    }
+                }
+            }
+        });
+        let instance = json!({
+            "id": "Alpha",
+            "extra/key": true,
+            "tasks": [{ "name": "long name", "status": "done" }, {}]
+        });
+
+        let errors = validate_json_against_schema(&schema, &instance);
+        assert_eq!(
+            pointers(&errors),
+            vec![
+                ("/extra~1key", "additionalProperties"),
+                ("/id", "pattern"),
+                ("/tasks/0/name", "maxLength"),
+                ("/tasks/0/status", "enum"),
+                ("/tasks/1", "required"),
+            ]
+        );
+        assert_eq!(errors[2].schema_path, "/properties/tasks/items/properties/name/maxLength");
+    }
+
+    #[test]
+    fn supports_type_unions_refs_and_combinators() {
+        let schema = json!({
+            "definitions": { "sha": { "type": ["string", "null"], "minLength": 7 } },
+            "type": "object",
+            "properties": {
+                "commit": { "$ref": "#/definitions/sha" },
+                "attempts": { "type": "integer", "minimum": 0 },
+                "mode": { "oneOf": [{ "const": "a" }, { "const": "b" }] }
+            }
+        });
+
+        assert!(validate_json_against_schema(
+            &schema,
+            &json!({ "commit": null, "attempts": 2, "mode": "a" })
+        )
+        .is_empty());
+
+        let errors = validate_json_against_schema(
+            &schema,
+            &json!({ "commit": "abc", "attempts": -1.5, "mode": "c" }),
+        );
+        assert_eq!(
+            pointers(&errors),
+            vec![
+                ("/attempts", "type"),
+                ("/attempts", "minimum"),
+                ("/commit", "minLength"),
+                ("/mode", "oneOf"),
+            ]
+        );
+    }
+}
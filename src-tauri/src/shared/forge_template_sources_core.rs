@@ -0,0 +1,642 @@
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::shared::forge_templates_core::{
+    bundled_template_dir, install_bundled_template_core, install_source_template_core,
+    list_bundled_templates_core, list_source_templates_core, read_installed_template_lock_core,
+    source_template_dir, validate_relative_file_path, ForgeBundledTemplateInfo,
+    ForgeTemplateLockV1, ForgeTemplateOriginV1, ForgeTemplateSourceKind,
+    FORGE_BUNDLED_TEMPLATE_SOURCE_ID,
+};
+
+const SOURCES_FILE_NAME: &str = "forge-template-sources.json";
+const SOURCES_FILE_SCHEMA: &str = "forge-template-sources-v1";
+const SOURCES_CACHE_DIR_NAME: &str = "forge-template-sources";
+
+/// A user-registered place to install Forge templates from, stored in the app data dir.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeTemplateSourceV1 {
+    pub(crate) id: String,
+    pub(crate) kind: ForgeTemplateSourceKind,
+    /// Absolute directory or `.tar.gz` path, or a git URL.
+    pub(crate) location: String,
+    /// Branch or tag to clone for git sources (remote HEAD when omitted).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) git_ref: Option<String>,
+}
+
+impl ForgeTemplateSourceV1 {
+    fn origin(&self) -> ForgeTemplateOriginV1 {
+        ForgeTemplateOriginV1 {
+            source_id: self.id.clone(),
+            kind: self.kind,
+            location: Some(self.location.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ForgeTemplateSourcesFileV1 {
+    schema: String,
+    sources: Vec<ForgeTemplateSourceV1>,
+}
+
+fn sources_file_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(SOURCES_FILE_NAME)
+}
+
+fn source_cache_dir(data_dir: &Path, source_id: &str) -> PathBuf {
+    data_dir.join(SOURCES_CACHE_DIR_NAME).join(source_id)
+}
+
+pub(crate) fn list_template_sources_core(
+    data_dir: &Path,
+) -> Result<Vec<ForgeTemplateSourceV1>, String> {
+    let path = sources_file_path(data_dir);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let raw = fs::read_to_string(&path).map_err(|err| err.to_string())?;
+    let parsed: ForgeTemplateSourcesFileV1 =
+        serde_json::from_str(&raw).map_err(|err| err.to_string())?;
+    if parsed.schema != SOURCES_FILE_SCHEMA {
+        return Err(format!(
+            "Unsupported {SOURCES_FILE_NAME} schema (expected {SOURCES_FILE_SCHEMA}): {}",
+            parsed.schema
+        ));
+    }
+    Ok(parsed.sources)
+}
+
+fn write_template_sources(data_dir: &Path, sources: Vec<ForgeTemplateSourceV1>) -> Result<(), String> {
+    fs::create_dir_all(data_dir).map_err(|err| err.to_string())?;
+    let file = ForgeTemplateSourcesFileV1 {
+        schema: SOURCES_FILE_SCHEMA.to_string(),
+        sources,
+    };
+    let raw = serde_json::to_string_pretty(&file).map_err(|err| err.to_string())?;
+    fs::write(sources_file_path(data_dir), format!("{raw}\n")).map_err(|err| err.to_string())
+}
+
+/// Derives a readable, unique source id from the last segment of the location.
+fn derive_source_id(location: &str, existing: &[ForgeTemplateSourceV1]) -> String {
+    let last_segment = location
+        .trim_end_matches(['/', '\\'])
+        .rsplit(['/', '\\', ':'])
+        .next()
+        .unwrap_or("");
+    let stem = last_segment
+        .trim_end_matches(".tar.gz")
+        .trim_end_matches(".tgz")
+        .trim_end_matches(".git");
+    let mut slug = String::new();
+    for ch in stem.chars() {
+        let ch = ch.to_ascii_lowercase();
+        if ch.is_ascii_lowercase() || ch.is_ascii_digit() {
+            slug.push(ch);
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_matches('-');
+    let base = if slug.is_empty() || slug == FORGE_BUNDLED_TEMPLATE_SOURCE_ID {
+        "source".to_string()
+    } else {
+        slug.to_string()
+    };
+
+    let taken = |id: &str| existing.iter().any(|source| source.id == id);
+    if !taken(&base) {
+        return base;
+    }
+    (2..)
+        .map(|index| format!("{base}-{index}"))
+        .find(|id| !taken(id))
+        .unwrap_or(base)
+}
+
+fn validate_source_location(kind: ForgeTemplateSourceKind, location: &str) -> Result<(), String> {
+    match kind {
+        ForgeTemplateSourceKind::Bundled => {
+            Err("Bundled templates are always available and cannot be added.".to_string())
+        }
+        ForgeTemplateSourceKind::Directory => {
+            let path = Path::new(location);
+            if !path.is_absolute() {
+                return Err("Template directory must be an absolute path.".to_string());
+            }
+            if !path.is_dir() {
+                return Err(format!("Template directory not found: {location}"));
+            }
+            Ok(())
+        }
+        ForgeTemplateSourceKind::Tarball => {
+            let path = Path::new(location);
+            if !path.is_absolute() {
+                return Err("Template archive must be an absolute path.".to_string());
+            }
+            if !(location.ends_with(".tar.gz") || location.ends_with(".tgz")) {
+                return Err("Template archive must be a .tar.gz or .tgz file.".to_string());
+            }
+            if !path.is_file() {
+                return Err(format!("Template archive not found: {location}"));
+            }
+            Ok(())
+        }
+        ForgeTemplateSourceKind::Git => Ok(()),
+    }
+}
+
+/// Registers a source after materializing it once, so unreachable repos or bad archives are
+/// reported up front rather than silently missing from the template list.
+pub(crate) fn add_template_source_core(
+    data_dir: &Path,
+    kind: ForgeTemplateSourceKind,
+    location: &str,
+    git_ref: Option<&str>,
+) -> Result<ForgeTemplateSourceV1, String> {
+    let location = location.trim();
+    if location.is_empty() {
+        return Err("location is required".to_string());
+    }
+    validate_source_location(kind, location)?;
+
+    let mut sources = list_template_sources_core(data_dir)?;
+    if sources
+        .iter()
+        .any(|source| source.kind == kind && source.location == location)
+    {
+        return Err(format!("Template source already registered: {location}"));
+    }
+
+    let git_ref = match kind {
+        ForgeTemplateSourceKind::Git => git_ref
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string),
+        _ => None,
+    };
+    let source = ForgeTemplateSourceV1 {
+        id: derive_source_id(location, &sources),
+        kind,
+        location: location.to_string(),
+        git_ref,
+    };
+
+    let root = resolve_source_templates_root(materialize_source(data_dir, &source, true)?);
+    if list_source_templates_core(&root, &source.origin()).is_empty() {
+        let _ = fs::remove_dir_all(source_cache_dir(data_dir, &source.id));
+        return Err(format!("No valid Forge templates found in {location}"));
+    }
+
+    sources.push(source.clone());
+    write_template_sources(data_dir, sources)?;
+    Ok(source)
+}
+
+pub(crate) fn remove_template_source_core(data_dir: &Path, source_id: &str) -> Result<(), String> {
+    let source_id = source_id.trim();
+    let mut sources = list_template_sources_core(data_dir)?;
+    let before = sources.len();
+    sources.retain(|source| source.id != source_id);
+    if sources.len() == before {
+        return Err(format!("Template source not found: {source_id}"));
+    }
+    write_template_sources(data_dir, sources)?;
+    let _ = fs::remove_dir_all(source_cache_dir(data_dir, source_id));
+    Ok(())
+}
+
+/// Returns a local directory holding the source's templates. Git and tarball sources are
+/// cached under the app data dir; `refresh` re-clones / re-extracts instead of reusing the cache.
+fn materialize_source(
+    data_dir: &Path,
+    source: &ForgeTemplateSourceV1,
+    refresh: bool,
+) -> Result<PathBuf, String> {
+    if source.kind == ForgeTemplateSourceKind::Directory {
+        let root = PathBuf::from(&source.location);
+        if !root.is_dir() {
+            return Err(format!("Template directory not found: {}", source.location));
+        }
+        return Ok(root);
+    }
+
+    let cache_dir = source_cache_dir(data_dir, &source.id);
+    if cache_dir.is_dir() && !refresh {
+        return Ok(cache_dir);
+    }
+
+    // Populate a staging folder first so a failed fetch keeps the previous cache.
+    let staging_dir = data_dir
+        .join(SOURCES_CACHE_DIR_NAME)
+        .join(format!(".{}-staging", source.id));
+    let _ = fs::remove_dir_all(&staging_dir);
+    if let Some(parent) = staging_dir.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+
+    let result = match source.kind {
+        ForgeTemplateSourceKind::Git => {
+            clone_git_source(&source.location, source.git_ref.as_deref(), &staging_dir)
+        }
+        ForgeTemplateSourceKind::Tarball => {
+            extract_tarball_source(Path::new(&source.location), &staging_dir)
+        }
+        ForgeTemplateSourceKind::Bundled | ForgeTemplateSourceKind::Directory => {
+            Err("Template source does not need caching.".to_string())
+        }
+    };
+    if let Err(err) = result {
+        let _ = fs::remove_dir_all(&staging_dir);
+        return Err(err);
+    }
+
+    let _ = fs::remove_dir_all(&cache_dir);
+    fs::rename(&staging_dir, &cache_dir).map_err(|err| err.to_string())?;
+    Ok(cache_dir)
+}
+
+fn clone_git_source(url: &str, git_ref: Option<&str>, dest: &Path) -> Result<(), String> {
+    let mut fetch_options = git2::FetchOptions::new();
+    // libgit2's local transport can't do shallow fetches.
+    let is_local = url.starts_with("file://") || Path::new(url).is_absolute();
+    if !is_local {
+        fetch_options.depth(1);
+    }
+    let mut builder = git2::build::RepoBuilder::new();
+    builder.fetch_options(fetch_options);
+    if let Some(git_ref) = git_ref {
+        builder.branch(git_ref);
+    }
+    builder
+        .clone(url, dest)
+        .map_err(|err| format!("Failed to clone template source {url}: {}", err.message()))?;
+    // Templates are plain files; drop the repository metadata from the cache.
+    let _ = fs::remove_dir_all(dest.join(".git"));
+    Ok(())
+}
+
+/// Extracts regular files and folders only, applying the same relative-path rules as
+/// `template.json` entries so archives can't write outside the cache folder.
+fn extract_tarball_source(archive_path: &Path, dest: &Path) -> Result<(), String> {
+    let file = fs::File::open(archive_path).map_err(|err| err.to_string())?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+    fs::create_dir_all(dest).map_err(|err| err.to_string())?;
+
+    let entries = archive
+        .entries()
+        .map_err(|err| format!("Invalid template archive: {err}"))?;
+    for entry in entries {
+        let mut entry = entry.map_err(|err| format!("Invalid template archive: {err}"))?;
+        let entry_path = entry
+            .path()
+            .map_err(|err| format!("Invalid template archive: {err}"))?
+            .to_string_lossy()
+            .to_string();
+        let entry_type = entry.header().entry_type();
+        if entry_type.is_dir() || entry_path.trim_end_matches('/').is_empty() {
+            continue;
+        }
+        if entry_type.is_pax_global_extensions() || entry_type.is_pax_local_extensions() {
+            continue;
+        }
+        if !entry_type.is_file() {
+            return Err(format!(
+                "Template archive may only contain regular files: {entry_path}"
+            ));
+        }
+
+        let rel_path = validate_relative_file_path(&entry_path)?;
+        let dest_path = dest.join(rel_path);
+        if let Some(parent) = dest_path.parent() {
+            fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+        }
+        entry
+            .unpack(&dest_path)
+            .map_err(|err| format!("Failed to extract {entry_path}: {err}"))?;
+    }
+    Ok(())
+}
+
+/// Archives are usually packed with a single top-level folder; look one level into it.
+fn resolve_source_templates_root(root: PathBuf) -> PathBuf {
+    if root.join("template.json").is_file() {
+        return root;
+    }
+    let children = fs::read_dir(&root)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    let hidden = path
+                        .file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| name.starts_with('.'));
+                    !hidden
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    match children.as_slice() {
+        [only] if only.is_dir() && !only.join("template.json").is_file() => only.clone(),
+        _ => root,
+    }
+}
+
+/// Bundled templates first, then every registered source. A source that can't be read is
+/// skipped so one broken entry doesn't hide the rest.
+pub(crate) fn list_all_templates_core(
+    bundled_templates_root: &Path,
+    data_dir: &Path,
+) -> Result<Vec<ForgeBundledTemplateInfo>, String> {
+    let mut output = list_bundled_templates_core(bundled_templates_root)?;
+    for source in list_template_sources_core(data_dir)? {
+        match materialize_source(data_dir, &source, false) {
+            Ok(root) => output.extend(list_source_templates_core(
+                &resolve_source_templates_root(root),
+                &source.origin(),
+            )),
+            Err(err) => eprintln!("forge template source {} unavailable: {err}", source.id),
+        }
+    }
+    Ok(output)
+}
+
+/// Installs from the bundled templates when `source_id` is empty or `bundled`; registered
+/// sources are refreshed first so installs pick up the latest revision.
+pub(crate) fn install_template_from_source_core(
+    bundled_templates_root: &Path,
+    data_dir: &Path,
+    workspace_root: &Path,
+    source_id: Option<&str>,
+    template_id: &str,
+) -> Result<ForgeTemplateLockV1, String> {
+    let source_id = source_id.map(str::trim).unwrap_or("");
+    if source_id.is_empty() || source_id == FORGE_BUNDLED_TEMPLATE_SOURCE_ID {
+        return install_bundled_template_core(bundled_templates_root, workspace_root, template_id);
+    }
+
+    let source = find_template_source(data_dir, source_id)?;
+    let root = resolve_source_templates_root(materialize_source(data_dir, &source, true)?);
+    install_source_template_core(&root, workspace_root, template_id, source.origin())
+}
+
+fn find_template_source(
+    data_dir: &Path,
+    source_id: &str,
+) -> Result<ForgeTemplateSourceV1, String> {
+    list_template_sources_core(data_dir)?
+        .into_iter()
+        .find(|source| source.id == source_id)
+        .ok_or_else(|| format!("Template source not found: {source_id}"))
+}
+
+/// Folder holding the latest version of the workspace's installed template, fetched from the
+/// source recorded in its lock (bundled for locks that predate sources).
+pub(crate) fn installed_template_upstream_dir_core(
+    bundled_templates_root: &Path,
+    data_dir: &Path,
+    workspace_root: &Path,
+) -> Result<PathBuf, String> {
+    let lock = read_installed_template_lock_core(workspace_root)?
+        .ok_or_else(|| "No Forge template installed.".to_string())?;
+    let template_id = lock.installed_template_id.as_str();
+    let origin = lock.installed_from.unwrap_or_default();
+    if origin.kind == ForgeTemplateSourceKind::Bundled {
+        return bundled_template_dir(bundled_templates_root, template_id);
+    }
+
+    let source = find_template_source(data_dir, &origin.source_id)?;
+    let root = resolve_source_templates_root(materialize_source(data_dir, &source, true)?);
+    source_template_dir(&root, template_id, &source.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn temp_dir(label: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!(
+            "codex-monitor-forge-sources-{label}-{}",
+            Uuid::new_v4()
+        ));
+        fs::create_dir_all(&root).expect("create temp dir");
+        root
+    }
+
+    fn bundled_templates_root() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("resources")
+            .join("forge")
+            .join("templates")
+    }
+
+    fn copy_dir(src: &Path, dest: &Path) {
+        for entry in fs::read_dir(src).expect("read dir").flatten() {
+            let path = entry.path();
+            let target = dest.join(entry.file_name());
+            if path.is_dir() {
+                fs::create_dir_all(&target).expect("create dir");
+                copy_dir(&path, &target);
+            } else {
+                fs::copy(&path, &target).expect("copy file");
+            }
+        }
+    }
+
+    /// A copy of the bundled ralph-loop template re-labelled as `team-loop`.
+    fn write_team_template(dir: &Path) {
+        fs::create_dir_all(dir).expect("create template dir");
+        copy_dir(&bundled_templates_root().join("ralph-loop"), dir);
+        let manifest_path = dir.join("template.json");
+        let mut manifest: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&manifest_path).expect("read manifest"))
+                .expect("parse manifest");
+        manifest["id"] = serde_json::json!("team-loop");
+        manifest["title"] = serde_json::json!("Team Loop");
+        fs::write(&manifest_path, manifest.to_string()).expect("write manifest");
+    }
+
+    #[test]
+    fn directory_source_lists_and_installs_with_origin() {
+        let data_dir = temp_dir("data");
+        let source_dir = temp_dir("dir");
+        let workspace = temp_dir("workspace");
+        write_team_template(&source_dir.join("team-loop"));
+        // Invalid templates in a source are skipped rather than failing the listing.
+        fs::create_dir_all(source_dir.join("broken")).expect("create broken dir");
+        fs::write(source_dir.join("broken").join("template.json"), "{}").expect("write broken");
+
+        let location = source_dir.to_string_lossy().to_string();
+        let source = add_template_source_core(
+            &data_dir,
+            ForgeTemplateSourceKind::Directory,
+            &location,
+            None,
+        )
+        .expect("add directory source");
+        assert!(add_template_source_core(
+            &data_dir,
+            ForgeTemplateSourceKind::Directory,
+            &location,
+            None
+        )
+        .unwrap_err()
+        .contains("already registered"));
+
+        let templates =
+            list_all_templates_core(&bundled_templates_root(), &data_dir).expect("list templates");
+        assert!(templates
+            .iter()
+            .any(|tpl| tpl.id == "ralph-loop" && tpl.origin.kind == ForgeTemplateSourceKind::Bundled));
+        let team = templates
+            .iter()
+            .find(|tpl| tpl.id == "team-loop")
+            .expect("team-loop listed");
+        assert_eq!(team.origin.source_id, source.id);
+        assert_eq!(team.origin.kind, ForgeTemplateSourceKind::Directory);
+        assert_eq!(team.origin.location.as_deref(), Some(location.as_str()));
+
+        let lock = install_template_from_source_core(
+            &bundled_templates_root(),
+            &data_dir,
+            &workspace,
+            Some(&source.id),
+            "team-loop",
+        )
+        .expect("install from directory source");
+        assert_eq!(lock.installed_template_id, "team-loop");
+        assert_eq!(lock.installed_from.as_ref(), Some(&team.origin));
+        assert!(workspace
+            .join(".agent")
+            .join("templates")
+            .join("team-loop")
+            .join("template.json")
+            .is_file());
+
+        remove_template_source_core(&data_dir, &source.id).expect("remove source");
+        assert!(list_template_sources_core(&data_dir)
+            .expect("list sources")
+            .is_empty());
+
+        for dir in [data_dir, source_dir, workspace] {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+
+    #[test]
+    fn tarball_source_extracts_nested_folder_and_rejects_unsafe_entries() {
+        let data_dir = temp_dir("data");
+        let staging = temp_dir("staging");
+        write_team_template(&staging.join("team-loop"));
+
+        let archive_path = staging.join("team-templates.tar.gz");
+        {
+            let file = fs::File::create(&archive_path).expect("create archive");
+            let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+            let mut builder = tar::Builder::new(encoder);
+            builder
+                .append_dir_all("bundle/team-loop", staging.join("team-loop"))
+                .expect("append template");
+            builder
+                .into_inner()
+                .expect("finish tar")
+                .finish()
+                .expect("finish gzip");
+        }
+
+        let source = add_template_source_core(
+            &data_dir,
+            ForgeTemplateSourceKind::Tarball,
+            &archive_path.to_string_lossy(),
+            None,
+        )
+        .expect("add tarball source");
+        assert_eq!(source.id, "team-templates");
+
+        let templates =
+            list_all_templates_core(&bundled_templates_root(), &data_dir).expect("list templates");
+        let team = templates
+            .iter()
+            .find(|tpl| tpl.id == "team-loop")
+            .expect("team-loop listed from archive");
+        assert_eq!(team.origin.kind, ForgeTemplateSourceKind::Tarball);
+
+        let evil_path = staging.join("evil.tar.gz");
+        {
+            let file = fs::File::create(&evil_path).expect("create archive");
+            let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+            let mut builder = tar::Builder::new(encoder);
+            let payload = b"oops";
+            let mut header = tar::Header::new_gnu();
+            header.set_size(payload.len() as u64);
+            header.set_mode(0o644);
+            // `set_path` refuses `..`, so write the raw name bytes like a hostile archive would.
+            header.as_old_mut().name[..12].copy_from_slice(b"../escape.md");
+            header.set_cksum();
+            builder
+                .append(&header, &payload[..])
+                .expect("append entry");
+            builder
+                .into_inner()
+                .expect("finish tar")
+                .finish()
+                .expect("finish gzip");
+        }
+        let err = add_template_source_core(
+            &data_dir,
+            ForgeTemplateSourceKind::Tarball,
+            &evil_path.to_string_lossy(),
+            None,
+        )
+        .unwrap_err();
+        assert!(err.contains("may not use '..'"), "unexpected error: {err}");
+        assert!(!data_dir.join(SOURCES_CACHE_DIR_NAME).join("escape.md").exists());
+
+        let _ = fs::remove_dir_all(&data_dir);
+        let _ = fs::remove_dir_all(&staging);
+    }
+
+    #[test]
+    fn git_source_clones_repository() {
+        let data_dir = temp_dir("data");
+        let repo_dir = temp_dir("repo");
+        write_team_template(&repo_dir.join("team-loop"));
+
+        let repo = git2::Repository::init(&repo_dir).expect("init repo");
+        let mut index = repo.index().expect("index");
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .expect("add files");
+        index.write().expect("write index");
+        let tree = repo
+            .find_tree(index.write_tree().expect("write tree"))
+            .expect("find tree");
+        let signature = git2::Signature::now("Forge", "forge@example.com").expect("signature");
+        repo.commit(Some("HEAD"), &signature, &signature, "templates", &tree, &[])
+            .expect("commit");
+
+        let url = format!("file://{}", repo_dir.to_string_lossy());
+        let source =
+            add_template_source_core(&data_dir, ForgeTemplateSourceKind::Git, &url, None)
+                .expect("add git source");
+        let cache_dir = source_cache_dir(&data_dir, &source.id);
+        assert!(cache_dir.join("team-loop").join("template.json").is_file());
+        assert!(!cache_dir.join(".git").exists());
+
+        let templates =
+            list_all_templates_core(&bundled_templates_root(), &data_dir).expect("list templates");
+        assert!(templates
+            .iter()
+            .any(|tpl| tpl.id == "team-loop" && tpl.origin.kind == ForgeTemplateSourceKind::Git));
+
+        let _ = fs::remove_dir_all(&data_dir);
+        let _ = fs::remove_dir_all(&repo_dir);
+    }
+}
@@ -1,14 +1,57 @@
 use chrono::{SecondsFormat, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Component, Path, PathBuf};
 
+#[path = "forge_templates_core/upgrade.rs"]
+mod upgrade;
+
+pub(crate) use upgrade::{
+    forge_template_upgrade_preview_core, forge_upgrade_template_core,
+    ForgeTemplateUpgradePreviewV1, ForgeTemplateUpgradeResultV1,
+};
+
+pub(crate) const FORGE_BUNDLED_TEMPLATE_SOURCE_ID: &str = "bundled";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum ForgeTemplateSourceKind {
+    Bundled,
+    Directory,
+    Git,
+    Tarball,
+}
+
+/// The template source an installable (or installed) template came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeTemplateOriginV1 {
+    pub(crate) source_id: String,
+    pub(crate) kind: ForgeTemplateSourceKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) location: Option<String>,
+}
+
+impl Default for ForgeTemplateOriginV1 {
+    fn default() -> Self {
+        Self {
+            source_id: FORGE_BUNDLED_TEMPLATE_SOURCE_ID.to_string(),
+            kind: ForgeTemplateSourceKind::Bundled,
+            location: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct ForgeBundledTemplateInfo {
     pub(crate) id: String,
     pub(crate) title: String,
     pub(crate) version: String,
+    #[serde(default)]
+    pub(crate) origin: ForgeTemplateOriginV1,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,6 +62,12 @@ pub(crate) struct ForgeTemplateLockV1 {
     pub(crate) installed_template_version: String,
     pub(crate) installed_at_iso: String,
     pub(crate) installed_files: Vec<String>,
+    /// sha256 of each file as shipped by the template, used to tell local edits from upstream
+    /// changes. Empty for locks written before hashes were recorded.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) installed_file_hashes: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) installed_from: Option<ForgeTemplateOriginV1>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,11 +96,35 @@ struct ForgeTemplateEntrypointsV1 {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct ForgeTemplateHooksV1 {
-    post_plan: String,
-    pre_execute: String,
-    post_step: String,
+    post_plan: ForgeTemplateHookV1,
+    pre_execute: ForgeTemplateHookV1,
+    post_step: ForgeTemplateHookV1,
+}
+
+/// A template hook entry. A bare string is a script path run with `node` (the original
+/// contract); an object names the interpreter explicitly.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum ForgeTemplateHookV1 {
+    Script(String),
+    Command(ForgeTemplateHookCommandV1),
 }
 
+/// `interpreter` is either `builtin` (the native render/state step for the hook's slot, where
+/// `script` only names the equivalent script) or a program on PATH that receives
+/// `[script] [args...] --context <path>`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeTemplateHookCommandV1 {
+    pub(crate) interpreter: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) script: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) args: Vec<String>,
+}
+
+pub(crate) const FORGE_BUILTIN_HOOK_INTERPRETER: &str = "builtin";
+
 fn is_ascii_lower_alnum(ch: char) -> bool {
     ch.is_ascii_lowercase() || ch.is_ascii_digit()
 }
@@ -81,7 +154,7 @@ fn validate_template_id(template_id: &str) -> Result<(), String> {
     Ok(())
 }
 
-fn validate_relative_file_path(rel: &str) -> Result<PathBuf, String> {
+pub(crate) fn validate_relative_file_path(rel: &str) -> Result<PathBuf, String> {
     let trimmed = rel.trim();
     if trimmed.is_empty() {
         return Err("template file path is empty".to_string());
@@ -136,6 +209,7 @@ pub(crate) fn list_bundled_templates_core(
             id: manifest.id,
             title: manifest.title,
             version: manifest.version,
+            origin: ForgeTemplateOriginV1::default(),
         });
     }
 
@@ -143,6 +217,96 @@ pub(crate) fn list_bundled_templates_core(
     Ok(output)
 }
 
+/// Template folders provided by a user source root. The root itself counts as a template when it
+/// holds a `template.json`; otherwise each direct child folder with one does.
+fn source_template_dirs(source_root: &Path) -> Vec<PathBuf> {
+    if source_root.join("template.json").is_file() {
+        return vec![source_root.to_path_buf()];
+    }
+    let mut dirs = fs::read_dir(source_root)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir() && path.join("template.json").is_file())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    dirs.sort();
+    dirs
+}
+
+/// Stricter than the bundled checks: user-authored templates must have a valid id, ship every
+/// listed file, and list every entrypoint they reference.
+fn verify_source_template(template_dir: &Path) -> Result<ForgeTemplateManifestV1, String> {
+    let manifest = read_manifest(template_dir)?;
+    validate_template_id(&manifest.id)?;
+
+    for rel in &manifest.files {
+        let rel_path = validate_relative_file_path(rel)?;
+        if !template_dir.join(rel_path).is_file() {
+            return Err(format!("Template file missing in source: {rel}"));
+        }
+    }
+
+    let entrypoints = &manifest.entrypoints;
+    let mut referenced = vec![
+        entrypoints.phases.as_str(),
+        entrypoints.plan_prompt.as_str(),
+        entrypoints.execute_prompt.as_str(),
+        entrypoints.plan_schema.as_str(),
+        entrypoints.state_schema.as_str(),
+    ];
+    for hook in [
+        &entrypoints.hooks.post_plan,
+        &entrypoints.hooks.pre_execute,
+        &entrypoints.hooks.post_step,
+    ] {
+        match hook {
+            ForgeTemplateHookV1::Script(script) => referenced.push(script.as_str()),
+            ForgeTemplateHookV1::Command(command) => {
+                if let Some(script) = command.script.as_deref() {
+                    referenced.push(script);
+                }
+            }
+        }
+    }
+    for rel in referenced {
+        let rel_path = validate_relative_file_path(rel)?;
+        let listed = manifest
+            .files
+            .iter()
+            .any(|file| Path::new(file.trim()) == rel_path.as_path());
+        if !listed {
+            return Err(format!(
+                "template.json entrypoint is not listed in files: {}",
+                rel.trim()
+            ));
+        }
+    }
+
+    Ok(manifest)
+}
+
+/// Lists templates under a user source root, skipping folders that fail verification.
+pub(crate) fn list_source_templates_core(
+    source_root: &Path,
+    origin: &ForgeTemplateOriginV1,
+) -> Vec<ForgeBundledTemplateInfo> {
+    let mut output = source_template_dirs(source_root)
+        .into_iter()
+        .filter_map(|dir| verify_source_template(&dir).ok())
+        .map(|manifest| ForgeBundledTemplateInfo {
+            id: manifest.id,
+            title: manifest.title,
+            version: manifest.version,
+            origin: origin.clone(),
+        })
+        .collect::<Vec<_>>();
+    output.sort_by_key(|template| template.title.to_ascii_lowercase());
+    output
+}
+
 pub(crate) fn read_installed_template_lock_core(
     workspace_root: &Path,
 ) -> Result<Option<ForgeTemplateLockV1>, String> {
@@ -178,6 +342,44 @@ pub(crate) fn read_installed_template_plan_prompt_core(
     Ok(normalize_plan_prompt(&raw))
 }
 
+/// Plan/state JSON Schemas declared by the installed template's `entrypoints`.
+#[derive(Debug, Clone)]
+pub(crate) struct ForgeInstalledTemplateSchemas {
+    pub(crate) plan_schema: serde_json::Value,
+    pub(crate) state_schema: serde_json::Value,
+}
+
+fn read_schema_file(template_root: &Path, rel: &str) -> Result<serde_json::Value, String> {
+    let schema_path = template_root.join(validate_relative_file_path(rel)?);
+    let raw = fs::read_to_string(&schema_path)
+        .map_err(|err| format!("Unable to read {}: {err}", schema_path.display()))?;
+    serde_json::from_str(&raw)
+        .map_err(|err| format!("Invalid JSON in {}: {err}", schema_path.display()))
+}
+
+/// Returns `None` when no template is installed.
+pub(crate) fn read_installed_template_schemas_core(
+    workspace_root: &Path,
+) -> Result<Option<ForgeInstalledTemplateSchemas>, String> {
+    let Some(lock) = read_installed_template_lock_core(workspace_root)? else {
+        return Ok(None);
+    };
+
+    let template_root = workspace_root
+        .join(".agent")
+        .join("templates")
+        .join(&lock.installed_template_id);
+    if !template_root.is_dir() {
+        return Err("Installed Forge template folder is missing.".to_string());
+    }
+
+    let manifest = read_manifest(&template_root)?;
+    Ok(Some(ForgeInstalledTemplateSchemas {
+        plan_schema: read_schema_file(&template_root, &manifest.entrypoints.plan_schema)?,
+        state_schema: read_schema_file(&template_root, &manifest.entrypoints.state_schema)?,
+    }))
+}
+
 fn normalize_plan_prompt(prompt: &str) -> String {
     let mut out = prompt.to_string();
 
@@ -312,6 +514,20 @@ pub(crate) fn install_bundled_template_core(
     workspace_root: &Path,
     template_id: &str,
 ) -> Result<ForgeTemplateLockV1, String> {
+    let src_template_dir = bundled_template_dir(bundled_templates_root, template_id)?;
+    let manifest = read_manifest(&src_template_dir)?;
+    install_template_files(
+        &src_template_dir,
+        manifest,
+        workspace_root,
+        ForgeTemplateOriginV1::default(),
+    )
+}
+
+pub(crate) fn bundled_template_dir(
+    bundled_templates_root: &Path,
+    template_id: &str,
+) -> Result<PathBuf, String> {
     validate_template_id(template_id)?;
 
     let src_template_dir = bundled_templates_root.join(template_id);
@@ -323,11 +539,97 @@ pub(crate) fn install_bundled_template_core(
     if manifest.id != template_id {
         return Err("template.json id does not match template folder".to_string());
     }
+    Ok(src_template_dir)
+}
+
+pub(crate) fn install_source_template_core(
+    source_root: &Path,
+    workspace_root: &Path,
+    template_id: &str,
+    origin: ForgeTemplateOriginV1,
+) -> Result<ForgeTemplateLockV1, String> {
+    let template_dir = source_template_dir(source_root, template_id, &origin.source_id)?;
+    let manifest = read_manifest(&template_dir)?;
+    install_template_files(&template_dir, manifest, workspace_root, origin)
+}
 
+/// Finds the verified folder for `template_id` within a user source root.
+pub(crate) fn source_template_dir(
+    source_root: &Path,
+    template_id: &str,
+    source_id: &str,
+) -> Result<PathBuf, String> {
+    validate_template_id(template_id)?;
+
+    let mut first_error = None;
+    for dir in source_template_dirs(source_root) {
+        match verify_source_template(&dir) {
+            Ok(manifest) if manifest.id == template_id => return Ok(dir),
+            Ok(_) => {}
+            Err(err) => {
+                if dir.file_name().and_then(|name| name.to_str()) == Some(template_id) {
+                    first_error.get_or_insert(err);
+                }
+            }
+        }
+    }
+
+    Err(first_error
+        .unwrap_or_else(|| format!("Template {template_id} not found in source {source_id}")))
+}
+
+pub(crate) fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn file_sha256(path: &Path) -> Option<String> {
+    fs::read(path).ok().map(|bytes| sha256_hex(&bytes))
+}
+
+/// Pristine copy of the installed template, kept as the merge base for upgrades.
+fn template_base_root(workspace_root: &Path, template_id: &str) -> PathBuf {
+    workspace_root
+        .join(".agent")
+        .join("template-base")
+        .join(template_id)
+}
+
+fn write_template_lock(workspace_root: &Path, lock: &ForgeTemplateLockV1) -> Result<(), String> {
+    let lock_path = workspace_root.join(".agent").join("template-lock.json");
+    let raw = serde_json::to_string_pretty(lock).map_err(|err| err.to_string())?;
+    fs::write(&lock_path, format!("{raw}\n")).map_err(|err| err.to_string())
+}
+
+fn remove_empty_dirs(dir: &Path) {
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                remove_empty_dirs(&path);
+            }
+        }
+    }
+    // Fails (and is ignored) while the folder still has files in it.
+    let _ = fs::remove_dir(dir);
+}
+
+fn install_template_files(
+    src_template_dir: &Path,
+    manifest: ForgeTemplateManifestV1,
+    workspace_root: &Path,
+    origin: ForgeTemplateOriginV1,
+) -> Result<ForgeTemplateLockV1, String> {
+    let template_id = manifest.id.as_str();
     let agent_dir = workspace_root.join(".agent");
     let dest_template_root = agent_dir.join("templates").join(template_id);
     fs::create_dir_all(&dest_template_root).map_err(|err| err.to_string())?;
+    let base_root = template_base_root(workspace_root, template_id);
+    let _ = fs::remove_dir_all(&base_root);
 
+    let mut installed_file_hashes = BTreeMap::new();
     for rel in &manifest.files {
         let rel_path = validate_relative_file_path(rel)?;
         let src_path = src_template_dir.join(&rel_path);
@@ -336,6 +638,10 @@ pub(crate) fn install_bundled_template_core(
         }
         let dest_path = dest_template_root.join(&rel_path);
         copy_file(&src_path, &dest_path)?;
+        copy_file(&src_path, &base_root.join(&rel_path))?;
+        if let Some(hash) = file_sha256(&src_path) {
+            installed_file_hashes.insert(rel.clone(), hash);
+        }
 
         // Mirror skills into workspace-local .agent/skills/*
         if let Some(stripped) = rel.strip_prefix("skills/") {
@@ -354,11 +660,10 @@ pub(crate) fn install_bundled_template_core(
         installed_template_version: manifest.version,
         installed_at_iso,
         installed_files: manifest.files,
+        installed_file_hashes,
+        installed_from: Some(origin),
     };
-
-    let lock_path = agent_dir.join("template-lock.json");
-    let raw = serde_json::to_string_pretty(&lock).map_err(|err| err.to_string())?;
-    fs::write(&lock_path, format!("{raw}\n")).map_err(|err| err.to_string())?;
+    write_template_lock(workspace_root, &lock)?;
 
     Ok(lock)
 }
@@ -366,15 +671,34 @@ pub(crate) fn install_bundled_template_core(
 pub(crate) fn uninstall_template_core(workspace_root: &Path) -> Result<(), String> {
     let agent_dir = workspace_root.join(".agent");
     let lock_path = agent_dir.join("template-lock.json");
-    let installed_template_id = fs::read_to_string(&lock_path)
+    let lock = fs::read_to_string(&lock_path)
         .ok()
-        .and_then(|raw| serde_json::from_str::<ForgeTemplateLockV1>(&raw).ok())
-        .map(|lock| lock.installed_template_id);
-
-    // Keep it simple for v1: remove the installed template folder recorded in the lock file.
-    if let Some(template_id) = installed_template_id {
-        let template_root = agent_dir.join("templates").join(template_id);
-        let _ = fs::remove_dir_all(&template_root);
+        .and_then(|raw| serde_json::from_str::<ForgeTemplateLockV1>(&raw).ok());
+
+    if let Some(lock) = lock {
+        let template_root = agent_dir.join("templates").join(&lock.installed_template_id);
+        if lock.installed_file_hashes.is_empty() {
+            // Older locks can't tell edited files apart, so remove the whole folder as before.
+            let _ = fs::remove_dir_all(&template_root);
+        } else {
+            // Only remove files that still match what was installed; local edits and files the
+            // user added stay in place.
+            for (rel, hash) in &lock.installed_file_hashes {
+                let Ok(rel_path) = validate_relative_file_path(rel) else {
+                    continue;
+                };
+                let path = template_root.join(rel_path);
+                if file_sha256(&path).as_deref() == Some(hash.as_str()) {
+                    let _ = fs::remove_file(&path);
+                }
+            }
+            remove_empty_dirs(&template_root);
+        }
+        let _ = fs::remove_dir_all(template_base_root(
+            workspace_root,
+            &lock.installed_template_id,
+        ));
+        remove_empty_dirs(&agent_dir.join("template-base"));
     }
 
     let _ = fs::remove_file(&lock_path);
@@ -0,0 +1,670 @@
+use super::*;
+
+/// Largest base x side line-count product we diff; bigger text files are treated as conflicts
+/// instead of merged.
+const MAX_MERGE_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum ForgeTemplateUpgradeFileStatus {
+    /// Neither side changed the file.
+    Unchanged,
+    /// Only upstream changed it; the new version replaces the installed one.
+    Updated,
+    /// Only the workspace changed it (or deleted it); the local copy is kept.
+    KeptLocal,
+    /// Both changed it and the edits merged cleanly.
+    Merged,
+    /// Both changed it and the edits overlap; the local copy is kept and the new upstream
+    /// version is written next to it as `<file>.upstream`.
+    Conflict,
+    /// New upstream file.
+    Added,
+    /// Dropped upstream and unmodified locally, so it is deleted.
+    Removed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeTemplateUpgradeFileV1 {
+    pub(crate) path: String,
+    pub(crate) status: ForgeTemplateUpgradeFileStatus,
+    pub(crate) upstream_changed: bool,
+    pub(crate) local_changed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeTemplateUpgradePreviewV1 {
+    pub(crate) template_id: String,
+    pub(crate) installed_version: String,
+    pub(crate) available_version: String,
+    pub(crate) files: Vec<ForgeTemplateUpgradeFileV1>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeTemplateUpgradeResultV1 {
+    pub(crate) lock: ForgeTemplateLockV1,
+    pub(crate) files: Vec<ForgeTemplateUpgradeFileV1>,
+}
+
+struct PlannedFile {
+    report: ForgeTemplateUpgradeFileV1,
+    rel_path: PathBuf,
+    /// Content to write into the installed template for this file, if it changes.
+    write: Option<Vec<u8>>,
+    /// Upstream content to park next to a conflicting file.
+    upstream_copy: Option<Vec<u8>>,
+    /// For a conflict with no recoverable base, the installed content. It becomes the new base
+    /// so the lock doesn't claim the local copy came from upstream.
+    local_base: Option<Vec<u8>>,
+}
+
+struct UpgradePlan {
+    manifest: ForgeTemplateManifestV1,
+    lock: ForgeTemplateLockV1,
+    template_root: PathBuf,
+    files: Vec<PlannedFile>,
+}
+
+fn read_installed_lock(workspace_root: &Path) -> Result<ForgeTemplateLockV1, String> {
+    read_installed_template_lock_core(workspace_root)?
+        .ok_or_else(|| "No Forge template installed.".to_string())
+}
+
+fn plan_upgrade(
+    workspace_root: &Path,
+    src_template_dir: &Path,
+) -> Result<UpgradePlan, String> {
+    let lock = read_installed_lock(workspace_root)?;
+    let manifest = read_manifest(src_template_dir)?;
+    if manifest.id != lock.installed_template_id {
+        return Err(format!(
+            "Template {} does not match the installed template {}",
+            manifest.id, lock.installed_template_id
+        ));
+    }
+
+    let template_root = workspace_root
+        .join(".agent")
+        .join("templates")
+        .join(&lock.installed_template_id);
+    if !template_root.is_dir() {
+        return Err("Installed Forge template folder is missing.".to_string());
+    }
+    let base_root = template_base_root(workspace_root, &lock.installed_template_id);
+    let same_version = manifest.version == lock.installed_template_version;
+
+    let mut paths: Vec<&String> = lock.installed_files.iter().collect();
+    for rel in &manifest.files {
+        if !lock.installed_files.contains(rel) {
+            paths.push(rel);
+        }
+    }
+
+    let mut files = Vec::new();
+    for rel in paths {
+        let rel_path = validate_relative_file_path(rel)?;
+        let in_old = lock.installed_files.contains(rel);
+        let upstream = if manifest.files.contains(rel) {
+            let bytes = fs::read(src_template_dir.join(&rel_path))
+                .map_err(|_| format!("Template file missing in bundle: {rel}"))?;
+            Some(bytes)
+        } else {
+            None
+        };
+        let local = fs::read(template_root.join(&rel_path)).ok();
+        let installed_hash = lock.installed_file_hashes.get(rel.as_str());
+        // Legacy installs have no base snapshot; a source still at the installed version is one.
+        let base = fs::read(base_root.join(&rel_path)).ok().or_else(|| {
+            if in_old && installed_hash.is_none() && same_version {
+                upstream.clone()
+            } else {
+                None
+            }
+        });
+        let no_base = installed_hash.is_none() && base.is_none();
+
+        let mut planned = plan_file(
+            rel,
+            rel_path,
+            in_old,
+            installed_hash.map(String::as_str),
+            base,
+            local.clone(),
+            upstream,
+        );
+        if no_base && planned.report.status == ForgeTemplateUpgradeFileStatus::Conflict {
+            planned.local_base = local;
+        }
+        files.push(planned);
+    }
+
+    Ok(UpgradePlan {
+        manifest,
+        lock,
+        template_root,
+        files,
+    })
+}
+
+fn plan_file(
+    rel: &str,
+    rel_path: PathBuf,
+    in_old: bool,
+    installed_hash: Option<&str>,
+    base: Option<Vec<u8>>,
+    local: Option<Vec<u8>>,
+    upstream: Option<Vec<u8>>,
+) -> PlannedFile {
+    let hash_of = |bytes: &Option<Vec<u8>>| bytes.as_deref().map(sha256_hex);
+    // Prefer the lock hash; fall back to the base snapshot. Legacy installs have neither, so
+    // any difference from upstream is treated as a change on both sides.
+    let reference = installed_hash
+        .map(str::to_string)
+        .or_else(|| hash_of(&base));
+    let (local_changed, upstream_changed) = match reference.as_deref() {
+        Some(reference) => (
+            hash_of(&local).as_deref() != Some(reference),
+            hash_of(&upstream).as_deref() != Some(reference),
+        ),
+        None => {
+            let differs = local != upstream;
+            (differs, differs)
+        }
+    };
+
+    let planned = |status, write, upstream_copy| PlannedFile {
+        report: ForgeTemplateUpgradeFileV1 {
+            path: rel.to_string(),
+            status,
+            upstream_changed,
+            local_changed,
+        },
+        rel_path: rel_path.clone(),
+        write,
+        upstream_copy,
+        local_base: None,
+    };
+
+    let Some(upstream) = upstream else {
+        // Upstream dropped the file.
+        return if local.is_none() {
+            planned(ForgeTemplateUpgradeFileStatus::Removed, None, None)
+        } else if local_changed {
+            planned(ForgeTemplateUpgradeFileStatus::KeptLocal, None, None)
+        } else {
+            planned(ForgeTemplateUpgradeFileStatus::Removed, None, None)
+        };
+    };
+
+    if !in_old {
+        return match local {
+            Some(local) if local != upstream => {
+                planned(ForgeTemplateUpgradeFileStatus::Conflict, None, Some(upstream))
+            }
+            _ => planned(ForgeTemplateUpgradeFileStatus::Added, Some(upstream), None),
+        };
+    }
+
+    let Some(local) = local else {
+        // A file the user deleted stays deleted.
+        return planned(ForgeTemplateUpgradeFileStatus::KeptLocal, None, None);
+    };
+    if local == upstream {
+        return planned(ForgeTemplateUpgradeFileStatus::Unchanged, None, None);
+    }
+    match (upstream_changed, local_changed) {
+        (false, false) => planned(ForgeTemplateUpgradeFileStatus::Unchanged, None, None),
+        (true, false) => planned(ForgeTemplateUpgradeFileStatus::Updated, Some(upstream), None),
+        (false, true) => planned(ForgeTemplateUpgradeFileStatus::KeptLocal, None, None),
+        (true, true) => {
+            let merged = base.as_deref().and_then(|base| {
+                let base = std::str::from_utf8(base).ok()?;
+                let ours = std::str::from_utf8(&local).ok()?;
+                let theirs = std::str::from_utf8(&upstream).ok()?;
+                merge_three_way(base, ours, theirs)
+            });
+            match merged {
+                Some(merged) => planned(
+                    ForgeTemplateUpgradeFileStatus::Merged,
+                    Some(merged.into_bytes()),
+                    None,
+                ),
+                None => planned(ForgeTemplateUpgradeFileStatus::Conflict, None, Some(upstream)),
+            }
+        }
+    }
+}
+
+/// Compares the installed template with `src_template_dir` (the same template from its source)
+/// without touching the workspace.
+pub(crate) fn forge_template_upgrade_preview_core(
+    workspace_root: &Path,
+    src_template_dir: &Path,
+) -> Result<ForgeTemplateUpgradePreviewV1, String> {
+    let plan = plan_upgrade(workspace_root, src_template_dir)?;
+    Ok(ForgeTemplateUpgradePreviewV1 {
+        template_id: plan.manifest.id,
+        installed_version: plan.lock.installed_template_version,
+        available_version: plan.manifest.version,
+        files: plan.files.into_iter().map(|file| file.report).collect(),
+    })
+}
+
+pub(crate) fn forge_upgrade_template_core(
+    workspace_root: &Path,
+    src_template_dir: &Path,
+) -> Result<ForgeTemplateUpgradeResultV1, String> {
+    let plan = plan_upgrade(workspace_root, src_template_dir)?;
+    let agent_dir = workspace_root.join(".agent");
+
+    for file in &plan.files {
+        let dest_path = plan.template_root.join(&file.rel_path);
+        if let Some(content) = &file.write {
+            if let Some(parent) = dest_path.parent() {
+                fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+            }
+            fs::write(&dest_path, content).map_err(|err| err.to_string())?;
+        }
+        if let Some(content) = &file.upstream_copy {
+            let mut upstream_path = dest_path.clone().into_os_string();
+            upstream_path.push(".upstream");
+            fs::write(PathBuf::from(upstream_path), content).map_err(|err| err.to_string())?;
+        }
+        if file.report.status == ForgeTemplateUpgradeFileStatus::Removed {
+            let _ = fs::remove_file(&dest_path);
+        }
+
+        // Keep the workspace skill mirror in step with the installed template.
+        if file.write.is_some() {
+            if let Some(stripped) = file.report.path.strip_prefix("skills/") {
+                copy_file(&dest_path, &agent_dir.join("skills").join(stripped))?;
+            }
+        }
+    }
+    remove_empty_dirs(&plan.template_root);
+    fs::create_dir_all(&plan.template_root).map_err(|err| err.to_string())?;
+
+    // The new upstream files become the merge base and hash reference for the next upgrade,
+    // except for conflicts whose base was lost; those keep the installed content.
+    let base_root = template_base_root(workspace_root, &plan.manifest.id);
+    let _ = fs::remove_dir_all(&base_root);
+    let mut installed_file_hashes = BTreeMap::new();
+    for rel in &plan.manifest.files {
+        let rel_path = validate_relative_file_path(rel)?;
+        let base_path = base_root.join(&rel_path);
+        let local_base = plan
+            .files
+            .iter()
+            .find(|file| &file.report.path == rel)
+            .and_then(|file| file.local_base.as_deref());
+        if let Some(content) = local_base {
+            if let Some(parent) = base_path.parent() {
+                fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+            }
+            fs::write(&base_path, content).map_err(|err| err.to_string())?;
+            installed_file_hashes.insert(rel.clone(), sha256_hex(content));
+            continue;
+        }
+        let src_path = src_template_dir.join(&rel_path);
+        copy_file(&src_path, &base_path)?;
+        if let Some(hash) = file_sha256(&src_path) {
+            installed_file_hashes.insert(rel.clone(), hash);
+        }
+    }
+
+    let lock = ForgeTemplateLockV1 {
+        schema: plan.lock.schema,
+        installed_template_id: plan.manifest.id,
+        installed_template_version: plan.manifest.version,
+        installed_at_iso: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        installed_files: plan.manifest.files,
+        installed_file_hashes,
+        installed_from: plan.lock.installed_from,
+    };
+    write_template_lock(workspace_root, &lock)?;
+
+    Ok(ForgeTemplateUpgradeResultV1 {
+        lock,
+        files: plan.files.into_iter().map(|file| file.report).collect(),
+    })
+}
+
+/// For each line of `a`, the index of the line of `b` it is paired with in a longest common
+/// subsequence. `None` when the inputs are too large to diff.
+fn lcs_matches(a: &[&str], b: &[&str]) -> Option<Vec<Option<usize>>> {
+    let (n, m) = (a.len(), b.len());
+    if n.saturating_mul(m) > MAX_MERGE_CELLS {
+        return None;
+    }
+    let width = m + 1;
+    let mut lengths = vec![0u32; (n + 1) * width];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[i * width + j] = if a[i] == b[j] {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut matches = vec![None; n];
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if a[i] == b[j] {
+            matches[i] = Some(j);
+            i += 1;
+            j += 1;
+        } else if lengths[(i + 1) * width + j] >= lengths[i * width + j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    Some(matches)
+}
+
+/// Line-based diff3: returns the merged text, or `None` when both sides changed the same
+/// region differently.
+fn merge_three_way(base: &str, ours: &str, theirs: &str) -> Option<String> {
+    let base: Vec<&str> = base.split_inclusive('\n').collect();
+    let ours: Vec<&str> = ours.split_inclusive('\n').collect();
+    let theirs: Vec<&str> = theirs.split_inclusive('\n').collect();
+    let ours_matches = lcs_matches(&base, &ours)?;
+    let theirs_matches = lcs_matches(&base, &theirs)?;
+
+    let mut merged = String::new();
+    let (mut i, mut j, mut k) = (0, 0, 0);
+    loop {
+        // Next base line both sides kept; everything before it is a (possibly empty) hunk.
+        let sync = (i..base.len()).find_map(|b| match (ours_matches[b], theirs_matches[b]) {
+            (Some(o), Some(t)) => Some((b, o, t)),
+            _ => None,
+        });
+        let (b_end, o_end, t_end) = sync.unwrap_or((base.len(), ours.len(), theirs.len()));
+
+        let base_hunk = &base[i..b_end];
+        let ours_hunk = &ours[j..o_end];
+        let theirs_hunk = &theirs[k..t_end];
+        let resolved = if ours_hunk == base_hunk || ours_hunk == theirs_hunk {
+            theirs_hunk
+        } else if theirs_hunk == base_hunk {
+            ours_hunk
+        } else {
+            return None;
+        };
+        merged.extend(resolved.iter().copied());
+
+        match sync {
+            Some((b, o, t)) => {
+                merged.push_str(base[b]);
+                i = b + 1;
+                j = o + 1;
+                k = t + 1;
+            }
+            None => break,
+        }
+    }
+    Some(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    #[test]
+    fn merge_three_way_combines_disjoint_edits_and_detects_overlap() {
+        let base = "one\ntwo\nthree\nfour\n";
+        let ours = "one\ntwo (local)\nthree\nfour\n";
+        let theirs = "one\ntwo\nthree\nfour (upstream)\nfive\n";
+        assert_eq!(
+            merge_three_way(base, ours, theirs).as_deref(),
+            Some("one\ntwo (local)\nthree\nfour (upstream)\nfive\n")
+        );
+
+        let conflicting = "one\ntwo (upstream)\nthree\nfour\n";
+        assert_eq!(merge_three_way(base, ours, conflicting), None);
+        // Identical edits on both sides are not a conflict.
+        assert_eq!(merge_three_way(base, ours, ours).as_deref(), Some(ours));
+    }
+
+    fn write_template(dir: &Path, version: &str, files: &[(&str, &str)]) {
+        let _ = fs::remove_dir_all(dir);
+        fs::create_dir_all(dir).expect("create template dir");
+        let mut listed = vec!["template.json".to_string()];
+        for (rel, content) in files {
+            let path = dir.join(rel);
+            fs::create_dir_all(path.parent().unwrap()).expect("create parent");
+            fs::write(&path, content).expect("write template file");
+            listed.push(rel.to_string());
+        }
+        let manifest = json!({
+          "schema": "forge-template-v1",
+          "id": "upgrade-me",
+          "title": "Upgrade Me",
+          "version": version,
+          "files": listed,
+          "entrypoints": {
+            "phases": "phases.json",
+            "planPrompt": "prompts/plan.md",
+            "executePrompt": "prompts/execute.md",
+            "planSchema": "schemas/plan.schema.json",
+            "stateSchema": "schemas/state.schema.json",
+            "requiredSkills": [],
+            "hooks": {
+              "postPlan": "scripts/post-plan.mjs",
+              "preExecute": "scripts/pre-execute.mjs",
+              "postStep": "scripts/post-step.mjs"
+            }
+          }
+        });
+        fs::write(dir.join("template.json"), manifest.to_string()).expect("write manifest");
+    }
+
+    #[test]
+    fn upgrade_merges_local_edits_and_reports_each_file() {
+        let root = std::env::temp_dir().join(format!(
+            "codex-monitor-forge-upgrade-test-{}",
+            Uuid::new_v4()
+        ));
+        let bundled = root.join("bundled");
+        let workspace = root.join("workspace");
+        fs::create_dir_all(&workspace).expect("create workspace");
+        let template_dir = bundled.join("upgrade-me");
+
+        write_template(
+            &template_dir,
+            "1.0.0",
+            &[
+                ("prompts/execute.md", "# Execute\nstep one\nstep two\n"),
+                ("prompts/plan.md", "# Plan\n"),
+                ("notes.md", "shared line\n"),
+                ("old.md", "going away\n"),
+            ],
+        );
+        let lock = install_bundled_template_core(&bundled, &workspace, "upgrade-me")
+            .expect("install v1");
+        assert_eq!(lock.installed_file_hashes.len(), 5);
+
+        let installed = workspace.join(".agent").join("templates").join("upgrade-me");
+        fs::write(
+            installed.join("prompts/execute.md"),
+            "# Execute\nstep one (repo rule)\nstep two\n",
+        )
+        .expect("edit execute prompt");
+        fs::write(installed.join("notes.md"), "local line\n").expect("edit notes");
+
+        write_template(
+            &template_dir,
+            "2.0.0",
+            &[
+                ("prompts/execute.md", "# Execute\nstep one\nstep two\nstep three\n"),
+                ("prompts/plan.md", "# Plan v2\n"),
+                ("notes.md", "upstream line\n"),
+                ("new.md", "brand new\n"),
+            ],
+        );
+
+        let preview =
+            forge_template_upgrade_preview_core(&workspace, &template_dir).expect("preview");
+        assert_eq!(preview.installed_version, "1.0.0");
+        assert_eq!(preview.available_version, "2.0.0");
+        let status_of = |files: &[ForgeTemplateUpgradeFileV1], path: &str| {
+            files
+                .iter()
+                .find(|file| file.path == path)
+                .map(|file| file.status)
+                .expect("file in report")
+        };
+        assert_eq!(
+            status_of(&preview.files, "prompts/execute.md"),
+            ForgeTemplateUpgradeFileStatus::Merged
+        );
+        assert_eq!(
+            status_of(&preview.files, "prompts/plan.md"),
+            ForgeTemplateUpgradeFileStatus::Updated
+        );
+        assert_eq!(
+            status_of(&preview.files, "notes.md"),
+            ForgeTemplateUpgradeFileStatus::Conflict
+        );
+        assert_eq!(
+            status_of(&preview.files, "old.md"),
+            ForgeTemplateUpgradeFileStatus::Removed
+        );
+        assert_eq!(
+            status_of(&preview.files, "new.md"),
+            ForgeTemplateUpgradeFileStatus::Added
+        );
+        // Preview leaves the workspace alone.
+        assert!(installed.join("old.md").is_file());
+
+        let result = forge_upgrade_template_core(&workspace, &template_dir).expect("upgrade");
+        assert_eq!(result.lock.installed_template_version, "2.0.0");
+        assert_eq!(
+            fs::read_to_string(installed.join("prompts/execute.md")).unwrap(),
+            "# Execute\nstep one (repo rule)\nstep two\nstep three\n"
+        );
+        assert_eq!(
+            fs::read_to_string(installed.join("prompts/plan.md")).unwrap(),
+            "# Plan v2\n"
+        );
+        assert_eq!(
+            fs::read_to_string(installed.join("notes.md")).unwrap(),
+            "local line\n"
+        );
+        assert_eq!(
+            fs::read_to_string(installed.join("notes.md.upstream")).unwrap(),
+            "upstream line\n"
+        );
+        assert!(!installed.join("old.md").exists());
+        assert!(installed.join("new.md").is_file());
+
+        // A second preview only reports the files that still carry local edits.
+        let again =
+            forge_template_upgrade_preview_core(&workspace, &template_dir).expect("preview again");
+        assert!(again
+            .files
+            .iter()
+            .all(|file| !file.upstream_changed));
+
+        // Uninstall keeps locally edited files.
+        uninstall_template_core(&workspace).expect("uninstall");
+        assert!(installed.join("prompts/execute.md").is_file());
+        assert!(!installed.join("prompts/plan.md").exists());
+        assert!(!workspace.join(".agent").join("template-base").exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn upgrade_of_legacy_lock_recovers_base_from_matching_source() {
+        let root = std::env::temp_dir().join(format!(
+            "codex-monitor-forge-upgrade-test-{}",
+            Uuid::new_v4()
+        ));
+        let bundled = root.join("bundled");
+        let workspace = root.join("workspace");
+        fs::create_dir_all(&workspace).expect("create workspace");
+        let template_dir = bundled.join("upgrade-me");
+
+        write_template(
+            &template_dir,
+            "1.0.0",
+            &[
+                ("prompts/plan.md", "# Plan\n"),
+                ("notes.md", "shared line\n"),
+            ],
+        );
+        let mut lock =
+            install_bundled_template_core(&bundled, &workspace, "upgrade-me").expect("install v1");
+        // Locks written before hashes were recorded have no base snapshot either.
+        lock.installed_file_hashes.clear();
+        write_template_lock(&workspace, &lock).expect("write legacy lock");
+        let _ = fs::remove_dir_all(workspace.join(".agent").join("template-base"));
+        let installed = workspace
+            .join(".agent")
+            .join("templates")
+            .join("upgrade-me");
+        fs::write(installed.join("notes.md"), "local line\n").expect("edit notes");
+
+        let status_of = |files: &[ForgeTemplateUpgradeFileV1], path: &str| {
+            files
+                .iter()
+                .find(|file| file.path == path)
+                .map(|file| file.status)
+                .expect("file in report")
+        };
+
+        // The source is still at the installed version, so it serves as the base.
+        let preview = forge_template_upgrade_preview_core(&workspace, &template_dir)
+            .expect("preview same version");
+        assert_eq!(
+            status_of(&preview.files, "notes.md"),
+            ForgeTemplateUpgradeFileStatus::KeptLocal
+        );
+        assert_eq!(
+            status_of(&preview.files, "prompts/plan.md"),
+            ForgeTemplateUpgradeFileStatus::Unchanged
+        );
+
+        // A newer source can't supply the old base: differing files conflict, and the lock
+        // records the installed content for them rather than upstream's.
+        write_template(
+            &template_dir,
+            "2.0.0",
+            &[
+                ("prompts/plan.md", "# Plan v2\n"),
+                ("notes.md", "shared line\n"),
+            ],
+        );
+        let result = forge_upgrade_template_core(&workspace, &template_dir).expect("upgrade");
+        assert_eq!(
+            status_of(&result.files, "prompts/plan.md"),
+            ForgeTemplateUpgradeFileStatus::Conflict
+        );
+        assert_eq!(
+            result.lock.installed_file_hashes.get("prompts/plan.md"),
+            Some(&sha256_hex(b"# Plan\n"))
+        );
+        assert_eq!(
+            result.lock.installed_file_hashes.get("notes.md"),
+            Some(&sha256_hex(b"local line\n"))
+        );
+
+        // The next upgrade can tell the untouched file apart and takes upstream.
+        let again =
+            forge_template_upgrade_preview_core(&workspace, &template_dir).expect("preview again");
+        assert_eq!(
+            status_of(&again.files, "prompts/plan.md"),
+            ForgeTemplateUpgradeFileStatus::Updated
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
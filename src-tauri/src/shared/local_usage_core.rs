@@ -29,6 +29,12 @@ struct UsageTotals {
 }
 
 const MAX_ACTIVITY_GAP_MS: i64 = 2 * 60 * 1000;
+const CACHED_INPUT_TOKEN_KEYS: &[&str] = &[
+    "cached_input_tokens",
+    "cache_read_input_tokens",
+    "cachedInputTokens",
+    "cacheReadInputTokens",
+];
 
 pub(crate) async fn local_usage_snapshot_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
@@ -282,78 +288,12 @@ fn scan_file(
                 continue;
             }
 
-            let info = payload
-                .and_then(|payload| payload.get("info"))
-                .and_then(|v| v.as_object());
-            let (input, cached, output, used_total) = if let Some(info) = info {
-                if let Some(total) = find_usage_map(info, &["total_token_usage", "totalTokenUsage"])
-                {
-                    (
-                        read_i64(total, &["input_tokens", "inputTokens"]),
-                        read_i64(
-                            total,
-                            &[
-                                "cached_input_tokens",
-                                "cache_read_input_tokens",
-                                "cachedInputTokens",
-                                "cacheReadInputTokens",
-                            ],
-                        ),
-                        read_i64(total, &["output_tokens", "outputTokens"]),
-                        true,
-                    )
-                } else if let Some(last) =
-                    find_usage_map(info, &["last_token_usage", "lastTokenUsage"])
-                {
-                    (
-                        read_i64(last, &["input_tokens", "inputTokens"]),
-                        read_i64(
-                            last,
-                            &[
-                                "cached_input_tokens",
-                                "cache_read_input_tokens",
-                                "cachedInputTokens",
-                                "cacheReadInputTokens",
-                            ],
-                        ),
-                        read_i64(last, &["output_tokens", "outputTokens"]),
-                        false,
-                    )
-                } else {
-                    continue;
-                }
-            } else {
+            let Some(delta) =
+                payload.and_then(|payload| token_count_delta(payload, &mut previous_totals))
+            else {
                 continue;
             };
 
-            let mut delta = UsageTotals {
-                input,
-                cached,
-                output,
-            };
-
-            if used_total {
-                let prev = previous_totals.unwrap_or_default();
-                delta = UsageTotals {
-                    input: (input - prev.input).max(0),
-                    cached: (cached - prev.cached).max(0),
-                    output: (output - prev.output).max(0),
-                };
-                previous_totals = Some(UsageTotals {
-                    input,
-                    cached,
-                    output,
-                });
-            } else {
-                // Some streams emit `last_token_usage` deltas between `total_token_usage` snapshots.
-                // Treat those as already-counted to avoid double-counting when the next total arrives.
-                let mut next = previous_totals.unwrap_or_default();
-                next.input += delta.input;
-                next.cached += delta.cached;
-                next.output += delta.output;
-                previous_totals = Some(next);
-            }
-
             if delta.input == 0 && delta.cached == 0 && delta.output == 0 {
                 continue;
             }
@@ -412,6 +352,195 @@ fn scan_file(
     Ok(())
 }
 
+/// Tokens and agent time recorded by one entry of a session log.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SessionUsageEntry {
+    pub(crate) timestamp_ms: i64,
+    pub(crate) input_tokens: i64,
+    pub(crate) cached_input_tokens: i64,
+    pub(crate) output_tokens: i64,
+    pub(crate) agent_time_ms: i64,
+}
+
+/// Reads the session logs of the given threads under every root. Codex names a session log
+/// `rollout-<timestamp>-<thread id>.jsonl`, so only matching files are opened.
+pub(crate) fn scan_thread_usage(
+    sessions_roots: &[PathBuf],
+    thread_ids: &HashSet<String>,
+) -> HashMap<String, Vec<SessionUsageEntry>> {
+    let mut usage: HashMap<String, Vec<SessionUsageEntry>> = HashMap::new();
+    let mut pending_dirs = sessions_roots.to_vec();
+    while let Some(dir) = pending_dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                pending_dirs.push(path);
+                continue;
+            }
+            if path.extension().and_then(|ext| ext.to_str()) != Some("jsonl") {
+                continue;
+            }
+            let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            let thread_id = thread_ids.iter().find(|thread_id| {
+                stem == thread_id.as_str()
+                    || stem
+                        .strip_suffix(thread_id.as_str())
+                        .is_some_and(|prefix| prefix.ends_with('-'))
+            });
+            if let Some(thread_id) = thread_id {
+                usage
+                    .entry(thread_id.clone())
+                    .or_default()
+                    .extend(scan_session_usage(&path));
+            }
+        }
+    }
+    for entries in usage.values_mut() {
+        entries.sort_by_key(|entry| entry.timestamp_ms);
+    }
+    usage
+}
+
+/// Per-entry variant of [`scan_file`] without the workspace filter: token deltas and the agent
+/// time since the previous activity, which counts gaps up to `MAX_ACTIVITY_GAP_MS`.
+fn scan_session_usage(path: &Path) -> Vec<SessionUsageEntry> {
+    let Ok(file) = File::open(path) else {
+        return Vec::new();
+    };
+    let mut entries = Vec::new();
+    let mut previous_totals: Option<UsageTotals> = None;
+    let mut last_activity_ms: Option<i64> = None;
+
+    for line in BufReader::new(file).lines() {
+        let Ok(line) = line else {
+            continue;
+        };
+        if line.len() > 512_000 {
+            continue;
+        }
+        let Ok(value) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        let entry_type = value
+            .get("type")
+            .and_then(|value| value.as_str())
+            .unwrap_or("");
+        let payload = value.get("payload").and_then(|value| value.as_object());
+        let payload_type = payload
+            .and_then(|payload| payload.get("type"))
+            .and_then(|value| value.as_str());
+
+        let mut tokens = UsageTotals::default();
+        match entry_type {
+            "event_msg" | "" => match payload_type {
+                Some("agent_message" | "agent_reasoning") => {}
+                Some("token_count") => {
+                    let Some(delta) = payload
+                        .and_then(|payload| token_count_delta(payload, &mut previous_totals))
+                    else {
+                        continue;
+                    };
+                    if delta.input == 0 && delta.cached == 0 && delta.output == 0 {
+                        continue;
+                    }
+                    tokens = delta;
+                }
+                _ => continue,
+            },
+            "response_item" => {
+                let role = payload
+                    .and_then(|payload| payload.get("role"))
+                    .and_then(|value| value.as_str())
+                    .unwrap_or("");
+                if role != "assistant" && payload_type == Some("message") {
+                    continue;
+                }
+            }
+            _ => continue,
+        }
+
+        let Some(timestamp_ms) = read_timestamp_ms(&value) else {
+            continue;
+        };
+        let agent_time_ms = match last_activity_ms {
+            Some(prev_ms) if (1..=MAX_ACTIVITY_GAP_MS).contains(&(timestamp_ms - prev_ms)) => {
+                timestamp_ms - prev_ms
+            }
+            _ => 0,
+        };
+        last_activity_ms = Some(timestamp_ms);
+        entries.push(SessionUsageEntry {
+            timestamp_ms,
+            input_tokens: tokens.input,
+            cached_input_tokens: tokens.cached.min(tokens.input),
+            output_tokens: tokens.output,
+            agent_time_ms,
+        });
+    }
+    entries
+}
+
+/// Tokens added since the previous `token_count` payload of the same session. `previous_totals`
+/// carries the running totals between calls. `None` when the payload has no usage.
+fn token_count_delta(
+    payload: &serde_json::Map<String, Value>,
+    previous_totals: &mut Option<UsageTotals>,
+) -> Option<UsageTotals> {
+    let info = payload.get("info").and_then(|v| v.as_object())?;
+    let (input, cached, output, used_total) =
+        if let Some(total) = find_usage_map(info, &["total_token_usage", "totalTokenUsage"]) {
+            (
+                read_i64(total, &["input_tokens", "inputTokens"]),
+                read_i64(total, CACHED_INPUT_TOKEN_KEYS),
+                read_i64(total, &["output_tokens", "outputTokens"]),
+                true,
+            )
+        } else if let Some(last) = find_usage_map(info, &["last_token_usage", "lastTokenUsage"]) {
+            (
+                read_i64(last, &["input_tokens", "inputTokens"]),
+                read_i64(last, CACHED_INPUT_TOKEN_KEYS),
+                read_i64(last, &["output_tokens", "outputTokens"]),
+                false,
+            )
+        } else {
+            return None;
+        };
+
+    let mut delta = UsageTotals {
+        input,
+        cached,
+        output,
+    };
+
+    if used_total {
+        let prev = previous_totals.unwrap_or_default();
+        delta = UsageTotals {
+            input: (input - prev.input).max(0),
+            cached: (cached - prev.cached).max(0),
+            output: (output - prev.output).max(0),
+        };
+        *previous_totals = Some(UsageTotals {
+            input,
+            cached,
+            output,
+        });
+    } else {
+        // Some streams emit `last_token_usage` deltas between `total_token_usage` snapshots.
+        // Treat those as already-counted to avoid double-counting when the next total arrives.
+        let mut next = previous_totals.unwrap_or_default();
+        next.input += delta.input;
+        next.cached += delta.cached;
+        next.output += delta.output;
+        *previous_totals = Some(next);
+    }
+    Some(delta)
+}
+
 fn extract_model_from_turn_context(value: &Value) -> Option<String> {
     let payload = value.get("payload").and_then(|value| value.as_object())?;
     if let Some(model) = payload.get("model").and_then(|value| value.as_str()) {
@@ -525,7 +654,7 @@ fn resolve_codex_sessions_root(codex_home_override: Option<PathBuf>) -> Option<P
         .map(|home| home.join("sessions"))
 }
 
-fn resolve_sessions_roots(
+pub(crate) fn resolve_sessions_roots(
     workspaces: &HashMap<String, WorkspaceEntry>,
     workspace_path: Option<&Path>,
 ) -> Vec<PathBuf> {
@@ -766,6 +895,40 @@ mod tests {
         assert_eq!(totals.input, 0);
     }
 
+    #[test]
+    fn scan_thread_usage_reads_only_the_logs_of_requested_threads() {
+        let root = make_temp_sessions_root();
+        let day_dir = day_dir_for_key(&root, "2026-01-19");
+        fs::create_dir_all(&day_dir).expect("create day dir");
+        let lines = [
+            r#"{"timestamp":"2026-01-19T12:00:00.000Z","type":"session_meta","payload":{"cwd":"/tmp"}}"#,
+            r#"{"timestamp":"2026-01-19T12:00:00.000Z","payload":{"type":"token_count","info":{"total_token_usage":{"input_tokens":10,"cached_input_tokens":4,"output_tokens":2}}}}"#,
+            r#"{"timestamp":"2026-01-19T12:00:05.000Z","type":"response_item","payload":{"type":"message","role":"user","content":[]}}"#,
+            r#"{"timestamp":"2026-01-19T12:00:30.000Z","payload":{"type":"token_count","info":{"total_token_usage":{"input_tokens":25,"cached_input_tokens":9,"output_tokens":5}}}}"#,
+        ];
+        for name in [
+            "rollout-2026-01-19T12-00-00-thread-a.jsonl",
+            "rollout-2026-01-19T12-00-00-thread-b.jsonl",
+        ] {
+            let mut file = File::create(day_dir.join(name)).expect("create session jsonl");
+            for line in lines {
+                writeln!(file, "{line}").expect("write jsonl line");
+            }
+        }
+
+        let thread_ids = HashSet::from(["thread-a".to_string()]);
+        let usage = scan_thread_usage(&[root], &thread_ids);
+
+        assert_eq!(usage.len(), 1);
+        let entries = usage.get("thread-a").expect("thread-a usage");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].input_tokens, 10);
+        assert_eq!(entries[0].cached_input_tokens, 4);
+        assert_eq!(entries[1].input_tokens, 15);
+        assert_eq!(entries[1].output_tokens, 3);
+        assert_eq!(entries[1].agent_time_ms, 30_000);
+    }
+
     #[test]
     fn scan_local_usage_aggregates_multiple_session_roots() {
         let day_keys = make_day_keys(2);
@@ -2,9 +2,12 @@ pub(crate) mod account;
 pub(crate) mod codex_aux_core;
 pub(crate) mod codex_update_core;
 pub(crate) mod codex_core;
+pub(crate) mod daemon_tls_core;
 pub(crate) mod files_core;
 pub(crate) mod forge_execute_core;
 pub(crate) mod forge_plans_core;
+pub(crate) mod forge_schema_core;
+pub(crate) mod forge_template_sources_core;
 pub(crate) mod forge_templates_core;
 pub(crate) mod git_core;
 pub(crate) mod git_ui_core;
@@ -13,5 +16,7 @@ pub(crate) mod orbit_core;
 pub(crate) mod process_core;
 pub(crate) mod prompts_core;
 pub(crate) mod settings_core;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+pub(crate) mod terminal_core;
 pub(crate) mod workspaces_core;
 pub(crate) mod worktree_core;
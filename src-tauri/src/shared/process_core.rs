@@ -31,6 +31,31 @@ pub(crate) fn std_command(program: impl AsRef<OsStr>) -> std::process::Command {
     command
 }
 
+/// Makes the child lead a new process group so [`kill_process_group`] also reaches
+/// everything it spawns (e.g. the test runner behind `sh -lc`).
+pub(crate) fn set_new_process_group(command: &mut Command) {
+    #[cfg(unix)]
+    command.process_group(0);
+    #[cfg(not(unix))]
+    let _ = command;
+}
+
+/// Kills a child started with [`set_new_process_group`] together with its descendants.
+pub(crate) async fn kill_process_group(child: &mut Child) {
+    #[cfg(unix)]
+    {
+        if let Some(pid) = child.id() {
+            // SAFETY: killpg only takes plain integers and touches no memory of ours. `id()`
+            // is `Some` only while the child is unreaped, so the group id is still its own.
+            unsafe {
+                libc::killpg(pid as libc::pid_t, libc::SIGKILL);
+            }
+        }
+    }
+
+    kill_child_process_tree(child).await;
+}
+
 pub(crate) async fn kill_child_process_tree(child: &mut Child) {
     #[cfg(windows)]
     {
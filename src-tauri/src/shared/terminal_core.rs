@@ -0,0 +1,498 @@
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use portable_pty::{native_pty_system, CommandBuilder, PtySize};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::backend::events::{EventSink, TerminalExit, TerminalOutput};
+use crate::types::WorkspaceEntry;
+
+struct TerminalSession {
+    id: String,
+    master: Mutex<Box<dyn portable_pty::MasterPty + Send>>,
+    writer: Mutex<Box<dyn Write + Send>>,
+    child: Mutex<Box<dyn portable_pty::Child + Send>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct TerminalSessionInfo {
+    id: String,
+}
+
+type TerminalSessionMap = HashMap<String, Arc<TerminalSession>>;
+
+/// Open PTY sessions keyed by workspace and terminal id. The reader thread of a session
+/// holds on to the map so it can drop the session once the shell exits.
+#[derive(Default)]
+pub(crate) struct TerminalSessions {
+    sessions: Arc<Mutex<TerminalSessionMap>>,
+}
+
+fn terminal_key(workspace_id: &str, terminal_id: &str) -> String {
+    format!("{workspace_id}:{terminal_id}")
+}
+
+fn is_terminal_closed_error(message: &str) -> bool {
+    let lower = message.to_ascii_lowercase();
+    lower.contains("broken pipe")
+        || lower.contains("input/output error")
+        || lower.contains("os error 5")
+        || lower.contains("eio")
+        || lower.contains("io error")
+        || lower.contains("not connected")
+        || lower.contains("closed")
+}
+
+async fn get_terminal_session(
+    sessions: &TerminalSessions,
+    key: &str,
+) -> Result<Arc<TerminalSession>, String> {
+    let sessions = sessions.sessions.lock().await;
+    sessions
+        .get(key)
+        .cloned()
+        .ok_or_else(|| "Terminal session not found".to_string())
+}
+
+fn shell_path() -> String {
+    std::env::var("SHELL").unwrap_or_else(|_| "/bin/zsh".to_string())
+}
+
+fn resolve_locale() -> String {
+    let candidate = std::env::var("LC_ALL")
+        .or_else(|_| std::env::var("LANG"))
+        .unwrap_or_else(|_| "en_US.UTF-8".to_string());
+    let lower = candidate.to_lowercase();
+    if lower.contains("utf-8") || lower.contains("utf8") {
+        return candidate;
+    }
+    "en_US.UTF-8".to_string()
+}
+
+fn spawn_terminal_reader(
+    event_sink: impl EventSink,
+    sessions: Arc<Mutex<TerminalSessionMap>>,
+    session: Arc<TerminalSession>,
+    workspace_id: String,
+    terminal_id: String,
+    mut reader: Box<dyn Read + Send>,
+) {
+    std::thread::spawn(move || {
+        let mut buffer = [0u8; 8192];
+        let mut pending: Vec<u8> = Vec::new();
+        loop {
+            match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(count) => {
+                    pending.extend_from_slice(&buffer[..count]);
+                    loop {
+                        match std::str::from_utf8(&pending) {
+                            Ok(decoded) => {
+                                if !decoded.is_empty() {
+                                    let payload = TerminalOutput {
+                                        workspace_id: workspace_id.clone(),
+                                        terminal_id: terminal_id.clone(),
+                                        data: decoded.to_string(),
+                                    };
+                                    event_sink.emit_terminal_output(payload);
+                                }
+                                pending.clear();
+                                break;
+                            }
+                            Err(error) => {
+                                let valid_up_to = error.valid_up_to();
+                                if valid_up_to == 0 {
+                                    if error.error_len().is_none() {
+                                        break;
+                                    }
+                                    let invalid_len = error.error_len().unwrap_or(1);
+                                    pending.drain(..invalid_len.min(pending.len()));
+                                    continue;
+                                }
+                                let chunk =
+                                    String::from_utf8_lossy(&pending[..valid_up_to]).to_string();
+                                if !chunk.is_empty() {
+                                    let payload = TerminalOutput {
+                                        workspace_id: workspace_id.clone(),
+                                        terminal_id: terminal_id.clone(),
+                                        data: chunk,
+                                    };
+                                    event_sink.emit_terminal_output(payload);
+                                }
+                                pending.drain(..valid_up_to);
+                                if error.error_len().is_none() {
+                                    break;
+                                }
+                                let invalid_len = error.error_len().unwrap_or(1);
+                                pending.drain(..invalid_len.min(pending.len()));
+                            }
+                        }
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+        let key = terminal_key(&workspace_id, &terminal_id);
+        event_sink.emit_terminal_exit(TerminalExit {
+            workspace_id,
+            terminal_id,
+        });
+        // A plain thread, so it may block on the async mutex.
+        let mut sessions = sessions.blocking_lock();
+        let should_remove = sessions
+            .get(&key)
+            .is_some_and(|current| Arc::ptr_eq(current, &session));
+        if should_remove {
+            sessions.remove(&key);
+        }
+    });
+}
+
+async fn get_workspace_path(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: &str,
+) -> Result<PathBuf, String> {
+    let workspaces = workspaces.lock().await;
+    let entry = workspaces
+        .get(workspace_id)
+        .ok_or_else(|| "Unknown workspace".to_string())?;
+    Ok(PathBuf::from(&entry.path))
+}
+
+/// Starts an interactive shell in the workspace, or returns the session already open under
+/// `terminal_id`. Output and exit are reported through `event_sink`.
+pub(crate) async fn terminal_open_core(
+    sessions: &TerminalSessions,
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    terminal_id: String,
+    cols: u16,
+    rows: u16,
+    event_sink: impl EventSink,
+) -> Result<TerminalSessionInfo, String> {
+    if terminal_id.is_empty() {
+        return Err("Terminal id is required".to_string());
+    }
+    let key = terminal_key(&workspace_id, &terminal_id);
+    {
+        let sessions = sessions.sessions.lock().await;
+        if let Some(existing) = sessions.get(&key) {
+            return Ok(TerminalSessionInfo {
+                id: existing.id.clone(),
+            });
+        }
+    }
+
+    let cwd = get_workspace_path(workspaces, &workspace_id).await?;
+    let pty_system = native_pty_system();
+    let size = PtySize {
+        rows: rows.max(2),
+        cols: cols.max(2),
+        pixel_width: 0,
+        pixel_height: 0,
+    };
+    let pair = pty_system
+        .openpty(size)
+        .map_err(|e| format!("Failed to open pty: {e}"))?;
+
+    let mut cmd = CommandBuilder::new(shell_path());
+    cmd.cwd(cwd);
+    cmd.arg("-i");
+    cmd.env("TERM", "xterm-256color");
+    let locale = resolve_locale();
+    cmd.env("LANG", &locale);
+    cmd.env("LC_ALL", &locale);
+    cmd.env("LC_CTYPE", &locale);
+
+    let child = pair
+        .slave
+        .spawn_command(cmd)
+        .map_err(|e| format!("Failed to spawn shell: {e}"))?;
+    let reader = pair
+        .master
+        .try_clone_reader()
+        .map_err(|e| format!("Failed to open pty reader: {e}"))?;
+    let writer = pair
+        .master
+        .take_writer()
+        .map_err(|e| format!("Failed to open pty writer: {e}"))?;
+
+    let session = Arc::new(TerminalSession {
+        id: terminal_id.clone(),
+        master: Mutex::new(pair.master),
+        writer: Mutex::new(writer),
+        child: Mutex::new(child),
+    });
+    let session_id = session.id.clone();
+
+    {
+        let mut open_sessions = sessions.sessions.lock().await;
+        if let Some(existing) = open_sessions.get(&key) {
+            let id = existing.id.clone();
+            drop(open_sessions);
+            let _ = tokio::task::spawn_blocking(move || {
+                let mut child = session.child.blocking_lock();
+                let _ = child.kill();
+            })
+            .await;
+            return Ok(TerminalSessionInfo { id });
+        }
+        open_sessions.insert(key, Arc::clone(&session));
+    }
+    spawn_terminal_reader(
+        event_sink,
+        Arc::clone(&sessions.sessions),
+        Arc::clone(&session),
+        workspace_id,
+        terminal_id,
+        reader,
+    );
+
+    Ok(TerminalSessionInfo { id: session_id })
+}
+
+pub(crate) async fn terminal_write_core(
+    sessions: &TerminalSessions,
+    workspace_id: String,
+    terminal_id: String,
+    data: String,
+) -> Result<(), String> {
+    let key = terminal_key(&workspace_id, &terminal_id);
+    let session = get_terminal_session(sessions, &key).await?;
+    let write_result = tokio::task::spawn_blocking(move || {
+        let mut writer = session.writer.blocking_lock();
+        writer
+            .write_all(data.as_bytes())
+            .map_err(|e| format!("Failed to write to pty: {e}"))?;
+        writer
+            .flush()
+            .map_err(|e| format!("Failed to flush pty: {e}"))?;
+        Ok::<(), String>(())
+    })
+    .await
+    .map_err(|e| format!("Terminal write task failed: {e}"))?;
+
+    if let Err(err) = write_result {
+        if is_terminal_closed_error(&err) {
+            sessions.sessions.lock().await.remove(&key);
+        }
+        return Err(err);
+    }
+    Ok(())
+}
+
+pub(crate) async fn terminal_resize_core(
+    sessions: &TerminalSessions,
+    workspace_id: String,
+    terminal_id: String,
+    cols: u16,
+    rows: u16,
+) -> Result<(), String> {
+    let key = terminal_key(&workspace_id, &terminal_id);
+    let session = get_terminal_session(sessions, &key).await?;
+    let size = PtySize {
+        rows: rows.max(2),
+        cols: cols.max(2),
+        pixel_width: 0,
+        pixel_height: 0,
+    };
+    let resize_result = tokio::task::spawn_blocking(move || {
+        let master = session.master.blocking_lock();
+        master
+            .resize(size)
+            .map_err(|e| format!("Failed to resize pty: {e}"))
+    })
+    .await
+    .map_err(|e| format!("Terminal resize task failed: {e}"))?;
+    if let Err(err) = resize_result {
+        if is_terminal_closed_error(&err) {
+            sessions.sessions.lock().await.remove(&key);
+        }
+        return Err(err);
+    }
+    Ok(())
+}
+
+pub(crate) async fn terminal_close_core(
+    sessions: &TerminalSessions,
+    workspace_id: String,
+    terminal_id: String,
+) -> Result<(), String> {
+    let key = terminal_key(&workspace_id, &terminal_id);
+    let session = sessions
+        .sessions
+        .lock()
+        .await
+        .remove(&key)
+        .ok_or_else(|| "Terminal session not found".to_string())?;
+    let _ = tokio::task::spawn_blocking(move || {
+        let mut child = session.child.blocking_lock();
+        let _ = child.kill();
+    })
+    .await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::events::{AppServerEvent, ForgeExecutionEvent};
+    use crate::types::{WorkspaceKind, WorkspaceSettings};
+    use std::future::Future;
+    use std::time::Duration;
+
+    const WORKSPACE_ID: &str = "ws-1";
+    const TERMINAL_ID: &str = "term-1";
+
+    #[derive(Clone, Default)]
+    struct RecordingEventSink {
+        output: Arc<std::sync::Mutex<String>>,
+        exits: Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    impl RecordingEventSink {
+        fn output(&self) -> String {
+            self.output.lock().expect("output lock").clone()
+        }
+
+        fn exits(&self) -> Vec<String> {
+            self.exits.lock().expect("exits lock").clone()
+        }
+    }
+
+    impl EventSink for RecordingEventSink {
+        fn emit_app_server_event(&self, _event: AppServerEvent) {}
+
+        fn emit_terminal_output(&self, event: TerminalOutput) {
+            self.output
+                .lock()
+                .expect("output lock")
+                .push_str(&event.data);
+        }
+
+        fn emit_terminal_exit(&self, event: TerminalExit) {
+            self.exits
+                .lock()
+                .expect("exits lock")
+                .push(event.terminal_id);
+        }
+
+        fn emit_forge_execution_event(&self, _event: ForgeExecutionEvent) {}
+    }
+
+    fn run_async_test<F>(future: F)
+    where
+        F: Future<Output = ()>,
+    {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("build tokio runtime")
+            .block_on(future);
+    }
+
+    fn test_workspaces(root: &std::path::Path) -> Mutex<HashMap<String, WorkspaceEntry>> {
+        let entry = WorkspaceEntry {
+            id: WORKSPACE_ID.to_string(),
+            name: "Workspace".to_string(),
+            path: root.to_string_lossy().to_string(),
+            codex_bin: None,
+            kind: WorkspaceKind::Main,
+            parent_id: None,
+            worktree: None,
+            settings: WorkspaceSettings::default(),
+        };
+        Mutex::new(HashMap::from([(WORKSPACE_ID.to_string(), entry)]))
+    }
+
+    async fn wait_for(what: &str, mut done: impl FnMut() -> bool) {
+        for _ in 0..200 {
+            if done() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        panic!("timed out waiting for {what}");
+    }
+
+    async fn open_count(sessions: &TerminalSessions) -> usize {
+        sessions.sessions.lock().await.len()
+    }
+
+    #[test]
+    fn terminal_runs_written_input_and_closes() {
+        run_async_test(async {
+            let root = std::env::temp_dir().join(format!(
+                "codex-monitor-terminal-test-{}",
+                uuid::Uuid::new_v4()
+            ));
+            std::fs::create_dir_all(&root).expect("create workspace");
+            let workspaces = test_workspaces(&root);
+            let sessions = TerminalSessions::default();
+            let sink = RecordingEventSink::default();
+
+            let opened = terminal_open_core(
+                &sessions,
+                &workspaces,
+                WORKSPACE_ID.to_string(),
+                TERMINAL_ID.to_string(),
+                80,
+                24,
+                sink.clone(),
+            )
+            .await
+            .expect("open terminal");
+            assert_eq!(opened.id, TERMINAL_ID);
+            let reopened = terminal_open_core(
+                &sessions,
+                &workspaces,
+                WORKSPACE_ID.to_string(),
+                TERMINAL_ID.to_string(),
+                80,
+                24,
+                sink.clone(),
+            )
+            .await
+            .expect("reopen terminal");
+            assert_eq!(reopened.id, TERMINAL_ID);
+            assert_eq!(open_count(&sessions).await, 1);
+
+            // The shell has to evaluate the input, so the echoed command line alone won't match.
+            terminal_write_core(
+                &sessions,
+                WORKSPACE_ID.to_string(),
+                TERMINAL_ID.to_string(),
+                "echo terminal-$((40 + 2))\n".to_string(),
+            )
+            .await
+            .expect("write to terminal");
+            wait_for("shell output", || sink.output().contains("terminal-42")).await;
+
+            terminal_close_core(&sessions, WORKSPACE_ID.to_string(), TERMINAL_ID.to_string())
+                .await
+                .expect("close terminal");
+            assert_eq!(open_count(&sessions).await, 0);
+            wait_for("terminal exit", || !sink.exits().is_empty()).await;
+            assert_eq!(sink.exits(), vec![TERMINAL_ID.to_string()]);
+
+            let err = terminal_write_core(
+                &sessions,
+                WORKSPACE_ID.to_string(),
+                TERMINAL_ID.to_string(),
+                "exit\n".to_string(),
+            )
+            .await
+            .expect_err("closed terminal rejects input");
+            assert_eq!(err, "Terminal session not found");
+            let err =
+                terminal_close_core(&sessions, WORKSPACE_ID.to_string(), TERMINAL_ID.to_string())
+                    .await
+                    .expect_err("terminal is already closed");
+            assert_eq!(err, "Terminal session not found");
+            let _ = std::fs::remove_dir_all(&root);
+        });
+    }
+}
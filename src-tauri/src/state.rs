@@ -7,6 +7,7 @@ use tokio::sync::Mutex;
 
 use crate::dictation::DictationState;
 use crate::shared::codex_core::CodexLoginCancelState;
+use crate::shared::forge_execute_core::{ForgeExecutionCancels, ForgeRuns};
 use crate::storage::{read_settings, read_workspaces};
 use crate::types::{
     AppSettings, OrbitRunnerState, OrbitRunnerStatus, TcpDaemonState, TcpDaemonStatus,
@@ -58,13 +59,17 @@ impl Default for TcpDaemonRuntime {
 pub(crate) struct AppState {
     pub(crate) workspaces: Mutex<HashMap<String, WorkspaceEntry>>,
     pub(crate) sessions: Mutex<HashMap<String, Arc<crate::codex::WorkspaceSession>>>,
-    pub(crate) terminal_sessions: Mutex<HashMap<String, Arc<crate::terminal::TerminalSession>>>,
+    pub(crate) terminal_sessions: crate::terminal::TerminalSessions,
     pub(crate) remote_backend: Mutex<Option<crate::remote_backend::RemoteBackend>>,
+    pub(crate) remote_event_cursor: crate::remote_backend::RemoteEventCursor,
+    pub(crate) remote_event_subscriptions: crate::remote_backend::RemoteEventSubscriptions,
     pub(crate) storage_path: PathBuf,
     pub(crate) settings_path: PathBuf,
     pub(crate) app_settings: Mutex<AppSettings>,
     pub(crate) dictation: Mutex<DictationState>,
     pub(crate) codex_login_cancels: Mutex<HashMap<String, CodexLoginCancelState>>,
+    pub(crate) forge_execution_cancels: ForgeExecutionCancels,
+    pub(crate) forge_runs: ForgeRuns,
     pub(crate) orbit_runner: Mutex<OrbitRunnerRuntime>,
     pub(crate) tcp_daemon: Mutex<TcpDaemonRuntime>,
 }
@@ -82,13 +87,17 @@ impl AppState {
         Self {
             workspaces: Mutex::new(workspaces),
             sessions: Mutex::new(HashMap::new()),
-            terminal_sessions: Mutex::new(HashMap::new()),
+            terminal_sessions: crate::terminal::TerminalSessions::default(),
             remote_backend: Mutex::new(None),
+            remote_event_cursor: crate::remote_backend::RemoteEventCursor::default(),
+            remote_event_subscriptions: crate::remote_backend::RemoteEventSubscriptions::default(),
             storage_path,
             settings_path,
             app_settings: Mutex::new(app_settings),
             dictation: Mutex::new(DictationState::default()),
             codex_login_cancels: Mutex::new(HashMap::new()),
+            forge_execution_cancels: ForgeExecutionCancels::default(),
+            forge_runs: ForgeRuns::default(),
             orbit_runner: Mutex::new(OrbitRunnerRuntime::default()),
             tcp_daemon: Mutex::new(TcpDaemonRuntime::default()),
         }
@@ -1,5 +1,7 @@
 use super::*;
 
+use crate::remote_backend::connect_daemon_tls;
+
 const DAEMON_RPC_TIMEOUT: Duration = Duration::from_millis(700);
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -22,7 +24,10 @@ pub(super) enum DaemonProbe {
     NotDaemon,
 }
 
-type DaemonLines = tokio::io::Lines<BufReader<OwnedReadHalf>>;
+// Probes only reach the daemon on loopback, so they do not pin its certificate.
+type DaemonStream = tokio_rustls::client::TlsStream<TcpStream>;
+type DaemonWriter = tokio::io::WriteHalf<DaemonStream>;
+type DaemonLines = tokio::io::Lines<BufReader<tokio::io::ReadHalf<DaemonStream>>>;
 
 fn parse_daemon_error_message(response: &Value) -> Option<String> {
     response
@@ -80,7 +85,7 @@ fn parse_daemon_info(value: &Value) -> Result<DaemonInfo, String> {
 }
 
 async fn send_rpc_request(
-    writer: &mut OwnedWriteHalf,
+    writer: &mut DaemonWriter,
     id: u64,
     method: &str,
     params: Value,
@@ -125,7 +130,7 @@ async fn read_rpc_response(lines: &mut DaemonLines, expected_id: u64) -> Result<
 }
 
 async fn send_and_expect_result(
-    writer: &mut OwnedWriteHalf,
+    writer: &mut DaemonWriter,
     lines: &mut DaemonLines,
     id: u64,
     method: &str,
@@ -143,7 +148,7 @@ async fn send_and_expect_result(
 }
 
 async fn request_daemon_info(
-    writer: &mut OwnedWriteHalf,
+    writer: &mut DaemonWriter,
     lines: &mut DaemonLines,
     id: u64,
 ) -> Result<DaemonInfo, String> {
@@ -160,8 +165,12 @@ pub(super) async fn probe_daemon(listen_addr: &str, token: Option<&str>) -> Daem
         Ok(Ok(stream)) => stream,
         Ok(Err(_)) | Err(_) => return DaemonProbe::NotReachable,
     };
+    let stream = match timeout(DAEMON_RPC_TIMEOUT, connect_daemon_tls(stream, None)).await {
+        Ok(Ok((stream, _fingerprint))) => stream,
+        Ok(Err(_)) | Err(_) => return DaemonProbe::NotDaemon,
+    };
 
-    let (reader, mut writer) = stream.into_split();
+    let (reader, mut writer) = tokio::io::split(stream);
     let mut lines = BufReader::new(reader).lines();
 
     match send_and_expect_result(&mut writer, &mut lines, 1, "ping", json!({})).await {
@@ -243,8 +252,11 @@ pub(super) async fn request_daemon_shutdown(
         .await
         .map_err(|_| format!("Timed out connecting to daemon at {connect_addr}"))?
         .map_err(|err| format!("Failed to connect to daemon at {connect_addr}: {err}"))?;
+    let (stream, _fingerprint) = timeout(DAEMON_RPC_TIMEOUT, connect_daemon_tls(stream, None))
+        .await
+        .map_err(|_| format!("Timed out connecting to daemon at {connect_addr}"))??;
 
-    let (reader, mut writer) = stream.into_split();
+    let (reader, mut writer) = tokio::io::split(stream);
     let mut lines = BufReader::new(reader).lines();
 
     match send_and_expect_result(&mut writer, &mut lines, 1, "ping", json!({})).await {
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub(crate) struct GitFileStatus {
@@ -453,6 +454,8 @@ pub(crate) struct AppSettings {
     pub(crate) remote_backend_host: String,
     #[serde(default, rename = "remoteBackendToken")]
     pub(crate) remote_backend_token: Option<String>,
+    #[serde(default, rename = "remoteBackendTlsPins")]
+    pub(crate) remote_backend_tls_pins: BTreeMap<String, String>,
     #[serde(default, rename = "orbitWsUrl")]
     pub(crate) orbit_ws_url: Option<String>,
     #[serde(default, rename = "orbitAuthUrl")]
@@ -1154,6 +1157,7 @@ impl Default for AppSettings {
             remote_backend_provider: RemoteBackendProvider::Tcp,
             remote_backend_host: default_remote_backend_host(),
             remote_backend_token: None,
+            remote_backend_tls_pins: BTreeMap::new(),
             orbit_ws_url: None,
             orbit_auth_url: None,
             orbit_runner_name: None,
@@ -1251,6 +1255,7 @@ mod tests {
         ));
         assert_eq!(settings.remote_backend_host, "127.0.0.1:4732");
         assert!(settings.remote_backend_token.is_none());
+        assert!(settings.remote_backend_tls_pins.is_empty());
         assert!(settings.orbit_ws_url.is_none());
         assert!(settings.orbit_auth_url.is_none());
         assert!(settings.orbit_runner_name.is_none());
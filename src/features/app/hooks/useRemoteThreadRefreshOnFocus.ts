@@ -1,4 +1,5 @@
 import { useEffect, useRef } from "react";
+import { subscribeRemoteEventGap } from "../../../services/events";
 import type { WorkspaceInfo } from "../../../types";
 
 type UseRemoteThreadRefreshOnFocusOptions = {
@@ -46,7 +47,10 @@ export function useRemoteThreadRefreshOnFocus({
 
     window.addEventListener("focus", refreshActiveThread);
     document.addEventListener("visibilitychange", handleVisibilityChange);
+    // Events the daemon could not replay may have touched the open thread.
+    const unsubscribeGap = subscribeRemoteEventGap(refreshActiveThread);
     return () => {
+      unsubscribeGap();
       window.removeEventListener("focus", refreshActiveThread);
       document.removeEventListener("visibilitychange", handleVisibilityChange);
       if (debounceTimer) {
@@ -20,17 +20,22 @@ import {
   forgeLoadPhaseView,
   forgeListBundledTemplates,
   forgeUninstallTemplate,
+  forgeUpgradeTemplate,
   type ForgeBundledTemplateInfo,
   type ForgeNextPhasePrompt,
   type ForgePhaseViewStatus,
   type ForgePhaseView,
   type ForgePhaseStatus,
   type ForgeRunPhaseChecksResponse,
+  type ForgeSchemaError,
+  type ForgeTemplateOrigin,
+  type ForgeTemplateUpgradeResult,
   type ForgeTemplateLock,
   type ForgeWorkspacePlan,
 } from "../../../services/tauri";
 import {
   connectWorkspace,
+  forgeCancelExecution,
   forgeResetExecutionProgress,
   forgeGetNextPhasePrompt,
   forgeGetPlanPrompt,
@@ -42,14 +47,24 @@ import {
   sendUserMessage,
   startThread,
 } from "../../../services/tauri";
+import {
+  subscribeForgeExecutionEvents,
+  type ForgeExecutionEvent,
+  type Unsubscribe,
+} from "../../../services/events";
 import { useForgeExecution } from "../hooks/useForgeExecution";
 import { getForgePhaseIconUrl } from "../../../utils/forgePhaseIcons";
 
 export type ForgeTemplatesClient = {
   listBundledTemplates: () => Promise<ForgeBundledTemplateInfo[]>;
   getInstalledTemplate: (workspaceId: string) => Promise<ForgeTemplateLock | null>;
-  installTemplate: (workspaceId: string, templateId: string) => Promise<ForgeTemplateLock>;
+  installTemplate: (
+    workspaceId: string,
+    templateId: string,
+    sourceId?: string,
+  ) => Promise<ForgeTemplateLock>;
   uninstallTemplate: (workspaceId: string) => Promise<void>;
+  upgradeTemplate?: (workspaceId: string) => Promise<ForgeTemplateUpgradeResult>;
 };
 
 export type ForgePlansClient = {
@@ -77,12 +92,14 @@ export type ForgePlansClient = {
     planId: string,
     taskId: string,
     phaseId: string,
+    threadId?: string | null,
   ) => Promise<ForgeRunPhaseChecksResponse>;
   interruptTurn: (
     workspaceId: string,
     threadId: string,
     turnId: string,
   ) => Promise<unknown>;
+  cancelExecution?: (workspaceId: string, planId: string) => Promise<unknown>;
   connectWorkspace: (workspaceId: string) => Promise<void>;
   startThread: (workspaceId: string) => Promise<any>;
   sendUserMessage: (
@@ -93,6 +110,9 @@ export type ForgePlansClient = {
       collaborationMode?: Record<string, unknown> | null;
     },
   ) => Promise<any>;
+  subscribeExecutionEvents?: (
+    onEvent: (event: ForgeExecutionEvent) => void,
+  ) => Unsubscribe;
 };
 
 const defaultForgeTemplatesClient: ForgeTemplatesClient = {
@@ -100,6 +120,7 @@ const defaultForgeTemplatesClient: ForgeTemplatesClient = {
   getInstalledTemplate: forgeGetInstalledTemplate,
   installTemplate: forgeInstallTemplate,
   uninstallTemplate: forgeUninstallTemplate,
+  upgradeTemplate: forgeUpgradeTemplate,
 };
 
 const defaultForgePlansClient: ForgePlansClient = {
@@ -112,9 +133,11 @@ const defaultForgePlansClient: ForgePlansClient = {
   getPhaseStatus: forgeGetPhaseStatus,
   runPhaseChecks: forgeRunPhaseChecks,
   interruptTurn,
+  cancelExecution: forgeCancelExecution,
   connectWorkspace,
   startThread,
   sendUserMessage,
+  subscribeExecutionEvents: subscribeForgeExecutionEvents,
 };
 
 type ForgeItemStatus = "pending" | "inProgress" | "completed";
@@ -129,6 +152,7 @@ type ForgePlan = {
   id: string;
   name: string;
   items: ForgePlanItem[];
+  validationErrors: string[];
 };
 
 const EMPTY_PHASE_VIEW: ForgePhaseView = {
@@ -140,6 +164,71 @@ type ForgePhaseChipState = "is-complete" | "is-current" | "is-pending";
 type ForgeRunningInfo = { taskId: string; phaseId: string } | null;
 const FORGE_PLANS_POLL_INTERVAL_MS = 2000;
 const FORGE_PHASE_VIEW_POLL_INTERVAL_MS = 2000;
+const FORGE_LIVE_CHECK_OUTPUT_LIMIT = 4000;
+
+type ForgeLiveCheck = {
+  taskId: string;
+  checkId: string;
+  title: string;
+  output: string;
+  exitCode: number | null;
+  timedOut: boolean;
+};
+
+function applyForgeExecutionEvent(
+  current: ForgeLiveCheck | null,
+  event: ForgeExecutionEvent,
+): ForgeLiveCheck | null {
+  switch (event.type) {
+    case "phaseStarted":
+      return null;
+    case "checkStarted":
+      return {
+        taskId: event.taskId,
+        checkId: event.checkId,
+        title: event.title,
+        output: "",
+        exitCode: null,
+        timedOut: false,
+      };
+    case "checkOutput":
+      if (current?.taskId !== event.taskId || current.checkId !== event.checkId) {
+        return current;
+      }
+      return {
+        ...current,
+        output: `${current.output}${event.data}`.slice(-FORGE_LIVE_CHECK_OUTPUT_LIMIT),
+      };
+    case "checkFinished":
+      if (current?.taskId !== event.taskId || current.checkId !== event.checkId) {
+        return current;
+      }
+      return { ...current, exitCode: event.exitCode, timedOut: event.timedOut };
+    default:
+      return current;
+  }
+}
+
+function formatLiveCheckTitle(check: ForgeLiveCheck): string {
+  if (check.timedOut) {
+    return `${check.title} timed out`;
+  }
+  if (check.exitCode === null) {
+    return `Running ${check.title}...`;
+  }
+  return check.exitCode === 0
+    ? `${check.title} passed`
+    : `${check.title} failed (exit ${check.exitCode})`;
+}
+
+function formatPlanValidationErrors(plan: ForgeWorkspacePlan): string[] {
+  const format = (file: string, error: ForgeSchemaError) =>
+    `${file}${error.instancePath || "/"}: ${error.message}`;
+  return [
+    ...(plan.planErrors ?? []).map((error) => format("plan.json", error)),
+    ...(plan.stateErrors ?? []).map((error) => format("state.json", error)),
+  ];
+}
 
 function formatPlanLabel(plan: ForgeWorkspacePlan): string {
   const title = plan.title?.trim() ?? "";
@@ -253,19 +342,36 @@ function resolveForgeTaskRowStatus({
   return resolvedStatus;
 }
 
+const FORGE_BUNDLED_TEMPLATE_SOURCE_ID = "bundled";
+
+function templateSourceId(origin?: ForgeTemplateOrigin | null): string {
+  return origin?.sourceId ?? FORGE_BUNDLED_TEMPLATE_SOURCE_ID;
+}
+
+function formatTemplateOrigin(origin?: ForgeTemplateOrigin | null): string | null {
+  if (!origin || origin.kind === "bundled") {
+    return null;
+  }
+  const label =
+    origin.kind === "git" ? "Git" : origin.kind === "tarball" ? "Archive" : "Folder";
+  return `${label}: ${origin.location ?? origin.sourceId}`;
+}
+
 function ForgeTemplatesModal({
   templates,
   installedTemplate,
   canManage,
   onInstallTemplate,
   onUninstallTemplate,
+  onUpgradeTemplate,
   onClose,
 }: {
   templates: ForgeBundledTemplateInfo[];
   installedTemplate: ForgeTemplateLock | null;
   canManage: boolean;
-  onInstallTemplate: (templateId: string) => void;
+  onInstallTemplate: (templateId: string, sourceId: string) => void;
   onUninstallTemplate: () => void;
+  onUpgradeTemplate?: () => void;
   onClose: () => void;
 }) {
   useEffect(() => {
@@ -280,16 +386,33 @@ function ForgeTemplatesModal({
     return () => window.removeEventListener("keydown", handleKeyDown);
   }, [onClose]);
 
-  const installed = installedTemplate
-    ? templates.find((t) => t.id === installedTemplate.installedTemplateId) ?? {
+  const installedSourceId = templateSourceId(installedTemplate?.installedFrom);
+  const available = installedTemplate
+    ? templates.find(
+        (t) =>
+          t.id === installedTemplate.installedTemplateId &&
+          templateSourceId(t.origin) === installedSourceId,
+      ) ?? null
+    : null;
+  const upgradeVersion =
+    available && installedTemplate && available.version !== installedTemplate.installedTemplateVersion
+      ? available.version
+      : null;
+  const installed: ForgeBundledTemplateInfo | null = installedTemplate
+    ? available ?? {
         id: installedTemplate.installedTemplateId,
         title: installedTemplate.installedTemplateId,
         version: installedTemplate.installedTemplateVersion,
+        origin: installedTemplate.installedFrom ?? undefined,
       }
     : null;
   const others = installed
-    ? templates.filter((t) => t.id !== installed.id)
+    ? templates.filter(
+        (t) =>
+          t.id !== installed.id || templateSourceId(t.origin) !== installedSourceId,
+      )
     : templates;
+  const installedOrigin = installed ? formatTemplateOrigin(installed.origin) : null;
 
   return (
     <ModalShell
@@ -308,10 +431,28 @@ function ForgeTemplatesModal({
             <div className="forge-template-main">
               <div className="forge-template-name">{installed.title}</div>
               <div className="forge-template-meta">
-                Installed{installed.version ? ` (${installed.version})` : ""}
+                Installed
+                {installedTemplate?.installedTemplateVersion
+                  ? ` (${installedTemplate.installedTemplateVersion})`
+                  : ""}
+                {installedOrigin ? ` • ${installedOrigin}` : ""}
               </div>
             </div>
             <div className="forge-template-actions">
+              {upgradeVersion && onUpgradeTemplate ? (
+                <button
+                  type="button"
+                  className="ghost forge-template-action"
+                  title="Upgrade and keep local edits to template files"
+                  onClick={() => {
+                    onUpgradeTemplate();
+                    onClose();
+                  }}
+                  disabled={!canManage}
+                >
+                  Upgrade to {upgradeVersion}
+                </button>
+              ) : null}
               <button
                 type="button"
                 className="ghost forge-template-icon"
@@ -342,17 +483,24 @@ function ForgeTemplatesModal({
 
         {others.map((template) => {
           const actionLabel = installed ? "Swap" : "Install";
+          const sourceId = templateSourceId(template.origin);
+          const origin = formatTemplateOrigin(template.origin);
           return (
-            <div key={template.id} className="forge-template-row" role="listitem">
+            <div
+              key={`${sourceId}:${template.id}`}
+              className="forge-template-row"
+              role="listitem"
+            >
               <div className="forge-template-main">
                 <div className="forge-template-name">{template.title}</div>
+                {origin ? <div className="forge-template-meta">{origin}</div> : null}
               </div>
               <div className="forge-template-actions">
                 <button
                   type="button"
                   className="ghost forge-template-action"
                   onClick={() => {
-                    onInstallTemplate(template.id);
+                    onInstallTemplate(template.id, sourceId);
                     onClose();
                   }}
                   disabled={!canManage}
@@ -406,6 +554,7 @@ export function Forge({
   const getInstalledTemplate = client.getInstalledTemplate;
   const installTemplate = client.installTemplate;
   const uninstallTemplate = client.uninstallTemplate;
+  const upgradeTemplate = client.upgradeTemplate;
   const listPlans = plansApi.listPlans;
   const loadPhaseView = plansApi.loadPhaseView ?? forgeLoadPhaseView;
   const getPlanPrompt = plansApi.getPlanPrompt;
@@ -414,7 +563,9 @@ export function Forge({
   const getNextPhasePrompt = plansApi.getNextPhasePrompt;
   const getPhaseStatus = plansApi.getPhaseStatus;
   const runPhaseChecks = plansApi.runPhaseChecks;
+  const subscribeExecutionEvents = plansApi.subscribeExecutionEvents;
   const interruptPlanTurn = plansApi.interruptTurn;
+  const cancelPlanExecution = plansApi.cancelExecution;
   const connectWorkspaceToRun = plansApi.connectWorkspace;
   const startThreadForPlan = plansApi.startThread;
   const sendUserMessageToPlanThread = plansApi.sendUserMessage;
@@ -595,16 +746,18 @@ export function Forge({
         id: plan.id,
         name: formatPlanLabel(plan),
         items,
+        validationErrors: formatPlanValidationErrors(plan),
       };
     });
   }, [workspacePlans]);
 
   const handleInstallTemplate = useCallback(
-    (templateId: string) => {
+    (templateId: string, sourceId: string) => {
       if (!activeWorkspaceId) {
         return;
       }
-      void installTemplate(activeWorkspaceId, templateId)
+      const source = sourceId === FORGE_BUNDLED_TEMPLATE_SOURCE_ID ? undefined : sourceId;
+      void installTemplate(activeWorkspaceId, templateId, source)
         .then((lock) => {
           setInstalledTemplate(lock);
         })
@@ -628,11 +781,33 @@ export function Forge({
       });
   }, [activeWorkspaceId, uninstallTemplate]);
 
+  const handleUpgradeTemplate = useCallback(() => {
+    if (!activeWorkspaceId || !upgradeTemplate) {
+      return;
+    }
+    void upgradeTemplate(activeWorkspaceId)
+      .then((result) => {
+        setInstalledTemplate(result.lock);
+        const conflicts = result.files.filter((file) => file.status === "conflict");
+        if (conflicts.length > 0) {
+          console.warn(
+            "Forge template upgrade kept local files; review the .upstream copies.",
+            { files: conflicts.map((file) => file.path) },
+          );
+        }
+      })
+      .catch((error) => {
+        console.warn("Failed to upgrade Forge template.", { error });
+      });
+  }, [activeWorkspaceId, upgradeTemplate]);
+
   const [selectedPlanId, setSelectedPlanId] = useState<string | null>(null);
   const selectedPlan = selectedPlanId
     ? plans.find((p) => p.id === selectedPlanId) ?? null
     : null;
+  const selectedPlanInvalid = (selectedPlan?.validationErrors.length ?? 0) > 0;
   const [phaseView, setPhaseView] = useState<ForgePhaseView>(EMPTY_PHASE_VIEW);
+  const [liveCheck, setLiveCheck] = useState<ForgeLiveCheck | null>(null);
 
   const [planMenuOpen, setPlanMenuOpen] = useState(false);
   const planMenuRef = useRef<HTMLDivElement | null>(null);
@@ -661,6 +836,19 @@ export function Forge({
     }
   }, [plans, selectedPlanId]);
 
+  useEffect(() => {
+    setLiveCheck(null);
+    if (!activeWorkspaceId || !selectedPlanId || !subscribeExecutionEvents) {
+      return;
+    }
+    return subscribeExecutionEvents((event) => {
+      if (event.workspaceId !== activeWorkspaceId || event.planId !== selectedPlanId) {
+        return;
+      }
+      setLiveCheck((current) => applyForgeExecutionEvent(current, event));
+    });
+  }, [activeWorkspaceId, selectedPlanId, subscribeExecutionEvents]);
+
   useEffect(() => {
     let cancelled = false;
     if (!activeWorkspaceId || !selectedPlanId) {
@@ -732,6 +920,7 @@ export function Forge({
     getPhaseStatus,
     runPhaseChecks,
     interruptTurn: interruptPlanTurn,
+    cancelExecution: cancelPlanExecution,
     startThread: startThreadForPlan,
     sendUserMessage: sendUserMessageToPlanThread,
     onSelectThread,
@@ -898,7 +1087,9 @@ export function Forge({
                   }}
                   data-tauri-drag-region="false"
                 >
-                  {plan.name}
+                  {plan.validationErrors.length > 0
+                    ? `${plan.name} (invalid)`
+                    : plan.name}
                 </PopoverMenuItem>
               ))}
               <div className="forge-plan-menu-divider" aria-hidden />
@@ -939,7 +1130,11 @@ export function Forge({
               type="button"
               className="ghost forge-execute-toggle"
               data-tauri-drag-region="false"
-              disabled={!hasActiveWorkspace || !selectedPlan}
+              disabled={
+                !hasActiveWorkspace ||
+                !selectedPlan ||
+                (!isExecuting && selectedPlanInvalid)
+              }
               aria-label={isExecuting ? "Pause plan" : "Resume plan"}
               aria-pressed={isExecuting}
               title={
@@ -949,7 +1144,9 @@ export function Forge({
                   ? "Select a plan to run"
                   : isExecuting
                     ? "Pause plan"
-                    : "Resume plan"
+                    : selectedPlanInvalid
+                      ? "Fix plan validation errors to run"
+                      : "Resume plan"
               }
               onClick={() => {
                 if (!hasActiveWorkspace || !selectedPlan) {
@@ -991,6 +1188,16 @@ export function Forge({
               {lastError}
             </div>
           ) : null}
+          {selectedPlanInvalid ? (
+            <div className="forge-execution-error" role="alert">
+              <div>This plan does not match the template schema:</div>
+              <ul className="forge-validation-errors">
+                {selectedPlan?.validationErrors.map((error) => (
+                  <li key={error}>{error}</li>
+                ))}
+              </ul>
+            </div>
+          ) : null}
           {!hasActiveWorkspace ? (
             <div className="forge-empty">
               Select a project first to use Forge.
@@ -1055,6 +1262,16 @@ export function Forge({
                         })}
                       </div>
                     ) : null}
+                    {liveCheck?.taskId === item.id ? (
+                      <div className="forge-live-check" aria-live="polite">
+                        <div className="forge-live-check-title">
+                          {formatLiveCheckTitle(liveCheck)}
+                        </div>
+                        {liveCheck.output ? (
+                          <pre className="forge-live-check-output">{liveCheck.output}</pre>
+                        ) : null}
+                      </div>
+                    ) : null}
                   </li>
                 );
               })}
@@ -1074,6 +1291,7 @@ export function Forge({
           canManage={Boolean(activeWorkspaceId)}
           onInstallTemplate={handleInstallTemplate}
           onUninstallTemplate={handleUninstallTemplate}
+          onUpgradeTemplate={upgradeTemplate ? handleUpgradeTemplate : undefined}
           onClose={() => setTemplatesOpen(false)}
         />
       )}
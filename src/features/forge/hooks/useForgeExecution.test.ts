@@ -145,4 +145,105 @@ describe("useForgeExecution execution limits", () => {
       await result.current.pauseExecution();
     });
   });
+
+  it("stops execution when the backend reports an exhausted retry budget", async () => {
+    vi.useFakeTimers();
+
+    const runPhaseChecks = vi.fn<HookArgs["runPhaseChecks"]>().mockResolvedValue({
+      ok: false,
+      results: [],
+      attempts: 3,
+      maxAttempts: 3,
+      retryAfterMs: null,
+      exhausted: true,
+    } satisfies ForgeRunPhaseChecksResponse);
+
+    const { result } = renderHook(() =>
+      useForgeExecution(
+        buildBaseArgs({
+          getNextPhasePrompt: async () => ({
+            planId: "alpha",
+            taskId: "task-1",
+            phaseId: "implementation",
+            isLastPhase: false,
+            promptText: "phase prompt",
+          }),
+          getPhaseStatus: async () => ({ status: "completed", commitSha: null }),
+          runPhaseChecks,
+          executionLimits: {
+            phaseStatusPollIntervalMs: 10,
+            phaseStatusTimeoutMs: 200,
+            maxPhaseCheckFailures: 5,
+          },
+        }),
+      ),
+    );
+
+    await act(async () => {
+      void result.current.startExecution("alpha");
+    });
+
+    await act(async () => {
+      await vi.advanceTimersByTimeAsync(500);
+    });
+
+    expect(result.current.lastError).toContain("exhausted its retry budget after 3 attempts");
+    expect(runPhaseChecks).toHaveBeenCalledTimes(1);
+    expect(result.current.isExecuting).toBe(false);
+  });
+
+  it("cancels running backend checks when execution is paused", async () => {
+    const phase: ForgeNextPhasePrompt = {
+      planId: "alpha",
+      taskId: "task-1",
+      phaseId: "implementation",
+      isLastPhase: false,
+      promptText: "phase prompt",
+    };
+    let resolveChecks: (response: ForgeRunPhaseChecksResponse) => void = () => {};
+    const runPhaseChecks = vi.fn<HookArgs["runPhaseChecks"]>().mockImplementation(
+      () =>
+        new Promise<ForgeRunPhaseChecksResponse>((resolve) => {
+          resolveChecks = resolve;
+        }),
+    );
+    const cancelExecution = vi
+      .fn<NonNullable<HookArgs["cancelExecution"]>>()
+      .mockImplementation(async () => {
+        resolveChecks({ ok: false, results: [], cancelled: true });
+        return { cancelledRuns: 1 };
+      });
+
+    const { result } = renderHook(() =>
+      useForgeExecution(
+        buildBaseArgs({
+          getNextPhasePrompt: async () => phase,
+          getPhaseStatus: async () => ({ status: "completed", commitSha: null }),
+          runPhaseChecks,
+          cancelExecution,
+        }),
+      ),
+    );
+
+    await act(async () => {
+      void result.current.startExecution("alpha");
+    });
+    await vi.waitFor(() => expect(runPhaseChecks).toHaveBeenCalledTimes(1));
+
+    await act(async () => {
+      await result.current.pauseExecution();
+    });
+
+    expect(runPhaseChecks).toHaveBeenCalledWith(
+      "ws-1",
+      "alpha",
+      "task-1",
+      "implementation",
+      "thread-1",
+    );
+    expect(cancelExecution).toHaveBeenCalledWith("ws-1", "alpha");
+    expect(result.current.isExecuting).toBe(false);
+    expect(result.current.lastError).toBeNull();
+    expect(runPhaseChecks).toHaveBeenCalledTimes(1);
+  });
 });
@@ -33,12 +33,14 @@ type ForgeExecutionArgs = {
     planId: string,
     taskId: string,
     phaseId: string,
+    threadId?: string | null,
   ) => Promise<ForgeRunPhaseChecksResponse>;
   interruptTurn: (
     workspaceId: string,
     threadId: string,
     turnId: string,
   ) => Promise<unknown>;
+  cancelExecution?: (workspaceId: string, planId: string) => Promise<unknown>;
   startThread: (workspaceId: string) => Promise<unknown>;
   sendUserMessage: (
     workspaceId: string,
@@ -65,6 +67,8 @@ type ForgeRunningInfo = {
 type ForgeExecutionState = {
   isExecuting: boolean;
   runningInfo: ForgeRunningInfo | null;
+  /** The manual-approval phase that stopped the last run, until the next run starts. */
+  awaitingApproval: ForgeRunningInfo | null;
   lastError: string | null;
   startExecution: (planId: string) => Promise<void>;
   pauseExecution: () => Promise<void>;
@@ -173,6 +177,7 @@ export function useForgeExecution({
   getPhaseStatus,
   runPhaseChecks,
   interruptTurn,
+  cancelExecution,
   startThread,
   sendUserMessage,
   onSelectThread,
@@ -181,9 +186,11 @@ export function useForgeExecution({
 }: ForgeExecutionArgs): ForgeExecutionState {
   const [isExecuting, setIsExecuting] = useState(false);
   const [runningInfo, setRunningInfo] = useState<ForgeRunningInfo | null>(null);
+  const [awaitingApproval, setAwaitingApproval] = useState<ForgeRunningInfo | null>(null);
   const [lastError, setLastError] = useState<string | null>(null);
   const runTokenRef = useRef(0);
   const activeRunRef = useRef<number | null>(null);
+  const activePlanRef = useRef<{ workspaceId: string; planId: string } | null>(null);
   const activeTurnRef = useRef<{
     workspaceId: string;
     threadId: string;
@@ -227,10 +234,20 @@ export function useForgeExecution({
 
   const pauseExecution = useCallback(async () => {
     const activeTurn = activeTurnRef.current;
+    const activePlan = activePlanRef.current;
     activeTurnRef.current = null;
+    activePlanRef.current = null;
     runTokenRef.current += 1;
     activeRunRef.current = null;
     clearExecutionState();
+    if (activePlan && cancelExecution) {
+      try {
+        // Stops phase checks or hooks that are still running on the backend.
+        await cancelExecution(activePlan.workspaceId, activePlan.planId);
+      } catch (error) {
+        console.warn("Forge execution cancel failed.", { error });
+      }
+    }
     if (!activeTurn) {
       return;
     }
@@ -244,7 +261,7 @@ export function useForgeExecution({
     } catch (error) {
       console.warn("Forge execution pause interrupt failed.", { error });
     }
-  }, [clearExecutionState, interruptTurn]);
+  }, [cancelExecution, clearExecutionState, interruptTurn]);
 
   useEffect(() => {
     return () => {
@@ -266,8 +283,10 @@ export function useForgeExecution({
       runTokenRef.current = token;
       activeRunRef.current = token;
       activeTurnRef.current = null;
+      activePlanRef.current = { workspaceId: workspace, planId: normalizedPlanId };
       setIsExecuting(true);
       setRunningInfo(null);
+      setAwaitingApproval(null);
       setLastError(null);
 
       const isActive = () => activeRunRef.current === token && runTokenRef.current === token;
@@ -343,6 +362,15 @@ export function useForgeExecution({
               `Forge execution received unexpected task id "${taskId}" not present in the selected plan.`,
             );
           }
+          if (phase.awaitingApproval) {
+            // Approving or rejecting the phase happens outside the run; the next run resumes.
+            setAwaitingApproval({ taskId, phaseId });
+            return;
+          }
+          if (phase.budgetExceeded) {
+            setLastError(phase.budgetExceeded);
+            return;
+          }
 
           const mappedThreadId = threadByTaskId.get(taskId) ?? null;
           if (
@@ -445,7 +473,16 @@ export function useForgeExecution({
             normalizedPlanId,
             taskId,
             phaseId,
+            threadId,
           );
+          if (!isActive() || checks.cancelled) {
+            return;
+          }
+          if (checks.exhausted) {
+            throw new Error(
+              `Phase ${taskId}/${phaseId} exhausted its retry budget after ${checks.attempts ?? 0} attempts.`,
+            );
+          }
           if (!checks.ok) {
             const failureKey = `${taskId}:${phaseId}`;
             const failures = (checkFailuresByTaskPhase.get(failureKey) ?? 0) + 1;
@@ -455,7 +492,7 @@ export function useForgeExecution({
                 `Phase ${taskId}/${phaseId} reached max check failures (${maxPhaseCheckFailures}).`,
               );
             }
-            await wait(phaseStatusPollIntervalMs);
+            await wait(Math.max(phaseStatusPollIntervalMs, checks.retryAfterMs ?? 0));
             continue;
           }
           checkFailuresByTaskPhase.delete(`${taskId}:${phaseId}`);
@@ -473,6 +510,7 @@ export function useForgeExecution({
         }
         if (activeRunRef.current === token) {
           activeRunRef.current = null;
+          activePlanRef.current = null;
           clearExecutionState();
         }
       }
@@ -500,10 +538,11 @@ export function useForgeExecution({
     () => ({
       isExecuting,
       runningInfo,
+      awaitingApproval,
       lastError,
       startExecution,
       pauseExecution,
     }),
-    [isExecuting, lastError, pauseExecution, runningInfo, startExecution],
+    [awaitingApproval, isExecuting, lastError, pauseExecution, runningInfo, startExecution],
   );
 }
@@ -11,7 +11,7 @@ import {
 import type { ComponentProps } from "react";
 import { describe, expect, it, vi } from "vitest";
 import type { AppSettings, WorkspaceInfo } from "@/types";
-import { getModelList } from "@services/tauri";
+import { forgetRemoteBackendTlsPin, getModelList } from "@services/tauri";
 import { DEFAULT_COMMIT_MESSAGE_PROMPT } from "@utils/commitMessagePrompt";
 import { SettingsView } from "./SettingsView";
 
@@ -26,10 +26,12 @@ vi.mock("@services/tauri", async () => {
   );
   return {
     ...actual,
+    forgetRemoteBackendTlsPin: vi.fn(),
     getModelList: vi.fn(),
   };
 });
 
+const forgetRemoteBackendTlsPinMock = vi.mocked(forgetRemoteBackendTlsPin);
 const getModelListMock = vi.mocked(getModelList);
 
 const baseSettings: AppSettings = {
@@ -39,6 +41,7 @@ const baseSettings: AppSettings = {
   remoteBackendProvider: "tcp",
   remoteBackendHost: "127.0.0.1:4732",
   remoteBackendToken: null,
+  remoteBackendTlsPins: {},
   orbitWsUrl: null,
   orbitAuthUrl: null,
   orbitRunnerName: null,
@@ -820,6 +823,68 @@ describe("SettingsView Codex overrides", () => {
     });
   });
 
+  it("forgets the certificate pinned for the configured TCP host", async () => {
+    cleanup();
+    const pinnedSettings: AppSettings = {
+      ...baseSettings,
+      remoteBackendHost: "macbook.tailnet.ts.net:4732",
+      remoteBackendTlsPins: {
+        "127.0.0.1:4732": "11:22",
+        "macbook.tailnet.ts.net:4732": "AA:BB",
+      },
+    };
+    const forgottenSettings: AppSettings = {
+      ...pinnedSettings,
+      remoteBackendTlsPins: { "127.0.0.1:4732": "11:22" },
+    };
+    forgetRemoteBackendTlsPinMock.mockResolvedValue(forgottenSettings);
+    const onUpdateAppSettings = vi.fn().mockResolvedValue(undefined);
+    render(
+      <SettingsView
+        workspaceGroups={[]}
+        groupedWorkspaces={[]}
+        ungroupedLabel="Ungrouped"
+        onClose={vi.fn()}
+        onMoveWorkspace={vi.fn()}
+        onDeleteWorkspace={vi.fn()}
+        onCreateWorkspaceGroup={vi.fn().mockResolvedValue(null)}
+        onRenameWorkspaceGroup={vi.fn().mockResolvedValue(null)}
+        onMoveWorkspaceGroup={vi.fn().mockResolvedValue(null)}
+        onDeleteWorkspaceGroup={vi.fn().mockResolvedValue(null)}
+        onAssignWorkspaceGroup={vi.fn().mockResolvedValue(null)}
+        reduceTransparency={false}
+        onToggleTransparency={vi.fn()}
+        appSettings={pinnedSettings}
+        openAppIconById={{}}
+        onUpdateAppSettings={onUpdateAppSettings}
+        onRunDoctor={vi.fn().mockResolvedValue(createDoctorResult())}
+        onUpdateWorkspaceCodexBin={vi.fn().mockResolvedValue(undefined)}
+        onUpdateWorkspaceSettings={vi.fn().mockResolvedValue(undefined)}
+        scaleShortcutTitle="Scale shortcut"
+        scaleShortcutText="Use Command +/-"
+        onTestNotificationSound={vi.fn()}
+        onTestSystemNotification={vi.fn()}
+        dictationModelStatus={null}
+        onDownloadDictationModel={vi.fn()}
+        onCancelDictationDownload={vi.fn()}
+        onRemoveDictationModel={vi.fn()}
+        initialSection="server"
+      />,
+    );
+
+    await waitFor(() => {
+      expect(screen.getByText("AA:BB")).toBeTruthy();
+    });
+    expect(screen.queryByText("11:22")).toBeNull();
+
+    await act(async () => {
+      fireEvent.click(screen.getByRole("button", { name: "Forget" }));
+    });
+
+    expect(forgetRemoteBackendTlsPinMock).toHaveBeenCalledTimes(1);
+    expect(onUpdateAppSettings).toHaveBeenCalledWith(forgottenSettings);
+  });
+
   it("shows mobile-only server controls on iOS runtime", async () => {
     cleanup();
     const originalPlatformDescriptor = Object.getOwnPropertyDescriptor(
@@ -1,4 +1,5 @@
 import type { Dispatch, SetStateAction } from "react";
+import { DEFAULT_REMOTE_HOST } from "@settings/components/settingsViewConstants";
 import type {
   AppSettings,
   TailscaleDaemonCommandPreview,
@@ -41,6 +42,7 @@ type SettingsServerSectionProps = {
   onSetOrbitAccessClientSecretRefDraft: Dispatch<SetStateAction<string>>;
   onCommitRemoteHost: () => Promise<void>;
   onCommitRemoteToken: () => Promise<void>;
+  onForgetRemoteCertificate: () => Promise<void>;
   onChangeRemoteProvider: (provider: AppSettings["remoteBackendProvider"]) => Promise<void>;
   onRefreshTailscaleStatus: () => void;
   onRefreshTailscaleCommandPreview: () => void;
@@ -97,6 +99,7 @@ export function SettingsServerSection({
   onSetOrbitAccessClientSecretRefDraft,
   onCommitRemoteHost,
   onCommitRemoteToken,
+  onForgetRemoteCertificate,
   onChangeRemoteProvider,
   onRefreshTailscaleStatus,
   onRefreshTailscaleCommandPreview,
@@ -118,6 +121,8 @@ export function SettingsServerSection({
   onMobileConnectTest,
 }: SettingsServerSectionProps) {
   const isMobileSimplified = isMobilePlatform;
+  const remoteHostKey = appSettings.remoteBackendHost.trim() || DEFAULT_REMOTE_HOST;
+  const pinnedCertificate = appSettings.remoteBackendTlsPins[remoteHostKey] ?? null;
   const tcpRunnerStatusText = (() => {
     if (!tcpDaemonStatus) {
       return null;
@@ -264,6 +269,29 @@ export function SettingsServerSection({
               </div>
             </div>
 
+            {pinnedCertificate && (
+              <div className="settings-field">
+                <div className="settings-field-label">Pinned certificate</div>
+                <div className="settings-field-row">
+                  <code>{pinnedCertificate}</code>
+                  <button
+                    type="button"
+                    className="button settings-button-compact"
+                    onClick={() => {
+                      void onForgetRemoteCertificate();
+                    }}
+                  >
+                    Forget
+                  </button>
+                </div>
+                <div className="settings-help">
+                  SHA-256 fingerprint of the daemon certificate, pinned on the first connection.
+                  Forget it only after regenerating the daemon certificate; the next connection pins
+                  the new one.
+                </div>
+              </div>
+            )}
+
             {isMobileSimplified && (
               <div className="settings-field">
                 <div className="settings-field-label">Connection test</div>
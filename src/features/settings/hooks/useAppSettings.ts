@@ -32,6 +32,7 @@ function buildDefaultSettings(): AppSettings {
     remoteBackendProvider: "tcp",
     remoteBackendHost: "127.0.0.1:4732",
     remoteBackendToken: null,
+    remoteBackendTlsPins: {},
     orbitWsUrl: null,
     orbitAuthUrl: null,
     orbitRunnerName: null,
@@ -7,6 +7,7 @@ import type {
   TcpDaemonStatus,
 } from "@/types";
 import {
+  forgetRemoteBackendTlsPin,
   listWorkspaces,
   tailscaleDaemonCommandPreview as fetchTailscaleDaemonCommandPreview,
   tailscaleDaemonStart,
@@ -70,6 +71,7 @@ export type SettingsServerSectionProps = {
   onSetOrbitAccessClientSecretRefDraft: Dispatch<SetStateAction<string>>;
   onCommitRemoteHost: () => Promise<void>;
   onCommitRemoteToken: () => Promise<void>;
+  onForgetRemoteCertificate: () => Promise<void>;
   onChangeRemoteProvider: (provider: AppSettings["remoteBackendProvider"]) => Promise<void>;
   onRefreshTailscaleStatus: () => void;
   onRefreshTailscaleCommandPreview: () => void;
@@ -245,6 +247,12 @@ export const useSettingsServerSection = ({
     await updateRemoteBackendSettings({ token: nextToken });
   };
 
+  const handleForgetRemoteCertificate = async () => {
+    const nextSettings = await forgetRemoteBackendTlsPin();
+    latestSettingsRef.current = nextSettings;
+    await onUpdateAppSettings(nextSettings);
+  };
+
   const handleMobileConnectTest = () => {
     void (async () => {
       const provider = latestSettingsRef.current.remoteBackendProvider;
@@ -666,6 +674,7 @@ export const useSettingsServerSection = ({
     onSetOrbitAccessClientSecretRefDraft: setOrbitAccessClientSecretRefDraft,
     onCommitRemoteHost: handleCommitRemoteHost,
     onCommitRemoteToken: handleCommitRemoteToken,
+    onForgetRemoteCertificate: handleForgetRemoteCertificate,
     onChangeRemoteProvider: handleChangeRemoteProvider,
     onRefreshTailscaleStatus: handleRefreshTailscaleStatus,
     onRefreshTailscaleCommandPreview: handleRefreshTailscaleCommandPreview,
@@ -14,6 +14,46 @@ export type TerminalExitEvent = {
   terminalId: string;
 };
 
+export type ForgeCheckOutputStream = "stdout" | "stderr";
+
+export type ForgeExecutionEventKind =
+  | { type: "phaseStarted"; isLastPhase: boolean }
+  | { type: "checkStarted"; checkId: string; title: string }
+  | {
+      type: "checkOutput";
+      checkId: string;
+      stream: ForgeCheckOutputStream;
+      data: string;
+    }
+  | {
+      type: "checkFinished";
+      checkId: string;
+      title: string;
+      exitCode: number;
+      durationMs: number;
+      timedOut: boolean;
+    }
+  | { type: "commitCreated"; commitSha: string }
+  | { type: "checksFailed"; reason: string }
+  | { type: "taskBlocked"; reason: string }
+  | { type: "approvalRequested" }
+  | { type: "cancelled" };
+
+export type ForgeExecutionEvent = {
+  workspaceId: string;
+  planId: string;
+  taskId: string;
+  phaseId: string;
+} & ForgeExecutionEventKind;
+
+/** Daemon events the app will never receive, e.g. after a long disconnect or a restart. */
+export type RemoteEventGap = {
+  streamId: string;
+  reason: "overflow" | "restarted";
+  fromSeq: number | null;
+  toSeq: number | null;
+};
+
 type SubscriptionOptions = {
   onError?: (error: unknown) => void;
 };
@@ -86,6 +126,8 @@ const dictationDownloadHub = createEventHub<DictationModelStatus>("dictation-dow
 const dictationEventHub = createEventHub<DictationEvent>("dictation-event");
 const terminalOutputHub = createEventHub<TerminalOutputEvent>("terminal-output");
 const terminalExitHub = createEventHub<TerminalExitEvent>("terminal-exit");
+const forgeExecutionHub = createEventHub<ForgeExecutionEvent>("forge-execution-event");
+const remoteEventGapHub = createEventHub<RemoteEventGap>("remote-event-gap");
 const updaterCheckHub = createEventHub<void>("updater-check");
 const menuNewAgentHub = createEventHub<void>("menu-new-agent");
 const menuNewWorktreeAgentHub = createEventHub<void>("menu-new-worktree-agent");
@@ -146,6 +188,20 @@ export function subscribeTerminalExit(
   return terminalExitHub.subscribe(onEvent, options);
 }
 
+export function subscribeForgeExecutionEvents(
+  onEvent: (event: ForgeExecutionEvent) => void,
+  options?: SubscriptionOptions,
+): Unsubscribe {
+  return forgeExecutionHub.subscribe(onEvent, options);
+}
+
+export function subscribeRemoteEventGap(
+  onEvent: (event: RemoteEventGap) => void,
+  options?: SubscriptionOptions,
+): Unsubscribe {
+  return remoteEventGapHub.subscribe(onEvent, options);
+}
+
 export function subscribeUpdaterCheck(
   onEvent: () => void,
   options?: SubscriptionOptions,
@@ -642,6 +642,10 @@ export async function updateAppSettings(settings: AppSettings): Promise<AppSetti
   return invoke<AppSettings>("update_app_settings", { settings });
 }
 
+export async function forgetRemoteBackendTlsPin(): Promise<AppSettings> {
+  return invoke<AppSettings>("forget_remote_backend_tls_pin");
+}
+
 export async function orbitConnectTest(): Promise<OrbitConnectTestResult> {
   return invoke<OrbitConnectTestResult>("orbit_connect_test");
 }
@@ -953,10 +957,26 @@ export async function sendNotification(
   await attemptFallback();
 }
 
+export type ForgeTemplateSourceKind = "bundled" | "directory" | "git" | "tarball";
+
+export type ForgeTemplateOrigin = {
+  sourceId: string;
+  kind: ForgeTemplateSourceKind;
+  location?: string | null;
+};
+
+export type ForgeTemplateSource = {
+  id: string;
+  kind: Exclude<ForgeTemplateSourceKind, "bundled">;
+  location: string;
+  gitRef?: string | null;
+};
+
 export type ForgeBundledTemplateInfo = {
   id: string;
   title: string;
   version: string;
+  origin?: ForgeTemplateOrigin;
 };
 
 export type ForgeTemplateLock = {
@@ -965,6 +985,36 @@ export type ForgeTemplateLock = {
   installedTemplateVersion: string;
   installedAtIso: string;
   installedFiles: string[];
+  installedFileHashes?: Record<string, string>;
+  installedFrom?: ForgeTemplateOrigin | null;
+};
+
+export type ForgeTemplateUpgradeFileStatus =
+  | "unchanged"
+  | "updated"
+  | "keptLocal"
+  | "merged"
+  | "conflict"
+  | "added"
+  | "removed";
+
+export type ForgeTemplateUpgradeFile = {
+  path: string;
+  status: ForgeTemplateUpgradeFileStatus;
+  upstreamChanged: boolean;
+  localChanged: boolean;
+};
+
+export type ForgeTemplateUpgradePreview = {
+  templateId: string;
+  installedVersion: string;
+  availableVersion: string;
+  files: ForgeTemplateUpgradeFile[];
+};
+
+export type ForgeTemplateUpgradeResult = {
+  lock: ForgeTemplateLock;
+  files: ForgeTemplateUpgradeFile[];
 };
 
 export type ForgePlanTask = {
@@ -981,6 +1031,24 @@ export type ForgeWorkspacePlan = {
   currentTaskId: string | null;
   planPath: string;
   updatedAtMs: number;
+  planErrors?: ForgeSchemaError[];
+  stateErrors?: ForgeSchemaError[];
+};
+
+export type ForgeSchemaError = {
+  instancePath: string;
+  schemaPath: string;
+  keyword: string;
+  message: string;
+};
+
+export type ForgePlanValidation = {
+  planId: string;
+  planPath: string;
+  statePath: string | null;
+  valid: boolean;
+  planErrors: ForgeSchemaError[];
+  stateErrors: ForgeSchemaError[];
 };
 
 export type ForgeNextPhasePrompt = {
@@ -989,6 +1057,10 @@ export type ForgeNextPhasePrompt = {
   phaseId: string;
   isLastPhase: boolean;
   promptText: string;
+  /** Set for a manual-approval phase: there is no prompt until a reviewer decides. */
+  awaitingApproval?: boolean;
+  /** Set when the plan's budget stopped the task; the task is blocked with this note. */
+  budgetExceeded?: string | null;
 };
 
 export type ForgePhaseStatus = {
@@ -996,6 +1068,62 @@ export type ForgePhaseStatus = {
   commitSha?: string | null;
 };
 
+export type ForgeJunitCase = {
+  name: string;
+  classname: string | null;
+  status: "passed" | "failed" | "error" | "skipped";
+  message: string | null;
+  durationMs: number | null;
+};
+
+export type ForgeCheckDetails =
+  | {
+      kind: "junit";
+      reports: string[];
+      tests: number;
+      passed: number;
+      failures: number;
+      errors: number;
+      skipped: number;
+      cases: ForgeJunitCase[];
+      casesTruncated: boolean;
+    }
+  | {
+      kind: "coverage";
+      report: string;
+      format: "lcov" | "cobertura";
+      linesFound: number;
+      linesHit: number;
+      linePercent: number;
+      minLinePercent: number;
+      branchesFound: number;
+      branchesHit: number;
+      branchPercent: number | null;
+      minBranchPercent: number | null;
+    }
+  | {
+      kind: "diff-limits";
+      baseSha: string;
+      filesChanged: number;
+      linesAdded: number;
+      linesRemoved: number;
+      maxFiles: number | null;
+      maxLines: number | null;
+      files: string[];
+    }
+  | {
+      kind: "file-exists";
+      present: string[];
+      missing: string[];
+    }
+  | {
+      kind: "artifact";
+      path: string;
+      present: boolean;
+      schemaErrors: ForgeSchemaError[];
+      failedRules: string[];
+    };
+
 export type ForgePhaseCheckResult = {
   id: string;
   title: string;
@@ -1004,11 +1132,215 @@ export type ForgePhaseCheckResult = {
   stdout: string;
   stderr: string;
   timedOut: boolean;
+  details?: ForgeCheckDetails;
 };
 
 export type ForgeRunPhaseChecksResponse = {
   ok: boolean;
   results: ForgePhaseCheckResult[];
+  cancelled?: boolean;
+  attempts?: number;
+  maxAttempts?: number | null;
+  retryAfterMs?: number | null;
+  exhausted?: boolean;
+};
+
+export type ForgeRunCheckRecord = {
+  id: string;
+  title: string;
+  exitCode: number;
+  durationMs: number;
+  timedOut: boolean;
+  stdout: string;
+  stderr: string;
+  outputTruncated: boolean;
+  details?: ForgeCheckDetails;
+};
+
+export type ForgeRunRecord = {
+  schema: string;
+  runId: string;
+  planId: string;
+  taskId: string;
+  phaseId: string;
+  attempt: number;
+  threadId: string | null;
+  startedAt: string;
+  finishedAt: string;
+  ok: boolean;
+  cancelled: boolean;
+  exhausted: boolean;
+  commitSha: string | null;
+  checks: ForgeRunCheckRecord[];
+};
+
+export type ForgeRunHistory = {
+  planId: string;
+  runs: ForgeRunRecord[];
+};
+
+export type ForgeRolledBackTask = {
+  taskId: string;
+  commitSha: string | null;
+  revertSha: string | null;
+};
+
+export type ForgePhaseReviewResult = {
+  planId: string;
+  taskId: string;
+  phaseId: string;
+  decision: "approved" | "rejected";
+  returnedToPhaseId: string | null;
+  checks: ForgeRunPhaseChecksResponse | null;
+};
+
+export type ForgeTaskRollbackResult = {
+  planId: string;
+  taskId: string;
+  tasks: ForgeRolledBackTask[];
+};
+
+export type ForgeTaskBlockedReason =
+  | { kind: "missingState" }
+  | { kind: "completed" }
+  | { kind: "retryExhausted"; phaseIds: string[] }
+  | { kind: "inCycle"; taskIds: string[] }
+  | { kind: "unknownDependencies"; taskIds: string[] }
+  | { kind: "dependencyFailed"; taskIds: string[] }
+  | { kind: "waitingOnDependencies"; taskIds: string[] };
+
+export type ForgePlanGraphTask = {
+  id: string;
+  name: string;
+  status: string | null;
+  dependsOn: string[];
+  dependents: string[];
+  runnable: boolean;
+  blockedReason: ForgeTaskBlockedReason | null;
+};
+
+export type ForgePlanAnalysis = {
+  planId: string;
+  tasks: ForgePlanGraphTask[];
+  cycles: string[][];
+  danglingDependencies: { taskId: string; dependsOn: string }[];
+  criticalPath: string[];
+};
+
+export type ForgeUsage = {
+  inputTokens: number;
+  cachedInputTokens: number;
+  outputTokens: number;
+  totalTokens: number;
+  agentTimeMs: number;
+};
+
+export type ForgePhaseCost = {
+  phaseId: string;
+  threadIds: string[];
+  usage: ForgeUsage;
+};
+
+export type ForgeTaskCost = {
+  taskId: string;
+  taskName: string | null;
+  threadIds: string[];
+  usage: ForgeUsage;
+  uncheckedUsage: ForgeUsage;
+  phases: ForgePhaseCost[];
+};
+
+export type ForgePlanCost = {
+  planId: string;
+  templateId: string | null;
+  templateVersion: string | null;
+  usage: ForgeUsage;
+  tasks: ForgeTaskCost[];
+  missingThreadIds: string[];
+};
+
+export type ForgePlanOperation =
+  | {
+      op: "addTask";
+      task: { name: string; depends_on?: string[] } & Record<string, unknown>;
+      position?: number | null;
+    }
+  | { op: "removeTask"; taskId: string }
+  | { op: "moveTask"; taskId: string; position: number }
+  | { op: "renameTask"; taskId: string; name: string }
+  | { op: "setDependencies"; taskId: string; dependsOn: string[] };
+
+export type ForgePlanUpdatedTask = {
+  id: string;
+  name: string;
+  previousId: string | null;
+};
+
+export type ForgePlanUpdateResult = {
+  planId: string;
+  tasks: ForgePlanUpdatedTask[];
+  removedTaskIds: string[];
+};
+
+export type ForgeCancelExecutionResult = {
+  cancelledRuns: number;
+};
+
+export type ForgeRunState =
+  | "running"
+  | "stopping"
+  | "stopped"
+  | "completed"
+  | "halted"
+  | "failed";
+
+export type ForgeStartRunOptions = {
+  accessMode?: "read-only" | "current" | "full-access" | null;
+  model?: string | null;
+  effort?: string | null;
+  collaborationMode?: Record<string, unknown> | null;
+};
+
+export type ForgeRunStatus = {
+  workspaceId: string;
+  planId: string;
+  state: ForgeRunState;
+  taskId: string | null;
+  phaseId: string | null;
+  threadId: string | null;
+  turnId: string | null;
+  completedPhases: number;
+  awaitingApproval: boolean;
+  startedAt: string;
+  updatedAt: string;
+  finishedAt: string | null;
+  error: string | null;
+};
+
+export type ForgeTaskWorktree = {
+  workspaceId: string;
+  path: string;
+  branch: string;
+  baseSha: string | null;
+};
+
+export type ForgeRunnableTask = {
+  taskId: string;
+  taskName: string;
+  phaseId: string | null;
+  isLastPhase: boolean;
+  awaitingMerge: boolean;
+  awaitingApproval: boolean;
+  commitSha: string | null;
+  worktree: ForgeTaskWorktree | null;
+};
+
+export type ForgeTaskMergeResult = {
+  ok: boolean;
+  taskId: string;
+  commitSha: string | null;
+  worktreeWorkspaceId: string | null;
+  message: string;
 };
 
 export type ForgePhaseViewStatus =
@@ -1023,6 +1355,7 @@ export type ForgePhaseViewMetadata = {
   title: string;
   iconId: string;
   order: number;
+  kind?: "manual-approval";
 };
 
 export type ForgePhaseView = {
@@ -1136,6 +1469,7 @@ function parseForgePhaseMetadata(value: unknown): ForgePhaseViewMetadata[] {
       const titleRaw = (phaseValue as { title?: unknown }).title;
       const iconIdRaw = (phaseValue as { iconId?: unknown }).iconId;
       const orderRaw = (phaseValue as { order?: unknown }).order;
+      const kindRaw = (phaseValue as { kind?: unknown }).kind;
 
       const title = typeof titleRaw === "string" && titleRaw.trim() ? titleRaw.trim() : id;
       const iconId =
@@ -1145,7 +1479,9 @@ function parseForgePhaseMetadata(value: unknown): ForgePhaseViewMetadata[] {
       const order =
         typeof orderRaw === "number" && Number.isFinite(orderRaw) ? orderRaw : index + 1;
 
-      return { id, title, iconId, order };
+      return kindRaw === "manual-approval"
+        ? { id, title, iconId, order, kind: kindRaw }
+        : { id, title, iconId, order };
     })
     .filter((phase): phase is ForgePhaseViewMetadata => Boolean(phase));
 
@@ -1230,8 +1566,47 @@ export async function forgeGetInstalledTemplate(
 export async function forgeInstallTemplate(
   workspaceId: string,
   templateId: string,
+  sourceId?: string,
 ): Promise<ForgeTemplateLock> {
-  return invoke<ForgeTemplateLock>("forge_install_template", { workspaceId, templateId });
+  return invoke<ForgeTemplateLock>("forge_install_template", {
+    workspaceId,
+    templateId,
+    ...(sourceId ? { sourceId } : {}),
+  });
+}
+
+export async function forgeListTemplateSources(): Promise<ForgeTemplateSource[]> {
+  return invoke<ForgeTemplateSource[]>("forge_list_template_sources");
+}
+
+export async function forgeAddTemplateSource(
+  kind: ForgeTemplateSource["kind"],
+  location: string,
+  gitRef?: string | null,
+): Promise<ForgeTemplateSource> {
+  return invoke<ForgeTemplateSource>("forge_add_template_source", {
+    kind,
+    location,
+    gitRef: gitRef ?? null,
+  });
+}
+
+export async function forgeRemoveTemplateSource(sourceId: string): Promise<void> {
+  await invoke("forge_remove_template_source", { sourceId });
+}
+
+export async function forgeTemplateUpgradePreview(
+  workspaceId: string,
+): Promise<ForgeTemplateUpgradePreview> {
+  return invoke<ForgeTemplateUpgradePreview>("forge_template_upgrade_preview", {
+    workspaceId,
+  });
+}
+
+export async function forgeUpgradeTemplate(
+  workspaceId: string,
+): Promise<ForgeTemplateUpgradeResult> {
+  return invoke<ForgeTemplateUpgradeResult>("forge_upgrade_template", { workspaceId });
 }
 
 export async function forgeUninstallTemplate(workspaceId: string): Promise<void> {
@@ -1252,6 +1627,16 @@ export async function forgeListPlans(workspaceId: string): Promise<ForgeWorkspac
   }
 }
 
+export async function forgeValidatePlan(
+  workspaceId: string,
+  planId: string,
+): Promise<ForgePlanValidation> {
+  return invoke<ForgePlanValidation>("forge_validate_plan", {
+    workspaceId,
+    planId,
+  });
+}
+
 export async function forgeGetPlanPrompt(workspaceId: string): Promise<string> {
   return invoke<string>("forge_get_plan_prompt", { workspaceId });
 }
@@ -1299,11 +1684,184 @@ export async function forgeRunPhaseChecks(
   planId: string,
   taskId: string,
   phaseId: string,
+  threadId?: string | null,
 ): Promise<ForgeRunPhaseChecksResponse> {
   return invoke<ForgeRunPhaseChecksResponse>("forge_run_phase_checks", {
     workspaceId,
     planId,
     taskId,
     phaseId,
+    threadId: threadId ?? null,
+  });
+}
+
+export async function forgeGetRunHistory(
+  workspaceId: string,
+  planId: string,
+  options?: { taskId?: string | null; limit?: number | null },
+): Promise<ForgeRunHistory> {
+  return invoke<ForgeRunHistory>("forge_get_run_history", {
+    workspaceId,
+    planId,
+    taskId: options?.taskId ?? null,
+    limit: options?.limit ?? null,
+  });
+}
+
+export async function forgeRollbackTask(
+  workspaceId: string,
+  planId: string,
+  taskId: string,
+  options?: { cascade?: boolean },
+): Promise<ForgeTaskRollbackResult> {
+  return invoke<ForgeTaskRollbackResult>("forge_rollback_task", {
+    workspaceId,
+    planId,
+    taskId,
+    cascade: options?.cascade ?? false,
+  });
+}
+
+export async function forgeAnalyzePlan(
+  workspaceId: string,
+  planId: string,
+): Promise<ForgePlanAnalysis> {
+  return invoke<ForgePlanAnalysis>("forge_analyze_plan", { workspaceId, planId });
+}
+
+export async function forgePlanCost(
+  workspaceId: string,
+  planId: string,
+): Promise<ForgePlanCost> {
+  return invoke<ForgePlanCost>("forge_plan_cost", { workspaceId, planId });
+}
+
+export async function forgeUpdatePlan(
+  workspaceId: string,
+  planId: string,
+  operations: ForgePlanOperation[],
+): Promise<ForgePlanUpdateResult> {
+  return invoke<ForgePlanUpdateResult>("forge_update_plan", {
+    workspaceId,
+    planId,
+    operations,
+  });
+}
+
+export async function forgeApprovePhase(
+  workspaceId: string,
+  planId: string,
+  taskId: string,
+  phaseId: string,
+  reviewer: string,
+  notes?: string | null,
+): Promise<ForgePhaseReviewResult> {
+  return invoke<ForgePhaseReviewResult>("forge_approve_phase", {
+    workspaceId,
+    planId,
+    taskId,
+    phaseId,
+    reviewer,
+    notes: notes ?? null,
+  });
+}
+
+export async function forgeRejectPhase(
+  workspaceId: string,
+  planId: string,
+  taskId: string,
+  phaseId: string,
+  reviewer: string,
+  notes: string,
+): Promise<ForgePhaseReviewResult> {
+  return invoke<ForgePhaseReviewResult>("forge_reject_phase", {
+    workspaceId,
+    planId,
+    taskId,
+    phaseId,
+    reviewer,
+    notes,
+  });
+}
+
+export async function forgeCancelExecution(
+  workspaceId: string,
+  planId: string,
+): Promise<ForgeCancelExecutionResult> {
+  return invoke<ForgeCancelExecutionResult>("forge_cancel_execution", {
+    workspaceId,
+    planId,
+  });
+}
+
+export async function forgeStartRun(
+  workspaceId: string,
+  planId: string,
+  options?: ForgeStartRunOptions,
+): Promise<ForgeRunStatus> {
+  return invoke<ForgeRunStatus>("forge_start_run", {
+    workspaceId,
+    planId,
+    options: options ?? null,
+  });
+}
+
+export async function forgeStopRun(
+  workspaceId: string,
+  planId: string,
+): Promise<ForgeRunStatus> {
+  return invoke<ForgeRunStatus>("forge_stop_run", { workspaceId, planId });
+}
+
+export async function forgeRunStatus(
+  workspaceId: string,
+  planId: string,
+): Promise<ForgeRunStatus | null> {
+  return invoke<ForgeRunStatus | null>("forge_run_status", { workspaceId, planId });
+}
+
+export async function forgeGetRunnableTasks(
+  workspaceId: string,
+  planId: string,
+): Promise<ForgeRunnableTask[]> {
+  return invoke<ForgeRunnableTask[]>("forge_get_runnable_tasks", {
+    workspaceId,
+    planId,
+  });
+}
+
+export async function forgeGetTaskPhasePrompt(
+  workspaceId: string,
+  planId: string,
+  taskId: string,
+): Promise<ForgeNextPhasePrompt | null> {
+  return invoke<ForgeNextPhasePrompt | null>("forge_get_task_phase_prompt", {
+    workspaceId,
+    planId,
+    taskId,
+  });
+}
+
+export async function forgeStartTaskWorktree(
+  workspaceId: string,
+  planId: string,
+  taskId: string,
+): Promise<ForgeTaskWorktree> {
+  return invoke<ForgeTaskWorktree>("forge_start_task_worktree", {
+    workspaceId,
+    planId,
+    taskId,
+  });
+}
+
+export async function forgeMergeTaskWorktree(
+  workspaceId: string,
+  planId: string,
+  taskId: string,
+): Promise<ForgeTaskMergeResult> {
+  return invoke<ForgeTaskMergeResult>("forge_merge_task_worktree", {
+    workspaceId,
+    planId,
+    taskId,
   });
 }
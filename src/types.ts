@@ -177,6 +177,7 @@ export type AppSettings = {
   remoteBackendProvider: RemoteBackendProvider;
   remoteBackendHost: string;
   remoteBackendToken: string | null;
+  remoteBackendTlsPins: Record<string, string>;
   orbitWsUrl: string | null;
   orbitAuthUrl: string | null;
   orbitRunnerName: string | null;